@@ -0,0 +1,256 @@
+//! Solid RAR / 7z archives unpacked once into a temp folder.
+//!
+//! 솔리드 아카이브는 엔트리 하나를 꺼낼 때마다 블록 앞부분부터 다시 풀어야 하므로,
+//! 처음 읽을 때 도구가 모든 엔트리를 차례로 표준 출력에 쓰게 하고(한 번만 푼다), 백그라운드
+//! 스레드가 목록의 크기대로 잘라 임시 폴더에 쓴다. 읽는 쪽은 전체가 끝날 때까지 기다리지 않고
+//! 자기 엔트리가 써지면 바로 읽는다. 파일 이름은 `archive_tools::entry_output_path`로 정리해서
+//! 폴더 밖에 쓰지 않는다.
+//! 폴더는 그 아카이브를 연 탭 수만큼 참조되고(`retain` / `discard_extracted`), 마지막 탭이
+//! 닫히면 지운다. 비정상 종료로 남은 폴더는 시작할 때 `sweep_stale`로 지운다.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Output};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+
+const DIR_PREFIX: &str = "simple-reader-extract-";
+/// Folders left by older versions, which unpacked solid 7z archives in one go.
+const OLD_DIR_PREFIX: &str = "simple-reader-7z-";
+
+/// Turns the finished process's status and stderr into an error (wrong password etc.).
+pub type ExitCheck = Box<dyn FnOnce(&Output) -> anyhow::Result<()> + Send>;
+
+/// An extraction started by a reader: the tool writes the data of every file in `entries`
+/// (archive order, with sizes) to `child`'s stdout, one after another.
+pub struct ExtractStream {
+    pub child: Child,
+    pub entries: Vec<(String, u64)>,
+    pub check: ExitCheck,
+}
+
+enum State {
+    Idle,
+    Running,
+    Finished,
+    /// Taken by the first reader that sees it; the next read starts over.
+    Failed(anyhow::Error),
+}
+
+struct Extraction {
+    /// Open tabs using the folder.
+    users: usize,
+    /// Entries written so far.
+    done: HashSet<String>,
+    state: State,
+    /// Set when the folder is discarded while the extraction still runs.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Extraction {
+    fn new() -> Self {
+        Self {
+            users: 0,
+            done: HashSet::new(),
+            state: State::Idle,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+/// Extractions by folder.
+static EXTRACTIONS: LazyLock<Mutex<HashMap<PathBuf, Extraction>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+/// Notified whenever an entry is written or an extraction ends.
+static PROGRESS: Condvar = Condvar::new();
+
+fn extract_dir(archive: &Path) -> PathBuf {
+    let key = crate::disk_cache::source_key(archive, &[]);
+    std::env::temp_dir().join(format!("{}{}", DIR_PREFIX, key))
+}
+
+fn lock() -> std::sync::MutexGuard<'static, HashMap<PathBuf, Extraction>> {
+    EXTRACTIONS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Count a tab reading the solid `archive`, so closing another tab keeps its folder.
+pub fn retain(archive: &Path) {
+    lock().entry(extract_dir(archive)).or_insert_with(Extraction::new).users += 1;
+}
+
+/// Release a tab's use of the folder of `archive` (when the tab closes); the last one deletes it.
+pub fn discard_extracted(archive: &Path) {
+    let dir = extract_dir(archive);
+    let mut extractions = lock();
+    let Some(extraction) = extractions.get_mut(&dir) else {
+        return;
+    };
+    extraction.users = extraction.users.saturating_sub(1);
+    if extraction.users == 0 {
+        extraction.cancelled.store(true, Ordering::Relaxed);
+        extractions.remove(&dir);
+        drop(extractions);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+/// Read `entry_name` of `archive`, starting the extraction with `start` if it isn't running,
+/// and waiting only until that entry has been written.
+pub fn read_extracted(
+    archive: &Path,
+    entry_name: &str,
+    start: impl FnOnce() -> anyhow::Result<ExtractStream>,
+) -> anyhow::Result<Vec<u8>> {
+    let dir = extract_dir(archive);
+    let relative = crate::archive_tools::entry_output_path(entry_name)
+        .ok_or_else(|| anyhow::anyhow!("Not a file entry: {}", entry_name))?;
+    let mut start = Some(start);
+    let mut extractions = lock();
+    loop {
+        let extraction = extractions.entry(dir.clone()).or_insert_with(Extraction::new);
+        if extraction.done.contains(entry_name) {
+            break;
+        }
+        match &extraction.state {
+            State::Running => {}
+            State::Finished => anyhow::bail!("Archive entry not found: {}", entry_name),
+            State::Failed(_) => {
+                if let State::Failed(e) = std::mem::replace(&mut extraction.state, State::Idle) {
+                    return Err(e);
+                }
+            }
+            State::Idle => {
+                let Some(start) = start.take() else {
+                    anyhow::bail!("Extraction of {} stopped", archive.display());
+                };
+                let stream = start()?;
+                std::fs::create_dir_all(&dir)?;
+                extraction.state = State::Running;
+                extraction.done.clear();
+                let cancelled = Arc::clone(&extraction.cancelled);
+                let dir = dir.clone();
+                std::thread::spawn(move || run_extraction(dir, stream, cancelled));
+            }
+        }
+        extractions = PROGRESS.wait(extractions).unwrap_or_else(|e| e.into_inner());
+    }
+    drop(extractions);
+    std::fs::read(dir.join(relative)).map_err(|e| anyhow::anyhow!("Failed to read {}: {}", entry_name, e))
+}
+
+fn run_extraction(dir: PathBuf, mut stream: ExtractStream, cancelled: Arc<AtomicBool>) {
+    let stdout = stream.child.stdout.take();
+    // 오류 메시지가 파이프를 가득 채워 도구가 멈추지 않게 따로 읽는다
+    let stderr = stream.child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut bytes = Vec::new();
+            let _ = stderr.read_to_end(&mut bytes);
+            bytes
+        })
+    });
+
+    let mut written = Ok(());
+    if let Some(mut stdout) = stdout {
+        for (name, size) in &stream.entries {
+            if cancelled.load(Ordering::Relaxed) {
+                let _ = stream.child.kill();
+                break;
+            }
+            written = write_entry(&dir, name, *size, &mut stdout);
+            if written.is_err() {
+                break;
+            }
+            if let Some(extraction) = own(&mut lock(), &dir, &cancelled) {
+                extraction.done.insert(name.clone());
+            }
+            PROGRESS.notify_all();
+        }
+        // 남은 출력을 비워야 도구가 끝난다
+        let _ = std::io::copy(&mut stdout, &mut std::io::sink());
+    }
+    let status = stream.child.wait();
+    let stderr = stderr.and_then(|reader| reader.join().ok()).unwrap_or_default();
+
+    if cancelled.load(Ordering::Relaxed) {
+        let extractions = lock();
+        // 그사이 새 탭이 같은 폴더에 다시 풀기 시작했으면 지우지 않는다
+        if !extractions.contains_key(&dir) {
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+        return;
+    }
+    let result = status
+        .map_err(|e| anyhow::anyhow!("Extraction failed: {}", e))
+        .and_then(|status| {
+            (stream.check)(&Output {
+                status,
+                stdout: Vec::new(),
+                stderr,
+            })
+        })
+        .and(written);
+    if let Some(extraction) = own(&mut lock(), &dir, &cancelled) {
+        extraction.state = match result {
+            Ok(()) => State::Finished,
+            Err(e) => State::Failed(e),
+        };
+    }
+    PROGRESS.notify_all();
+}
+
+/// The extraction of `dir` if it is still the one this thread runs.
+fn own<'a>(
+    extractions: &'a mut HashMap<PathBuf, Extraction>,
+    dir: &Path,
+    cancelled: &Arc<AtomicBool>,
+) -> Option<&'a mut Extraction> {
+    extractions
+        .get_mut(dir)
+        .filter(|extraction| Arc::ptr_eq(&extraction.cancelled, cancelled))
+}
+
+/// Copy the next `size` bytes of the stream into the entry's file (via a `.part` file,
+/// so a reader never sees half of it). Entries without a safe file path are skipped.
+fn write_entry(dir: &Path, name: &str, size: u64, stdout: &mut impl Read) -> anyhow::Result<()> {
+    let mut data = stdout.take(size);
+    let Some(relative) = crate::archive_tools::entry_output_path(name) else {
+        std::io::copy(&mut data, &mut std::io::sink())?;
+        return Ok(());
+    };
+    let target = dir.join(relative);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut part_name = target.file_name().unwrap_or_default().to_os_string();
+    part_name.push(".part");
+    let part = target.with_file_name(part_name);
+    let mut file = std::fs::File::create(&part)?;
+    let copied = std::io::copy(&mut data, &mut file)?;
+    file.flush()?;
+    drop(file);
+    if copied != size {
+        let _ = std::fs::remove_file(&part);
+        anyhow::bail!("Archive ended early at {}", name);
+    }
+    std::fs::rename(&part, &target)?;
+    Ok(())
+}
+
+/// Delete extraction folders left behind by a crash (run once at startup).
+pub fn sweep_stale() {
+    let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if (name.starts_with(DIR_PREFIX) || name.starts_with(OLD_DIR_PREFIX))
+            && entry.file_type().is_ok_and(|t| t.is_dir())
+        {
+            let extractions = lock();
+            // 이미 열린 탭이 쓰고 있는 폴더는 남긴다
+            if !extractions.contains_key(&entry.path()) {
+                let _ = std::fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+}
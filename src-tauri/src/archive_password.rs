@@ -0,0 +1,92 @@
+//! Per-archive passwords for encrypted ZIP collections.
+//!
+//! 비밀번호는 bookmark store에 저장하거나, 선택 시 OS 키링(Windows Credential Manager,
+//! macOS Keychain, Linux kernel keyring)에 저장하고 store에는 표시만 남긴다.
+//! 암호화된 엔트리는 zip_fast 대신 zip 크레이트(ZipCrypto / AES)로 읽는다 (RAR/7z는 unrar/7-Zip에 넘긴다).
+
+use std::io::Read;
+use std::path::Path;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+
+const KEYRING_SERVICE: &str = "simplereader-archive";
+
+/// Read and decrypt a single entry.
+pub fn read_encrypted_entry(zip_path: &Path, entry_name: &str, password: &str) -> anyhow::Result<Vec<u8>> {
+    let file = std::fs::File::open(zip_path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let index = archive
+        .index_for_name(entry_name)
+        .ok_or_else(|| anyhow::anyhow!("ZIP entry not found: {}", entry_name))?;
+    let mut entry = archive
+        .by_index_decrypt(index, password.as_bytes())
+        .map_err(|e| match e {
+            zip::result::ZipError::InvalidPassword => {
+                anyhow::Error::from(AppError::WrongPassword(zip_path.to_string_lossy().to_string()))
+            }
+            other => other.into(),
+        })?;
+    let mut buf = Vec::with_capacity(entry.size() as usize);
+    // 읽기가 끝나면 CRC 검사까지 수행되므로 ZipCrypto의 1/256 오탐도 걸러진다
+    entry
+        .read_to_end(&mut buf)
+        .map_err(|_| AppError::WrongPassword(zip_path.to_string_lossy().to_string()))?;
+    Ok(buf)
+}
+
+/// Check a password against the smallest encrypted entry of the archive.
+/// Returns Ok(true) for archives without encrypted entries.
+pub fn test_password(zip_path: &Path, password: &str, config: &AppConfig) -> anyhow::Result<bool> {
+    let ext = zip_path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if crate::rar_reader::is_rar_extension(&ext) {
+        let unrar = crate::rar_reader::find_unrar(&config.unrar_path)?;
+        return crate::rar_reader::test_password(&unrar, zip_path, password);
+    }
+    if crate::sevenz_reader::is_7z_extension(&ext) {
+        let tool = crate::sevenz_reader::find_7z(&config.sevenzip_path)?;
+        return crate::sevenz_reader::test_password(&tool, zip_path, password);
+    }
+    let index = crate::zip_fast::ZipIndex::open(zip_path)?;
+    if !index.is_encrypted() {
+        return Ok(true);
+    }
+    let Some(smallest) = index
+        .entry_sizes()
+        .filter(|(name, _)| index.is_entry_encrypted(name))
+        .min_by_key(|(_, size)| *size)
+        .map(|(name, _)| name.to_string())
+    else {
+        return Ok(true);
+    };
+
+    match read_encrypted_entry(zip_path, &smallest, password) {
+        Ok(_) => Ok(true),
+        Err(e) if matches!(e.downcast_ref::<AppError>(), Some(AppError::WrongPassword(_))) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn keyring_entry(archive_path: &str) -> anyhow::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, archive_path)
+        .map_err(|e| anyhow::anyhow!("OS keyring unavailable: {}", e))
+}
+
+pub fn keyring_store(archive_path: &str, password: &str) -> anyhow::Result<()> {
+    keyring_entry(archive_path)?
+        .set_password(password)
+        .map_err(|e| anyhow::anyhow!("Failed to store password in keyring: {}", e))
+}
+
+pub fn keyring_load(archive_path: &str) -> Option<String> {
+    keyring_entry(archive_path).ok()?.get_password().ok()
+}
+
+pub fn keyring_clear(archive_path: &str) {
+    if let Ok(entry) = keyring_entry(archive_path) {
+        let _ = entry.delete_credential();
+    }
+}
@@ -0,0 +1,135 @@
+//! Packing image folders into CBZ files and unpacking ZIP/CBZ archives.
+//!
+//! CBZ는 이미지가 이미 압축된 형식이라 Deflate로 다시 압축해도 거의 줄지 않으므로
+//! 무압축(Stored)으로 묶는다. 결과는 `.part` 임시 파일에 쓴 뒤 이름을 바꿔서,
+//! 중간에 실패해도 반쯤 쓴 CBZ가 남지 않는다.
+//! 압축 풀기는 `zip_fast`로 하고, 엔트리 경로의 `..`/드라이브 문자는 버려서 출력 폴더
+//! 밖에 쓰지 않는다. 같은 이름의 파일이 있으면 "이름 (2).jpg"처럼 새 이름을 붙인다.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+fn part_path(output: &Path) -> PathBuf {
+    let mut name = output.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    output.with_file_name(name)
+}
+
+/// Zip the images of `dir` (natural sort order, junk files skipped) into `output`.
+/// `progress(done, total, name)` is called after each image. Returns the image count.
+pub fn folder_to_cbz(
+    dir: &Path,
+    output: &Path,
+    mut progress: impl FnMut(usize, usize, &str),
+) -> anyhow::Result<usize> {
+    let (_, images) = crate::image_reader::scan_directory_images(dir)?;
+    if images.is_empty() {
+        anyhow::bail!("No images in {}", dir.display());
+    }
+
+    let part = part_path(output);
+    let result = write_cbz(&images, &part, &mut progress);
+    match result {
+        Ok(()) => {
+            std::fs::rename(&part, output)?;
+            Ok(images.len())
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&part);
+            Err(e)
+        }
+    }
+}
+
+fn write_cbz(
+    images: &[PathBuf],
+    part: &Path,
+    progress: &mut impl FnMut(usize, usize, &str),
+) -> anyhow::Result<()> {
+    let file = std::fs::File::create(part)?;
+    let mut zip = zip::ZipWriter::new(std::io::BufWriter::new(file));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored);
+
+    for (i, path) in images.iter().enumerate() {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut source = std::fs::File::open(path)?;
+        let large = source.metadata()?.len() >= u32::MAX as u64;
+        zip.start_file(name.as_str(), options.large_file(large))?;
+        std::io::copy(&mut source, &mut zip)?;
+        progress(i + 1, images.len(), &name);
+    }
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+/// Safe relative path of an archive entry ('/' or '\' separated), None for directories.
+pub fn entry_output_path(name: &str) -> Option<PathBuf> {
+    if name.ends_with('/') || name.ends_with('\\') {
+        return None;
+    }
+    let path: PathBuf = name
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != "." && *c != "..")
+        .map(crate::series::sanitize_file_name)
+        .filter(|c| !c.is_empty())
+        .collect();
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+/// `path`, or "stem (2).ext", "stem (3).ext", … if it already exists.
+fn unique_path(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, ext)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(path)
+}
+
+/// Unpack every file of a ZIP/CBZ into `output_dir`, keeping its folders.
+/// Encrypted entries need `password`. `progress(done, total, name)` is called after each
+/// file. Returns the number of files written.
+pub fn extract_zip(
+    zip_path: &Path,
+    output_dir: &Path,
+    password: Option<&str>,
+    mut progress: impl FnMut(usize, usize, &str),
+) -> anyhow::Result<usize> {
+    let index = crate::zip_fast::ZipIndex::open(zip_path)?;
+    let entries: Vec<(String, PathBuf)> = index
+        .entry_sizes()
+        .filter_map(|(name, _)| entry_output_path(name).map(|path| (name.to_string(), path)))
+        .collect();
+    std::fs::create_dir_all(output_dir)?;
+
+    for (i, (name, relative)) in entries.iter().enumerate() {
+        let bytes = if index.is_entry_encrypted(name) {
+            let password = password.ok_or_else(|| {
+                crate::error::AppError::PasswordRequired(zip_path.to_string_lossy().to_string())
+            })?;
+            crate::archive_password::read_encrypted_entry(zip_path, name, password)?
+        } else {
+            index.read_entry(name)?
+        };
+        let target = unique_path(output_dir.join(relative));
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&target, bytes)?;
+        progress(i + 1, entries.len(), name);
+    }
+    Ok(entries.len())
+}
@@ -0,0 +1,84 @@
+//! Crash-safe file saves: write a temp file next to the target, fsync, then rename over it.
+//! 저장 도중 앱이 죽어도 원본은 그대로 남는다.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static KEEP_BACKUP: AtomicBool = AtomicBool::new(false);
+
+/// Keep the previous version as `<name>.bak` when overwriting a file.
+pub fn set_keep_backup(enabled: bool) {
+    KEEP_BACKUP.store(enabled, Ordering::Relaxed);
+}
+
+/// Replace `path` with whatever `write` produces. The target is only touched once
+/// the new content is fully on disk.
+pub fn write_atomic<F>(path: &Path, write: F) -> anyhow::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+{
+    let temp = temp_path(path);
+    let result = write_temp(&temp, path, write).and_then(|_| {
+        if KEEP_BACKUP.load(Ordering::Relaxed) && path.exists() {
+            fs::copy(path, backup_path(path))?;
+        }
+        fs::rename(&temp, path)?;
+        Ok(())
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result?;
+    sync_parent_dir(path);
+    Ok(())
+}
+
+fn write_temp<F>(temp: &Path, target: &Path, write: F) -> std::io::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+{
+    let mut writer = BufWriter::new(File::create(temp)?);
+    write(&mut writer)?;
+    writer.flush()?;
+    let file = writer.into_inner().map_err(|e| e.into_error())?;
+    file.sync_all()?;
+    // 기존 파일의 권한(읽기 전용 등)을 그대로 유지
+    if let Ok(meta) = fs::metadata(target) {
+        let _ = fs::set_permissions(temp, meta.permissions());
+    }
+    Ok(())
+}
+
+/// Hidden temp file in the same directory (so the rename stays on one filesystem).
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    path.with_file_name(format!(".{}.{}-{}.tmp", name, std::process::id(), nanos))
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Persist the rename itself (directory entry) on Unix; a no-op elsewhere.
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
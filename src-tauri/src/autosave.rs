@@ -0,0 +1,145 @@
+//! Periodic autosave of modified text buffers to `~/.simple-reader/recovery/`,
+//! so edits survive a crash. 저장하거나 닫으면 복구 파일은 지워진다.
+
+use ropey::Rope;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecoveryMeta {
+    original_path: String,
+    saved_at: String,
+    /// Process that wrote it; entries from the running process aren't offered for recovery.
+    pid: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveredFile {
+    pub original_path: String,
+    pub saved_at: String,
+    pub size: u64,
+    /// Whether the original file still exists.
+    pub original_exists: bool,
+}
+
+pub fn spawn_autosave<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
+    use tauri::Manager;
+    std::thread::spawn(move || {
+        // tab id -> revision last written
+        let mut written: HashMap<String, u64> = HashMap::new();
+        loop {
+            std::thread::sleep(AUTOSAVE_INTERVAL);
+            let state = app.state::<crate::AppState>();
+            let snapshots = match state.tab_manager.lock() {
+                Ok(tab_manager) => tab_manager.modified_text_snapshots(),
+                Err(_) => continue,
+            };
+
+            for (id, rope, revision) in &snapshots {
+                if written.get(id) == Some(revision) {
+                    continue;
+                }
+                if let Err(e) = write_recovery(id, rope) {
+                    log::warn!("Autosave failed for {}: {}", id, e);
+                    continue;
+                }
+                written.insert(id.clone(), *revision);
+                // 쓰는 동안 저장되거나 닫혔으면 방금 쓴 복구 파일을 지운다. 저장은 TabManager
+                // 잠금 안에서 복구 파일을 지우므로, 잠금을 잡고 확인하면 어느 쪽이 먼저든 남지 않는다
+                if let Ok(tab_manager) = state.tab_manager.lock() {
+                    if !tab_manager.get_revision(id).is_some_and(|(_, modified)| modified) {
+                        discard(id);
+                        written.remove(id);
+                    }
+                }
+            }
+
+            // 저장됐거나 닫힌 탭의 복구 파일 정리
+            written.retain(|id, _| {
+                let still_modified = snapshots.iter().any(|(s, _, _)| s == id);
+                if !still_modified {
+                    discard(id);
+                }
+                still_modified
+            });
+        }
+    });
+}
+
+/// Unsaved edits left behind by a previous session (crash or forced quit).
+pub fn list_recovered() -> Vec<RecoveredFile> {
+    let Ok(dir) = recovery_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<RecoveredFile> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| {
+            let meta: RecoveryMeta = serde_json::from_str(&std::fs::read_to_string(e.path()).ok()?).ok()?;
+            if meta.pid == std::process::id() {
+                return None;
+            }
+            let size = std::fs::metadata(e.path().with_extension("txt")).ok()?.len();
+            Some(RecoveredFile {
+                original_exists: Path::new(&meta.original_path).exists(),
+                original_path: meta.original_path,
+                saved_at: meta.saved_at,
+                size,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+    files
+}
+
+/// Recovered text for a file.
+pub fn read_recovered(original_path: &str) -> anyhow::Result<String> {
+    let (text_path, _) = recovery_paths(original_path)?;
+    Ok(std::fs::read_to_string(text_path)?)
+}
+
+/// Delete the recovery copy of a file (after saving, closing or an explicit discard).
+pub fn discard(original_path: &str) {
+    if let Ok((text_path, meta_path)) = recovery_paths(original_path) {
+        let _ = std::fs::remove_file(text_path);
+        let _ = std::fs::remove_file(meta_path);
+    }
+}
+
+fn write_recovery(original_path: &str, rope: &Rope) -> anyhow::Result<()> {
+    let (text_path, meta_path) = recovery_paths(original_path)?;
+    if let Some(dir) = text_path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    crate::atomic_write::write_atomic(&text_path, |writer| rope.write_to(writer))?;
+    let meta = RecoveryMeta {
+        original_path: original_path.to_string(),
+        saved_at: chrono::Local::now().to_rfc3339(),
+        pid: std::process::id(),
+    };
+    std::fs::write(meta_path, serde_json::to_string_pretty(&meta)?)?;
+    Ok(())
+}
+
+fn recovery_paths(original_path: &str) -> anyhow::Result<(PathBuf, PathBuf)> {
+    use sha1::{Digest, Sha1};
+
+    let key: String = Sha1::digest(original_path.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let dir = recovery_dir()?;
+    Ok((dir.join(format!("{}.txt", key)), dir.join(format!("{}.json", key))))
+}
+
+fn recovery_dir() -> anyhow::Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+    Ok(home.join(".simple-reader").join("recovery"))
+}
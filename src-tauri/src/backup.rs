@@ -0,0 +1,147 @@
+//! Full app data backup / restore.
+//!
+//! ~/.simple-reader 아래의 사용자 데이터(설정, 책 목록/책갈피, 통계, 테마, 복구 초안)를
+//! ZIP 하나로 묶는다. 캐시(warm/remote/ocr)는 다시 만들 수 있으므로 제외한다.
+//! manifest.json의 format_version으로 호환성을 확인한다.
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Bump when the backup layout changes incompatibly.
+const BACKUP_FORMAT_VERSION: u32 = 1;
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Files and directories (relative to the data dir) included in a backup.
+const BACKUP_FILES: &[&str] = &["config.json", "books.json", "reading_stats.json"];
+const BACKUP_DIRS: &[&str] = &["themes", "recovery"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created: String,
+    pub entries: Vec<String>,
+}
+
+pub fn data_dir() -> anyhow::Result<PathBuf> {
+    let home = dirs::home_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+    Ok(home.join(".simple-reader"))
+}
+
+/// Relative paths (with '/' separators) of everything that should be backed up.
+fn collect_entries(data_dir: &Path) -> Vec<String> {
+    let mut entries: Vec<String> = BACKUP_FILES
+        .iter()
+        .filter(|name| data_dir.join(name).is_file())
+        .map(|name| name.to_string())
+        .collect();
+
+    for dir in BACKUP_DIRS {
+        let mut stack = vec![data_dir.join(dir)];
+        while let Some(current) = stack.pop() {
+            let Ok(items) = std::fs::read_dir(&current) else {
+                continue;
+            };
+            for item in items.flatten() {
+                let path = item.path();
+                if path.is_dir() {
+                    stack.push(path);
+                } else if let Ok(rel) = path.strip_prefix(data_dir) {
+                    entries.push(rel.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+    entries.sort();
+    entries
+}
+
+/// Write a backup ZIP of the app data to `target`.
+pub fn backup_to(target: &Path) -> anyhow::Result<BackupManifest> {
+    let data_dir = data_dir()?;
+    let entries = collect_entries(&data_dir);
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created: chrono::Local::now().to_rfc3339(),
+        entries: entries.clone(),
+    };
+
+    let file = std::fs::File::create(target)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file(MANIFEST_NAME, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    for entry in &entries {
+        zip.start_file(entry.as_str(), options)?;
+        zip.write_all(&std::fs::read(data_dir.join(entry))?)?;
+    }
+    zip.finish()?;
+    Ok(manifest)
+}
+
+/// Restore app data from a backup ZIP. The current data is first saved to
+/// `pre-restore-backup.zip` in the data dir so a bad restore can be undone.
+pub fn restore_from(source: &Path) -> anyhow::Result<BackupManifest> {
+    let file = std::fs::File::open(source)?;
+    let mut zip = zip::ZipArchive::new(file)?;
+
+    let manifest: BackupManifest = {
+        let mut entry = zip
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| anyhow::anyhow!("Not a SimpleReader backup (manifest missing)"))?;
+        let mut content = String::new();
+        entry.read_to_string(&mut content)?;
+        serde_json::from_str(&content)?
+    };
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        anyhow::bail!(
+            "Backup format {} is newer than supported ({}); update the app first",
+            manifest.format_version,
+            BACKUP_FORMAT_VERSION
+        );
+    }
+
+    // 경로 탈출(../, ..\, 절대 경로) 방지: 알려진 파일/디렉토리의 일반 경로만 허용
+    for name in &manifest.entries {
+        let allowed = BACKUP_FILES.contains(&name.as_str())
+            || BACKUP_DIRS
+                .iter()
+                .any(|dir| name.starts_with(&format!("{}/", dir)));
+        if !allowed || !is_plain_relative_path(name) {
+            anyhow::bail!("Backup contains an unexpected entry: {}", name);
+        }
+    }
+
+    let data_dir = data_dir()?;
+    std::fs::create_dir_all(&data_dir)?;
+    backup_to(&data_dir.join("pre-restore-backup.zip"))?;
+
+    for name in &manifest.entries {
+        let mut entry = zip.by_name(name)?;
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        let target = data_dir.join(name);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(target, content)?;
+    }
+    Ok(manifest)
+}
+
+/// A relative path made of plain names only, split on both separators (so a Windows
+/// `..\` or `C:` can't hide inside one `/` component).
+fn is_plain_relative_path(name: &str) -> bool {
+    name.split(['/', '\\']).all(|part| {
+        !part.is_empty()
+            && !part.contains(':')
+            && std::path::Path::new(part)
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)))
+    })
+}
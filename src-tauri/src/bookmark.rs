@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Reading progress reported while scrolling is written at most this often.
+const PROGRESS_SAVE_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bookmark {
@@ -10,6 +14,64 @@ pub struct Bookmark {
     pub created: String,
 }
 
+/// Highlighted range in an EPUB chapter, optionally with a note.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpubHighlight {
+    /// Stable id (UUID) the highlight is addressed by; filled in on load for older entries.
+    #[serde(default)]
+    pub id: String,
+    pub chapter_index: usize,
+    /// `#id` of the element the offsets are relative to; None = the chapter body.
+    #[serde(default)]
+    pub selector: Option<String>,
+    /// Char offsets into the element's text content (`start..end`).
+    pub start_offset: usize,
+    pub end_offset: usize,
+    /// Highlighted text (shown in the list, and kept if the offsets go stale).
+    pub text: String,
+    /// CSS color, e.g. "#ffeb3b".
+    pub color: String,
+    #[serde(default)]
+    pub note: String,
+    pub created: String,
+}
+
+/// How a PDF was last displayed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfViewState {
+    /// Zoom factor (1.0 = 100%).
+    pub zoom: f64,
+    /// Fit mode chosen in the viewer (e.g. "width", "page", "none").
+    #[serde(default)]
+    pub fit_mode: String,
+}
+
+/// Page turning direction of a book.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadingDirection {
+    #[default]
+    Ltr,
+    /// Right to left (Japanese manga): the next page is on the left.
+    Rtl,
+}
+
+/// Per-book reading goal. Either (or both) of the targets may be set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingGoal {
+    /// Target finish date (YYYY-MM-DD).
+    #[serde(default)]
+    pub target_date: Option<String>,
+    /// Pages / chapters / lines per day.
+    #[serde(default)]
+    pub units_per_day: Option<u64>,
+    /// Total pages/chapters/lines of the book when the goal was set.
+    pub total_units: usize,
+    /// Position when the goal was set (progress is measured from here).
+    pub start_position: usize,
+    pub created: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileBookmarks {
     pub last_position: usize,
@@ -23,6 +85,29 @@ pub struct FileBookmarks {
     pub display_order: Option<usize>,
     #[serde(default)]
     pub format_type: Option<String>,
+    /// Saved archive password (None when stored in the OS keyring or not saved).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_password: Option<String>,
+    #[serde(default)]
+    pub password_in_keyring: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goal: Option<ReadingGoal>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<EpubHighlight>,
+    /// Whole-book progress (0–100), for formats that record it (EPUB).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress_percent: Option<f64>,
+    /// PDF zoom / fit mode (the page index is `last_position`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdf_view: Option<PdfViewState>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_direction: Option<ReadingDirection>,
+    /// Trim white/black scan margins of image pages.
+    #[serde(default)]
+    pub crop_margins: bool,
+    /// Clockwise rotation (degrees) of image pages, by page index.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub page_rotations: BTreeMap<usize, u16>,
 }
 
 impl Default for FileBookmarks {
@@ -35,6 +120,15 @@ impl Default for FileBookmarks {
             last_scroll_offset: 0,
             display_order: None,
             format_type: None,
+            archive_password: None,
+            password_in_keyring: false,
+            goal: None,
+            highlights: Vec::new(),
+            progress_percent: None,
+            pdf_view: None,
+            reading_direction: None,
+            crop_margins: false,
+            page_rotations: BTreeMap::new(),
         }
     }
 }
@@ -55,24 +149,42 @@ pub struct FileListEntry {
     pub bookmark_count: usize,
     pub favorite: bool,
     pub display_order: Option<usize>,
+    pub progress_percent: Option<f64>,
 }
 
 pub struct BookmarkStore {
     data: HashMap<String, FileBookmarks>,
     store_path: PathBuf,
+    /// Progress changed since it was last written (see `flush_progress`).
+    progress_dirty: bool,
+    last_progress_save: Instant,
 }
 
 impl BookmarkStore {
     /// Create a new BookmarkStore, loading from disk if the file exists.
     pub fn new() -> anyhow::Result<Self> {
         let store_path = Self::default_path()?;
-        let data = if store_path.exists() {
-            let content = std::fs::read_to_string(&store_path)?;
-            serde_json::from_str(&content).unwrap_or_default()
-        } else {
-            HashMap::new()
+        let (mut data, recovered): (HashMap<String, FileBookmarks>, bool) =
+            crate::store_recovery::load_map::<FileBookmarks, _>(&store_path)?
+            .unwrap_or_default();
+        // id가 없던 시절의 하이라이트에 id를 붙여 저장해 둔다
+        let mut assigned_ids = false;
+        for highlight in data.values_mut().flat_map(|entry| entry.highlights.iter_mut()) {
+            if highlight.id.is_empty() {
+                highlight.id = uuid::Uuid::new_v4().to_string();
+                assigned_ids = true;
+            }
+        }
+        let store = Self {
+            data,
+            store_path,
+            progress_dirty: false,
+            last_progress_save: Instant::now(),
         };
-        Ok(Self { data, store_path })
+        if recovered || assigned_ids {
+            store.save_to_disk()?;
+        }
+        Ok(store)
     }
 
     fn default_path() -> anyhow::Result<PathBuf> {
@@ -114,6 +226,29 @@ impl BookmarkStore {
         Ok(())
     }
 
+    /// Add imported bookmarks, skipping ones already present (same line and memo).
+    /// Returns the number added.
+    pub fn import_bookmarks(&mut self, file_path: &str, bookmarks: Vec<Bookmark>) -> anyhow::Result<usize> {
+        let entry = self
+            .data
+            .entry(file_path.to_string())
+            .or_default();
+
+        let mut added = 0;
+        for bookmark in bookmarks {
+            let exists = entry
+                .bookmarks
+                .iter()
+                .any(|b| b.line == bookmark.line && b.memo == bookmark.memo);
+            if !exists {
+                entry.bookmarks.push(bookmark);
+                added += 1;
+            }
+        }
+        self.save_to_disk()?;
+        Ok(added)
+    }
+
     /// Remove a bookmark by index for a specific file.
     pub fn remove_bookmark(&mut self, file_path: &str, index: usize) -> anyhow::Result<()> {
         if let Some(entry) = self.data.get_mut(file_path) {
@@ -137,6 +272,37 @@ impl BookmarkStore {
             .unwrap_or_default()
     }
 
+    /// Add an EPUB highlight, giving it a new id. Returns the id.
+    pub fn add_highlight(&mut self, file_path: &str, mut highlight: EpubHighlight) -> anyhow::Result<String> {
+        highlight.id = uuid::Uuid::new_v4().to_string();
+        let id = highlight.id.clone();
+        let entry = self.data.entry(file_path.to_string()).or_default();
+        entry.highlights.push(highlight);
+        self.save_to_disk()?;
+        Ok(id)
+    }
+
+    /// Remove an EPUB highlight by id.
+    pub fn remove_highlight(&mut self, file_path: &str, id: &str) -> anyhow::Result<()> {
+        let entry = self.data.get_mut(file_path)
+            .ok_or_else(|| anyhow::anyhow!("File not found: {}", file_path))?;
+        let index = entry
+            .highlights
+            .iter()
+            .position(|h| h.id == id)
+            .ok_or_else(|| anyhow::anyhow!("Highlight not found: {}", id))?;
+        entry.highlights.remove(index);
+        self.save_to_disk()
+    }
+
+    /// Get all highlights of a file, in the order they were added.
+    pub fn get_highlights(&self, file_path: &str) -> Vec<EpubHighlight> {
+        self.data
+            .get(file_path)
+            .map(|entry| entry.highlights.clone())
+            .unwrap_or_default()
+    }
+
     /// Get all bookmarks for all files.
     pub fn get_all_bookmarks(&self) -> &HashMap<String, FileBookmarks> {
         &self.data
@@ -179,6 +345,96 @@ impl BookmarkStore {
         Ok(())
     }
 
+    /// Record the whole-book progress percentage (only if already tracked). It is
+    /// reported on every scroll, so it's written at most every `PROGRESS_SAVE_INTERVAL`;
+    /// `flush_progress` writes the rest (tab close, idle, exit).
+    pub fn save_progress(&mut self, file_path: &str, percent: f64) -> anyhow::Result<()> {
+        if let Some(entry) = self.data.get_mut(file_path) {
+            if entry.progress_percent == Some(percent) {
+                return Ok(());
+            }
+            entry.progress_percent = Some(percent);
+            self.progress_dirty = true;
+            if self.last_progress_save.elapsed() >= PROGRESS_SAVE_INTERVAL {
+                self.flush_progress()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write progress recorded by `save_progress` that hasn't been saved yet.
+    pub fn flush_progress(&mut self) -> anyhow::Result<()> {
+        if !self.progress_dirty {
+            return Ok(());
+        }
+        self.save_to_disk()?;
+        self.progress_dirty = false;
+        self.last_progress_save = Instant::now();
+        Ok(())
+    }
+
+    /// Save the PDF zoom / fit mode (only if already tracked).
+    pub fn save_pdf_view(&mut self, file_path: &str, view: PdfViewState) -> anyhow::Result<()> {
+        if let Some(entry) = self.data.get_mut(file_path) {
+            entry.pdf_view = Some(view);
+            self.save_to_disk()?;
+        }
+        Ok(())
+    }
+
+    pub fn get_pdf_view(&self, file_path: &str) -> Option<PdfViewState> {
+        self.data.get(file_path).and_then(|entry| entry.pdf_view.clone())
+    }
+
+    /// Save the margin crop toggle (only if already tracked).
+    pub fn save_crop_margins(&mut self, file_path: &str, enabled: bool) -> anyhow::Result<()> {
+        if let Some(entry) = self.data.get_mut(file_path) {
+            entry.crop_margins = enabled;
+            self.save_to_disk()?;
+        }
+        Ok(())
+    }
+
+    pub fn get_crop_margins(&self, file_path: &str) -> bool {
+        self.data.get(file_path).is_some_and(|entry| entry.crop_margins)
+    }
+
+    /// Save the rotation of one image page; 0 clears it (only if already tracked).
+    pub fn save_page_rotation(&mut self, file_path: &str, index: usize, degrees: u16) -> anyhow::Result<()> {
+        if let Some(entry) = self.data.get_mut(file_path) {
+            if degrees == 0 {
+                entry.page_rotations.remove(&index);
+            } else {
+                entry.page_rotations.insert(index, degrees);
+            }
+            self.save_to_disk()?;
+        }
+        Ok(())
+    }
+
+    pub fn get_page_rotations(&self, file_path: &str) -> BTreeMap<usize, u16> {
+        self.data
+            .get(file_path)
+            .map(|entry| entry.page_rotations.clone())
+            .unwrap_or_default()
+    }
+
+    /// Save the reading direction (only if already tracked).
+    pub fn save_reading_direction(&mut self, file_path: &str, direction: ReadingDirection) -> anyhow::Result<()> {
+        if let Some(entry) = self.data.get_mut(file_path) {
+            entry.reading_direction = Some(direction);
+            self.save_to_disk()?;
+        }
+        Ok(())
+    }
+
+    pub fn get_reading_direction(&self, file_path: &str) -> ReadingDirection {
+        self.data
+            .get(file_path)
+            .and_then(|entry| entry.reading_direction)
+            .unwrap_or_default()
+    }
+
     /// Get the last reading position for a file.
     pub fn get_last_position(&self, file_path: &str) -> Option<(usize, usize)> {
         self.data.get(file_path).map(|entry| (entry.last_position, entry.last_scroll_offset))
@@ -195,6 +451,29 @@ impl BookmarkStore {
         Ok(())
     }
 
+    /// Add a file to the list without marking it opened. Returns false if already tracked.
+    /// The caller is responsible for saving.
+    pub fn add_file_entry(&mut self, file_path: &str) -> bool {
+        if self.data.contains_key(file_path) {
+            return false;
+        }
+        self.data.insert(file_path.to_string(), FileBookmarks::default());
+        true
+    }
+
+    /// Move a file's entry (bookmarks, position, goal...) to a new path after "Save As".
+    pub fn rename_file_entry(&mut self, old_path: &str, new_path: &str) -> anyhow::Result<()> {
+        if old_path == new_path {
+            return Ok(());
+        }
+        if let Some(mut entry) = self.data.remove(old_path) {
+            entry.last_opened = chrono::Local::now().to_rfc3339();
+            self.data.insert(new_path.to_string(), entry);
+            self.save_to_disk()?;
+        }
+        Ok(())
+    }
+
     /// Get a list of all tracked files with metadata.
     pub fn get_file_list(&self) -> Vec<FileListEntry> {
         let mut entries: Vec<FileListEntry> = self
@@ -213,6 +492,7 @@ impl BookmarkStore {
                     bookmark_count: file_bookmarks.bookmarks.len(),
                     favorite: file_bookmarks.favorite,
                     display_order: file_bookmarks.display_order,
+                    progress_percent: file_bookmarks.progress_percent,
                 }
             })
             .collect();
@@ -291,4 +571,79 @@ impl BookmarkStore {
     pub fn get_format_type(&self, file_path: &str) -> Option<String> {
         self.data.get(file_path).and_then(|e| e.format_type.clone())
     }
+
+    /// Save an archive password, either in the store itself or in the OS keyring.
+    pub fn save_archive_password(&mut self, file_path: &str, password: &str, use_keyring: bool) -> anyhow::Result<()> {
+        if use_keyring {
+            crate::archive_password::keyring_store(file_path, password)?;
+        }
+        let entry = self.data.entry(file_path.to_string()).or_default();
+        entry.archive_password = (!use_keyring).then(|| password.to_string());
+        entry.password_in_keyring = use_keyring;
+        self.save_to_disk()
+    }
+
+    /// Get a saved archive password (resolving keyring-backed entries).
+    pub fn get_archive_password(&self, file_path: &str) -> Option<String> {
+        let entry = self.data.get(file_path)?;
+        if entry.password_in_keyring {
+            crate::archive_password::keyring_load(file_path)
+        } else {
+            entry.archive_password.clone()
+        }
+    }
+
+    /// Forget a saved archive password.
+    pub fn clear_archive_password(&mut self, file_path: &str) -> anyhow::Result<()> {
+        let Some(entry) = self.data.get_mut(file_path) else {
+            return Ok(());
+        };
+        if entry.password_in_keyring {
+            crate::archive_password::keyring_clear(file_path);
+        }
+        entry.archive_password = None;
+        entry.password_in_keyring = false;
+        self.save_to_disk()
+    }
+
+    /// Set (or replace) the reading goal for a file, starting from its current position.
+    pub fn set_goal(
+        &mut self,
+        file_path: &str,
+        target_date: Option<String>,
+        units_per_day: Option<u64>,
+        total_units: usize,
+    ) -> anyhow::Result<ReadingGoal> {
+        if target_date.is_none() && units_per_day.is_none() {
+            anyhow::bail!("A goal needs a target date or a daily amount");
+        }
+        if let Some(date) = target_date.as_deref() {
+            chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| anyhow::anyhow!("Invalid target date: {}", date))?;
+        }
+        let entry = self.data.entry(file_path.to_string()).or_default();
+        let goal = ReadingGoal {
+            target_date,
+            units_per_day,
+            total_units,
+            start_position: entry.last_position,
+            created: chrono::Local::now().to_rfc3339(),
+        };
+        entry.goal = Some(goal.clone());
+        self.save_to_disk()?;
+        Ok(goal)
+    }
+
+    pub fn get_goal(&self, file_path: &str) -> Option<ReadingGoal> {
+        self.data.get(file_path).and_then(|e| e.goal.clone())
+    }
+
+    pub fn clear_goal(&mut self, file_path: &str) -> anyhow::Result<()> {
+        if let Some(entry) = self.data.get_mut(file_path) {
+            if entry.goal.take().is_some() {
+                self.save_to_disk()?;
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,484 @@
+//! Import bookmarks/highlights from other readers.
+//!
+//! - Calibre: annotations export JSON (`calibre_annotation_collection`)
+//! - KOReader: sidecar `<book>.sdr/metadata.<ext>.lua`
+//! - Moon+ Reader: reading position file (`.po`)
+//!
+//! 다른 앱의 위치 표현(CFI, xpointer, 퍼센트)은 그대로 옮길 수 없으므로, 하이라이트된
+//! 문장을 본문에서 찾아 줄 번호로 바꾸고, 찾지 못하면 챕터 번호나 진행률로 근사한다.
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use ropey::Rope;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static XPOINTER_PARAGRAPH_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"/p\[(\d+)\]").unwrap());
+static XPOINTER_FRAGMENT_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"DocFragment\[(\d+)\]").unwrap());
+
+/// The book the bookmarks are imported into.
+pub struct ImportTarget<'a> {
+    /// Document text (text files only).
+    pub text: Option<&'a Rope>,
+    /// Total lines / chapters / pages of the document.
+    pub total_units: usize,
+}
+
+pub struct ImportedBookmark {
+    /// Line (text) or chapter/page index.
+    pub unit: usize,
+    pub memo: String,
+    pub created: Option<String>,
+}
+
+pub struct ImportedData {
+    pub format: &'static str,
+    pub bookmarks: Vec<ImportedBookmark>,
+    /// Entries whose position couldn't be mapped.
+    pub skipped: usize,
+}
+
+/// Detect the format from the file name and import it.
+pub fn import_file(source: &Path, target: &ImportTarget) -> anyhow::Result<ImportedData> {
+    let source = resolve_koreader_sidecar(source);
+    let ext = source
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let content = std::fs::read_to_string(&source)?;
+    match ext.as_str() {
+        "json" => import_calibre(&content, target),
+        "lua" => import_koreader(&content, target),
+        "po" => import_moon_reader(&content, target),
+        _ => anyhow::bail!("Unsupported bookmark file: {}", source.display()),
+    }
+}
+
+/// `book.sdr` 폴더를 넘기면 안의 metadata.*.lua를 찾는다.
+fn resolve_koreader_sidecar(source: &Path) -> PathBuf {
+    if !source.is_dir() {
+        return source.to_path_buf();
+    }
+    std::fs::read_dir(source)
+        .ok()
+        .and_then(|entries| {
+            entries.flatten().map(|e| e.path()).find(|p| {
+                let name = p.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+                name.starts_with("metadata.") && name.ends_with(".lua") && !name.ends_with(".old.lua")
+            })
+        })
+        .unwrap_or_else(|| source.to_path_buf())
+}
+
+fn combine_memo(highlight: &str, note: &str) -> String {
+    match (highlight.trim(), note.trim()) {
+        ("", note) => note.to_string(),
+        (highlight, "") => highlight.to_string(),
+        (highlight, note) => format!("{}\n{}", highlight, note),
+    }
+}
+
+/// Find the line containing `needle` (whitespace-normalized first 80 chars).
+fn locate_text(rope: &Rope, haystack: &str, needle: &str) -> Option<usize> {
+    let probe: String = needle.split_whitespace().collect::<Vec<_>>().join(" ");
+    let probe: String = probe.chars().take(80).collect();
+    if probe.chars().count() < 4 {
+        return None;
+    }
+    let byte = haystack.find(&probe).or_else(|| {
+        // 줄바꿈이 섞인 하이라이트는 첫 줄만으로 다시 찾는다
+        let first_line = needle.lines().map(str::trim).find(|l| l.chars().count() >= 4)?;
+        haystack.find(first_line)
+    })?;
+    Some(rope.byte_to_line(byte))
+}
+
+/// Map a reading progress fraction (0.0–1.0) onto the target's units.
+fn fraction_to_unit(fraction: f64, total_units: usize) -> usize {
+    let unit = (fraction.clamp(0.0, 1.0) * total_units as f64) as usize;
+    unit.min(total_units.saturating_sub(1))
+}
+
+// --- Calibre ---
+
+fn import_calibre(content: &str, target: &ImportTarget) -> anyhow::Result<ImportedData> {
+    let json: serde_json::Value = serde_json::from_str(content)?;
+    let annotations = json
+        .get("annotations")
+        .and_then(|a| a.as_array())
+        .or_else(|| json.as_array())
+        .ok_or_else(|| anyhow::anyhow!("Not a Calibre annotations file"))?;
+
+    let text = target.text.map(|r| r.to_string());
+    let mut data = ImportedData {
+        format: "calibre",
+        bookmarks: Vec::new(),
+        skipped: 0,
+    };
+    for annotation in annotations {
+        if annotation.get("removed").and_then(|v| v.as_bool()).unwrap_or(false) {
+            continue;
+        }
+        let field = |key: &str| annotation.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+        let highlight = field("highlighted_text");
+        let memo = match field("type") {
+            "bookmark" => field("title").to_string(),
+            _ => combine_memo(highlight, field("notes")),
+        };
+        let spine_index = annotation.get("spine_index").and_then(|v| v.as_u64()).map(|v| v as usize);
+
+        let unit = match (target.text, text.as_deref()) {
+            (Some(rope), Some(haystack)) => locate_text(rope, haystack, highlight),
+            _ => spine_index.filter(|&i| i < target.total_units.max(1)),
+        };
+        match unit {
+            Some(unit) => data.bookmarks.push(ImportedBookmark {
+                unit,
+                memo,
+                created: annotation.get("timestamp").and_then(|v| v.as_str()).map(String::from),
+            }),
+            None => data.skipped += 1,
+        }
+    }
+    Ok(data)
+}
+
+// --- KOReader ---
+
+#[derive(Debug, Clone)]
+enum LuaValue {
+    /// nil, booleans and anything else the importer doesn't need.
+    Nil,
+    Number(f64),
+    Str(String),
+    Table(BTreeMap<String, LuaValue>),
+}
+
+impl LuaValue {
+    fn get(&self, key: &str) -> Option<&LuaValue> {
+        match self {
+            LuaValue::Table(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            LuaValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            LuaValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Array-style entries ([1], [2], ...) in index order.
+    fn entries(&self) -> Vec<&LuaValue> {
+        let LuaValue::Table(map) = self else {
+            return Vec::new();
+        };
+        let mut items: Vec<(usize, &LuaValue)> = map
+            .iter()
+            .filter_map(|(k, v)| k.parse::<usize>().ok().map(|i| (i, v)))
+            .collect();
+        items.sort_by_key(|(i, _)| *i);
+        items.into_iter().map(|(_, v)| v).collect()
+    }
+}
+
+/// Minimal parser for the Lua table literals KOReader writes (`return { ... }`).
+struct LuaParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> LuaParser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            chars: source.chars().peekable(),
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        loop {
+            match self.chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some('-') => {
+                    // "--" comment to end of line
+                    let mut probe = self.chars.clone();
+                    probe.next();
+                    if probe.peek() != Some(&'-') {
+                        return;
+                    }
+                    for c in self.chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> anyhow::Result<()> {
+        self.skip_ws();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            other => anyhow::bail!("Invalid Lua table: expected '{}', found {:?}", expected, other),
+        }
+    }
+
+    fn parse_value(&mut self) -> anyhow::Result<LuaValue> {
+        self.skip_ws();
+        match self.chars.peek().copied() {
+            Some('{') => self.parse_table(),
+            Some(q @ ('"' | '\'')) => {
+                self.chars.next();
+                self.parse_string(q).map(LuaValue::Str)
+            }
+            Some(c) if c == '-' || c == '.' || c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.') {
+                        number.push(c);
+                        self.chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                number
+                    .parse()
+                    .map(LuaValue::Number)
+                    .map_err(|_| anyhow::anyhow!("Invalid Lua number: {}", number))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                self.parse_ident();
+                Ok(LuaValue::Nil)
+            }
+            other => anyhow::bail!("Invalid Lua value: {:?}", other),
+        }
+    }
+
+    fn parse_ident(&mut self) -> String {
+        let mut ident = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_alphanumeric() || c == '_' {
+                ident.push(c);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        ident
+    }
+
+    fn parse_string(&mut self, quote: char) -> anyhow::Result<String> {
+        let mut out = String::new();
+        while let Some(c) = self.chars.next() {
+            match c {
+                c if c == quote => return Ok(out),
+                '\\' => match self.chars.next() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some('\n') => out.push('\n'),
+                    Some(d) if d.is_ascii_digit() => {
+                        // \ddd decimal byte escape
+                        let mut code = d.to_digit(10).unwrap_or(0);
+                        for _ in 0..2 {
+                            match self.chars.peek().and_then(|c| c.to_digit(10)) {
+                                Some(digit) => {
+                                    code = code * 10 + digit;
+                                    self.chars.next();
+                                }
+                                None => break,
+                            }
+                        }
+                        out.push(char::from_u32(code).unwrap_or('?'));
+                    }
+                    Some(other) => out.push(other),
+                    None => break,
+                },
+                _ => out.push(c),
+            }
+        }
+        anyhow::bail!("Unterminated Lua string")
+    }
+
+    fn parse_table(&mut self) -> anyhow::Result<LuaValue> {
+        self.expect('{')?;
+        let mut map = BTreeMap::new();
+        let mut next_index = 1usize;
+        loop {
+            self.skip_ws();
+            match self.chars.peek().copied() {
+                Some('}') => {
+                    self.chars.next();
+                    return Ok(LuaValue::Table(map));
+                }
+                Some(',') | Some(';') => {
+                    self.chars.next();
+                }
+                Some('[') => {
+                    self.chars.next();
+                    let key = match self.parse_value()? {
+                        LuaValue::Str(s) => s,
+                        LuaValue::Number(n) => format!("{}", n),
+                        other => format!("{:?}", other),
+                    };
+                    self.expect(']')?;
+                    self.expect('=')?;
+                    map.insert(key, self.parse_value()?);
+                }
+                Some(c) if c.is_alphabetic() || c == '_' => {
+                    let mut probe = self.chars.clone();
+                    let ident: String = std::iter::from_fn(|| probe.next_if(|c| c.is_alphanumeric() || *c == '_')).collect();
+                    while probe.next_if(|c| c.is_whitespace()).is_some() {}
+                    if probe.peek() == Some(&'=') {
+                        self.parse_ident();
+                        self.expect('=')?;
+                        map.insert(ident, self.parse_value()?);
+                    } else {
+                        map.insert(next_index.to_string(), self.parse_value()?);
+                        next_index += 1;
+                    }
+                }
+                Some(_) => {
+                    map.insert(next_index.to_string(), self.parse_value()?);
+                    next_index += 1;
+                }
+                None => anyhow::bail!("Unterminated Lua table"),
+            }
+        }
+    }
+}
+
+fn parse_lua_metadata(content: &str) -> anyhow::Result<LuaValue> {
+    let start = content
+        .find('{')
+        .ok_or_else(|| anyhow::anyhow!("Not a KOReader metadata file"))?;
+    LuaParser::new(&content[start..]).parse_table()
+}
+
+/// KOReader datetime ("2024-01-31 21:05:00") → RFC 3339.
+fn koreader_datetime(value: Option<&LuaValue>) -> Option<String> {
+    let text = value?.as_str()?;
+    let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").ok()?;
+    Local.from_local_datetime(&naive).single().map(|dt| dt.to_rfc3339())
+}
+
+/// Paragraph index from a crengine xpointer ("/body/DocFragment/body/p[42]/text().3").
+fn xpointer_paragraph(xpointer: &str) -> Option<usize> {
+    let caps = XPOINTER_PARAGRAPH_RE.captures_iter(xpointer).last()?;
+    caps[1].parse::<usize>().ok().map(|p| p.saturating_sub(1))
+}
+
+/// Chapter index from an xpointer ("/body/DocFragment[5]/...").
+fn xpointer_fragment(xpointer: &str) -> Option<usize> {
+    let caps = XPOINTER_FRAGMENT_RE.captures(xpointer)?;
+    caps[1].parse::<usize>().ok().map(|p| p.saturating_sub(1))
+}
+
+fn import_koreader(content: &str, target: &ImportTarget) -> anyhow::Result<ImportedData> {
+    let metadata = parse_lua_metadata(content)?;
+    let total_pages = metadata
+        .get("doc_pages")
+        .or_else(|| metadata.get("stats").and_then(|s| s.get("pages")))
+        .and_then(LuaValue::as_f64)
+        .unwrap_or(0.0);
+    let text = target.text.map(|r| r.to_string());
+
+    // 새 형식은 "annotations", 예전 형식은 "bookmarks" (+ "highlight")
+    let (entries, highlight_key, note_key) = match metadata.get("annotations") {
+        Some(annotations) => (annotations.entries(), "text", "note"),
+        None => (
+            metadata.get("bookmarks").map(LuaValue::entries).unwrap_or_default(),
+            "notes",
+            "text",
+        ),
+    };
+
+    let mut data = ImportedData {
+        format: "koreader",
+        bookmarks: Vec::new(),
+        skipped: 0,
+    };
+    for entry in entries {
+        let field = |key: &str| entry.get(key).and_then(LuaValue::as_str).unwrap_or_default();
+        let highlight = field(highlight_key);
+        let note = field(note_key);
+        // 예전 형식의 자동 생성 문구("Page 12 ...")는 메모로 쓰지 않는다
+        let note = if note.starts_with("Page ") { "" } else { note };
+        let memo = combine_memo(highlight, note);
+
+        let page = entry.get("page").or_else(|| entry.get("pos0"));
+        let unit = match (target.text, text.as_deref()) {
+            (Some(rope), Some(haystack)) => locate_text(rope, haystack, highlight)
+                .or_else(|| page.and_then(LuaValue::as_str).and_then(xpointer_paragraph)),
+            // 페이지 번호는 KOReader의 전체 페이지 수 기준 비율로 맞춘다
+            _ => page.and_then(|p| match p {
+                LuaValue::Str(xpointer) => xpointer_fragment(xpointer),
+                LuaValue::Number(n) if total_pages > 0.0 => {
+                    Some(fraction_to_unit((n - 1.0).max(0.0) / total_pages, target.total_units))
+                }
+                LuaValue::Number(n) => Some((*n as usize).saturating_sub(1)),
+                _ => None,
+            }),
+        };
+
+        match unit.filter(|&u| u < target.total_units.max(1)) {
+            Some(unit) => data.bookmarks.push(ImportedBookmark {
+                unit,
+                memo,
+                created: koreader_datetime(entry.get("datetime")),
+            }),
+            None => data.skipped += 1,
+        }
+    }
+    Ok(data)
+}
+
+// --- Moon+ Reader ---
+
+/// `.po` 파일: "<timestamp ms>*<chapter>@<split>#<offset>:<percent>%" (퍼센트는 항상 0–100)
+fn import_moon_reader(content: &str, target: &ImportTarget) -> anyhow::Result<ImportedData> {
+    let content = content.trim();
+    let invalid = || anyhow::anyhow!("Not a Moon+ Reader position file");
+    let (timestamp, rest) = content.split_once('*').ok_or_else(invalid)?;
+    let (chapter, rest) = rest.split_once('@').ok_or_else(invalid)?;
+    let percent = rest
+        .split_once(':')
+        .map(|(_, p)| p.trim_end_matches('%'))
+        .and_then(|p| p.parse::<f64>().ok())
+        .ok_or_else(invalid)?;
+    let chapter: usize = chapter.parse().map_err(|_| invalid())?;
+
+    let unit = if target.text.is_some() || chapter == 0 {
+        fraction_to_unit(percent / 100.0, target.total_units)
+    } else {
+        chapter.min(target.total_units.saturating_sub(1))
+    };
+    let created = timestamp
+        .parse::<i64>()
+        .ok()
+        .and_then(|ms| Local.timestamp_millis_opt(ms).single())
+        .map(|dt| dt.to_rfc3339());
+
+    Ok(ImportedData {
+        format: "moon+",
+        bookmarks: vec![ImportedBookmark {
+            unit,
+            memo: format!("Moon+ Reader 읽던 위치 ({:.1}%)", percent),
+            created,
+        }],
+        skipped: 0,
+    })
+}
@@ -0,0 +1,63 @@
+//! Chapter heading detection for plain-text files (webnovel dumps etc.).
+//!
+//! "제1화", "1화 시작", "< 12화 >", "Chapter 3", "第三章", "프롤로그", "# 제목" 같은
+//! 짧은 줄을 챕터 제목으로 본다.
+
+use regex::Regex;
+use ropey::Rope;
+use serde::Serialize;
+use std::sync::LazyLock;
+
+/// Headings longer than this (in chars) are treated as body text.
+const MAX_HEADING_CHARS: usize = 60;
+
+static HEADING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?ix)^(?:
+            (?:제\s*)?\d+\s*(?:화|장|편|권|부|회|막)(?:\s|$|[.:\-)\]>】〉》]).*
+          | (?:프롤로그|에필로그|외전|서장|종장|후기|작가의\s*말|prologue|epilogue|interlude)\b.*
+          | (?:chapter|chap\.|episode|ep\.|part)\s*(?:\d+|[ivxlc]+)\b.*
+          | 第\s*[0-9〇零一二三四五六七八九十百千]+\s*[章回話话節节卷部].*
+          | \#{1,3}\s+\S.*
+        )$",
+    )
+    .unwrap()
+});
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TextChapter {
+    /// First line of the chapter (the heading line).
+    pub line: usize,
+    pub title: String,
+}
+
+/// Strip decoration around headings: "< 1화 >", "[1화]", "【1화】", "=== 1화 ===".
+fn strip_heading_decoration(line: &str) -> &str {
+    line.trim()
+        .trim_matches(|c: char| "<>[]()〈〉《》【】「」『』=-*~ ".contains(c))
+        .trim()
+}
+
+pub fn is_heading(line: &str) -> bool {
+    let core = strip_heading_decoration(line);
+    !core.is_empty() && core.chars().count() <= MAX_HEADING_CHARS && HEADING_RE.is_match(core)
+}
+
+/// Detect chapter headings, in document order.
+pub fn detect_chapters(rope: &Rope) -> Vec<TextChapter> {
+    let mut chapters = Vec::new();
+    for (idx, line) in rope.lines().enumerate() {
+        // 긴 줄은 문자열로 만들지 않고 건너뛴다
+        if line.len_chars() > MAX_HEADING_CHARS * 2 {
+            continue;
+        }
+        let text = line.to_string();
+        if is_heading(&text) {
+            chapters.push(TextChapter {
+                line: idx,
+                title: strip_heading_decoration(&text).trim_start_matches('#').trim().to_string(),
+            });
+        }
+    }
+    chapters
+}
@@ -0,0 +1,144 @@
+use crate::AppState;
+use serde::Serialize;
+use tauri::{command, AppHandle, Emitter};
+
+/// Payload of `archive-progress` events.
+#[derive(Clone, Serialize)]
+struct ArchiveProgress {
+    /// "pack" (folder to CBZ) or "extract".
+    operation: &'static str,
+    source: String,
+    done: usize,
+    total: usize,
+    name: String,
+}
+
+fn emit_progress(app: &AppHandle, operation: &'static str, source: &str, done: usize, total: usize, name: &str) {
+    let _ = app.emit(
+        "archive-progress",
+        ArchiveProgress {
+            operation,
+            source: source.to_string(),
+            done,
+            total,
+            name: name.to_string(),
+        },
+    );
+}
+
+/// Pack a folder's images (natural sort order, uncompressed) into a CBZ at `output_path`.
+/// Progress is reported with `archive-progress` events. Returns the number of images.
+#[command]
+pub async fn archive_folder_to_cbz(
+    dir_path: String,
+    output_path: String,
+    app: AppHandle,
+) -> Result<usize, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::archive_tools::folder_to_cbz(
+            std::path::Path::new(&dir_path),
+            std::path::Path::new(&output_path),
+            |done, total, name| emit_progress(&app, "pack", &dir_path, done, total, name),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Unpack the open ZIP/CBZ tab into `output_dir` (existing files are kept; clashing
+/// names get a " (2)" suffix). Progress is reported with `archive-progress` events.
+/// Returns the number of files written.
+#[command]
+pub async fn extract_archive(
+    file_id: String,
+    output_dir: String,
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    let source = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    let ext = source
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !matches!(ext.as_str(), "zip" | "cbz") {
+        return Err(format!("Only ZIP/CBZ archives can be extracted: {}", source.display()));
+    }
+    let password = {
+        let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+        store.get_archive_password(&file_id)
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::archive_tools::extract_zip(
+            &source,
+            std::path::Path::new(&output_dir),
+            password.as_deref(),
+            |done, total, name| emit_progress(&app, "extract", &file_id, done, total, name),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Check whether a password opens an archive (without saving it).
+#[command]
+pub async fn test_archive_password(
+    file_path: String,
+    password: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, String> {
+    let config = state.config.read().map_err(|e| e.to_string())?;
+    crate::archive_password::test_password(std::path::Path::new(&file_path), &password, &config)
+        .map_err(|e| e.to_string())
+}
+
+/// Verify and save an archive password. Open tabs of the archive start using it immediately.
+#[command]
+pub async fn save_archive_password(
+    file_path: String,
+    password: String,
+    use_keyring: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let ok = {
+        let config = state.config.read().map_err(|e| e.to_string())?;
+        crate::archive_password::test_password(std::path::Path::new(&file_path), &password, &config)
+            .map_err(|e| e.to_string())?
+    };
+    if !ok {
+        return Err(crate::error::AppError::WrongPassword(file_path).into());
+    }
+    {
+        let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+        store
+            .save_archive_password(&file_path, &password, use_keyring)
+            .map_err(|e| e.to_string())?;
+    }
+    state.image_cache.set_password(&file_path, Some(password));
+    Ok(())
+}
+
+#[command]
+pub async fn clear_archive_password(
+    file_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    store
+        .clear_archive_password(&file_path)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn has_archive_password(
+    file_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, String> {
+    let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    Ok(store.get_archive_password(&file_path).is_some())
+}
@@ -0,0 +1,47 @@
+use crate::backup::{self, BackupManifest};
+use crate::config::AppConfig;
+use crate::AppState;
+use tauri::{command, AppHandle};
+
+#[command]
+pub async fn backup_app_data(
+    target_zip: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<BackupManifest, String> {
+    // 메모리에만 있는 통계를 먼저 저장
+    if let Ok(mut stats) = state.reading_stats.lock() {
+        stats.flush();
+    }
+    backup::backup_to(std::path::Path::new(&target_zip)).map_err(|e| e.to_string())
+}
+
+/// Restore from a backup and reload the in-memory stores and config.
+#[command]
+pub async fn restore_app_data(
+    source_zip: String,
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<BackupManifest, String> {
+    if let Ok(mut stats) = state.reading_stats.lock() {
+        stats.flush();
+    }
+    let manifest =
+        backup::restore_from(std::path::Path::new(&source_zip)).map_err(|e| e.to_string())?;
+
+    {
+        let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+        *store = crate::bookmark::BookmarkStore::new().map_err(|e| e.to_string())?;
+    }
+    {
+        let mut stats = state.reading_stats.lock().map_err(|e| e.to_string())?;
+        *stats = crate::reading_stats::ReadingStats::load();
+    }
+
+    // 복원된 설정 파일로 바꾼다
+    let config = AppConfig::load().map_err(|e| e.to_string())?;
+    *state.config.write().map_err(|e| e.to_string())? = config.clone();
+    if let Err(e) = crate::apply_runtime_config(&config, &state, &app) {
+        log::warn!("Failed to apply restored settings: {}", e);
+    }
+    Ok(manifest)
+}
@@ -1,6 +1,10 @@
-use crate::bookmark::{Bookmark, BookmarkSearchResult, FileBookmarks, FileListEntry};
+use crate::bookmark::{Bookmark, BookmarkSearchResult, EpubHighlight, FileBookmarks, FileListEntry, PdfViewState, ReadingDirection};
+use crate::bookmark_import::{self, ImportTarget};
+use crate::text_buffer::TextBuffer;
 use crate::AppState;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::path::Path;
 use tauri::command;
 
 #[command]
@@ -68,6 +72,63 @@ pub async fn get_bookmarks(
     Ok(store.get_bookmarks(&file_path))
 }
 
+/// Save a highlight of selected EPUB text. Returns its id.
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn add_epub_highlight(
+    file_path: String,
+    chapter_index: usize,
+    selector: Option<String>,
+    start_offset: usize,
+    end_offset: usize,
+    text: String,
+    color: String,
+    note: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    if start_offset >= end_offset {
+        return Err("Empty highlight range".to_string());
+    }
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    store
+        .add_highlight(
+            &file_path,
+            EpubHighlight {
+                id: String::new(),
+                chapter_index,
+                selector,
+                start_offset,
+                end_offset,
+                text,
+                color,
+                note: note.unwrap_or_default(),
+                created: chrono::Local::now().to_rfc3339(),
+            },
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn remove_epub_highlight(
+    file_path: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    store
+        .remove_highlight(&file_path, &id)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn get_epub_highlights(
+    file_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<EpubHighlight>, String> {
+    let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    Ok(store.get_highlights(&file_path))
+}
+
 #[command]
 pub async fn get_all_bookmarks(
     state: tauri::State<'_, AppState>,
@@ -138,11 +199,59 @@ pub async fn save_last_position(
     file_path: String,
     position: usize,
     scroll_offset: Option<usize>,
+    window: tauri::Window,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
+    let scroll_offset = scroll_offset.unwrap_or(0);
+    if let Ok(mut stats) = state.reading_stats.lock() {
+        stats.record_activity(&file_path, position);
+    }
+    // 열린 탭의 위치도 갱신해서, 닫을 때 다른 창의 오래된 위치로 덮어쓰지 않게 한다
+    if let Ok(mut tab_manager) = state.tab_manager.lock() {
+        tab_manager.set_last_position(&file_path, position, scroll_offset);
+    }
+    {
+        let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+        store
+            .save_last_position(&file_path, position, scroll_offset)
+            .map_err(|e| e.to_string())?;
+    }
+    crate::sync::emit_position_changed(&window, &file_path, position, scroll_offset);
+    Ok(())
+}
+
+/// Remember the zoom / fit mode of a PDF (its page is saved with `save_last_position`).
+#[command]
+pub async fn save_pdf_view(
+    file_path: String,
+    zoom: f64,
+    fit_mode: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if !zoom.is_finite() || zoom <= 0.0 {
+        return Err(format!("Invalid zoom: {}", zoom));
+    }
+    let view = PdfViewState { zoom, fit_mode };
+    if let Ok(mut tab_manager) = state.tab_manager.lock() {
+        tab_manager.set_pdf_view(&file_path, Some(view.clone()));
+    }
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    store.save_pdf_view(&file_path, view).map_err(|e| e.to_string())
+}
+
+/// Set the page direction of a file ("ltr" or "rtl").
+#[command]
+pub async fn save_reading_direction(
+    file_path: String,
+    direction: ReadingDirection,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if let Ok(mut tab_manager) = state.tab_manager.lock() {
+        tab_manager.set_reading_direction(&file_path, direction);
+    }
     let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
     store
-        .save_last_position(&file_path, position, scroll_offset.unwrap_or(0))
+        .save_reading_direction(&file_path, direction)
         .map_err(|e| e.to_string())
 }
 
@@ -166,3 +275,103 @@ pub async fn get_format_type(
     let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
     Ok(store.get_format_type(&file_path))
 }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BookmarkImportSummary {
+    pub format: String,
+    pub imported: usize,
+    /// Already present or not mappable to a position in this book.
+    pub skipped: usize,
+}
+
+/// Import bookmarks/highlights exported by another reader (Calibre JSON,
+/// KOReader `.sdr` metadata, Moon+ Reader `.po`) into `file_path`.
+#[command]
+pub async fn import_bookmarks(
+    file_path: String,
+    source_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<BookmarkImportSummary, String> {
+    let is_text = crate::reading_stats::unit_for_path(&file_path) == "line";
+
+    // 열린 탭에서 필요한 것만 복사하고, 파일 읽기/파싱은 잠금을 놓은 뒤에 한다
+    let (open_rope, open_units) = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        if is_text {
+            match tab_manager.get_buffer(&file_path) {
+                // 읽기 전용(대용량) 버퍼는 로프가 없으니 줄 수만 쓴다
+                Ok(buffer) if buffer.is_read_only() => (None, buffer.get_total_lines()),
+                Ok(buffer) => (Some(buffer.rope().clone()), 0),
+                Err(_) => (None, 0),
+            }
+        } else {
+            let units = match tab_manager.get_epub_chapter_infos(&file_path) {
+                Ok(chapters) => chapters.len(),
+                Err(_) => tab_manager.get_image_count(&file_path),
+            };
+            (None, units)
+        }
+    };
+    let (rope, total_units) = match open_rope {
+        Some(rope) => {
+            let lines = rope.len_lines();
+            (Some(rope), lines)
+        }
+        None if open_units > 0 => (None, open_units),
+        None if is_text => {
+            let buffer = TextBuffer::from_file(Path::new(&file_path)).map_err(|e| e.to_string())?;
+            if buffer.is_read_only() {
+                (None, buffer.get_total_lines())
+            } else {
+                (Some(buffer.rope().clone()), buffer.get_total_lines())
+            }
+        }
+        None => {
+            let mutool_path = state.config.read().map_err(|e| e.to_string())?.mutool_path.clone();
+            (None, closed_book_units(Path::new(&file_path), &mutool_path))
+        }
+    };
+    let target = ImportTarget {
+        text: rope.as_ref(),
+        total_units,
+    };
+    let data = bookmark_import::import_file(Path::new(&source_path), &target).map_err(|e| e.to_string())?;
+
+    let now = chrono::Local::now().to_rfc3339();
+    let total = data.bookmarks.len();
+    let bookmarks: Vec<Bookmark> = data
+        .bookmarks
+        .into_iter()
+        .map(|b| Bookmark {
+            position: b.unit,
+            line: b.unit,
+            memo: b.memo,
+            created: b.created.unwrap_or_else(|| now.clone()),
+        })
+        .collect();
+
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    let imported = store
+        .import_bookmarks(&file_path, bookmarks)
+        .map_err(|e| e.to_string())?;
+    Ok(BookmarkImportSummary {
+        format: data.format.to_string(),
+        imported,
+        skipped: data.skipped + (total - imported),
+    })
+}
+
+/// Chapter or page count of a book that is not open in a tab (0 if it can't be determined).
+fn closed_book_units(path: &Path, mutool_path: &str) -> usize {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "epub" => crate::epub_reader::parse_epub(path)
+            .map(|book| book.chapters.len())
+            .unwrap_or(0),
+        "pdf" => crate::pdf_render::page_count(path, mutool_path).unwrap_or(0),
+        _ => crate::image_reader::volume_page_count(path).unwrap_or(0),
+    }
+}
@@ -0,0 +1,76 @@
+use crate::AppState;
+use tauri::{command, AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Copy a char range of a text file. With `with_citation`, a "file, line N" reference
+/// (the same position a bookmark would record) is appended on its own line.
+#[command]
+pub async fn copy_text_range(
+    file_id: String,
+    start_char: usize,
+    end_char: usize,
+    with_citation: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let text = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        let rope = tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?;
+        let end = end_char.min(rope.len_chars());
+        let start = start_char.min(end);
+        let mut text = rope.slice(start..end).to_string();
+
+        if with_citation {
+            let path = tab_manager.get_file_path(&file_id).map_err(|e| e.to_string())?;
+            let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let line = rope.char_to_line(start) + 1;
+            text.push_str(&format!("\n— {}, line {}", file_name, line));
+        }
+        text
+    };
+
+    app.clipboard()
+        .write_text(text.clone())
+        .map_err(|e| e.to_string())?;
+    Ok(text)
+}
+
+/// Copy an EPUB chapter as plain text.
+#[command]
+pub async fn copy_epub_chapter_text(
+    file_id: String,
+    chapter_index: usize,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let text = {
+        let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager
+            .get_epub_chapter_text(&file_id, chapter_index)
+            .map_err(|e| e.to_string())?
+    };
+    app.clipboard().write_text(text).map_err(|e| e.to_string())
+}
+
+/// Copy an image (decoded to RGBA) to the clipboard.
+#[command]
+pub async fn copy_image_to_clipboard(
+    file_id: String,
+    index: usize,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let bytes = state
+        .image_cache
+        .read_image(&file_id, index)
+        .map_err(|e| e.to_string())?;
+    let rgba = image::load_from_memory(&bytes)
+        .map_err(|e| e.to_string())?
+        .into_rgba8();
+    let (width, height) = rgba.dimensions();
+    let image = tauri::image::Image::new_owned(rgba.into_raw(), width, height);
+    app.clipboard().write_image(&image).map_err(|e| e.to_string())
+}
@@ -1,12 +1,20 @@
 use crate::config::AppConfig;
-use tauri::command;
+use crate::AppState;
+use tauri::{command, AppHandle};
 
 #[command]
-pub async fn get_config() -> Result<AppConfig, String> {
-    AppConfig::load().map_err(|e| e.to_string())
+pub async fn get_config(state: tauri::State<'_, AppState>) -> Result<AppConfig, String> {
+    let config = state.config.read().map_err(|e| e.to_string())?;
+    Ok(config.clone())
 }
 
 #[command]
-pub async fn save_config(config: AppConfig) -> Result<(), String> {
-    config.save().map_err(|e| e.to_string())
+pub async fn save_config(
+    config: AppConfig,
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    config.save().map_err(|e| e.to_string())?;
+    *state.config.write().map_err(|e| e.to_string())? = config.clone();
+    crate::apply_runtime_config(&config, &state, &app).map_err(|e| e.to_string())
 }
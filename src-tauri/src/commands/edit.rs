@@ -1,3 +1,4 @@
+use crate::text_buffer::ColumnUnit;
 use crate::AppState;
 use tauri::command;
 
@@ -6,6 +7,7 @@ pub async fn insert_text(
     file_id: String,
     position: usize,
     text: String,
+    window: tauri::Window,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
     let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
@@ -16,6 +18,7 @@ pub async fn insert_text(
         buffer.insert_text(position, &text);
     }
     tab_manager.set_modified(&file_id, true);
+    crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
     Ok(())
 }
 
@@ -24,6 +27,7 @@ pub async fn replace_line(
     file_id: String,
     line_index: usize,
     new_text: String,
+    window: tauri::Window,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
     let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
@@ -36,6 +40,7 @@ pub async fn replace_line(
         }
     }
     tab_manager.set_modified(&file_id, true);
+    crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
     Ok(())
 }
 
@@ -44,6 +49,7 @@ pub async fn delete_text(
     file_id: String,
     start: usize,
     end: usize,
+    window: tauri::Window,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
     let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
@@ -54,12 +60,69 @@ pub async fn delete_text(
         buffer.delete_text(start, end);
     }
     tab_manager.set_modified(&file_id, true);
+    crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
+    Ok(())
+}
+
+/// Insert at a line/column position. `column_unit`: "utf16" (default, JS string indices) or "char".
+#[command]
+pub async fn insert_at(
+    file_id: String,
+    line: usize,
+    column: usize,
+    text: String,
+    column_unit: Option<ColumnUnit>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    {
+        let buffer = tab_manager
+            .get_buffer_mut(&file_id)
+            .map_err(|e| e.to_string())?;
+        if !buffer.insert_at(line, column, column_unit.unwrap_or_default(), &text) {
+            return Err(format!("Line index out of range: {}", line));
+        }
+    }
+    tab_manager.set_modified(&file_id, true);
+    crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
+    Ok(())
+}
+
+/// Delete between two line/column positions (end exclusive); columns as in `insert_at`.
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn delete_range(
+    file_id: String,
+    start_line: usize,
+    start_col: usize,
+    end_line: usize,
+    end_col: usize,
+    column_unit: Option<ColumnUnit>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    {
+        let buffer = tab_manager
+            .get_buffer_mut(&file_id)
+            .map_err(|e| e.to_string())?;
+        if !buffer.delete_range(start_line, start_col, end_line, end_col, column_unit.unwrap_or_default()) {
+            return Err(format!(
+                "Invalid range: {}:{} - {}:{}",
+                start_line, start_col, end_line, end_col
+            ));
+        }
+    }
+    tab_manager.set_modified(&file_id, true);
+    crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
     Ok(())
 }
 
 #[command]
 pub async fn undo(
     file_id: String,
+    window: tauri::Window,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
     let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
@@ -73,12 +136,14 @@ pub async fn undo(
         buffer.is_modified
     };
     tab_manager.set_modified(&file_id, is_modified);
+    crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
     Ok(())
 }
 
 #[command]
 pub async fn redo(
     file_id: String,
+    window: tauri::Window,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
     let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
@@ -92,5 +157,35 @@ pub async fn redo(
         buffer.is_modified
     };
     tab_manager.set_modified(&file_id, is_modified);
+    crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
+    Ok(())
+}
+
+/// Start an undo group: edits until `end_edit_group` are undone with a single undo.
+#[command]
+pub async fn begin_edit_group(
+    file_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    let buffer = tab_manager
+        .get_buffer_mut(&file_id)
+        .map_err(|e| e.to_string())?;
+    buffer.begin_group();
+    Ok(())
+}
+
+#[command]
+pub async fn end_edit_group(
+    file_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    let buffer = tab_manager
+        .get_buffer_mut(&file_id)
+        .map_err(|e| e.to_string())?;
+    if !buffer.end_group() {
+        return Err("No edit group is open".to_string());
+    }
     Ok(())
 }
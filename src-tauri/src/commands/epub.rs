@@ -1,6 +1,10 @@
-use crate::epub_reader::ChapterInfo;
+use crate::config::EpubBookStyle;
+use crate::epub_reader::{ChapterInfo, ChapterRenderJob, EpubLinkTarget, EpubMetadata};
+use crate::epub_search::{self, EpubSearchMatch};
+use crate::search::{Matcher, SearchOptions};
+use crate::tab_manager::FileInfo;
 use crate::AppState;
-use tauri::command;
+use tauri::{command, Manager};
 
 #[command]
 pub async fn get_epub_chapters(
@@ -17,13 +21,56 @@ pub async fn get_epub_chapters(
 pub async fn get_epub_chapter(
     file_id: String,
     chapter_index: usize,
+    window: tauri::Window,
     state: tauri::State<'_, AppState>,
 ) -> Result<String, String> {
-    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
-    tab_manager.set_last_position(&file_id, chapter_index, 0);
-    tab_manager
-        .get_epub_chapter_html(&file_id, chapter_index)
-        .map_err(|e| e.to_string())
+    let html = {
+        let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.set_last_position(&file_id, chapter_index, 0);
+        let html = tab_manager
+            .get_epub_chapter_html(&file_id, chapter_index)
+            .map_err(|e| e.to_string())?;
+        state.memory_budget.enforce(&mut tab_manager, &state.image_cache);
+        prefetch_chapters(&window, &file_id, tab_manager.epub_prefetch_jobs(&file_id, chapter_index));
+        html
+    };
+    let (user_css, disable_publisher_styles, block_remote_images) = {
+        let config = state.config.read().map_err(|e| e.to_string())?;
+        let (css, disable) = config.epub_styles_for(&file_id);
+        (css, disable, config.epub_block_remote_images)
+    };
+    let mut html = crate::epub_reader::apply_user_styles(&html, &user_css, disable_publisher_styles);
+    if block_remote_images {
+        html = crate::epub_sanitize::block_remote_resources(&html);
+    }
+    // 저장된 하이라이트는 캐시된 HTML이 아니라 내보낼 때마다 입힌다
+    let highlights = state
+        .bookmark_store
+        .lock()
+        .map(|store| store.get_highlights(&file_id))
+        .unwrap_or_default();
+    let html = crate::epub_highlights::inject_highlights(&html, chapter_index, &highlights);
+    if let Ok(mut stats) = state.reading_stats.lock() {
+        stats.record_activity(&file_id, chapter_index);
+    }
+    crate::sync::emit_position_changed(&window, &file_id, chapter_index, 0);
+    Ok(html)
+}
+
+/// Render neighbouring chapters in the background so the next page turn hits the cache
+/// (like `ImageCacheManager::prefetch` for comics).
+fn prefetch_chapters(window: &tauri::Window, file_id: &str, jobs: Vec<ChapterRenderJob>) {
+    for job in jobs {
+        let app = window.app_handle().clone();
+        let file_id = file_id.to_string();
+        tauri::async_runtime::spawn_blocking(move || {
+            let html = job.render();
+            let state = app.state::<AppState>();
+            if let Ok(mut tab_manager) = state.tab_manager.lock() {
+                tab_manager.store_prefetched_chapter(&file_id, &job, html);
+            };
+        });
+    }
 }
 
 #[command]
@@ -31,8 +78,133 @@ pub async fn get_epub_font_styles(
     file_id: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<String, String> {
-    let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
     tab_manager
         .get_epub_font_styles(&file_id)
         .map_err(|e| e.to_string())
 }
+
+/// Language, page direction and accessibility metadata of the book (for `lang`/`dir`,
+/// hyphenation and font fallback in the reader).
+#[command]
+pub async fn get_epub_metadata(
+    file_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<EpubMetadata, String> {
+    let path = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    crate::epub_reader::read_epub_metadata(&path).map_err(|e| e.to_string())
+}
+
+/// Search the text of every chapter (tags stripped). Chapters don't need to be rendered first.
+#[command]
+pub async fn search_epub(
+    file_id: String,
+    query: String,
+    case_sensitive: Option<bool>,
+    whole_word: Option<bool>,
+    regex: Option<bool>,
+    ignore_diacritics: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<EpubSearchMatch>, String> {
+    let options = SearchOptions {
+        case_sensitive: case_sensitive.unwrap_or(false),
+        whole_word: whole_word.unwrap_or(false),
+        regex: regex.unwrap_or(false),
+        ignore_diacritics: ignore_diacritics.unwrap_or(false),
+    };
+    let matcher = Matcher::new(&query, options).map_err(|e| e.to_string())?;
+    let chapters = {
+        let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_epub_chapters(&file_id).map_err(|e| e.to_string())?
+    };
+    tauri::async_runtime::spawn_blocking(move || epub_search::search_chapters(&chapters, &matcher))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Target of a link clicked in a chapter (`epublink:` URL, or a raw href relative to
+/// `from_chapter`).
+#[command]
+pub async fn resolve_epub_link(
+    file_id: String,
+    href: String,
+    from_chapter: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<EpubLinkTarget, String> {
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    tab_manager
+        .resolve_epub_link(&file_id, &href, from_chapter)
+        .map_err(|e| e.to_string())
+}
+
+/// Whole-book progress (0–100) at `scroll_fraction` through `chapter_index`, weighted by
+/// chapter length. Also recorded in the bookmark store for the library list.
+#[command]
+pub async fn get_epub_progress(
+    file_id: String,
+    chapter_index: usize,
+    scroll_fraction: f64,
+    state: tauri::State<'_, AppState>,
+) -> Result<f64, String> {
+    let chapters = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager
+            .get_epub_chapter_infos(&file_id)
+            .map_err(|e| e.to_string())?
+    };
+    let percent = crate::epub_reader::book_progress(&chapters, chapter_index, scroll_fraction);
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    store
+        .save_progress(&file_id, percent)
+        .map_err(|e| e.to_string())?;
+    Ok(percent)
+}
+
+#[command]
+pub async fn get_epub_book_style(
+    file_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<EpubBookStyle, String> {
+    let config = state.config.read().map_err(|e| e.to_string())?;
+    Ok(config.epub_book_styles.get(&file_path).cloned().unwrap_or_default())
+}
+
+/// Set the per-book user CSS and publisher-style toggle (applied on the next chapter load).
+#[command]
+pub async fn set_epub_book_style(
+    file_path: String,
+    css: String,
+    disable_publisher_styles: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut config = state.config.write().map_err(|e| e.to_string())?;
+    if css.trim().is_empty() && disable_publisher_styles.is_none() {
+        config.epub_book_styles.remove(&file_path);
+    } else {
+        config.epub_book_styles.insert(
+            file_path,
+            EpubBookStyle {
+                css,
+                disable_publisher_styles,
+            },
+        );
+    }
+    config.save().map_err(|e| e.to_string())
+}
+
+/// Open a chapter's XHTML in an editable text tab. The usual edit/undo commands work on
+/// the returned tab id, and `save_file` writes the chapter back into the .epub.
+#[command]
+pub async fn open_epub_chapter_editor(
+    file_id: String,
+    chapter_index: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<FileInfo, String> {
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    tab_manager
+        .open_epub_chapter_editor(&file_id, chapter_index)
+        .map_err(|e| e.to_string())
+}
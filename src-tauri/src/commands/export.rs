@@ -0,0 +1,251 @@
+use crate::exporter::{self, AnkiCard, AnkiDeckOptions, ExportOptions, TextToEpubOptions};
+use crate::tab_manager::FileInfo;
+use crate::text_buffer::TextBuffer;
+use crate::AppState;
+use tauri::command;
+
+/// Export the current text (with the saved or requested formatting) to "html" or "pdf".
+/// Returns the written file path.
+#[command]
+pub async fn export_document(
+    file_id: String,
+    format: String,
+    mut options: ExportOptions,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let (text, file_path) = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        let rope = tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?;
+        let path = tab_manager.get_file_path(&file_id).map_err(|e| e.to_string())?;
+        (rope.to_string(), path)
+    };
+
+    if options.format_type.is_none() {
+        let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+        options.format_type = store.get_format_type(&file_path.to_string_lossy());
+    }
+
+    let title = file_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let config = state.config.read().map_err(|e| e.to_string())?.clone();
+    // PDF 변환은 외부 브라우저 프로세스를 기다리므로 blocking 스레드에서 실행
+    tauri::async_runtime::spawn_blocking(move || {
+        exporter::export_text(&text, &title, &format, &options, &config)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map(|p| p.to_string_lossy().to_string())
+    .map_err(|e| e.to_string())
+}
+
+/// Write an EPUB's text (tags stripped, chapter headings kept) to a UTF-8 .txt file.
+/// With `open_in_tab`, the written file is opened as a text tab for editing.
+#[command]
+pub async fn export_epub_text(
+    file_id: String,
+    output_path: String,
+    open_in_tab: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<FileInfo>, String> {
+    let text = {
+        let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager
+            .get_epub_plain_text(&file_id)
+            .map_err(|e| e.to_string())?
+    };
+    std::fs::write(&output_path, text).map_err(|e| e.to_string())?;
+    if open_in_tab.unwrap_or(false) {
+        crate::commands::file::open_file_inner(&output_path, &state).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Export a file's bookmarks (passage + memo) as an Anki flashcard TSV.
+/// Returns the written file path.
+#[command]
+pub async fn export_highlights_to_anki(
+    file_path: String,
+    deck_options: AnkiDeckOptions,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let bookmarks = {
+        let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+        store.get_bookmarks(&file_path)
+    };
+    if bookmarks.is_empty() {
+        return Err(format!("No bookmarks to export for {}", file_path));
+    }
+
+    let path = std::path::Path::new(&file_path);
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let is_text = crate::reading_stats::unit_for_path(&file_path) == "line";
+
+    // 텍스트 파일은 열린 버퍼(수정 내용 포함)를 우선 사용
+    let passage_lines: Option<Vec<String>> = if is_text {
+        let lines = |buffer: &TextBuffer| {
+            bookmarks
+                .iter()
+                .map(|b| {
+                    buffer
+                        .get_chunk(b.line, b.line + 1 + deck_options.context_lines)
+                        .join("")
+                })
+                .collect::<Vec<String>>()
+        };
+        let open_lines = {
+            let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+            tab_manager.get_buffer(&file_path).ok().map(lines)
+        };
+        match open_lines {
+            Some(open_lines) => Some(open_lines),
+            None => {
+                // 열려 있지 않으면 잠금을 놓은 뒤 파일을 읽는다
+                let buffer = TextBuffer::from_file(path).map_err(|e| e.to_string())?;
+                Some(lines(&buffer))
+            }
+        }
+    } else {
+        None
+    };
+
+    let cards: Vec<AnkiCard> = bookmarks
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let location = if is_text {
+                format!("{}, line {}", file_name, b.line + 1)
+            } else {
+                format!(
+                    "{}, {} {}",
+                    file_name,
+                    crate::reading_stats::unit_for_path(&file_path),
+                    b.position + 1
+                )
+            };
+            let passage = passage_lines
+                .as_ref()
+                .and_then(|lines| lines.get(i).cloned())
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or_else(|| location.clone());
+            AnkiCard {
+                passage,
+                memo: b.memo.clone(),
+                location,
+            }
+        })
+        .collect();
+
+    let deck_name = deck_options.deck_name.clone().unwrap_or_else(|| {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+    exporter::write_anki_tsv(&cards, &deck_name, &deck_options)
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Copy or convert the current book onto an e-reader folder.
+/// `format`: "txt" (text documents, re-encoded with `encoding`) or the book's own format.
+#[command]
+pub async fn send_to_device(
+    file_id: String,
+    target_dir: String,
+    format: String,
+    encoding: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let (source, text) = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        let source = tab_manager.get_file_path(&file_id).map_err(|e| e.to_string())?;
+        // 열린 텍스트는 편집 중인 내용 그대로 보낸다 (읽기 전용으로 연 큰 파일은 지원하지 않는다)
+        let text = match tab_manager.get_buffer(&file_id) {
+            Ok(_) => Some(tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?.to_string()),
+            Err(_) => None,
+        };
+        (source, text)
+    };
+
+    let target_dir = std::path::PathBuf::from(target_dir);
+    tauri::async_runtime::spawn_blocking(move || {
+        exporter::send_to_device(
+            &source,
+            text.as_deref(),
+            &target_dir,
+            &format,
+            encoding.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map(|p| p.to_string_lossy().to_string())
+    .map_err(|e| e.to_string())
+}
+
+/// Split a text tab into per-chapter files (or one EPUB) at detected chapter headings.
+/// `naming`: "number" or "title"; `format`: "txt" (default) or "epub". Returns the written paths.
+#[command]
+pub async fn split_text_by_chapters(
+    file_id: String,
+    target_dir: String,
+    naming: String,
+    format: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let (rope, title) = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        let path = tab_manager.get_file_path(&file_id).map_err(|e| e.to_string())?;
+        let rope = tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?;
+        let title = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "book".to_string());
+        // Rope clone is cheap (shared chunks)
+        (rope.clone(), title)
+    };
+
+    let target_dir = std::path::PathBuf::from(target_dir);
+    let format = format.unwrap_or_else(|| "txt".to_string());
+    let language = state.config.read().map_err(|e| e.to_string())?.epub_language.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        exporter::split_by_chapters(&rope, &title, &target_dir, &naming, &format, &language)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map(|paths| paths.iter().map(|p| p.to_string_lossy().to_string()).collect())
+    .map_err(|e| e.to_string())
+}
+
+/// Convert a text tab to an EPUB: chapters from the detected headings, plus the given
+/// metadata and optional cover. Returns the written path.
+#[command]
+pub async fn convert_text_to_epub(
+    file_id: String,
+    options: TextToEpubOptions,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let (rope, title) = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        let path = tab_manager.get_file_path(&file_id).map_err(|e| e.to_string())?;
+        let rope = tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?;
+        let title = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "book".to_string());
+        (rope.clone(), title)
+    };
+
+    let language = state.config.read().map_err(|e| e.to_string())?.epub_language.clone();
+    tauri::async_runtime::spawn_blocking(move || exporter::text_to_epub(&rope, &title, &language, &options))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
@@ -1,32 +1,121 @@
-use crate::tab_manager::{FileInfo, TabInfo, TextChunk};
+use crate::bookmark::Bookmark;
+use crate::hex_view::HexChunk;
+use crate::pagination::Page;
+use crate::quick_open::QuickOpenEntry;
+use crate::tab_manager::{FileInfo, TabInfo, TextChunk, TextRange};
+use crate::text_buffer::LineEnding;
 use crate::AppState;
+use serde::Serialize;
+use std::path::Path;
 use tauri::command;
 use tauri::AppHandle;
 
+/// Lines fetched around last_position by `open_file_full` (matches the editor's chunk alignment).
+const INITIAL_CHUNK_ALIGN: usize = 100;
+const INITIAL_CHUNK_LINES: usize = 300;
+
+#[derive(Serialize)]
+pub struct OpenFileResult {
+    pub file_info: FileInfo,
+    pub initial_chunk: Option<TextChunk>,
+    pub bookmarks: Vec<Bookmark>,
+}
+
 #[command]
 pub async fn open_file(
     path: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<FileInfo, String> {
+    open_file_inner(&path, &state)
+}
+
+/// Open a file and return its info, the text chunk around the last reading
+/// position and its bookmarks in a single IPC round trip.
+#[command]
+pub async fn open_file_full(
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<OpenFileResult, String> {
+    let file_info = open_file_inner(&path, &state)?;
+
+    let initial_chunk = if file_info.file_type == "text" {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        let start_line = (file_info.last_position / INITIAL_CHUNK_ALIGN * INITIAL_CHUNK_ALIGN)
+            .saturating_sub(INITIAL_CHUNK_ALIGN);
+        Some(
+            tab_manager
+                .get_text_chunk(&file_info.id, start_line, start_line + INITIAL_CHUNK_LINES)
+                .map_err(|e| e.to_string())?,
+        )
+    } else {
+        None
+    };
+
+    let bookmarks = {
+        let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+        store.get_bookmarks(&file_info.id)
+    };
+
+    Ok(OpenFileResult {
+        file_info,
+        initial_chunk,
+        bookmarks,
+    })
+}
+
+pub(crate) fn open_file_inner(path: &str, state: &AppState) -> Result<FileInfo, String> {
     // Get last position from bookmark store
     let (last_position, last_scroll_offset) = {
         let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
-        store.get_last_position(&path).unwrap_or((0, 0))
+        store.get_last_position(path).unwrap_or((0, 0))
     };
 
+    let config = state.config.read().map_err(|e| e.to_string())?.clone();
+    // 네트워크 경로는 타임아웃을 걸어 확인하고 (설정에 따라 로컬로 복사하고) 나서 탭 잠금을 잡는다.
+    // 느린 공유 폴더 때문에 다른 명령이 모두 멈추지 않게 한다. 이미 열린 탭은 그냥 전환한다.
+    let already_open = state.tab_manager.lock().map_err(|e| e.to_string())?.has_tab(path);
+    let source_path = if already_open {
+        std::path::PathBuf::from(path)
+    } else {
+        crate::remote_io::resolve_source(std::path::Path::new(path)).map_err(|e| e.to_string())?
+    };
     let file_info = {
         let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
-        let info = tab_manager
-            .open_file(&path, last_position, last_scroll_offset)
+        let mut info = tab_manager
+            .open_file(path, &source_path, last_position, last_scroll_offset, &config)
             .map_err(|e| e.to_string())?;
 
+        if let Ok(store) = state.bookmark_store.lock() {
+            if info.file_type == "pdf" && info.pdf_view.is_none() {
+                info.pdf_view = store.get_pdf_view(path);
+                tab_manager.set_pdf_view(&info.id, info.pdf_view.clone());
+            }
+            info.reading_direction = store.get_reading_direction(path);
+            tab_manager.set_reading_direction(&info.id, info.reading_direction);
+        }
+
         // Register image source in cache for fast access
         if info.file_type == "image" {
             if let Some(source_info) = tab_manager.get_image_source_info(&info.id) {
                 state.image_cache.register(&info.id, source_info);
             }
+            // Saved password for encrypted archives, so they don't prompt on every open
+            if let Ok(store) = state.bookmark_store.lock() {
+                if let Some(password) = store.get_archive_password(path) {
+                    state.image_cache.set_password(&info.id, Some(password));
+                }
+                state
+                    .image_cache
+                    .set_rotations(&info.id, store.get_page_rotations(path));
+                if store.get_crop_margins(path) {
+                    state
+                        .image_cache
+                        .set_crop_threshold(&info.id, Some(config.margin_crop_threshold));
+                }
+            }
         }
 
+        state.memory_budget.enforce(&mut tab_manager, &state.image_cache);
         info
     };
 
@@ -38,19 +127,32 @@ pub async fn close_file(
     file_id: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
-    let (last_position, last_scroll_offset) = {
+    let (last_position, last_scroll_offset, pdf_view) = {
         let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
-        tab_manager.close_tab(&file_id).map_err(|e| e.to_string())?
+        let pdf_view = tab_manager.get_pdf_view(&file_id);
+        let (position, scroll_offset) = tab_manager.close_tab(&file_id).map_err(|e| e.to_string())?;
+        (position, scroll_offset, pdf_view)
     };
 
     // Clean up image cache
+    state.slideshow.stop(&file_id);
+    state.search_cursors.clear(&file_id);
+    state.follow_watcher.stop(&file_id);
+    crate::autosave::discard(&file_id);
     state.image_cache.unregister(&file_id);
+    if let Ok(mut stats) = state.reading_stats.lock() {
+        stats.end_activity(&file_id);
+    }
 
     // Save last position to bookmark store
     let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
     store
         .save_last_position(&file_id, last_position, last_scroll_offset)
         .map_err(|e| e.to_string())?;
+    if let Some(view) = pdf_view {
+        store.save_pdf_view(&file_id, view).map_err(|e| e.to_string())?;
+    }
+    store.flush_progress().map_err(|e| e.to_string())?;
 
     Ok(())
 }
@@ -63,7 +165,69 @@ pub async fn save_file(
     let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
     tab_manager
         .save_file(&file_id)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    crate::autosave::discard(&file_id);
+    Ok(())
+}
+
+/// Save a text tab to `path`, optionally converting the encoding
+/// ("UTF-8" / "UTF-8-BOM" / "EUC-KR" / any WHATWG label) and line endings ("LF" / "CRLF").
+/// The tab and its bookmark entry move to the new path; returns the new file id.
+#[command]
+pub async fn save_file_as(
+    file_id: String,
+    path: String,
+    encoding: Option<String>,
+    line_ending: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let (encoding, bom) = match encoding.as_deref() {
+        None => (None, None),
+        Some(label) if label.eq_ignore_ascii_case("UTF-8-BOM") => (Some(encoding_rs::UTF_8), Some(true)),
+        Some(label) => {
+            let encoding = encoding_rs::Encoding::for_label(label.trim().as_bytes())
+                .ok_or_else(|| format!("Unknown encoding: {}", label))?;
+            (Some(encoding), Some(false))
+        }
+    };
+    let line_ending = line_ending
+        .map(|label| LineEnding::from_label(&label).ok_or_else(|| format!("Unknown line ending: {}", label)))
+        .transpose()?;
+
+    let new_id = {
+        let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager
+            .save_file_as(&file_id, Path::new(&path), encoding, bom, line_ending)
+            .map_err(|e| e.to_string())?
+    };
+    state.search_cursors.clear(&file_id);
+    state.follow_watcher.stop(&file_id);
+    crate::autosave::discard(&file_id);
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    store
+        .rename_file_entry(&file_id, &new_id)
+        .map_err(|e| e.to_string())?;
+    Ok(new_id)
+}
+
+/// Reload a text tab decoded with `encoding_label` (WHATWG label, e.g. "EUC-KR", "Shift_JIS")
+/// when auto-detection guessed wrong. The tab id, bookmarks and position are kept.
+#[command]
+pub async fn reopen_with_encoding(
+    file_id: String,
+    encoding_label: String,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<FileInfo, String> {
+    let encoding = encoding_rs::Encoding::for_label(encoding_label.trim().as_bytes())
+        .ok_or_else(|| format!("Unknown encoding: {}", encoding_label))?;
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    let info = tab_manager
+        .reopen_with_encoding(&file_id, encoding)
+        .map_err(|e| e.to_string())?;
+    state.search_cursors.clear(&file_id);
+    crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
+    Ok(info)
 }
 
 #[command]
@@ -79,6 +243,58 @@ pub async fn get_text_chunk(
         .map_err(|e| e.to_string())
 }
 
+/// Hex-dump rows for `binary` tabs (16 bytes per row).
+#[command]
+pub async fn get_hex_chunk(
+    file_id: String,
+    start_row: usize,
+    end_row: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<HexChunk, String> {
+    let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    tab_manager
+        .get_hex_chunk(&file_id, start_row, end_row)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn get_text_range(
+    file_id: String,
+    start_char: usize,
+    end_char: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<TextRange, String> {
+    let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    tab_manager
+        .get_text_range(&file_id, start_char, end_char)
+        .map_err(|e| e.to_string())
+}
+
+/// Book page mode: page `page_index` when the text is split into pages of about
+/// `chars_per_page` chars, breaking at line ends or spaces.
+#[command]
+pub async fn get_page(
+    file_id: String,
+    page_index: usize,
+    chars_per_page: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<Page, String> {
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    tab_manager
+        .get_page(&file_id, page_index, chars_per_page)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn exit_page_mode(
+    file_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    tab_manager.exit_page_mode(&file_id);
+    Ok(())
+}
+
 #[command]
 pub async fn get_open_tabs(
     state: tauri::State<'_, AppState>,
@@ -111,10 +327,9 @@ pub async fn get_full_text(
     state: tauri::State<'_, AppState>,
 ) -> Result<String, String> {
     let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
-    let buffer = tab_manager
-        .get_buffer(&file_id)
-        .map_err(|e| e.to_string())?;
-    Ok(buffer.to_string_full())
+    // 읽기 전용으로 연 큰 파일은 통째로 문자열로 만들지 않는다
+    let rope = tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?;
+    Ok(rope.to_string())
 }
 
 #[command]
@@ -128,7 +343,38 @@ pub async fn get_total_lines(
         .map_err(|e| e.to_string())
 }
 
+/// Chapter headings detected in a text tab ("제1화", "Chapter 3", "第三章", ...).
+#[command]
+pub async fn get_text_chapters(
+    file_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<crate::chapter_detect::TextChapter>, String> {
+    let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    let rope = tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?;
+    Ok(crate::chapter_detect::detect_chapters(rope))
+}
+
 #[command]
 pub async fn exit_app(app: AppHandle) {
     app.exit(0);
 }
+
+/// Safe-mode flag and data files recovered at startup (also sent as `startup-issues`).
+#[command]
+pub async fn get_startup_issues() -> Result<crate::store_recovery::StartupReport, String> {
+    Ok(crate::store_recovery::startup_report())
+}
+
+/// Ranked quick-open results (tabs, favorites, recent files, bookmark memos) in one call.
+#[command]
+pub async fn quick_open(
+    query: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<QuickOpenEntry>, String> {
+    let tabs = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_open_tabs()
+    };
+    let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    Ok(crate::quick_open::quick_open(&query, &tabs, &store))
+}
@@ -0,0 +1,23 @@
+use crate::AppState;
+use tauri::{command, AppHandle};
+
+/// Follow a growing text file (e.g. a log): appended lines arrive as `follow-appended` events.
+#[command]
+pub async fn start_follow(
+    file_id: String,
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .follow_watcher
+        .start(&app, &file_id)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn stop_follow(
+    file_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, String> {
+    Ok(state.follow_watcher.stop(&file_id))
+}
@@ -1,4 +1,5 @@
 use crate::formatter;
+use crate::text_buffer::LineEnding;
 use crate::AppState;
 use tauri::command;
 
@@ -9,10 +10,8 @@ pub async fn preview_format(
     state: tauri::State<'_, AppState>,
 ) -> Result<String, String> {
     let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
-    let buffer = tab_manager
-        .get_buffer(&file_id)
-        .map_err(|e| e.to_string())?;
-    let text = buffer.to_string_full();
+    // 읽기 전용(대용량) 파일은 서식을 적용할 수 없으니 미리보기도 만들지 않는다
+    let text = tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?.to_string();
     formatter::apply_format(&text, &format_type).map_err(|e| e.to_string())
 }
 
@@ -20,6 +19,7 @@ pub async fn preview_format(
 pub async fn apply_format(
     file_id: String,
     format_type: String,
+    window: tauri::Window,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
     let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
@@ -27,10 +27,50 @@ pub async fn apply_format(
         let buffer = tab_manager
             .get_buffer_mut(&file_id)
             .map_err(|e| e.to_string())?;
-        let text = buffer.to_string_full();
-        let formatted = formatter::apply_format(&text, &format_type).map_err(|e| e.to_string())?;
-        buffer.replace_all(&formatted);
+        if format_type == "strip_trailing_whitespace" {
+            // 줄 단위 편집이라 전체 텍스트를 복사하지 않고 바뀐 곳만 기록한다
+            let edits = formatter::normalize_edits(buffer.rope(), None, true);
+            buffer.bulk_replace(edits);
+        } else {
+            let text = buffer.to_string_full();
+            let formatted = formatter::apply_format(&text, &format_type).map_err(|e| e.to_string())?;
+            buffer.replace_all(&formatted);
+        }
     }
     tab_manager.set_modified(&file_id, true);
+    crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
     Ok(())
 }
+
+/// Convert every line break to `target` ("LF" / "CRLF"), optionally stripping trailing
+/// whitespace too. Later saves use `target`. Returns the number of edits made.
+#[command]
+pub async fn normalize_line_endings(
+    file_id: String,
+    target: String,
+    strip_trailing_whitespace: Option<bool>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    let target = LineEnding::from_label(&target).ok_or_else(|| format!("Unknown line ending: {}", target))?;
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    let (count, eol_changed) = {
+        let buffer = tab_manager
+            .get_buffer_mut(&file_id)
+            .map_err(|e| e.to_string())?;
+        let edits = formatter::normalize_edits(
+            buffer.rope(),
+            Some(target),
+            strip_trailing_whitespace.unwrap_or(false),
+        );
+        let count = buffer.bulk_replace(edits);
+        let eol_changed = buffer.line_ending() != target;
+        buffer.set_save_format(None, None, Some(target));
+        (count, eol_changed)
+    };
+    if count > 0 || eol_changed {
+        tab_manager.set_modified(&file_id, true);
+        crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
+    }
+    Ok(count)
+}
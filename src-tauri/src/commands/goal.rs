@@ -0,0 +1,51 @@
+use crate::bookmark::ReadingGoal;
+use crate::reading_stats::GoalStatus;
+use crate::AppState;
+use tauri::command;
+
+/// Set a reading goal. `total_units`: pages / chapters / lines of the book
+/// (the frontend knows PDF page counts, the backend doesn't).
+#[command]
+pub async fn set_reading_goal(
+    file_path: String,
+    target_date: Option<String>,
+    units_per_day: Option<u64>,
+    total_units: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<ReadingGoal, String> {
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    store
+        .set_goal(&file_path, target_date, units_per_day, total_units)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn clear_reading_goal(
+    file_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    store.clear_goal(&file_path).map_err(|e| e.to_string())
+}
+
+/// Progress against the book's goal (None if no goal is set).
+#[command]
+pub async fn get_goal_status(
+    file_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<GoalStatus>, String> {
+    let (goal, saved_position) = {
+        let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+        let Some(goal) = store.get_goal(&file_path) else {
+            return Ok(None);
+        };
+        (goal, store.get_last_position(&file_path).map(|(p, _)| p).unwrap_or(0))
+    };
+    // 열려 있는 탭의 위치가 저장된 위치보다 최신
+    let position = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_last_position(&file_path).unwrap_or(saved_position)
+    };
+    let stats = state.reading_stats.lock().map_err(|e| e.to_string())?;
+    Ok(Some(stats.goal_status(&file_path, &goal, position)))
+}
@@ -1,30 +1,76 @@
+use crate::bookmark::ReadingDirection;
 use crate::AppState;
 use serde::Serialize;
-use tauri::{command, ipc::Response, State};
+use tauri::{command, ipc::Response, AppHandle, Manager, State};
 
 #[derive(Serialize)]
 pub struct AdjacentZips {
     pub prev_path: Option<String>,
     pub next_path: Option<String>,
+    /// The same two volumes placed by screen side for the file's reading direction
+    /// (RTL: next volume on the left), for arrow-key navigation.
+    pub left_path: Option<String>,
+    pub right_path: Option<String>,
 }
 
+/// Previous/next volume of the series (image archives and image folders, see `get_series_info`).
 #[command]
 pub async fn get_adjacent_zips(
     file_id: String,
     state: State<'_, AppState>,
 ) -> Result<AdjacentZips, String> {
-    let zip_path = {
+    let (zip_path, direction) = {
         let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
-        tab_manager.get_file_path(&file_id).map_err(|e| e.to_string())?
+        let path = tab_manager.get_file_path(&file_id).map_err(|e| e.to_string())?;
+        (path, tab_manager.get_reading_direction(&file_id))
     };
 
-    let (prev, next) = crate::image_reader::find_adjacent_zips(&zip_path)
-        .map_err(|e| e.to_string())?;
+    let volume = crate::image_reader::volume_path(&zip_path);
+    let (prev, next) = match crate::image_reader::find_series_volumes(&volume).map_err(|e| e.to_string())? {
+        Some((volumes, pos)) => (
+            pos.checked_sub(1).map(|prev| volumes[prev].clone()),
+            volumes.get(pos + 1).cloned(),
+        ),
+        None => (None, None),
+    };
+    let prev_path = prev.map(|p| p.to_string_lossy().to_string());
+    let next_path = next.map(|p| p.to_string_lossy().to_string());
+    let (left_path, right_path) = match direction {
+        ReadingDirection::Ltr => (prev_path.clone(), next_path.clone()),
+        ReadingDirection::Rtl => (next_path.clone(), prev_path.clone()),
+    };
 
     Ok(AdjacentZips {
-        prev_path: prev.map(|p| p.to_string_lossy().to_string()),
-        next_path: next.map(|p| p.to_string_lossy().to_string()),
+        prev_path,
+        next_path,
+        left_path,
+        right_path,
+    })
+}
+
+/// All volumes of the open file's series, with whether each was read and its progress.
+#[command]
+pub async fn get_series_info(
+    file_id: String,
+    app: AppHandle,
+) -> Result<crate::series::SeriesInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let (path, position, pages) = {
+            let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+            let path = tab_manager.get_file_path(&file_id).map_err(|e| e.to_string())?;
+            let position = tab_manager.get_last_position(&file_id).unwrap_or(0);
+            (path, position, tab_manager.get_image_count(&file_id))
+        };
+        let volume = crate::image_reader::volume_path(&path);
+        crate::series::series_info(&volume, position, pages, |volume_path| {
+            let store = state.bookmark_store.lock().ok()?;
+            store.get_last_position(volume_path).map(|(position, _)| position)
+        })
+        .map_err(|e| e.to_string())
     })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 #[command]
@@ -42,19 +88,30 @@ pub async fn get_image_list(
 pub async fn get_image_bytes(
     file_id: String,
     index: usize,
+    max_dimension: Option<u32>,
+    window: tauri::Window,
     state: State<'_, AppState>,
 ) -> Result<Response, String> {
+    // 큰 스캔본은 긴 변을 max_dimension에 맞춰 줄여서 보낸다 (캐시는 크기별로 따로 저장,
+    // 미리 읽기도 이 크기로 한다)
+    state.image_cache.set_max_dimension(&file_id, max_dimension);
+
     // Read via cache (LRU hit → instant, miss → cached ZipArchive or fs::read)
     let bytes = state
         .image_cache
-        .read_image(&file_id, index)
+        .read_image_sized(&file_id, index, max_dimension)
         .map_err(|e| e.to_string())?;
 
     // Update last position
     {
         let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
         tab_manager.set_last_position(&file_id, index, 0);
+        state.memory_budget.enforce(&mut tab_manager, &state.image_cache);
+    }
+    if let Ok(mut stats) = state.reading_stats.lock() {
+        stats.record_activity(&file_id, index);
     }
+    crate::sync::emit_position_changed(&window, &file_id, index, 0);
 
     // Trigger background prefetch for adjacent images
     let total = {
@@ -65,3 +122,193 @@ pub async fn get_image_bytes(
 
     Ok(Response::new(bytes))
 }
+
+/// Width, height, file size and format of one image, from its header (no decode).
+#[command]
+pub async fn get_image_info(
+    file_id: String,
+    index: usize,
+    state: State<'_, AppState>,
+) -> Result<crate::image_reader::ImageInfo, String> {
+    let (name, bytes) = state
+        .image_cache
+        .read_image_raw(&file_id, index)
+        .map_err(|e| e.to_string())?;
+    Ok(crate::image_reader::image_info(&name, &bytes))
+}
+
+/// Two-page spread plan from the page aspect ratios: portrait pages pair up, wide pages
+/// stay alone. `cover_alone` (default true) keeps the first page by itself; `rtl` defaults
+/// to the file's saved reading direction.
+#[command]
+pub async fn get_spread_plan(
+    file_id: String,
+    rtl: Option<bool>,
+    cover_alone: Option<bool>,
+    app: AppHandle,
+) -> Result<crate::spread::SpreadPlan, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let (total, direction) = {
+            let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+            (tab_manager.get_image_count(&file_id), tab_manager.get_reading_direction(&file_id))
+        };
+        let dimensions: Vec<Option<(u32, u32)>> = (0..total)
+            .map(|index| state.image_cache.image_dimensions(&file_id, index))
+            .collect();
+        Ok(crate::spread::SpreadPlan {
+            rtl: rtl.unwrap_or(direction == ReadingDirection::Rtl),
+            spreads: crate::spread::pair_pages(&dimensions, cover_alone.unwrap_or(true)),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Turn automatic margin cropping of scanned pages on or off for a file (remembered per file).
+#[command]
+pub async fn set_crop_margins(
+    file_id: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let threshold = state.config.read().map_err(|e| e.to_string())?.margin_crop_threshold;
+    state
+        .image_cache
+        .set_crop_threshold(&file_id, enabled.then_some(threshold));
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    store
+        .save_crop_margins(&file_id, enabled)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn get_crop_margins(
+    file_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    Ok(store.get_crop_margins(&file_id))
+}
+
+/// Rotate one page clockwise by `degrees` (a multiple of 90; 0 restores it).
+/// The rotation is remembered, so the page stays rotated when the file is reopened.
+#[command]
+pub async fn set_image_rotation(
+    file_id: String,
+    index: usize,
+    degrees: i32,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let degrees = degrees.rem_euclid(360);
+    if degrees % 90 != 0 {
+        return Err(format!("Rotation must be a multiple of 90 degrees: {}", degrees));
+    }
+    let degrees = degrees as u16;
+    state.image_cache.set_rotation(&file_id, index, degrees);
+    let mut store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    store
+        .save_page_rotation(&file_id, index, degrees)
+        .map_err(|e| e.to_string())
+}
+
+/// Saved page rotations of a file (page index → clockwise degrees).
+#[command]
+pub async fn get_image_rotations(
+    file_id: String,
+    state: State<'_, AppState>,
+) -> Result<std::collections::BTreeMap<usize, u16>, String> {
+    let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+    Ok(store.get_page_rotations(&file_id))
+}
+
+const DEFAULT_THUMBNAIL_SIZE: u32 = 160;
+
+/// Small previews of pages `start..start + count` for the page-grid overview (disk cached).
+#[command]
+pub async fn get_image_thumbnails(
+    file_id: String,
+    start: usize,
+    count: usize,
+    max_px: Option<u32>,
+    app: AppHandle,
+) -> Result<Vec<crate::image_thumbs::ImageThumbnail>, String> {
+    let max_px = max_px.unwrap_or(DEFAULT_THUMBNAIL_SIZE).clamp(16, 1024);
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let (source, total) = {
+            let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+            let source = tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?;
+            (source, tab_manager.get_image_count(&file_id))
+        };
+        crate::image_thumbs::thumbnails(&state.image_cache, &file_id, &source, total, start, count, max_px)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Webtoon mode: all pages stitched into one scroll stream of `width` (default 800),
+/// with pages taller than `tile_height` (default 2048 px) split into tiles.
+#[command]
+pub async fn get_strip_layout(
+    file_id: String,
+    width: Option<u32>,
+    tile_height: Option<u32>,
+    app: AppHandle,
+) -> Result<crate::webtoon::StripLayout, String> {
+    let width = width.unwrap_or(crate::webtoon::DEFAULT_STRIP_WIDTH).max(1);
+    let tile_height = tile_height.unwrap_or(crate::webtoon::DEFAULT_TILE_HEIGHT).max(256);
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let total = {
+            let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+            tab_manager.get_image_count(&file_id)
+        };
+        let dimensions: Vec<Option<(u32, u32)>> = (0..total)
+            .map(|index| state.image_cache.image_dimensions(&file_id, index))
+            .collect();
+        Ok(crate::webtoon::strip_layout(&dimensions, width, tile_height))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// One tile of the strip layout: source rows `y..y + height` of a page.
+#[command]
+pub async fn get_image_tile(
+    file_id: String,
+    index: usize,
+    y: u32,
+    height: u32,
+    app: AppHandle,
+) -> Result<Response, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let tile = crate::webtoon::tile(&state.image_cache, &file_id, index, y, height).map_err(|e| e.to_string())?;
+        // 디코드한 페이지도 이미지 캐시 사용량에 들어간다
+        if let Ok(mut tab_manager) = state.tab_manager.lock() {
+            state.memory_budget.enforce(&mut tab_manager, &state.image_cache);
+        }
+        Ok(Response::new(tile))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+const DEFAULT_PREVIEW_SIZE: u32 = 320;
+
+/// Cover thumbnail and page count for a ZIP/CBZ or image folder (library grid).
+#[command]
+pub async fn get_archive_preview(
+    path: String,
+    max_size: Option<u32>,
+) -> Result<crate::image_reader::ArchivePreview, String> {
+    let max_size = max_size.unwrap_or(DEFAULT_PREVIEW_SIZE).max(16);
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::image_reader::archive_preview(std::path::Path::new(&path), max_size)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,96 @@
+use crate::duplicates::DuplicateGroup;
+use crate::AppState;
+use tauri::{command, AppHandle};
+
+#[command]
+pub async fn get_watched_folders(state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
+    let config = state.config.read().map_err(|e| e.to_string())?;
+    Ok(config.watched_folders.clone())
+}
+
+/// Register a folder to watch. Supported files already in it are added to the file list.
+/// Returns the newly added file paths.
+#[command]
+pub async fn add_watched_folder(
+    folder: String,
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let folder_path = std::path::Path::new(&folder);
+    if !folder_path.is_dir() {
+        return Err(format!("Not a directory: {}", folder));
+    }
+
+    let watched_folders = {
+        let mut config = state.config.write().map_err(|e| e.to_string())?;
+        if !config.watched_folders.contains(&folder) {
+            config.watched_folders.push(folder.clone());
+            config.save().map_err(|e| e.to_string())?;
+        }
+        config.watched_folders.clone()
+    };
+    // --safe-mode에서는 폴더를 감시하지 않는다 (목록에는 남긴다)
+    if !crate::store_recovery::is_safe_mode() {
+        state
+            .library_watcher
+            .watch(&app, &watched_folders)
+            .map_err(|e| e.to_string())?;
+    }
+
+    let files = crate::library_watcher::scan_library_folder(folder_path);
+    Ok(crate::library_watcher::add_to_library(&app, &files))
+}
+
+#[command]
+pub async fn remove_watched_folder(
+    folder: String,
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let watched_folders = {
+        let mut config = state.config.write().map_err(|e| e.to_string())?;
+        config.watched_folders.retain(|f| f != &folder);
+        config.save().map_err(|e| e.to_string())?;
+        config.watched_folders.clone()
+    };
+    if crate::store_recovery::is_safe_mode() {
+        return Ok(());
+    }
+    state
+        .library_watcher
+        .watch(&app, &watched_folders)
+        .map_err(|e| e.to_string())
+}
+
+/// Find tracked files with identical content.
+#[command]
+pub async fn find_duplicate_files(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let paths: Vec<String> = {
+        let store = state.bookmark_store.lock().map_err(|e| e.to_string())?;
+        store.get_all_bookmarks().keys().cloned().collect()
+    };
+    tauri::async_runtime::spawn_blocking(move || crate::duplicates::find_duplicates(&paths))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+const DEFAULT_COVER_SIZE: u32 = 240;
+
+/// Cached cover thumbnail (JPEG data URI) for the bookshelf view; None if the book has no cover.
+#[command]
+pub async fn get_cover_thumbnail(
+    path: String,
+    max_size: Option<u32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let max_size = max_size.unwrap_or(DEFAULT_COVER_SIZE).max(16);
+    let config = state.config.read().map_err(|e| e.to_string())?.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::covers::cover_thumbnail(std::path::Path::new(&path), max_size, &config)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,10 @@
+use crate::memory_budget::MemoryUsage;
+use crate::AppState;
+use tauri::command;
+
+/// Release all rebuildable caches (image LRU, EPUB chapters, inactive text buffers).
+#[command]
+pub async fn trim_memory(state: tauri::State<'_, AppState>) -> Result<MemoryUsage, String> {
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    Ok(state.memory_budget.trim(&mut tab_manager, &state.image_cache))
+}
@@ -1,21 +1,51 @@
+pub mod archive;
+pub mod backup;
 pub mod bookmark;
+pub mod clipboard;
 pub mod config;
 pub mod edit;
 pub mod epub;
+pub mod export;
 pub mod file;
+pub mod follow;
 pub mod format;
+pub mod goal;
 pub mod image;
+pub mod library;
+pub mod memory;
+pub mod ocr;
 pub mod pdf;
+pub mod recovery;
 pub mod search;
 pub mod shell_menu;
+pub mod slideshow;
+pub mod stats;
+pub mod text_stats;
+pub mod translation;
+pub mod vocabulary;
 
+pub use archive::*;
+pub use backup::*;
 pub use bookmark::*;
+pub use clipboard::*;
 pub use config::*;
 pub use edit::*;
 pub use epub::*;
+pub use export::*;
 pub use file::*;
+pub use follow::*;
 pub use format::*;
+pub use goal::*;
 pub use image::*;
+pub use library::*;
+pub use memory::*;
+pub use ocr::*;
 pub use pdf::*;
+pub use recovery::*;
 pub use search::*;
 pub use shell_menu::*;
+pub use slideshow::*;
+pub use stats::*;
+pub use text_stats::*;
+pub use translation::*;
+pub use vocabulary::*;
@@ -0,0 +1,141 @@
+use crate::ocr::{self, OcrPage};
+use crate::AppState;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+#[derive(Clone, Serialize)]
+struct OcrProgress {
+    file_id: String,
+    index: usize,
+    done: usize,
+    total: usize,
+    page: Option<OcrPage>,
+    error: Option<String>,
+}
+
+fn ocr_page_of_tab(
+    state: &AppState,
+    file_id: &str,
+    index: usize,
+    lang: &str,
+    tesseract_path: &str,
+) -> anyhow::Result<OcrPage> {
+    let source = {
+        let tab_manager = state
+            .tab_manager
+            .lock()
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        tab_manager.get_source_path(file_id)?
+    };
+    let key = ocr::cache_key(&source, index, lang);
+    if let Some(page) = ocr::load_cached(&key) {
+        return Ok(page);
+    }
+    let bytes = state.image_cache.read_image(file_id, index)?;
+    ocr::ocr_bytes(&bytes, lang, &key, tesseract_path)
+}
+
+/// (OCR language, tesseract path): `lang` or the configured language.
+fn ocr_settings(state: &AppState, lang: Option<String>) -> Result<(String, String), String> {
+    let config = state.config.read().map_err(|e| e.to_string())?;
+    Ok((
+        lang.unwrap_or_else(|| config.ocr_lang.clone()),
+        config.tesseract_path.clone(),
+    ))
+}
+
+/// OCR one image of an image/comic tab. `lang` defaults to the configured OCR language.
+#[command]
+pub async fn ocr_image(
+    file_id: String,
+    index: usize,
+    lang: Option<String>,
+    app: AppHandle,
+) -> Result<OcrPage, String> {
+    let (lang, tesseract_path) = ocr_settings(&app.state::<AppState>(), lang)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        ocr_page_of_tab(&state, &file_id, index, &lang, &tesseract_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// OCR several images in the background. Each finished page is reported with an
+/// `ocr-progress` event; the command returns immediately. Batches run one at a time, and a
+/// new batch for the same tab replaces the previous one (see `cancel_ocr_batch`).
+#[command]
+pub async fn ocr_image_batch(
+    file_id: String,
+    indices: Vec<usize>,
+    lang: Option<String>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let (lang, tesseract_path) = ocr_settings(&app.state::<AppState>(), lang)?;
+
+    let batch_file_id = file_id.clone();
+    let handle = app.clone();
+    let batch = move |cancelled: Arc<AtomicBool>| {
+        let state = app.state::<AppState>();
+        let total = indices.len();
+        for (done, index) in indices.into_iter().enumerate() {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let result = ocr_page_of_tab(&state, &file_id, index, &lang, &tesseract_path);
+            let (page, error) = match result {
+                Ok(page) => (Some(page), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            let _ = app.emit(
+                "ocr-progress",
+                OcrProgress {
+                    file_id: file_id.clone(),
+                    index,
+                    done: done + 1,
+                    total,
+                    page,
+                    error,
+                },
+            );
+        }
+        state.ocr_batches.finish(&file_id, &cancelled);
+    };
+    handle
+        .state::<AppState>()
+        .ocr_batches
+        .submit(&batch_file_id, batch)
+        .map_err(|e| e.to_string())
+}
+
+/// Stop the pending or running OCR batch of a tab. Returns false if there was none.
+#[command]
+pub async fn cancel_ocr_batch(file_id: String, state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.ocr_batches.cancel(&file_id))
+}
+
+/// OCR a PDF page rendered by the frontend (PNG/JPEG bytes), cached per page.
+#[command]
+pub async fn ocr_pdf_page(
+    file_id: String,
+    page: usize,
+    image: Vec<u8>,
+    lang: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<OcrPage, String> {
+    let (lang, tesseract_path) = ocr_settings(&state, lang)?;
+    let source = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    let key = ocr::cache_key(&source, page, &lang);
+    tauri::async_runtime::spawn_blocking(move || {
+        ocr::ocr_bytes(&image, &lang, &key, &tesseract_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
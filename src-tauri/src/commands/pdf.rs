@@ -1,10 +1,198 @@
+use std::io::{Read, Seek, SeekFrom};
+
 use tauri::{command, ipc::Response, State};
+use crate::pdf_render::{self, PdfMetadata, PdfOutlineItem, PdfSearchHit, PdfThumbnail};
+use crate::pdf_stream::{self, MAX_PDF_RANGE_BYTES};
 use crate::AppState;
 
 #[command]
 pub async fn read_pdf_bytes(file_id: String, state: State<'_, AppState>) -> Result<Response, String> {
     let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
-    let path = tab_manager.get_file_path(&file_id).map_err(|e| e.to_string())?;
+    let path = tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?;
     let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
     Ok(Response::new(bytes))
 }
+
+/// Total size of the PDF in bytes (needed by PDF.js range transport).
+#[command]
+pub async fn get_pdf_size(file_id: String, state: State<'_, AppState>) -> Result<u64, String> {
+    let path = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    std::fs::metadata(&path)
+        .map(|m| m.len())
+        .map_err(|e| e.to_string())
+}
+
+/// URL serving the PDF over the `pdfstream` scheme with HTTP Range support, so pdf.js
+/// can fetch only the parts it needs.
+#[command]
+pub async fn get_pdf_stream_url(file_id: String, state: State<'_, AppState>) -> Result<String, String> {
+    let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    tab_manager.get_pdf_source_path(&file_id).map_err(|e| e.to_string())?;
+    Ok(pdf_stream::stream_url(&file_id))
+}
+
+/// Read `length` bytes starting at `offset`. Reads past EOF are truncated.
+#[command]
+pub async fn read_pdf_range(
+    file_id: String,
+    offset: u64,
+    length: usize,
+    state: State<'_, AppState>,
+) -> Result<Response, String> {
+    if length > MAX_PDF_RANGE_BYTES {
+        return Err(format!(
+            "Requested range too large: {} bytes (max {})",
+            length, MAX_PDF_RANGE_BYTES
+        ));
+    }
+
+    let path = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+
+    let mut file = std::fs::File::open(&path).map_err(|e| e.to_string())?;
+    let file_len = file.metadata().map_err(|e| e.to_string())?.len();
+    if offset >= file_len {
+        return Ok(Response::new(Vec::new()));
+    }
+
+    let actual_len = length.min((file_len - offset) as usize);
+    let mut buf = vec![0u8; actual_len];
+    file.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+    file.read_exact(&mut buf).map_err(|e| e.to_string())?;
+    Ok(Response::new(buf))
+}
+
+/// Number of pages, read natively (without pdf.js).
+#[command]
+pub async fn get_pdf_page_count(file_id: String, state: State<'_, AppState>) -> Result<usize, String> {
+    let path = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    let mutool_path = state.config.read().map_err(|e| e.to_string())?.mutool_path.clone();
+    tauri::async_runtime::spawn_blocking(move || pdf_render::page_count(&path, &mutool_path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Render one page (0-based) to PNG at `scale` (1.0 = 72 DPI), so large scanned PDFs
+/// never have to be loaded into the webview as a whole.
+#[command]
+pub async fn render_pdf_page(
+    file_id: String,
+    page: usize,
+    scale: f64,
+    state: State<'_, AppState>,
+) -> Result<Response, String> {
+    let path = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    let mutool_path = state.config.read().map_err(|e| e.to_string())?.mutool_path.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        pdf_render::render_page(&path, page, scale, &mutool_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map(Response::new)
+    .map_err(|e| e.to_string())
+}
+
+/// Bookmark tree of the PDF (title, 0-based page, depth) for the sidebar TOC.
+#[command]
+pub async fn get_pdf_outline(
+    file_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<PdfOutlineItem>, String> {
+    let path = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    let mutool_path = state.config.read().map_err(|e| e.to_string())?.mutool_path.clone();
+    tauri::async_runtime::spawn_blocking(move || pdf_render::outline(&path, &mutool_path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Title, author, page count and page size of the PDF.
+#[command]
+pub async fn get_pdf_metadata(file_id: String, state: State<'_, AppState>) -> Result<PdfMetadata, String> {
+    let path = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    let mutool_path = state.config.read().map_err(|e| e.to_string())?.mutool_path.clone();
+    tauri::async_runtime::spawn_blocking(move || pdf_render::metadata(&path, &mutool_path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Extracted text of one page (0-based), for copying and the text layer.
+#[command]
+pub async fn get_pdf_page_text(
+    file_id: String,
+    page: usize,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let path = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    let mutool_path = state.config.read().map_err(|e| e.to_string())?.mutool_path.clone();
+    tauri::async_runtime::spawn_blocking(move || pdf_render::page_text(&path, page, &mutool_path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Search the whole PDF. Each hit has its page and rects in page points.
+#[command]
+pub async fn search_pdf(
+    file_id: String,
+    query: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<PdfSearchHit>, String> {
+    let path = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    let mutool_path = state.config.read().map_err(|e| e.to_string())?.mutool_path.clone();
+    tauri::async_runtime::spawn_blocking(move || pdf_render::search(&path, &query, &mutool_path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+const DEFAULT_THUMBNAIL_SIZE: u32 = 160;
+
+/// Low-resolution previews of `count` pages from `start` (0-based) for the thumbnail strip,
+/// cached on disk.
+#[command]
+pub async fn get_pdf_thumbnails(
+    file_id: String,
+    start: usize,
+    count: usize,
+    max_size: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<PdfThumbnail>, String> {
+    let path = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_source_path(&file_id).map_err(|e| e.to_string())?
+    };
+    let max_size = max_size.unwrap_or(DEFAULT_THUMBNAIL_SIZE).clamp(16, 1024);
+    let mutool_path = state.config.read().map_err(|e| e.to_string())?.mutool_path.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        pdf_render::thumbnails(&path, start, count, max_size, &mutool_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
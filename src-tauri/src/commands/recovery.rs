@@ -0,0 +1,43 @@
+use crate::autosave::{self, RecoveredFile};
+use crate::tab_manager::FileInfo;
+use crate::AppState;
+use tauri::command;
+
+/// Files with unsaved edits autosaved by a previous session.
+#[command]
+pub async fn recover_unsaved_files() -> Result<Vec<RecoveredFile>, String> {
+    Ok(autosave::list_recovered())
+}
+
+/// Open the original file and replace its content with the recovered text
+/// (as one undoable edit, left unsaved).
+#[command]
+pub async fn restore_recovered_file(
+    original_path: String,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<FileInfo, String> {
+    let text = autosave::read_recovered(&original_path).map_err(|e| e.to_string())?;
+    let mut info = super::file::open_file_inner(&original_path, &state)?;
+
+    let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+    {
+        let buffer = tab_manager
+            .get_buffer_mut(&info.id)
+            .map_err(|e| e.to_string())?;
+        buffer.replace_all(&text);
+        info.total_lines = buffer.get_total_lines();
+        info.total_chars = buffer.get_total_chars();
+    }
+    tab_manager.set_modified(&info.id, true);
+    info.is_modified = true;
+    crate::sync::emit_document_changed(&window, &tab_manager, &info.id);
+    autosave::discard(&original_path);
+    Ok(info)
+}
+
+#[command]
+pub async fn discard_recovered_file(original_path: String) -> Result<(), String> {
+    autosave::discard(&original_path);
+    Ok(())
+}
@@ -1,68 +1,295 @@
-use crate::search::{self, SearchMatch};
+use crate::search::{self, Matcher, ReplacePreview, SearchMatch, SearchOptions};
 use crate::AppState;
-use tauri::command;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use tauri::{command, Emitter, Manager};
+
+fn build_matcher(
+    query: &str,
+    case_sensitive: bool,
+    whole_word: Option<bool>,
+    regex: Option<bool>,
+    ignore_diacritics: Option<bool>,
+) -> Result<Matcher, String> {
+    let options = SearchOptions {
+        case_sensitive,
+        whole_word: whole_word.unwrap_or(false),
+        regex: regex.unwrap_or(false),
+        ignore_diacritics: ignore_diacritics.unwrap_or(false),
+    };
+    Matcher::new(query, options).map_err(|e| e.to_string())
+}
 
 #[command]
 pub async fn search_text(
     file_id: String,
     query: String,
     case_sensitive: bool,
+    whole_word: Option<bool>,
+    regex: Option<bool>,
+    ignore_diacritics: Option<bool>,
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<SearchMatch>, String> {
-    let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
-    let buffer = tab_manager
-        .get_buffer(&file_id)
-        .map_err(|e| e.to_string())?;
-    Ok(search::search_in_rope(buffer.rope(), &query, case_sensitive))
+    let matcher = build_matcher(&query, case_sensitive, whole_word, regex, ignore_diacritics)?;
+    // 검색하는 동안 TabManager를 잠그지 않도록 rope를 복제(청크 공유라 저렴)
+    let rope = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?.clone()
+    };
+    tauri::async_runtime::spawn_blocking(move || search::search_in_rope(&rope, &matcher))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Clone, Serialize)]
+struct SearchProgress {
+    search_id: String,
+    lines_searched: usize,
+    total_lines: usize,
+    /// Matches found in this chunk (in document order).
+    matches: Vec<SearchMatch>,
+}
+
+#[derive(Clone, Serialize)]
+struct SearchFinished {
+    search_id: String,
+    total_matches: usize,
+    cancelled: bool,
+}
+
+/// Search in the background. Partial results arrive as `search-progress` events
+/// and completion as `search-finished`; `cancel_search(search_id)` aborts.
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_search(
+    file_id: String,
+    search_id: String,
+    query: String,
+    case_sensitive: bool,
+    whole_word: Option<bool>,
+    regex: Option<bool>,
+    ignore_diacritics: Option<bool>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let matcher = build_matcher(&query, case_sensitive, whole_word, regex, ignore_diacritics)?;
+    let rope = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?.clone()
+    };
+    let cancelled = state.search_jobs.register(&search_id);
+
+    std::thread::spawn(move || {
+        let total_lines = rope.len_lines();
+        let mut total_matches = 0;
+        let mut start = 0;
+        while start < total_lines && !cancelled.load(Ordering::Relaxed) {
+            let end = (start + search::SEARCH_CHUNK_LINES).min(total_lines);
+            let matches = search::search_lines_threaded(&rope, &matcher, start..end);
+            total_matches += matches.len();
+            let _ = window.emit(
+                "search-progress",
+                SearchProgress {
+                    search_id: search_id.clone(),
+                    lines_searched: end,
+                    total_lines,
+                    matches,
+                },
+            );
+            start = end;
+        }
+        let _ = window.emit(
+            "search-finished",
+            SearchFinished {
+                search_id: search_id.clone(),
+                total_matches,
+                cancelled: cancelled.load(Ordering::Relaxed),
+            },
+        );
+        window
+            .state::<AppState>()
+            .search_jobs
+            .finish(&search_id, &cancelled);
+    });
+    Ok(())
 }
 
 #[command]
+pub async fn cancel_search(search_id: String, state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.search_jobs.cancel(&search_id))
+}
+
+#[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn replace_text(
     file_id: String,
     query: String,
     replacement: String,
     position: usize,
     case_sensitive: bool,
+    whole_word: Option<bool>,
+    regex: Option<bool>,
+    ignore_diacritics: Option<bool>,
+    window: tauri::Window,
     state: tauri::State<'_, AppState>,
 ) -> Result<Option<usize>, String> {
+    let matcher = build_matcher(&query, case_sensitive, whole_word, regex, ignore_diacritics)?;
     let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
     let result = {
         let buffer = tab_manager
             .get_buffer_mut(&file_id)
             .map_err(|e| e.to_string())?;
-        let result = search::replace_next(buffer.rope_mut(), &query, &replacement, position, case_sensitive);
-        if result.is_some() {
-            buffer.is_modified = true;
-        }
-        result
+        let edit = search::find_next_replacement(buffer.rope(), &matcher, &replacement, position);
+        edit.map(|edit| {
+            let char_start = edit.char_start;
+            buffer.apply_replacement(edit);
+            char_start
+        })
     };
     if result.is_some() {
         tab_manager.set_modified(&file_id, true);
+        crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
     }
     Ok(result)
 }
 
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn replace_all_text(
     file_id: String,
     query: String,
     replacement: String,
     case_sensitive: bool,
+    whole_word: Option<bool>,
+    regex: Option<bool>,
+    ignore_diacritics: Option<bool>,
+    window: tauri::Window,
     state: tauri::State<'_, AppState>,
 ) -> Result<usize, String> {
+    let matcher = build_matcher(&query, case_sensitive, whole_word, regex, ignore_diacritics)?;
     let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
     let count = {
         let buffer = tab_manager
             .get_buffer_mut(&file_id)
             .map_err(|e| e.to_string())?;
-        let count = search::replace_all_in_rope(buffer.rope_mut(), &query, &replacement, case_sensitive);
-        if count > 0 {
-            buffer.is_modified = true;
-        }
-        count
+        // 버퍼를 거쳐 적용해야 Ctrl+Z 한 번으로 전부 되돌릴 수 있다
+        let edits = search::find_replacements(buffer.rope(), &matcher, &replacement);
+        buffer.bulk_replace(edits)
     };
     if count > 0 {
         tab_manager.set_modified(&file_id, true);
+        crate::sync::emit_document_changed(&window, &tab_manager, &file_id);
     }
     Ok(count)
 }
+
+/// Default number of previewed replacements.
+const DEFAULT_PREVIEW_LIMIT: usize = 1000;
+
+#[derive(Serialize)]
+pub struct ReplaceAllPreview {
+    pub total: usize,
+    pub items: Vec<ReplacePreview>,
+}
+
+/// Show what `replace_all_text` would change, without modifying the document.
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn preview_replace_all(
+    file_id: String,
+    query: String,
+    replacement: String,
+    case_sensitive: bool,
+    whole_word: Option<bool>,
+    regex: Option<bool>,
+    ignore_diacritics: Option<bool>,
+    limit: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ReplaceAllPreview, String> {
+    let matcher = build_matcher(&query, case_sensitive, whole_word, regex, ignore_diacritics)?;
+    let rope = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?.clone()
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let replacements = search::find_replacements(&rope, &matcher, &replacement);
+        ReplaceAllPreview {
+            total: replacements.len(),
+            items: search::preview_replacements(&rope, &replacements, limit.unwrap_or(DEFAULT_PREVIEW_LIMIT)),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Serialize)]
+pub struct FoundMatch {
+    #[serde(flatten)]
+    pub found: SearchMatch,
+    /// The search wrapped past the end (or start) of the document.
+    pub wrapped: bool,
+}
+
+/// Next match after the previous find_next/find_prev result for this tab
+/// (or from `from` / the top when the query changes).
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn find_next(
+    file_id: String,
+    query: String,
+    case_sensitive: bool,
+    whole_word: Option<bool>,
+    regex: Option<bool>,
+    ignore_diacritics: Option<bool>,
+    from: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<FoundMatch>, String> {
+    let matcher = build_matcher(&query, case_sensitive, whole_word, regex, ignore_diacritics)?;
+    find_step(file_id, query, matcher, from, true, &state).await
+}
+
+/// Previous match before the last find_next/find_prev result for this tab.
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn find_prev(
+    file_id: String,
+    query: String,
+    case_sensitive: bool,
+    whole_word: Option<bool>,
+    regex: Option<bool>,
+    ignore_diacritics: Option<bool>,
+    from: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<FoundMatch>, String> {
+    let matcher = build_matcher(&query, case_sensitive, whole_word, regex, ignore_diacritics)?;
+    find_step(file_id, query, matcher, from, false, &state).await
+}
+
+async fn find_step(
+    file_id: String,
+    query: String,
+    matcher: Matcher,
+    from: Option<usize>,
+    forward: bool,
+    state: &tauri::State<'_, AppState>,
+) -> Result<Option<FoundMatch>, String> {
+    let options = matcher.options();
+    let anchor = state.search_cursors.anchor(&file_id, &query, options, from, forward);
+    let rope = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        tab_manager.get_rope(&file_id).map_err(|e| e.to_string())?.clone()
+    };
+    let found = tauri::async_runtime::spawn_blocking(move || {
+        if forward {
+            search::find_next_match(&rope, &matcher, anchor)
+        } else {
+            search::find_prev_match(&rope, &matcher, anchor)
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let last = found.as_ref().map(|(m, _)| (m.char_start, m.char_end));
+    state.search_cursors.record(&file_id, &query, options, last);
+    Ok(found.map(|(found, wrapped)| FoundMatch { found, wrapped }))
+}
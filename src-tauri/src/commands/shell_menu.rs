@@ -5,7 +5,8 @@ use winreg::enums::*;
 #[cfg(target_os = "windows")]
 use winreg::RegKey;
 
-/// Register "Open with SimpleReader" context menu in Windows Explorer
+/// Register "Open with SimpleReader": Explorer context menu on Windows,
+/// a user .desktop entry on Linux, LaunchServices registration on macOS
 #[command]
 pub fn register_context_menu() -> Result<bool, String> {
     #[cfg(target_os = "windows")]
@@ -65,7 +66,17 @@ pub fn register_context_menu() -> Result<bool, String> {
         Ok(true)
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::register()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        macos::register()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
     {
         Ok(false)
     }
@@ -89,7 +100,17 @@ pub fn unregister_context_menu() -> Result<bool, String> {
         Ok(true)
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::unregister()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        macos::unregister()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
     {
         Ok(false)
     }
@@ -157,8 +178,155 @@ pub fn is_context_menu_registered() -> Result<bool, String> {
         Ok(result.is_ok())
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    {
+        Ok(linux::is_registered())
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Ok(macos::is_registered())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
     {
         Ok(false)
     }
 }
+
+/// MIME types offered in "Open with" (Linux .desktop MimeType).
+#[cfg(target_os = "linux")]
+const SUPPORTED_MIME_TYPES: &[&str] = &[
+    "text/plain",
+    "application/epub+zip",
+    "application/pdf",
+    "application/zip",
+    "application/vnd.comicbook+zip",
+    "image/jpeg",
+    "image/png",
+    "image/gif",
+    "image/webp",
+    "image/bmp",
+    "image/svg+xml",
+    "inode/directory",
+];
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::SUPPORTED_MIME_TYPES;
+    use std::path::PathBuf;
+
+    const DESKTOP_FILE: &str = "simplereader.desktop";
+
+    fn applications_dir() -> Result<PathBuf, String> {
+        dirs::data_dir()
+            .map(|d| d.join("applications"))
+            .ok_or_else(|| "Could not find XDG data directory".to_string())
+    }
+
+    /// AppImage 실행 시 실제 실행 파일은 임시 마운트 경로이므로 $APPIMAGE를 우선 사용
+    fn exec_path() -> Result<String, String> {
+        if let Ok(appimage) = std::env::var("APPIMAGE") {
+            return Ok(appimage);
+        }
+        std::env::current_exe()
+            .map(|p| p.to_string_lossy().to_string())
+            .map_err(|e| format!("Failed to get exe path: {}", e))
+    }
+
+    fn refresh_database(dir: &std::path::Path) {
+        // Best effort: not every desktop ships update-desktop-database
+        let _ = std::process::Command::new("update-desktop-database")
+            .arg(dir)
+            .status();
+    }
+
+    pub fn register() -> Result<bool, String> {
+        let dir = applications_dir()?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create applications directory: {}", e))?;
+
+        let exec = exec_path()?;
+        let content = format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=SimpleReader\n\
+             GenericName=Reader\n\
+             Comment=Open with SimpleReader\n\
+             Exec=\"{}\" %f\n\
+             Icon=simple-reader\n\
+             Terminal=false\n\
+             Categories=Office;Viewer;\n\
+             MimeType={};\n",
+            exec.replace('"', "\\\""),
+            SUPPORTED_MIME_TYPES.join(";")
+        );
+        std::fs::write(dir.join(DESKTOP_FILE), content)
+            .map_err(|e| format!("Failed to write desktop entry: {}", e))?;
+
+        refresh_database(&dir);
+        Ok(true)
+    }
+
+    pub fn unregister() -> Result<bool, String> {
+        let dir = applications_dir()?;
+        let path = dir.join(DESKTOP_FILE);
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove desktop entry: {}", e))?;
+            refresh_database(&dir);
+        }
+        Ok(true)
+    }
+
+    pub fn is_registered() -> bool {
+        applications_dir()
+            .map(|d| d.join(DESKTOP_FILE).exists())
+            .unwrap_or(false)
+    }
+}
+
+/// macOS document types are declared statically in Info.plist (CFBundleDocumentTypes,
+/// generated from `bundle.fileAssociations` in tauri.macos.conf.json). Registering here
+/// only asks LaunchServices to (re)index or forget the bundle. To make SimpleReader the
+/// default viewer for a type, use `duti -s com.simplereader.app <UTI> viewer`.
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::path::PathBuf;
+
+    const LSREGISTER: &str = "/System/Library/Frameworks/CoreServices.framework/Frameworks/LaunchServices.framework/Support/lsregister";
+
+    /// Resolve `<Name>.app` from `<Name>.app/Contents/MacOS/<exe>`.
+    fn bundle_path() -> Option<PathBuf> {
+        let exe = std::env::current_exe().ok()?;
+        let bundle = exe.parent()?.parent()?.parent()?.to_path_buf();
+        if bundle.extension().map(|e| e == "app").unwrap_or(false) {
+            Some(bundle)
+        } else {
+            None
+        }
+    }
+
+    fn lsregister(flag: &str) -> Result<bool, String> {
+        let bundle = bundle_path()
+            .ok_or_else(|| "Not running from an .app bundle".to_string())?;
+        let status = std::process::Command::new(LSREGISTER)
+            .arg(flag)
+            .arg(&bundle)
+            .status()
+            .map_err(|e| format!("Failed to run lsregister: {}", e))?;
+        Ok(status.success())
+    }
+
+    pub fn register() -> Result<bool, String> {
+        lsregister("-f")
+    }
+
+    pub fn unregister() -> Result<bool, String> {
+        lsregister("-u")
+    }
+
+    pub fn is_registered() -> bool {
+        bundle_path().is_some()
+    }
+}
@@ -0,0 +1,34 @@
+use tauri::command;
+
+use crate::AppState;
+
+/// Start a slideshow on an image tab. `loop` wraps back to the first image at the end.
+#[command]
+pub async fn start_slideshow(
+    file_id: String,
+    interval_ms: u64,
+    r#loop: bool,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .slideshow
+        .start(&app, &file_id, interval_ms, r#loop)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn pause_slideshow(file_id: String, state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.slideshow.pause(&file_id))
+}
+
+#[command]
+pub async fn resume_slideshow(file_id: String, state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.slideshow.resume(&file_id))
+}
+
+#[command]
+pub async fn stop_slideshow(file_id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.slideshow.stop(&file_id);
+    Ok(())
+}
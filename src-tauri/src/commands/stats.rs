@@ -0,0 +1,19 @@
+use crate::reading_stats::StatsSummary;
+use crate::AppState;
+use tauri::command;
+
+/// Aggregated reading stats for the dashboard. `range`: "week", "month", "year" or "all".
+#[command]
+pub async fn get_stats_summary(
+    range: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<StatsSummary, String> {
+    let stats = state.reading_stats.lock().map_err(|e| e.to_string())?;
+    stats.summary(&range).map_err(|e| e.to_string())
+}
+
+/// Seconds since the last user input (None if the platform can't report it).
+#[command]
+pub async fn get_idle_seconds() -> Result<Option<u64>, String> {
+    Ok(crate::idle::idle_seconds())
+}
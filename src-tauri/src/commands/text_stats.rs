@@ -0,0 +1,33 @@
+use crate::text_stats::{TextStats, TextStatsCounter};
+use crate::AppState;
+use tauri::command;
+
+/// Word/character/paragraph counts and estimated reading time of a text tab.
+#[command]
+pub async fn get_text_stats(
+    file_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<TextStats, String> {
+    // 잠금 안에서는 로프나 mmap 핸들만 복사한다
+    let (rope, lazy) = {
+        let tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        let buffer = tab_manager.get_buffer(&file_id).map_err(|e| e.to_string())?;
+        match buffer.lazy() {
+            // 대용량(mmap) 파일은 로프가 비어 있다
+            Some(lazy) => (None, Some(lazy.reader())),
+            None => (Some(buffer.rope().clone()), None),
+        }
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut counter = TextStatsCounter::new();
+        if let Some(rope) = rope {
+            rope.chunks().for_each(|chunk| counter.feed(chunk));
+        }
+        if let Some(lazy) = lazy {
+            lazy.for_each_chunk(|chunk| counter.feed(chunk));
+        }
+        counter.finish()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
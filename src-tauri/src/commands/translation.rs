@@ -0,0 +1,46 @@
+use crate::translation;
+use crate::AppState;
+use tauri::{command, AppHandle, Manager};
+
+/// Translate a passage with the configured provider. Paragraphs are cached individually,
+/// so only new paragraphs hit the provider.
+#[command]
+pub async fn translate_text(
+    text: String,
+    target_lang: String,
+    app: AppHandle,
+) -> Result<String, String> {
+    let config = app
+        .state::<AppState>()
+        .config
+        .read()
+        .map_err(|e| e.to_string())?
+        .clone();
+    let pending = {
+        let state = app.state::<AppState>();
+        let cache = state.translation_cache.lock().map_err(|e| e.to_string())?;
+        translation::pending_paragraphs(&cache, &config, &text, &target_lang)
+    };
+
+    // 네트워크/외부 프로세스 호출은 캐시 잠금 밖에서
+    let (config, translated) = {
+        let target_lang = target_lang.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let results = translation::translate_paragraphs(&config, &pending, &target_lang)?;
+            Ok::<_, anyhow::Error>((config, pending.into_iter().zip(results).collect()))
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?
+    };
+
+    let state = app.state::<AppState>();
+    let mut cache = state.translation_cache.lock().map_err(|e| e.to_string())?;
+    Ok(translation::assemble(
+        &mut cache,
+        &config,
+        &text,
+        &target_lang,
+        translated,
+    ))
+}
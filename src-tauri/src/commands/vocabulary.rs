@@ -0,0 +1,50 @@
+use crate::vocabulary::{self, VocabularyOptions, VocabularyReport};
+use crate::AppState;
+use std::sync::Arc;
+use tauri::command;
+
+enum Source {
+    Lazy(crate::lazy_text::LazyTextReader),
+    Rope(ropey::Rope),
+    Epub(Arc<Vec<crate::epub_reader::EpubChapter>>),
+}
+
+/// Word-frequency table and hapax list for a text or EPUB tab.
+#[command]
+pub async fn analyze_vocabulary(
+    file_id: String,
+    options: Option<VocabularyOptions>,
+    state: tauri::State<'_, AppState>,
+) -> Result<VocabularyReport, String> {
+    // 잠금 안에서는 로프/챕터 핸들만 복사하고, 텍스트 추출은 잠금을 놓은 뒤에 한다
+    let source = {
+        let mut tab_manager = state.tab_manager.lock().map_err(|e| e.to_string())?;
+        match tab_manager.get_buffer(&file_id) {
+            Ok(buffer) => match buffer.lazy() {
+                Some(lazy) => Source::Lazy(lazy.reader()),
+                None => Source::Rope(buffer.rope().clone()),
+            },
+            Err(_) => Source::Epub(tab_manager.get_epub_chapters(&file_id).map_err(|e| e.to_string())?),
+        }
+    };
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        match source {
+            // 대용량(mmap) 파일은 줄 단위로 끊은 조각씩 센다
+            Source::Lazy(lazy) => vocabulary::analyze_chunks(|feed| lazy.for_each_chunk(feed), &options),
+            Source::Rope(rope) => vocabulary::analyze(&rope.to_string(), &options),
+            // EPUB: 모든 챕터의 본문 텍스트
+            Source::Epub(chapters) => vocabulary::analyze_chunks(
+                |feed| {
+                    for chapter in chapters.iter() {
+                        feed(&chapter.plain_text());
+                    }
+                },
+                &options,
+            ),
+        }
+    })
+    .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
@@ -1,10 +1,38 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 fn default_true() -> bool {
     true
 }
 
+fn default_memory_budget_mb() -> usize {
+    512
+}
+
+fn default_margin_crop_threshold() -> u8 {
+    24
+}
+
+fn default_image_ignore_patterns() -> Vec<String> {
+    ["__MACOSX", "@eaDir", "Thumbs.db"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn default_zip_crc_check_max_mb() -> u64 {
+    crate::zip_fast::DEFAULT_CRC_CHECK_MAX_BYTES / (1024 * 1024)
+}
+
+fn default_ocr_lang() -> String {
+    "kor+eng".to_string()
+}
+
+fn default_epub_language() -> String {
+    "ko".to_string()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub font_family: String,
@@ -19,6 +47,122 @@ pub struct AppConfig {
     pub recent_files: Vec<String>,
     pub window_width: u32,
     pub window_height: u32,
+    /// Combined cap for image cache, EPUB chapter cache and text buffers.
+    #[serde(default = "default_memory_budget_mb")]
+    pub memory_budget_mb: usize,
+    /// Global hotkey that instantly hides the window (e.g. "CommandOrControl+Shift+H"). Empty = disabled.
+    #[serde(default)]
+    pub boss_key: String,
+    /// Ask the frontend to mute audio (TTS) when the boss key is pressed.
+    #[serde(default = "default_true")]
+    pub boss_key_mute_audio: bool,
+    /// Global hotkey that toggles window visibility. Empty = disabled.
+    #[serde(default)]
+    pub toggle_window_key: String,
+    /// Copy files from network paths (SMB/NAS) to a local cache before reading.
+    #[serde(default)]
+    pub remote_read_cache: bool,
+    /// Folders watched for new files, which are added to the file list automatically.
+    #[serde(default)]
+    pub watched_folders: Vec<String>,
+    /// Path to the tesseract executable for OCR. Empty = search PATH.
+    #[serde(default)]
+    pub tesseract_path: String,
+    /// Default OCR language(s), tesseract syntax (e.g. "kor+eng").
+    #[serde(default = "default_ocr_lang")]
+    pub ocr_lang: String,
+    /// Path to MuPDF's mutool executable for native PDF rendering. Empty = search PATH.
+    #[serde(default)]
+    pub mutool_path: String,
+    /// Path to the unrar executable for RAR/CBR archives. Empty = search PATH.
+    #[serde(default)]
+    pub unrar_path: String,
+    /// Path to the 7-Zip executable for 7z/CB7 archives. Empty = search PATH.
+    #[serde(default)]
+    pub sevenzip_path: String,
+    /// Path to ImageMagick (`magick`) for AVIF/HEIC/JPEG XL images. Empty = search PATH.
+    #[serde(default)]
+    pub imagemagick_path: String,
+    /// How far (0–255) a pixel may be from pure white/black and still count as margin
+    /// when auto-cropping scanned pages.
+    #[serde(default = "default_margin_crop_threshold")]
+    pub margin_crop_threshold: u8,
+    /// File or folder names skipped when listing images in archives and folders
+    /// (`*` at either end matches any prefix/suffix). Dot files and folders are always skipped.
+    #[serde(default = "default_image_ignore_patterns")]
+    pub image_ignore_patterns: Vec<String>,
+    /// Largest ZIP entry (MB, uncompressed) whose CRC is checked when read. 0 = never check.
+    #[serde(default = "default_zip_crc_check_max_mb")]
+    pub zip_crc_check_max_mb: u64,
+    /// Night-mode filter for image pages.
+    #[serde(default)]
+    pub image_filter: ImageFilter,
+    /// Translation provider: "" (disabled), "deepl", "http" (LibreTranslate-compatible) or "command".
+    #[serde(default)]
+    pub translation_provider: String,
+    /// Endpoint URL for "http" (optional override for "deepl").
+    #[serde(default)]
+    pub translation_endpoint: String,
+    #[serde(default)]
+    pub translation_api_key: String,
+    /// Command line for "command"; `{target}` is replaced with the target language.
+    #[serde(default)]
+    pub translation_command: String,
+    /// Keep the previous version of a text file as `<name>.bak` when saving.
+    #[serde(default)]
+    pub keep_backup_on_save: bool,
+    /// CSS applied on top of every EPUB's own styles.
+    #[serde(default)]
+    pub epub_user_css: String,
+    /// Drop the publisher's stylesheets and inline styles from EPUB chapters.
+    #[serde(default)]
+    pub epub_disable_publisher_styles: bool,
+    /// Per-book overrides, keyed by file path.
+    #[serde(default)]
+    pub epub_book_styles: HashMap<String, EpubBookStyle>,
+    /// Don't load remote (http/https) images and CSS resources in EPUB chapters.
+    #[serde(default = "default_true")]
+    pub epub_block_remote_images: bool,
+    /// `dc:language` (BCP 47) of EPUBs made from text files, unless given per conversion.
+    #[serde(default = "default_epub_language")]
+    pub epub_language: String,
+}
+
+/// Per-book EPUB style settings (added after the global `epub_user_css`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EpubBookStyle {
+    #[serde(default)]
+    pub css: String,
+    /// Overrides `epub_disable_publisher_styles` for this book.
+    #[serde(default)]
+    pub disable_publisher_styles: Option<bool>,
+}
+
+/// Brightness/contrast/invert/sepia applied to image pages before they are sent,
+/// so the frontend doesn't have to filter every page on a canvas.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageFilter {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Swap light and dark (white pages become black).
+    #[serde(default)]
+    pub invert: bool,
+    /// -100–100 (%); negative values dim the page.
+    #[serde(default)]
+    pub brightness: i32,
+    /// -100–100 (%).
+    #[serde(default)]
+    pub contrast: i32,
+    #[serde(default)]
+    pub sepia: bool,
+}
+
+impl ImageFilter {
+    /// The filter to apply, or None when it is disabled or changes nothing.
+    pub fn active(&self) -> Option<ImageFilter> {
+        let changes = self.invert || self.brightness != 0 || self.contrast != 0 || self.sepia;
+        (self.enabled && changes).then(|| self.clone())
+    }
 }
 
 impl Default for AppConfig {
@@ -33,6 +177,32 @@ impl Default for AppConfig {
             recent_files: Vec::new(),
             window_width: 1200,
             window_height: 800,
+            memory_budget_mb: default_memory_budget_mb(),
+            boss_key: String::new(),
+            boss_key_mute_audio: true,
+            toggle_window_key: String::new(),
+            remote_read_cache: false,
+            watched_folders: Vec::new(),
+            tesseract_path: String::new(),
+            ocr_lang: default_ocr_lang(),
+            mutool_path: String::new(),
+            unrar_path: String::new(),
+            sevenzip_path: String::new(),
+            imagemagick_path: String::new(),
+            margin_crop_threshold: default_margin_crop_threshold(),
+            image_ignore_patterns: default_image_ignore_patterns(),
+            zip_crc_check_max_mb: default_zip_crc_check_max_mb(),
+            image_filter: ImageFilter::default(),
+            translation_provider: String::new(),
+            translation_endpoint: String::new(),
+            translation_api_key: String::new(),
+            translation_command: String::new(),
+            keep_backup_on_save: false,
+            epub_user_css: String::new(),
+            epub_disable_publisher_styles: false,
+            epub_book_styles: HashMap::new(),
+            epub_block_remote_images: true,
+            epub_language: default_epub_language(),
         }
     }
 }
@@ -40,11 +210,14 @@ impl Default for AppConfig {
 impl AppConfig {
     pub fn load() -> anyhow::Result<Self> {
         let path = Self::config_path()?;
-        if path.exists() {
-            let content = std::fs::read_to_string(&path)?;
-            Ok(serde_json::from_str(&content)?)
-        } else {
-            Ok(Self::default())
+        match crate::store_recovery::load_struct::<Self>(&path)? {
+            Some((config, recovered)) => {
+                if recovered {
+                    config.save()?;
+                }
+                Ok(config)
+            }
+            None => Ok(Self::default()),
         }
     }
 
@@ -58,6 +231,19 @@ impl AppConfig {
         Ok(())
     }
 
+    /// User CSS (global, then per-book) and whether to drop publisher styles for a book.
+    pub fn epub_styles_for(&self, file_path: &str) -> (String, bool) {
+        let book = self.epub_book_styles.get(file_path);
+        let css = match book.map(|b| b.css.trim()).filter(|css| !css.is_empty()) {
+            Some(book_css) => format!("{}\n{}", self.epub_user_css, book_css),
+            None => self.epub_user_css.clone(),
+        };
+        let disable = book
+            .and_then(|b| b.disable_publisher_styles)
+            .unwrap_or(self.epub_disable_publisher_styles);
+        (css, disable)
+    }
+
     fn config_path() -> anyhow::Result<PathBuf> {
         let home = dirs::home_dir()
             .ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
@@ -0,0 +1,106 @@
+//! Cover thumbnails for the library bookshelf, cached in `~/.simple-reader/covers/`.
+//!
+//! EPUB은 OPF에 지정된 표지(없으면 첫 이미지), ZIP/CBZ·RAR/CBR·7z/CB7과 이미지 폴더는 첫 페이지를
+//! 줄여서 JPEG로 저장한다 (`disk_cache::COVERS`). 표지가 없는 파일은 빈 캐시 항목을 남겨
+//! 다시 찾지 않지만, 도구가 없거나 읽기/디코드에 실패한 경우는 캐시하지 않고 다음에 다시 시도한다.
+//! PDF는 첫 페이지를 mutool로 렌더링한다.
+
+use base64::Engine;
+use std::path::Path;
+
+use crate::config::AppConfig;
+use crate::disk_cache;
+
+/// Cover thumbnail (JPEG data URI) for a book file, or None if it has no usable cover.
+pub fn cover_thumbnail(path: &Path, max_size: u32, config: &AppConfig) -> anyhow::Result<Option<String>> {
+    let name = format!("{}.jpg", disk_cache::source_key(path, &[&max_size.to_le_bytes()]));
+    let thumb = match disk_cache::COVERS.read(&name) {
+        Some(bytes) => bytes,
+        None => {
+            let found = find_cover(path, config).and_then(|cover| match cover {
+                Some(bytes) => crate::image_reader::encode_thumbnail(&bytes, max_size)
+                    .ok_or_else(|| anyhow::anyhow!("Could not decode the cover")),
+                // 표지가 없다는 것도 기억해 둔다
+                None => Ok(Vec::new()),
+            });
+            let thumb = match found {
+                Ok(thumb) => thumb,
+                Err(e) => {
+                    log::warn!("No cover for {}: {}", path.display(), e);
+                    return Ok(None);
+                }
+            };
+            disk_cache::COVERS.write(&name, &thumb)?;
+            thumb
+        }
+    };
+    if thumb.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(format!(
+        "data:image/jpeg;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(thumb)
+    )))
+}
+
+/// Full-size cover image bytes; Ok(None) when the book has no cover or pages.
+fn find_cover(path: &Path, config: &AppConfig) -> anyhow::Result<Option<Vec<u8>>> {
+    if path.is_dir() {
+        let (_, images) = crate::image_reader::scan_directory_images(path)?;
+        return match images.first() {
+            Some(first) => Ok(Some(std::fs::read(first)?)),
+            None => Ok(None),
+        };
+    }
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "epub" => epub_cover(path),
+        "pdf" => Ok(Some(crate::pdf_render::render_page(path, 0, 1.0, &config.mutool_path)?)),
+        "zip" | "cbz" => match crate::image_reader::list_zip_images(path)?.into_iter().next() {
+            Some((name, _)) => Ok(Some(crate::image_reader::read_zip_image(path, &name)?)),
+            None => Ok(None),
+        },
+        "rar" | "cbr" => {
+            let unrar = crate::rar_reader::find_unrar(&config.unrar_path)?;
+            let listing = crate::rar_reader::list_images(&unrar, path)?;
+            match listing.entry_names.first() {
+                // 표지 하나 때문에 솔리드 아카이브 전체를 풀지 않는다
+                Some(name) => Ok(Some(crate::rar_reader::read_entry(&unrar, path, name, false, None)?)),
+                None => Ok(None),
+            }
+        }
+        "7z" | "cb7" => {
+            let tool = crate::sevenz_reader::find_7z(&config.sevenzip_path)?;
+            let listing = crate::sevenz_reader::list_images(&tool, path)?;
+            match listing.entry_names.first() {
+                // 표지 하나 때문에 솔리드 아카이브 전체를 풀지 않는다
+                Some(name) => Ok(Some(crate::sevenz_reader::read_entry(&tool, path, name, false, None)?)),
+                None => Ok(None),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+fn epub_cover(path: &Path) -> anyhow::Result<Option<Vec<u8>>> {
+    let mut doc = epub::doc::EpubDoc::new(path)?;
+    if let Some((bytes, _)) = doc.get_cover() {
+        return Ok(Some(bytes));
+    }
+    // 표지 지정이 없으면 첫 번째 이미지 리소스
+    let mut images: Vec<(String, String)> = doc
+        .resources
+        .iter()
+        .filter(|(_, resource)| resource.mime.starts_with("image/"))
+        .map(|(id, resource)| (resource.path.to_string_lossy().to_string(), id.clone()))
+        .collect();
+    images.sort();
+    Ok(images
+        .into_iter()
+        .next()
+        .and_then(|(_, id)| doc.get_resource(&id))
+        .map(|(bytes, _)| bytes))
+}
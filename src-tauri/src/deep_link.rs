@@ -0,0 +1,69 @@
+//! `simplereader://open?path=...&line=...` 딥링크 처리.
+//!
+//! 링크의 line 값은 bookmark store의 last_position으로 기록한 뒤, 기존
+//! `open-file-from-args` 이벤트로 파일을 열게 해서 프론트엔드가 별도 처리 없이
+//! 해당 위치로 이동하도록 한다.
+
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use url::Url;
+
+use crate::AppState;
+
+pub const SCHEME: &str = "simplereader";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeepLinkTarget {
+    pub path: String,
+    pub line: Option<usize>,
+}
+
+/// Parse a `simplereader://open?path=...&line=...` URL.
+pub fn parse_open_url(url: &Url) -> Option<DeepLinkTarget> {
+    if url.scheme() != SCHEME {
+        return None;
+    }
+    // simplereader://open?... → host = "open"; simplereader:open?... → path = "open"
+    let action = url.host_str().unwrap_or_else(|| url.path().trim_matches('/'));
+    if action != "open" {
+        return None;
+    }
+
+    let mut path = None;
+    let mut line = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "path" => path = Some(value.to_string()),
+            "line" => line = value.parse::<usize>().ok(),
+            _ => {}
+        }
+    }
+
+    Some(DeepLinkTarget { path: path?, line })
+}
+
+/// Handle URLs delivered by the deep-link plugin (or forwarded from a second instance).
+pub fn handle_urls<R: Runtime>(app: &AppHandle<R>, urls: Vec<Url>) {
+    for url in urls {
+        let Some(target) = parse_open_url(&url) else {
+            log::warn!("Ignoring unsupported deep link: {}", url);
+            continue;
+        };
+        if !std::path::Path::new(&target.path).exists() {
+            log::warn!("Deep link target not found: {}", target.path);
+            continue;
+        }
+
+        if let Some(line) = target.line {
+            let state = app.state::<AppState>();
+            let store = state.bookmark_store.lock();
+            if let Ok(mut store) = store {
+                let _ = store.track_file_open(&target.path);
+                let _ = store.save_last_position(&target.path, line, 0);
+            }
+            // Already-open tabs pick up the new position in open_file → set_last_position
+        }
+
+        crate::focus_main_window(app);
+        let _ = app.emit("open-file-from-args", target.path);
+    }
+}
@@ -0,0 +1,161 @@
+//! Size-bounded file caches under `~/.simple-reader/` (covers, page thumbnails, OCR results,
+//! local copies of remote files).
+//!
+//! 항목 이름은 `source_key`로 원본 경로·크기·수정 시각에서 만들어서, 파일이 바뀌면 새 항목이
+//! 생긴다. 읽을 때 수정 시각을 갱신하고, 용량을 넘으면 오래 안 쓴 항목부터 지운다.
+
+use sha1::{Digest, Sha1};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MB: u64 = 1024 * 1024;
+
+/// A cache is pruned once every this many writes (and at startup).
+const PRUNE_EVERY_WRITES: usize = 64;
+
+pub static COVERS: DiskCache = DiskCache::new("covers", 64 * MB);
+pub static PDF_THUMBS: DiskCache = DiskCache::new("pdf_thumbs", 256 * MB);
+pub static IMAGE_THUMBS: DiskCache = DiskCache::new("image_thumbs", 256 * MB);
+pub static OCR: DiskCache = DiskCache::new("ocr_cache", 64 * MB);
+/// Copies of files on network shares (`remote_io`), one folder per copy.
+pub static REMOTE_FILES: DiskCache = DiskCache::new("remote_cache", 2048 * MB);
+
+pub struct DiskCache {
+    dir_name: &'static str,
+    max_bytes: u64,
+    writes: AtomicUsize,
+}
+
+impl DiskCache {
+    const fn new(dir_name: &'static str, max_bytes: u64) -> Self {
+        Self {
+            dir_name,
+            max_bytes,
+            writes: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn dir(&self) -> anyhow::Result<PathBuf> {
+        let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+        Ok(home.join(".simple-reader").join(self.dir_name))
+    }
+
+    /// Whether an entry exists (without marking it used).
+    pub fn contains(&self, name: &str) -> bool {
+        self.dir().is_ok_and(|dir| dir.join(name).is_file())
+    }
+
+    /// Read an entry and mark it recently used.
+    pub fn read(&self, name: &str) -> Option<Vec<u8>> {
+        let path = self.dir().ok()?.join(name);
+        let bytes = std::fs::read(&path).ok()?;
+        if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(bytes)
+    }
+
+    pub fn write(&self, name: &str, bytes: &[u8]) -> anyhow::Result<()> {
+        let dir = self.dir()?;
+        std::fs::create_dir_all(&dir)?;
+        let count = self.writes.fetch_add(1, Ordering::Relaxed);
+        // 다 쓴 뒤에 이름을 바꿔서, 중간에 죽어도 반쯤 쓴 항목이 캐시 적중으로 읽히지 않게 한다
+        let part = dir.join(format!("{}.{}-{}.part", name, std::process::id(), count));
+        let result = std::fs::write(&part, bytes).and_then(|_| std::fs::rename(&part, dir.join(name)));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&part);
+        }
+        result?;
+        if count % PRUNE_EVERY_WRITES == PRUNE_EVERY_WRITES - 1 {
+            self.prune();
+        }
+        Ok(())
+    }
+
+    /// Delete the least recently used entries until the cache is under 3/4 of its limit.
+    /// An entry may be a folder of files; it counts as used when its newest file was.
+    pub fn prune(&self) {
+        let Ok(entries) = self.dir().and_then(|dir| Ok(std::fs::read_dir(dir)?)) else {
+            return;
+        };
+        let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                if metadata.is_dir() {
+                    let (modified, len) = folder_usage(&entry.path());
+                    return Some((modified, len, entry.path()));
+                }
+                metadata
+                    .is_file()
+                    .then(|| (metadata.modified().unwrap_or(UNIX_EPOCH), metadata.len(), entry.path()))
+            })
+            .collect();
+        let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+        if total <= self.max_bytes {
+            return;
+        }
+        files.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in files {
+            if total <= self.max_bytes / 4 * 3 {
+                break;
+            }
+            let removed = if path.is_dir() {
+                std::fs::remove_dir_all(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+            if removed.is_ok() {
+                total -= len;
+            }
+        }
+    }
+}
+
+/// (newest modification time, total size) of the files directly in `dir`.
+fn folder_usage(dir: &Path) -> (SystemTime, u64) {
+    let files = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file());
+    files.fold((UNIX_EPOCH, 0), |(newest, total), metadata| {
+        (
+            newest.max(metadata.modified().unwrap_or(UNIX_EPOCH)),
+            total + metadata.len(),
+        )
+    })
+}
+
+/// Trim every cache to its limit (run once at startup).
+pub fn prune_all() {
+    for cache in [&COVERS, &PDF_THUMBS, &IMAGE_THUMBS, &OCR, &REMOTE_FILES] {
+        cache.prune();
+    }
+}
+
+/// Hex SHA-1 of `source`'s path, size and modification time plus `parts`,
+/// so entries made from an older version of the file are never returned.
+pub fn source_key(source: &Path, parts: &[&[u8]]) -> String {
+    let metadata = std::fs::metadata(source).ok();
+    let mtime_ms = metadata
+        .as_ref()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let mut hasher = Sha1::new();
+    hasher.update(source.to_string_lossy().as_bytes());
+    hasher.update(metadata.map(|m| m.len()).unwrap_or(0).to_le_bytes());
+    hasher.update(mtime_ms.to_le_bytes());
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,120 @@
+//! Duplicate detection across tracked files.
+//!
+//! 크기로 먼저 후보를 좁히고, 앞/뒤 일부만 해시한 뒤, 그래도 겹치는 파일만
+//! 전체 해시로 확인한다. 대용량 다운로드 폴더도 대부분 크기 비교에서 끝난다.
+
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Bytes hashed from each end of a file for the partial hash.
+const PARTIAL_HASH_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub size: u64,
+    pub files: Vec<String>,
+}
+
+/// Hash of the first and last `PARTIAL_HASH_BYTES`; files up to twice that are hashed whole,
+/// so for them the partial hash is the full hash.
+fn partial_hash(path: &Path, size: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha1::new();
+    if size <= PARTIAL_HASH_BYTES * 2 {
+        let mut buf = vec![0u8; size as usize];
+        file.read_exact(&mut buf)?;
+        hasher.update(&buf);
+        return Ok(hasher.finalize().to_vec());
+    }
+    let mut buf = vec![0u8; PARTIAL_HASH_BYTES as usize];
+    file.read_exact(&mut buf)?;
+    hasher.update(&buf);
+    file.seek(SeekFrom::End(-(PARTIAL_HASH_BYTES as i64)))?;
+    file.read_exact(&mut buf)?;
+    hasher.update(&buf);
+    Ok(hasher.finalize().to_vec())
+}
+
+fn full_hash(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha1::new();
+    let mut buf = vec![0u8; 256 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Group `paths` by identical content. Missing/unreadable files and directories are skipped.
+/// Paths naming the same file (other spelling, `..`, symlinks) are hashed once, as the first of them.
+pub fn find_duplicates(paths: &[String]) -> Vec<DuplicateGroup> {
+    let mut seen = HashSet::new();
+    let mut by_size: HashMap<u64, Vec<&String>> = HashMap::new();
+    for path in paths {
+        let Ok(canonical) = std::fs::canonicalize(path) else {
+            continue;
+        };
+        if !seen.insert(canonical) {
+            continue;
+        }
+        if let Ok(meta) = std::fs::metadata(path) {
+            if meta.is_file() && meta.len() > 0 {
+                by_size.entry(meta.len()).or_default().push(path);
+            }
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (size, candidates) in by_size {
+        if candidates.len() < 2 {
+            continue;
+        }
+
+        let mut by_partial: HashMap<Vec<u8>, Vec<&String>> = HashMap::new();
+        for path in candidates {
+            if let Ok(hash) = partial_hash(Path::new(path), size) {
+                by_partial.entry(hash).or_default().push(path);
+            }
+        }
+
+        for (_, partial_matches) in by_partial {
+            if partial_matches.len() < 2 {
+                continue;
+            }
+            // 작은 파일은 partial hash가 곧 전체 해시
+            if size <= PARTIAL_HASH_BYTES * 2 {
+                groups.push(group(size, partial_matches));
+                continue;
+            }
+            let mut by_full: HashMap<Vec<u8>, Vec<&String>> = HashMap::new();
+            for path in partial_matches {
+                if let Ok(hash) = full_hash(Path::new(path)) {
+                    by_full.entry(hash).or_default().push(path);
+                }
+            }
+            groups.extend(
+                by_full
+                    .into_values()
+                    .filter(|files| files.len() > 1)
+                    .map(|files| group(size, files)),
+            );
+        }
+    }
+
+    // 큰 파일부터 (정리 효과가 큰 순서)
+    groups.sort_by_key(|g| std::cmp::Reverse(g.size));
+    groups
+}
+
+fn group(size: u64, files: Vec<&String>) -> DuplicateGroup {
+    let mut files: Vec<String> = files.into_iter().cloned().collect();
+    files.sort();
+    DuplicateGroup { size, files }
+}
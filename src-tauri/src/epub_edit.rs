@@ -0,0 +1,41 @@
+//! Writing edited chapter XHTML back into an .epub.
+//!
+//! 나머지 항목은 압축을 풀지 않고 그대로 복사(`raw_copy_file`)하므로 순서와 압축 방식
+//! (맨 앞의 무압축 `mimetype` 포함)이 유지된다. 저장은 atomic_write로 임시 파일에 쓴 뒤 교체한다.
+
+use std::io::Write;
+use std::path::Path;
+
+/// Replace the contents of `entry_name` in the EPUB at `path`.
+pub fn replace_entry(path: &Path, entry_name: &str, data: &[u8]) -> anyhow::Result<()> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    if archive.index_for_name(entry_name).is_none() {
+        anyhow::bail!("Entry not found in EPUB: {}", entry_name);
+    }
+
+    crate::atomic_write::write_atomic(path, |writer| {
+        let mut zip = zip::ZipWriter::new(writer);
+        for i in 0..archive.len() {
+            let entry = archive.by_index_raw(i).map_err(std::io::Error::other)?;
+            if entry.name() == entry_name {
+                drop(entry);
+                let options = zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated);
+                zip.start_file(entry_name, options).map_err(std::io::Error::other)?;
+                zip.write_all(data)?;
+            } else {
+                zip.raw_copy_file(entry).map_err(std::io::Error::other)?;
+            }
+        }
+        zip.finish().map_err(std::io::Error::other)?;
+        Ok(())
+    })
+}
+
+/// Read an entry as UTF-8 text for editing (a UTF-8 BOM is dropped).
+pub fn read_entry_text(path: &Path, entry_name: &str) -> anyhow::Result<String> {
+    let bytes = crate::zip_fast::ZipIndex::open(path)?.read_entry(entry_name)?;
+    let text = String::from_utf8(bytes)
+        .map_err(|_| anyhow::anyhow!("Chapter is not UTF-8 and can't be edited: {}", entry_name))?;
+    Ok(text.strip_prefix('\u{feff}').map(str::to_string).unwrap_or(text))
+}
@@ -0,0 +1,63 @@
+//! Lazy serving of EPUB embedded fonts over the `epubfont` URI scheme.
+//!
+//! 폰트를 base64로 CSS에 통째로 넣으면 webview 메모리가 수십 MB씩 늘어나므로,
+//! font_styles에는 URL만 넣고 실제 폰트는 webview가 요청할 때 내려준다.
+
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::AppState;
+
+pub const SCHEME: &str = "epubfont";
+
+/// URL prefix for a tab's fonts; the font index is appended.
+pub fn font_base_url(file_id: &str) -> String {
+    let encoded: String = url::form_urlencoded::byte_serialize(file_id.as_bytes()).collect();
+    // Windows(WebView2)는 커스텀 스킴을 http://<scheme>.localhost 로 노출한다
+    #[cfg(windows)]
+    let origin = format!("http://{}.localhost", SCHEME);
+    #[cfg(not(windows))]
+    let origin = format!("{}://localhost", SCHEME);
+    format!("{}/?id={}&font=", origin, encoded)
+}
+
+fn error_response(status: StatusCode) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .body(Vec::new())
+        .unwrap_or_default()
+}
+
+/// Handle `epubfont://localhost/?id=<file_id>&font=<index>`.
+pub fn handle_request<R: Runtime>(app: &AppHandle<R>, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let Ok(url) = url::Url::parse(&request.uri().to_string()) else {
+        return error_response(StatusCode::BAD_REQUEST);
+    };
+    let mut file_id = None;
+    let mut font_index = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "id" => file_id = Some(value.to_string()),
+            "font" => font_index = value.parse::<usize>().ok(),
+            _ => {}
+        }
+    }
+    let (Some(file_id), Some(font_index)) = (file_id, font_index) else {
+        return error_response(StatusCode::BAD_REQUEST);
+    };
+
+    let state = app.state::<AppState>();
+    let font = match state.tab_manager.lock() {
+        Ok(mut tab_manager) => tab_manager.get_epub_font(&file_id, font_index),
+        Err(_) => return error_response(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    match font {
+        Ok((mime, data)) => Response::builder()
+            .header(header::CONTENT_TYPE, mime)
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .header(header::CACHE_CONTROL, "max-age=86400")
+            .body(data)
+            .unwrap_or_default(),
+        Err(_) => error_response(StatusCode::NOT_FOUND),
+    }
+}
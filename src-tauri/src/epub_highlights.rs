@@ -0,0 +1,105 @@
+//! Re-inserts saved highlights into processed EPUB chapter HTML.
+//!
+//! 오프셋은 챕터(또는 `#id` 요소) 텍스트 노드를 이어 붙인 문자열의 글자 위치로,
+//! 브라우저의 `textContent`와 같은 방식으로 센다 (`<style>`/`<script>` 제외, 엔티티는 한 글자).
+//! 태그 구조를 건드리지 않도록 텍스트 노드마다 따로 `<mark>`로 감싼다.
+
+use crate::bookmark::EpubHighlight;
+use crate::html_text::{unit_len, walk, Segment, ID_RE};
+use std::collections::HashMap;
+
+struct Range<'a> {
+    start: usize,
+    end: usize,
+    id: &'a str,
+    color: String,
+}
+
+/// Wrap the highlights of `chapter_index` in `<mark class="epub-highlight">` elements.
+pub fn inject_highlights(html: &str, chapter_index: usize, highlights: &[EpubHighlight]) -> String {
+    if !highlights.iter().any(|h| h.chapter_index == chapter_index) {
+        return html.to_string();
+    }
+    // 1차: 요소 id별 텍스트 시작 위치
+    let mut id_offsets = HashMap::new();
+    walk(html, |segment| {
+        if let Segment::Tag(tag, offset) = segment {
+            if let Some(caps) = ID_RE.captures(tag) {
+                id_offsets.entry(caps[1].to_string()).or_insert(offset);
+            }
+        }
+    });
+
+    let ranges: Vec<Range> = highlights
+        .iter()
+        .filter(|h| h.chapter_index == chapter_index && h.start_offset < h.end_offset)
+        .filter_map(|h| {
+            let base = match h.selector.as_deref() {
+                None | Some("") => 0,
+                Some(selector) => *id_offsets.get(selector.trim_start_matches('#'))?,
+            };
+            Some(Range {
+                start: base + h.start_offset,
+                end: base + h.end_offset,
+                id: &h.id,
+                color: sanitize_color(&h.color),
+            })
+        })
+        .collect();
+
+    // 2차: 텍스트 노드 안에서 범위에 걸친 부분만 감싼다
+    let mut out = String::with_capacity(html.len() + ranges.len() * 96);
+    walk(html, |segment| match segment {
+        Segment::Tag(raw, _) | Segment::Raw(raw) => out.push_str(raw),
+        Segment::Text(text, offset) if !text.is_empty() => {
+            let mut pos = offset;
+            let mut run_start = 0;
+            let mut run_range: Option<&Range> = None;
+            let mut i = 0;
+            while i < text.len() {
+                let len = unit_len(&text[i..]);
+                let active = ranges.iter().find(|r| r.start <= pos && pos < r.end);
+                if active.map(|r| r.id) != run_range.map(|r| r.id) {
+                    push_run(&mut out, &text[run_start..i], run_range);
+                    run_start = i;
+                    run_range = active;
+                }
+                pos += 1;
+                i += len;
+            }
+            push_run(&mut out, &text[run_start..], run_range);
+        }
+        Segment::Text(..) => {}
+    });
+    out
+}
+
+fn push_run(out: &mut String, text: &str, range: Option<&Range>) {
+    if text.is_empty() {
+        return;
+    }
+    match range {
+        Some(range) => {
+            out.push_str(&format!(
+                "<mark class=\"epub-highlight\" data-highlight-id=\"{}\" style=\"background-color: {}\">",
+                range.id, range.color
+            ));
+            out.push_str(text);
+            out.push_str("</mark>");
+        }
+        None => out.push_str(text),
+    }
+}
+
+/// Keep only characters that can appear in a CSS color value.
+fn sanitize_color(color: &str) -> String {
+    let cleaned: String = color
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '#' | '(' | ')' | ',' | '.' | '%' | ' '))
+        .collect();
+    if cleaned.trim().is_empty() {
+        "yellow".to_string()
+    } else {
+        cleaned
+    }
+}
@@ -1,24 +1,132 @@
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Serialize)]
+use crate::html_text::decode_entities;
+
+/// 처리된 챕터 HTML을 최대 몇 개까지 캐시에 보관할지.
+const MAX_CACHED_CHAPTERS: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChapterInfo {
     pub index: usize,
     pub title: String,
+    /// `lang` / `xml:lang` of the chapter document, if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// `dir` of the chapter document ("ltr" / "rtl"), if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+    /// Visible text length in chars (weights the whole-book progress).
+    #[serde(default)]
+    pub text_length: usize,
+    /// Words, with Chinese/Japanese characters counted one each.
+    #[serde(default)]
+    pub word_count: usize,
+    #[serde(default)]
+    pub estimated_minutes: f64,
+}
+
+/// How an EPUB is laid out, for paginating vertical / right-to-left books.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EpubLayout {
+    /// `writing-mode` used by the book's CSS ("vertical-rl", "vertical-lr"), None = horizontal.
+    pub writing_mode: Option<String>,
+    /// Spine `page-progression-direction` ("ltr" / "rtl").
+    pub page_direction: Option<String>,
+}
+
+/// Book-level language, reading direction and accessibility metadata (from the OPF).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EpubMetadata {
+    /// First `dc:language` (BCP 47, e.g. "ko", "ja").
+    pub language: Option<String>,
+    pub languages: Vec<String>,
+    /// Spine `page-progression-direction`: "ltr", "rtl" or None (default).
+    pub page_direction: Option<String>,
+    /// `rendition:layout` ("reflowable" / "pre-paginated").
+    pub layout: Option<String>,
+    pub accessibility: AccessibilityMetadata,
+}
+
+/// schema.org accessibility properties (EPUB Accessibility 1.x).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AccessibilityMetadata {
+    pub access_modes: Vec<String>,
+    pub access_modes_sufficient: Vec<String>,
+    pub features: Vec<String>,
+    pub hazards: Vec<String>,
+    pub summary: Option<String>,
+    /// `dcterms:conformsTo` (e.g. "EPUB Accessibility 1.1 - WCAG 2.1 Level AA").
+    pub conforms_to: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct EpubChapter {
     pub title: String,
-    pub html: String,
+    /// Path of the chapter document inside the EPUB (used to resolve relative links).
+    pub path: String,
+    /// Raw (X)HTML source; processed into displayable HTML on demand.
+    pub source: String,
+    pub language: Option<String>,
+    pub direction: Option<String>,
+    pub text_length: usize,
+    pub word_count: usize,
+    pub estimated_minutes: f64,
+}
+
+impl EpubChapter {
+    /// Body text with tags stripped.
+    pub fn plain_text(&self) -> String {
+        html_to_plain_text(&extract_body_content(&self.source))
+    }
+}
+
+/// Font file embedded in the EPUB, served on demand over the `epubfont` protocol.
+pub struct EmbeddedFont {
+    pub mime: &'static str,
+    pub data: Vec<u8>,
+}
+
+/// Stands in for the protocol URL in `font_styles` until the tab id is known.
+const FONT_URL_PLACEHOLDER: &str = "epubfont-placeholder:";
+
+/// Scheme that internal links are rewritten to: `epublink:OEBPS/chap05.xhtml#sec2`
+/// (the full path inside the EPUB, resolved with `EpubBook::resolve_link`).
+pub const EPUB_LINK_SCHEME: &str = "epublink:";
+
+/// Chapter an internal link points at.
+#[derive(Debug, Clone, Serialize)]
+pub struct EpubLinkTarget {
+    pub chapter_index: usize,
+    /// Element id to scroll to, without the `#`.
+    pub fragment: Option<String>,
 }
 
 pub struct EpubBook {
-    pub font_styles: String,
-    pub chapters: Vec<EpubChapter>,
+    /// @font-face CSS; font URLs use `FONT_URL_PLACEHOLDER` (see `font_styles_for`).
+    font_styles: String,
+    /// Only fonts referenced by the book's styles are kept, keyed by font index.
+    fonts: HashMap<usize, EmbeddedFont>,
+    /// Shared so commands can work on the chapter sources without holding the tab lock.
+    pub chapters: Arc<Vec<EpubChapter>>,
+    /// Vertical `writing-mode` found in the book's styles (see `EpubLayout`).
+    pub writing_mode: Option<String>,
+    /// Spine `page-progression-direction` ("ltr" / "rtl"), read while parsing.
+    pub page_direction: Option<String>,
+    /// Shared with background prefetch jobs (see `ChapterRenderJob`).
+    image_map: Arc<HashMap<String, String>>,
+    css_map: Arc<HashMap<String, String>>,
+    /// Processed chapter HTML, ordered from oldest (front) to newest (back).
+    chapter_cache: VecDeque<(usize, String)>,
+    /// Unique per parse, so a job rendered from an older parse of the file isn't cached.
+    generation: u64,
 }
 
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
 impl EpubBook {
     pub fn get_chapter_infos(&self) -> Vec<ChapterInfo> {
         self.chapters
@@ -27,16 +135,185 @@ impl EpubBook {
             .map(|(i, ch)| ChapterInfo {
                 index: i,
                 title: ch.title.clone(),
+                language: ch.language.clone(),
+                direction: ch.direction.clone(),
+                text_length: ch.text_length,
+                word_count: ch.word_count,
+                estimated_minutes: ch.estimated_minutes,
             })
             .collect()
     }
 
-    pub fn get_chapter_html(&self, index: usize) -> Option<String> {
-        self.chapters.get(index).map(|ch| ch.html.clone())
+    /// Get processed chapter HTML, rendering it on first access and caching the result.
+    pub fn get_chapter_html(&mut self, index: usize) -> Option<String> {
+        if let Some(pos) = self.chapter_cache.iter().position(|(i, _)| *i == index) {
+            // Move to back (most recently used)
+            let entry = self.chapter_cache.remove(pos)?;
+            let html = entry.1.clone();
+            self.chapter_cache.push_back(entry);
+            return Some(html);
+        }
+
+        let chapter = self.chapters.get(index)?;
+        let html = process_chapter_html(
+            &chapter.source,
+            &chapter.path,
+            &self.image_map,
+            &self.css_map,
+        );
+        self.push_cached(index, html.clone());
+        Some(html)
+    }
+
+    /// Job that renders chapter `index` off the lock, or None if it is already cached.
+    pub fn render_job(&self, index: usize) -> Option<ChapterRenderJob> {
+        if self.chapter_cache.iter().any(|(i, _)| *i == index) {
+            return None;
+        }
+        let chapter = self.chapters.get(index)?;
+        Some(ChapterRenderJob {
+            index,
+            source: chapter.source.clone(),
+            path: chapter.path.clone(),
+            image_map: Arc::clone(&self.image_map),
+            css_map: Arc::clone(&self.css_map),
+            generation: self.generation,
+        })
+    }
+
+    /// Store a chapter rendered by a `ChapterRenderJob` (ignored if it got cached meanwhile,
+    /// or the job was made from an earlier parse of the file).
+    pub fn insert_rendered(&mut self, job: &ChapterRenderJob, html: String) {
+        let index = job.index;
+        if job.generation == self.generation
+            && index < self.chapters.len()
+            && !self.chapter_cache.iter().any(|(i, _)| *i == index)
+        {
+            self.push_cached(index, html);
+        }
+    }
+
+    fn push_cached(&mut self, index: usize, html: String) {
+        while self.chapter_cache.len() >= MAX_CACHED_CHAPTERS {
+            self.chapter_cache.pop_front();
+        }
+        self.chapter_cache.push_back((index, html));
+    }
+
+    /// Get a chapter as plain text (tags stripped, block elements become line breaks).
+    pub fn get_chapter_text(&self, index: usize) -> Option<String> {
+        self.chapters.get(index).map(EpubChapter::plain_text)
+    }
+
+    /// Whole book as plain text: each chapter under its title heading (unless the chapter
+    /// already starts with it), chapters separated by a blank line.
+    pub fn to_plain_text(&self) -> String {
+        let mut out = String::new();
+        for chapter in self.chapters.iter() {
+            let text = chapter.plain_text();
+            if text.is_empty() {
+                continue;
+            }
+            if !out.is_empty() {
+                out.push_str("\n\n");
+            }
+            let first_line = text.lines().next().unwrap_or("");
+            if first_line != chapter.title.trim() {
+                out.push_str(chapter.title.trim());
+                out.push_str("\n\n");
+            }
+            out.push_str(&text);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Resolve a link `href`: an `epublink:` URL from the processed HTML, or a raw href
+    /// relative to chapter `from_chapter`. Returns None if it doesn't point at a chapter.
+    pub fn resolve_link(&self, href: &str, from_chapter: Option<usize>) -> Option<EpubLinkTarget> {
+        let (path, fragment) = match href.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (href, None),
+        };
+        let path = percent_decode(path);
+        let target = match path.strip_prefix(EPUB_LINK_SCHEME) {
+            Some(full) => full.to_string(),
+            None if path.is_empty() => self.chapters.get(from_chapter?)?.path.clone(),
+            None => {
+                let base = from_chapter
+                    .and_then(|i| self.chapters.get(i))
+                    .map_or("", |ch| ch.path.as_str());
+                resolve_path(base, &path)
+            }
+        };
+        let chapter_index = self
+            .chapters
+            .iter()
+            .position(|ch| ch.path == target)
+            .or_else(|| {
+                // 경로 표기가 조금 다른 경우 (앞쪽 폴더 생략 등)
+                self.chapters.iter().position(|ch| {
+                    !ch.path.is_empty() && (ch.path.ends_with(&target) || target.ends_with(&ch.path))
+                })
+            })?;
+        Some(EpubLinkTarget {
+            chapter_index,
+            fragment: fragment.filter(|f| !f.is_empty()).map(percent_decode),
+        })
+    }
+
+    /// @font-face CSS pointing at `font_base_url` (e.g. "epubfont://localhost/?id=...&font=").
+    pub fn font_styles_for(&self, font_base_url: &str) -> String {
+        self.font_styles.replace(FONT_URL_PLACEHOLDER, font_base_url)
+    }
+
+    pub fn get_font(&self, index: usize) -> Option<&EmbeddedFont> {
+        self.fonts.get(&index)
+    }
+
+    /// Bytes held by the processed chapter cache (the evictable part of the book).
+    pub fn cache_bytes(&self) -> usize {
+        self.chapter_cache.iter().map(|(_, html)| html.len()).sum()
+    }
+
+    /// Drop all processed chapters. They are re-rendered from source on next access.
+    pub fn clear_chapter_cache(&mut self) {
+        self.chapter_cache.clear();
     }
+}
+
+/// Whole-book progress (0–100) at `scroll_fraction` (0–1) through chapter `index`,
+/// weighted by chapter text length. Falls back to equal weights when lengths are
+/// unknown (chapter lists cached before lengths were recorded).
+pub fn book_progress(chapters: &[ChapterInfo], index: usize, scroll_fraction: f64) -> f64 {
+    if chapters.is_empty() {
+        return 0.0;
+    }
+    let index = index.min(chapters.len() - 1);
+    let fraction = scroll_fraction.clamp(0.0, 1.0);
+    let total: usize = chapters.iter().map(|c| c.text_length).sum();
+    let progress = if total == 0 {
+        (index as f64 + fraction) / chapters.len() as f64
+    } else {
+        let before: usize = chapters[..index].iter().map(|c| c.text_length).sum();
+        (before as f64 + chapters[index].text_length as f64 * fraction) / total as f64
+    };
+    (progress * 100.0).clamp(0.0, 100.0)
+}
 
-    pub fn total_chapters(&self) -> usize {
-        self.chapters.len()
+/// Everything needed to render one chapter without holding the tab manager lock.
+pub struct ChapterRenderJob {
+    pub index: usize,
+    source: String,
+    path: String,
+    image_map: Arc<HashMap<String, String>>,
+    css_map: Arc<HashMap<String, String>>,
+    generation: u64,
+}
+
+impl ChapterRenderJob {
+    pub fn render(&self) -> String {
+        process_chapter_html(&self.source, &self.path, &self.image_map, &self.css_map)
     }
 }
 
@@ -57,23 +334,44 @@ struct EncryptionInfo {
 // --- Main parse function ---
 
 pub fn parse_epub(path: &Path) -> anyhow::Result<EpubBook> {
+    // encryption.xml과 OPF의 페이지 방향은 ZIP을 한 번 열어서 읽는다
+    let mut archive = std::fs::File::open(path)
+        .ok()
+        .and_then(|file| zip::ZipArchive::new(file).ok());
+    // Find obfuscated fonts in encryption.xml (fails with `DrmProtected` for DRM'd books)
+    let encryption_infos = match archive.as_mut() {
+        Some(archive) => parse_encryption_xml(archive)?,
+        None => Vec::new(),
+    };
+    let page_direction = archive
+        .as_mut()
+        .and_then(|archive| read_opf_in(archive).ok())
+        .and_then(|opf| page_progression_direction(&opf));
+
+    let mut book = parse_epub_strict(path, &encryption_infos).or_else(|err| {
+        // container.xml/OPF가 깨진 변환본도 열 수 있게 ZIP을 직접 훑는다
+        log::warn!("Falling back to lenient EPUB parsing for {}: {}", path.display(), err);
+        parse_epub_lenient(path).map_err(|_| err)
+    })?;
+    book.page_direction = page_direction;
+    Ok(book)
+}
+
+fn parse_epub_strict(path: &Path, encryption_infos: &[EncryptionInfo]) -> anyhow::Result<EpubBook> {
     let mut doc = epub::doc::EpubDoc::new(path)
         .map_err(|e| anyhow::anyhow!("Failed to open EPUB: {}", e))?;
 
     // Get unique identifier for font deobfuscation
     let unique_id = get_unique_identifier(&doc);
 
-    // Parse encryption.xml to find obfuscated fonts
-    let encryption_infos = parse_encryption_xml(path);
-
     // Build image map: path -> base64 data URI (images only)
     let image_map = build_image_map(&mut doc);
 
-    // Build font map: path -> base64 data URI (deobfuscated fonts)
-    let font_map = build_font_map(&mut doc, &encryption_infos, unique_id.as_deref());
+    // Load fonts (deobfuscated); CSS refers to them by index instead of inlining base64
+    let (font_map, fonts) = build_font_map(&mut doc, encryption_infos, unique_id.as_deref());
 
-    // Build CSS map (no font data) and font_styles (@font-face with data URIs, stored once)
-    let (css_map, font_styles) = build_css_and_font_styles(&mut doc, &image_map, &font_map);
+    // Build CSS map (no font data) and the @font-face blocks (stored once)
+    let (css_map, font_faces) = build_css_and_font_faces(&mut doc, &image_map, &font_map);
 
     // Build TOC title lookup
     let toc_titles = build_toc_titles(&doc.toc);
@@ -98,29 +396,286 @@ pub fn parse_epub(path: &Path) -> anyhow::Result<EpubBook> {
                     .and_then(|p| find_toc_title(p, &toc_titles))
                     .unwrap_or_else(|| format!("Chapter {}", chapters.len() + 1));
 
-                let base_path = current_path.as_deref().unwrap_or("");
-                // Process with image_map only (no font data in per-chapter HTML)
-                let processed_html =
-                    process_chapter_html(&content, base_path, &image_map, &css_map);
-
-                chapters.push(EpubChapter {
-                    title: chapter_title,
-                    html: processed_html,
-                });
+                // HTML processing is deferred until the chapter is requested
+                chapters.push(new_chapter(chapter_title, current_path.unwrap_or_default(), content));
             }
         }
     }
 
+    assemble_book(chapters, fonts, font_faces, image_map, css_map)
+}
+
+/// Fallback for EPUBs the `epub` crate can't open (broken container.xml, OPF or NCX):
+/// every (X)HTML file in the ZIP becomes a chapter, in natural path order, titled
+/// "Chapter N". Obfuscated fonts can't be recovered without the OPF identifier.
+fn parse_epub_lenient(path: &Path) -> anyhow::Result<EpubBook> {
+    use base64::Engine;
+
+    let zip = crate::zip_fast::ZipIndex::open(path)?;
+    let mut names: Vec<String> = zip
+        .entry_sizes()
+        .map(|(name, _)| name.to_string())
+        .filter(|name| !name.ends_with('/'))
+        .collect();
+    names.sort_by_cached_key(|name| crate::image_reader::natural_sort_key(name));
+    let extension = |name: &str| name.rsplit('.').next().unwrap_or_default().to_lowercase();
+
+    let mut image_map = HashMap::new();
+    let mut font_map = HashMap::new();
+    let mut fonts = HashMap::new();
+    for name in &names {
+        let ext = extension(name);
+        let is_image = matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" | "svg");
+        let is_font = matches!(ext.as_str(), "ttf" | "otf" | "woff" | "woff2");
+        if !is_image && !is_font {
+            continue;
+        }
+        let Ok(data) = zip.read_entry(name) else {
+            continue;
+        };
+        let (map, value) = if is_image {
+            let b64 = base64::engine::general_purpose::STANDARD.encode(&data);
+            let data_uri = format!("data:{};base64,{}", crate::image_reader::image_mime(name), b64);
+            (&mut image_map, data_uri)
+        } else {
+            let index = fonts.len();
+            fonts.insert(
+                index,
+                EmbeddedFont {
+                    mime: font_data_uri_mime(&ext),
+                    data,
+                },
+            );
+            (&mut font_map, format!("{}{}", FONT_URL_PLACEHOLDER, index))
+        };
+        map.insert(name.clone(), value.clone());
+        if let Some(pos) = name.rfind('/') {
+            map.insert(name[pos + 1..].to_string(), value);
+        }
+    }
+
+    let mut font_face_map = image_map.clone();
+    font_face_map.extend(font_map.iter().map(|(k, v)| (k.clone(), v.clone())));
+    let mut css_map = HashMap::new();
+    let mut font_faces = Vec::new();
+    for name in names.iter().filter(|name| extension(name) == "css") {
+        let Ok(data) = zip.read_entry(name) else {
+            continue;
+        };
+        let (faces, remaining) = extract_font_face_blocks(&String::from_utf8_lossy(&data));
+        for block in &faces {
+            font_faces.push(replace_css_urls(block, name, &font_face_map));
+        }
+        let processed = replace_css_urls(&remaining, name, &image_map);
+        css_map.insert(name.clone(), processed.clone());
+        if let Some(pos) = name.rfind('/') {
+            css_map.insert(name[pos + 1..].to_string(), processed);
+        }
+    }
+
+    let mut chapters = Vec::new();
+    for name in names
+        .iter()
+        .filter(|name| matches!(extension(name).as_str(), "xhtml" | "html" | "htm"))
+    {
+        let Ok(data) = zip.read_entry(name) else {
+            continue;
+        };
+        let content = String::from_utf8_lossy(&data).into_owned();
+        let title = format!("Chapter {}", chapters.len() + 1);
+        chapters.push(new_chapter(title, name.clone(), content));
+    }
+
+    assemble_book(chapters, fonts, font_faces, image_map, css_map)
+}
+
+fn new_chapter(title: String, path: String, source: String) -> EpubChapter {
+    let (language, direction) = chapter_lang_dir(&source);
+    let mut counter = crate::text_stats::TextStatsCounter::new();
+    counter.feed(&html_to_plain_text(&extract_body_content(&source)));
+    let stats = counter.finish();
+    EpubChapter {
+        title,
+        path,
+        source,
+        language,
+        direction,
+        text_length: stats.characters_no_spaces,
+        word_count: stats.words + stats.cjk_chars,
+        estimated_minutes: stats.reading_minutes,
+    }
+}
+
+fn assemble_book(
+    chapters: Vec<EpubChapter>,
+    mut fonts: HashMap<usize, EmbeddedFont>,
+    font_faces: Vec<String>,
+    image_map: HashMap<String, String>,
+    css_map: HashMap<String, String>,
+) -> anyhow::Result<EpubBook> {
     if chapters.is_empty() {
         anyhow::bail!("No readable chapters found in EPUB");
     }
 
+    // Ship only the fonts the book actually uses
+    let font_styles = referenced_font_faces(&font_faces, &css_map, &chapters);
+    let used_fonts = used_font_indices(&font_styles);
+    fonts.retain(|index, _| used_fonts.contains(index));
+    let writing_mode = detect_writing_mode(&css_map, &chapters);
+
     Ok(EpubBook {
         font_styles,
-        chapters,
+        fonts,
+        chapters: Arc::new(chapters),
+        writing_mode,
+        page_direction: None,
+        image_map: Arc::new(image_map),
+        css_map: Arc::new(css_map),
+        chapter_cache: VecDeque::new(),
+        generation: NEXT_GENERATION.fetch_add(1, Ordering::Relaxed),
     })
 }
 
+// --- Language / accessibility metadata ---
+
+/// Vertical `writing-mode` (incl. `-epub-` / `-webkit-` prefixes and the old `tb-rl`)
+/// used by most spine documents. A document's mode is the first one in its own
+/// `<style>`/`style=`, else in its linked stylesheets in link order. A tie between
+/// vertical modes goes to the one met first in spine order.
+fn detect_writing_mode(css_map: &HashMap<String, String>, chapters: &[EpubChapter]) -> Option<String> {
+    let re = regex::Regex::new(r"(?i)(?:-epub-|-webkit-)?writing-mode\s*:\s*(vertical-rl|vertical-lr|tb-rl|tb)\b")
+        .unwrap();
+    let mode_in = |text: &str| {
+        re.captures(text).map(|caps| match caps[1].to_ascii_lowercase().as_str() {
+            "vertical-lr" => "vertical-lr",
+            _ => "vertical-rl",
+        })
+    };
+
+    // 표지·판권 페이지 하나만 세로쓰기인 책을 세로로 열지 않도록 과반수로 정한다
+    let mut votes: Vec<(&str, usize)> = Vec::new();
+    for chapter in chapters {
+        let mode = mode_in(&chapter.source).or_else(|| {
+            linked_stylesheets(&chapter.source, &chapter.path, css_map)
+                .into_iter()
+                .find_map(mode_in)
+        });
+        if let Some(mode) = mode {
+            match votes.iter_mut().find(|(m, _)| *m == mode) {
+                Some((_, count)) => *count += 1,
+                None => votes.push((mode, 1)),
+            }
+        }
+    }
+    let vertical: usize = votes.iter().map(|(_, count)| count).sum();
+    if vertical * 2 <= chapters.len() {
+        return None;
+    }
+    // max_by_key는 같은 값이면 마지막 것을 고르므로 역순으로 훑는다
+    votes
+        .iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(mode, _)| mode.to_string())
+}
+
+/// `lang`/`xml:lang` and `dir` from the chapter's <html> or <body> tag.
+fn chapter_lang_dir(source: &str) -> (Option<String>, Option<String>) {
+    let tag_re = regex::Regex::new(r"(?is)<(html|body)\b[^>]*>").unwrap();
+    let lang_re = regex::Regex::new(r#"(?i)\b(?:xml:)?lang\s*=\s*["']([^"']+)["']"#).unwrap();
+    let dir_re = regex::Regex::new(r#"(?i)\bdir\s*=\s*["'](ltr|rtl)["']"#).unwrap();
+
+    let mut language = None;
+    let mut direction = None;
+    for tag in tag_re.find_iter(source).map(|m| m.as_str()) {
+        // body의 값이 html의 값보다 우선
+        if let Some(caps) = lang_re.captures(tag) {
+            language = Some(caps[1].trim().to_string());
+        }
+        if let Some(caps) = dir_re.captures(tag) {
+            direction = Some(caps[1].to_lowercase());
+        }
+    }
+    (language, direction)
+}
+
+/// Read the OPF package document (path from META-INF/container.xml).
+fn read_opf(path: &Path) -> anyhow::Result<String> {
+    let file = std::fs::File::open(path)?;
+    read_opf_in(&mut zip::ZipArchive::new(file)?)
+}
+
+fn read_opf_in(archive: &mut zip::ZipArchive<std::fs::File>) -> anyhow::Result<String> {
+    let mut container = String::new();
+    std::io::Read::read_to_string(&mut archive.by_name("META-INF/container.xml")?, &mut container)?;
+    let rootfile_re = regex::Regex::new(r#"(?i)<rootfile\b[^>]*full-path\s*=\s*["']([^"']+)["']"#).unwrap();
+    let opf_path = rootfile_re
+        .captures(&container)
+        .map(|c| c[1].to_string())
+        .ok_or_else(|| anyhow::anyhow!("No rootfile in container.xml"))?;
+    let mut opf = String::new();
+    std::io::Read::read_to_string(&mut archive.by_name(&opf_path)?, &mut opf)?;
+    Ok(opf)
+}
+
+/// Parse language, page direction and accessibility metadata without parsing the whole book.
+pub fn read_epub_metadata(path: &Path) -> anyhow::Result<EpubMetadata> {
+    let opf = read_opf(path)?;
+    let language_re = regex::Regex::new(r"(?is)<dc:language\b[^>]*>(.*?)</dc:language>").unwrap();
+    // EPUB 3: <meta property="schema:accessMode">textual</meta>
+    let meta3_re = regex::Regex::new(r#"(?is)<meta\b[^>]*property\s*=\s*["']([^"']+)["'][^>]*>(.*?)</meta>"#).unwrap();
+    // EPUB 2: <meta name="schema:accessMode" content="textual"/>
+    let meta2_re = regex::Regex::new(r#"(?is)<meta\b[^>]*name\s*=\s*["']([^"']+)["'][^>]*content\s*=\s*["']([^"']*)["']"#).unwrap();
+    let link_re = regex::Regex::new(r#"(?is)<link\b[^>]*rel\s*=\s*["']dcterms:conformsTo["'][^>]*href\s*=\s*["']([^"']+)["']"#).unwrap();
+
+    let mut metadata = EpubMetadata {
+        languages: language_re
+            .captures_iter(&opf)
+            .map(|c| decode_entities(c[1].trim()))
+            .filter(|l| !l.is_empty())
+            .collect(),
+        page_direction: page_progression_direction(&opf),
+        ..Default::default()
+    };
+    metadata.language = metadata.languages.first().cloned();
+
+    let properties = meta3_re
+        .captures_iter(&opf)
+        .map(|c| (c[1].to_string(), c[2].to_string()))
+        .chain(meta2_re.captures_iter(&opf).map(|c| (c[1].to_string(), c[2].to_string())));
+    let mut layout = None;
+    let a11y = &mut metadata.accessibility;
+    for (property, value) in properties {
+        let value = decode_entities(value.trim());
+        if value.is_empty() {
+            continue;
+        }
+        match property.as_str() {
+            "schema:accessMode" => a11y.access_modes.push(value),
+            "schema:accessModeSufficient" => a11y.access_modes_sufficient.push(value),
+            "schema:accessibilityFeature" => a11y.features.push(value),
+            "schema:accessibilityHazard" => a11y.hazards.push(value),
+            "schema:accessibilitySummary" => a11y.summary = Some(value),
+            "dcterms:conformsTo" => a11y.conforms_to.push(value),
+            "rendition:layout" => layout = Some(value),
+            _ => {}
+        }
+    }
+    a11y.conforms_to
+        .extend(link_re.captures_iter(&opf).map(|c| c[1].to_string()));
+    metadata.layout = layout;
+    Ok(metadata)
+}
+
+/// `page-progression-direction` of the OPF spine, if it's "ltr" or "rtl".
+fn page_progression_direction(opf: &str) -> Option<String> {
+    let spine_re = regex::Regex::new(r#"(?is)<spine\b[^>]*page-progression-direction\s*=\s*["'](\w+)["']"#).unwrap();
+    spine_re
+        .captures(opf)
+        .map(|c| c[1].to_lowercase())
+        .filter(|d| d == "ltr" || d == "rtl")
+}
+
 // --- Unique identifier ---
 
 fn get_unique_identifier(
@@ -135,24 +690,26 @@ fn get_unique_identifier(
 
 // --- encryption.xml parsing ---
 
-fn parse_encryption_xml(path: &Path) -> Vec<EncryptionInfo> {
-    let file = match std::fs::File::open(path) {
-        Ok(f) => f,
-        Err(_) => return Vec::new(),
-    };
-    let mut archive = match zip::ZipArchive::new(file) {
-        Ok(a) => a,
-        Err(_) => return Vec::new(),
-    };
-    let mut enc_file = match archive.by_name("META-INF/encryption.xml") {
-        Ok(f) => f,
-        Err(_) => return Vec::new(),
+/// Font obfuscation entries from META-INF/encryption.xml. Returns `AppError::DrmProtected`
+/// when the book carries DRM: an Adobe ADEPT `rights.xml`, an Apple `sinf.xml`, or real
+/// encryption (not font obfuscation) of content documents, as in Kobo / B&N books.
+fn parse_encryption_xml(archive: &mut zip::ZipArchive<std::fs::File>) -> anyhow::Result<Vec<EncryptionInfo>> {
+    let read_entry = |archive: &mut zip::ZipArchive<std::fs::File>, name: &str| {
+        let mut entry = archive.by_name(name).ok()?;
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut entry, &mut content).ok()?;
+        Some(content)
     };
-
-    let mut content = String::new();
-    if std::io::Read::read_to_string(&mut enc_file, &mut content).is_err() {
-        return Vec::new();
+    if let Some(rights) = read_entry(archive, "META-INF/rights.xml") {
+        let scheme = if rights.contains("adobe.com/adept") { "Adobe ADEPT" } else { "rights.xml" };
+        return Err(crate::error::AppError::DrmProtected(scheme.to_string()).into());
+    }
+    if archive.by_name("META-INF/sinf.xml").is_ok() {
+        return Err(crate::error::AppError::DrmProtected("Apple FairPlay".to_string()).into());
     }
+    let Some(content) = read_entry(archive, "META-INF/encryption.xml") else {
+        return Ok(Vec::new());
+    };
 
     let mut infos = Vec::new();
 
@@ -169,12 +726,22 @@ fn parse_encryption_xml(path: &Path) -> Vec<EncryptionInfo> {
     for block in block_re.captures_iter(&content) {
         let block_text = &block[1];
 
+        let uri = if let Some(uri_caps) = uri_re.captures(block_text) {
+            percent_decode(&uri_caps[1])
+        } else {
+            continue;
+        };
+
         let algorithm = if let Some(algo_caps) = algo_re.captures(block_text) {
             let algo_str = &algo_caps[1];
             if algo_str.contains("idpf.org/2008/embedding") {
                 ObfuscationAlgorithm::Idpf
             } else if algo_str.contains("ns.adobe.com/pdf/enc") {
                 ObfuscationAlgorithm::Adobe
+            } else if is_content_document(&uri) {
+                // 폰트 난독화가 아닌 실제 암호화가 본문에 걸려 있으면 DRM이다 (Kobo 등)
+                let scheme = "encrypted content (Kobo, Barnes & Noble, ...)";
+                return Err(crate::error::AppError::DrmProtected(scheme.to_string()).into());
             } else {
                 continue;
             }
@@ -182,16 +749,17 @@ fn parse_encryption_xml(path: &Path) -> Vec<EncryptionInfo> {
             continue;
         };
 
-        let uri = if let Some(uri_caps) = uri_re.captures(block_text) {
-            percent_decode(&uri_caps[1])
-        } else {
-            continue;
-        };
-
         infos.push(EncryptionInfo { uri, algorithm });
     }
 
-    infos
+    Ok(infos)
+}
+
+fn is_content_document(uri: &str) -> bool {
+    let lower = uri.to_lowercase();
+    [".xhtml", ".html", ".htm", ".xml", ".opf", ".ncx"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
 }
 
 fn percent_decode(s: &str) -> String {
@@ -249,7 +817,7 @@ fn is_font_mime(mime: &str) -> bool {
         || mime.contains("woff")
 }
 
-fn font_data_uri_mime(mime: &str) -> &str {
+fn font_data_uri_mime(mime: &str) -> &'static str {
     if mime.contains("woff2") {
         "font/woff2"
     } else if mime.contains("woff") {
@@ -297,13 +865,14 @@ fn build_image_map(
     map
 }
 
+/// Returns (path -> font URL placeholder, font index -> font data).
 fn build_font_map(
     doc: &mut epub::doc::EpubDoc<std::io::BufReader<std::fs::File>>,
     encryption_infos: &[EncryptionInfo],
     unique_id: Option<&str>,
-) -> HashMap<String, String> {
-    use base64::Engine;
+) -> (HashMap<String, String>, HashMap<usize, EmbeddedFont>) {
     let mut map = HashMap::new();
+    let mut fonts = HashMap::new();
 
     let font_resources: Vec<(String, String, String)> = doc
         .resources
@@ -329,18 +898,24 @@ fn build_font_map(
                 }
             }
 
-            let css_mime = font_data_uri_mime(&mime);
-            let b64 = base64::engine::general_purpose::STANDARD.encode(&data);
-            let data_uri = format!("data:{};base64,{}", css_mime, b64);
-
-            map.insert(path.clone(), data_uri.clone());
+            let index = fonts.len();
+            fonts.insert(
+                index,
+                EmbeddedFont {
+                    mime: font_data_uri_mime(&mime),
+                    data,
+                },
+            );
+            let font_url = format!("{}{}", FONT_URL_PLACEHOLDER, index);
+
+            map.insert(path.clone(), font_url.clone());
             if let Some(pos) = path.rfind('/') {
-                map.insert(path[pos + 1..].to_string(), data_uri);
+                map.insert(path[pos + 1..].to_string(), font_url);
             }
         }
     }
 
-    map
+    (map, fonts)
 }
 
 fn find_encryption_info<'a>(
@@ -360,26 +935,22 @@ fn find_encryption_info<'a>(
 }
 
 /// Extract @font-face blocks from CSS. Returns (font_face_blocks, remaining_css).
-fn extract_font_face_blocks(css: &str) -> (String, String) {
+fn extract_font_face_blocks(css: &str) -> (Vec<String>, String) {
     let re = regex::Regex::new(r"(?s)@font-face\s*\{[^}]*\}").unwrap();
-    let font_faces: String = re
-        .find_iter(css)
-        .map(|m| m.as_str())
-        .collect::<Vec<_>>()
-        .join("\n");
+    let font_faces: Vec<String> = re.find_iter(css).map(|m| m.as_str().to_string()).collect();
     let remaining = re.replace_all(css, "").to_string();
     (font_faces, remaining)
 }
 
-/// Build css_map (CSS without @font-face) and font_styles (@font-face with data URIs).
-/// Font data is stored only in font_styles (once), not in per-chapter CSS.
-fn build_css_and_font_styles(
+/// Build css_map (CSS without @font-face) and the list of @font-face blocks (font URLs resolved).
+/// Font faces are served once via font_styles, not in per-chapter CSS.
+fn build_css_and_font_faces(
     doc: &mut epub::doc::EpubDoc<std::io::BufReader<std::fs::File>>,
     image_map: &HashMap<String, String>,
     font_map: &HashMap<String, String>,
-) -> (HashMap<String, String>, String) {
+) -> (HashMap<String, String>, Vec<String>) {
     let mut css_map = HashMap::new();
-    let mut all_font_styles = String::new();
+    let mut all_font_faces = Vec::new();
 
     let css_resources: Vec<(String, String)> = doc
         .resources
@@ -398,12 +969,9 @@ fn build_css_and_font_styles(
                 // Split: @font-face blocks -> font_styles, rest -> css_map
                 let (font_faces, remaining) = extract_font_face_blocks(&css_text);
 
-                if !font_faces.is_empty() {
-                    // Replace url() in @font-face with font data URIs
-                    let processed_fonts =
-                        replace_css_urls(&font_faces, &path, &font_face_map);
-                    all_font_styles.push_str(&processed_fonts);
-                    all_font_styles.push('\n');
+                // Replace url() in @font-face with font URLs
+                for block in &font_faces {
+                    all_font_faces.push(replace_css_urls(block, &path, &font_face_map));
                 }
 
                 // Replace url() in remaining CSS with image-only data URIs
@@ -416,7 +984,51 @@ fn build_css_and_font_styles(
         }
     }
 
-    (css_map, all_font_styles)
+    (css_map, all_font_faces)
+}
+
+/// Keep only @font-face blocks whose family is used by some stylesheet or chapter.
+fn referenced_font_faces(
+    font_faces: &[String],
+    css_map: &HashMap<String, String>,
+    chapters: &[EpubChapter],
+) -> String {
+    let decl_re = regex::Regex::new(r"(?i)font(?:-family)?\s*:\s*([^;}>]+)").unwrap();
+    let family_re = regex::Regex::new(r#"(?i)font-family\s*:\s*['"]?([^;'"}]+)"#).unwrap();
+
+    // All font/font-family declaration values, lowercased
+    let mut declared = String::new();
+    let sources = css_map
+        .values()
+        .map(String::as_str)
+        .chain(chapters.iter().map(|ch| ch.source.as_str()));
+    for source in sources {
+        for caps in decl_re.captures_iter(source) {
+            declared.push_str(&caps[1].to_lowercase());
+            declared.push('\n');
+        }
+    }
+
+    let mut out = String::new();
+    for block in font_faces {
+        let family = family_re
+            .captures(block)
+            .map(|caps| caps[1].trim().to_lowercase())
+            .unwrap_or_default();
+        // 이름을 못 읽은 블록은 보수적으로 유지
+        if family.is_empty() || declared.contains(&family) {
+            out.push_str(block);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn used_font_indices(font_styles: &str) -> std::collections::HashSet<usize> {
+    let re = regex::Regex::new(&format!(r"{}(\d+)", regex::escape(FONT_URL_PLACEHOLDER))).unwrap();
+    re.captures_iter(font_styles)
+        .filter_map(|caps| caps[1].parse().ok())
+        .collect()
 }
 
 // --- CSS processing ---
@@ -445,6 +1057,24 @@ fn replace_css_urls(
     .to_string()
 }
 
+/// Contents of the stylesheets a chapter links to, in link order.
+fn linked_stylesheets<'a>(html: &str, chapter_path: &str, css_map: &'a HashMap<String, String>) -> Vec<&'a str> {
+    let link_re = regex::Regex::new(r#"(?i)<link\b[^>]*>"#).unwrap();
+    let rel_re = regex::Regex::new(r#"(?i)rel\s*=\s*["']stylesheet["']"#).unwrap();
+    let href_re = regex::Regex::new(r#"(?i)href\s*=\s*["']([^"']+)["']"#).unwrap();
+
+    link_re
+        .find_iter(html)
+        .map(|m| m.as_str())
+        .filter(|tag| rel_re.is_match(tag))
+        .filter_map(|tag| {
+            let href = &href_re.captures(tag)?[1];
+            let resolved = resolve_path(chapter_path, href);
+            find_in_resource_map(&resolved, href, css_map).map(String::as_str)
+        })
+        .collect()
+}
+
 fn inline_linked_stylesheets(
     html: &str,
     chapter_path: &str,
@@ -467,7 +1097,10 @@ fn inline_linked_stylesheets(
                 let resolved = resolve_path(chapter_path, href);
 
                 if let Some(css_content) = find_in_resource_map(&resolved, href, css_map) {
-                    return format!("<style>{}</style>", css_content);
+                    return format!(
+                        "<style>{}</style>",
+                        crate::epub_sanitize::escape_style_text(css_content)
+                    );
                 }
             }
 
@@ -509,11 +1142,110 @@ fn process_chapter_html(
     // Step 6: Replace image sources in body
     let processed_body = replace_image_sources(&body, chapter_path, image_map);
 
+    // Step 7: Rewrite links to other documents in the book as `epublink:` URLs
+    let processed_body = rewrite_internal_links(&processed_body, chapter_path);
+
+    // Step 8: Remove scripts, frames and event handlers
+    let processed_body = crate::epub_sanitize::strip_active_content(&processed_body);
+
     if processed_styles.trim().is_empty() {
         processed_body
     } else {
-        format!("<style>{}</style>\n{}", processed_styles, processed_body)
+        // 스타일은 새니타이저를 거치지 않으므로 `</style`로 요소를 빠져나가지 못하게 한다
+        format!(
+            "<style>{}</style>\n{}",
+            crate::epub_sanitize::escape_style_text(&processed_styles),
+            processed_body
+        )
+    }
+}
+
+/// Apply the reader's own CSS to processed chapter HTML. User rules are scoped with
+/// `:root:root:root` so they outrank typical publisher selectors, and appended last.
+pub fn apply_user_styles(html: &str, user_css: &str, disable_publisher_styles: bool) -> String {
+    let mut out = if disable_publisher_styles {
+        strip_publisher_styles(html)
+    } else {
+        html.to_string()
+    };
+    if !user_css.trim().is_empty() {
+        let comment_re = regex::Regex::new(r"(?s)/\*.*?\*/").unwrap();
+        let css = comment_re.replace_all(user_css, "");
+        out.push_str("\n<style>");
+        out.push_str(&crate::epub_sanitize::escape_style_text(&scope_css(&css)));
+        out.push_str("</style>");
+    }
+    out
+}
+
+/// Remove `<style>` blocks and `style="..."` attributes.
+fn strip_publisher_styles(html: &str) -> String {
+    let style_block_re = regex::Regex::new(r"(?is)<style\b[^>]*>.*?</style>").unwrap();
+    let style_attr_re = regex::Regex::new(r#"(?i)\s+style\s*=\s*("[^"]*"|'[^']*')"#).unwrap();
+    let html = style_block_re.replace_all(html, "");
+    // 속성만 지우고 태그 밖의 "style=" 텍스트는 건드리지 않는다
+    let tag_re = regex::Regex::new(r"(?s)<[a-zA-Z][^>]*>").unwrap();
+    tag_re
+        .replace_all(&html, |caps: &regex::Captures| style_attr_re.replace_all(&caps[0], "").into_owned())
+        .into_owned()
+}
+
+/// Prefix every selector with `:root:root:root` (inside @media/@supports too).
+fn scope_css(css: &str) -> String {
+    let mut out = String::with_capacity(css.len() + 64);
+    let mut rest = css;
+    while let Some(open) = rest.find('{') {
+        let (statements, selector) = match rest[..open].rfind([';', '}']) {
+            Some(i) => rest[..open].split_at(i + 1),
+            None => ("", &rest[..open]),
+        };
+        // `@import ...;` 같은 블록 없는 문장은 그대로
+        out.push_str(statements);
+        let close = matching_brace(rest, open);
+        let block = &rest[open + 1..close];
+        let selector = selector.trim();
+        let lower = selector.to_ascii_lowercase();
+        if lower.starts_with("@media") || lower.starts_with("@supports") {
+            out.push_str(&format!("{} {{{}}}", selector, scope_css(block)));
+        } else if selector.starts_with('@') {
+            out.push_str(&format!("{} {{{}}}", selector, block));
+        } else {
+            let scoped: Vec<String> = selector.split(',').map(scope_selector).collect();
+            out.push_str(&format!("{} {{{}}}", scoped.join(", "), block));
+        }
+        out.push('\n');
+        rest = rest.get(close + 1..).unwrap_or("");
+    }
+    out.push_str(rest);
+    out
+}
+
+fn scope_selector(selector: &str) -> String {
+    let selector = selector.trim();
+    let lower = selector.to_ascii_lowercase();
+    if lower.starts_with(":root") || lower == "html" || lower.starts_with("html ") {
+        selector.to_string()
+    } else {
+        format!(":root:root:root {}", selector)
+    }
+}
+
+/// Index of the `}` closing the `{` at `open` (or the end of `text`).
+fn matching_brace(text: &str, open: usize) -> usize {
+    let mut depth = 0;
+    for (i, b) in text.bytes().enumerate().skip(open) {
+        match b {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
     }
+    text.len()
 }
 
 /// XHTML의 자기 닫힘 비-void 태그를 HTML5 호환 형태로 변환.
@@ -521,7 +1253,7 @@ fn process_chapter_html(
 /// HTML5에서는 div, span, p 등의 자기 닫힘을 인식하지 않아 후속 콘텐츠가 안에 들어감.
 fn fix_self_closing_tags(html: &str) -> String {
     let re = regex::Regex::new(
-        r#"(?i)<(div|span|p|section|article|aside|header|footer|nav|main|figure|figcaption|blockquote|pre|ul|ol|li|dl|dt|dd|table|thead|tbody|tfoot|tr|th|td|caption|form|fieldset|label|select|option|textarea|button|details|summary|dialog|a|abbr|b|bdi|bdo|cite|code|data|dfn|em|i|kbd|mark|q|rp|rt|ruby|s|samp|small|strong|sub|sup|time|u|var)\b([^>]*?)\s*/>"#,
+        r#"(?i)<(div|span|p|section|article|aside|header|footer|nav|main|figure|figcaption|blockquote|pre|ul|ol|li|dl|dt|dd|table|thead|tbody|tfoot|tr|th|td|caption|form|fieldset|label|select|option|textarea|button|details|summary|dialog|a|abbr|b|bdi|bdo|cite|code|data|dfn|em|i|kbd|mark|q|rb|rp|rt|rtc|ruby|s|samp|small|strong|sub|sup|time|u|var)\b([^>]*?)\s*/>"#,
     )
     .unwrap();
     re.replace_all(html, |caps: &regex::Captures| {
@@ -565,7 +1297,7 @@ fn resolve_doctype_entities(html: &str) -> String {
     result
 }
 
-fn extract_body_content(html: &str) -> String {
+pub(crate) fn extract_body_content(html: &str) -> String {
     let lower = html.to_lowercase();
     if let Some(body_start) = lower.find("<body") {
         if let Some(tag_end) = html[body_start..].find('>') {
@@ -579,6 +1311,12 @@ fn extract_body_content(html: &str) -> String {
     html.to_string()
 }
 
+/// Convert (X)HTML to plain text: scripts/styles/ruby readings are dropped, block-level
+/// tags become line breaks (a blank line at most), and common entities are decoded.
+pub fn html_to_plain_text(html: &str) -> String {
+    crate::html_text::visible_text(html).text.trim_end().to_string()
+}
+
 fn extract_head_styles(html: &str) -> String {
     let mut styles = String::new();
     let lower = html.to_lowercase();
@@ -629,6 +1367,34 @@ fn replace_image_sources(
     .to_string()
 }
 
+/// `<a href="chap05.xhtml#sec2">` → `<a href="epublink:OEBPS/chap05.xhtml#sec2">`.
+/// 같은 문서 안의 `#id` 링크와 외부 URL은 그대로 둔다.
+fn rewrite_internal_links(html: &str, chapter_path: &str) -> String {
+    let re = regex::Regex::new(r#"(?i)(<a\b[^>]*?\bhref\s*=\s*["'])([^"']+)(["'])"#).unwrap();
+
+    re.replace_all(html, |caps: &regex::Captures| {
+        let href = caps[2].trim();
+        let is_external = href
+            .split(['/', '#', '?'])
+            .next()
+            .is_some_and(|first| first.contains(':'));
+        if href.starts_with('#') || is_external {
+            return caps[0].to_string();
+        }
+        let (path, fragment) = match href.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (href, None),
+        };
+        let mut target = format!("{}{}", EPUB_LINK_SCHEME, resolve_path(chapter_path, path));
+        if let Some(fragment) = fragment {
+            target.push('#');
+            target.push_str(fragment);
+        }
+        format!("{}{}{}", &caps[1], target, &caps[3])
+    })
+    .to_string()
+}
+
 // --- TOC helpers ---
 
 fn build_toc_titles(toc: &[epub::doc::NavPoint]) -> HashMap<String, String> {
@@ -645,9 +1411,9 @@ fn collect_toc_titles(navpoints: &[epub::doc::NavPoint], titles: &mut HashMap<St
             .next()
             .unwrap_or(&content_path)
             .to_string();
-        if !titles.contains_key(&clean_path) {
-            titles.insert(clean_path, nav.label.clone());
-        }
+        titles
+            .entry(clean_path)
+            .or_insert_with(|| nav.label.clone());
         if !nav.children.is_empty() {
             collect_toc_titles(&nav.children, titles);
         }
@@ -671,8 +1437,8 @@ fn find_toc_title(resource_path: &str, toc_titles: &HashMap<String, String>) ->
 // --- Path resolution & resource lookup ---
 
 fn resolve_path(base: &str, relative: &str) -> String {
-    if relative.starts_with('/') {
-        return relative[1..].to_string();
+    if let Some(stripped) = relative.strip_prefix('/') {
+        return stripped.to_string();
     }
 
     let base_dir = base.rfind('/').map(|i| &base[..i]).unwrap_or("");
@@ -692,27 +1458,27 @@ fn resolve_path(base: &str, relative: &str) -> String {
     parts.join("/")
 }
 
-fn find_in_resource_map(
+fn find_in_resource_map<'a>(
     resolved_path: &str,
     original_src: &str,
-    resource_map: &HashMap<String, String>,
-) -> Option<String> {
+    resource_map: &'a HashMap<String, String>,
+) -> Option<&'a String> {
     if let Some(uri) = resource_map.get(resolved_path) {
-        return Some(uri.clone());
+        return Some(uri);
     }
 
     if let Some(uri) = resource_map.get(original_src) {
-        return Some(uri.clone());
+        return Some(uri);
     }
 
     let filename = original_src.rsplit('/').next().unwrap_or(original_src);
     if let Some(uri) = resource_map.get(filename) {
-        return Some(uri.clone());
+        return Some(uri);
     }
 
     for (key, uri) in resource_map {
         if key.ends_with(filename) || resolved_path.ends_with(key.as_str()) {
-            return Some(uri.clone());
+            return Some(uri);
         }
     }
 
@@ -0,0 +1,349 @@
+//! Sanitizing EPUB chapter HTML before it reaches the webview.
+//!
+//! 챕터 HTML은 앱과 같은 webview에서 렌더링되므로, html5ever 토크나이저로 읽어서 허용 목록에
+//! 있는 요소·속성만 다시 써 낸다. 모르는 요소는 태그만 빼고 내용은 남기고, 스크립트·프레임
+//! 같은 요소는 내용째 버린다. URL 속성은 안전한 스킴만 남긴다. 원격 이미지/CSS 리소스는
+//! 읽기 추적에 쓰일 수 있어 설정(`epub_block_remote_images`)에 따라 막는다.
+
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use std::cell::{Cell, RefCell};
+
+/// Elements kept (HTML content, ruby, tables, media, SVG and MathML used in books).
+const ALLOWED_ELEMENTS: &[&str] = &[
+    "a", "abbr", "address", "article", "aside", "audio", "b", "bdi", "bdo", "big", "blockquote", "br",
+    "caption", "center", "cite", "code", "col", "colgroup", "dd", "del", "details", "dfn", "div", "dl",
+    "dt", "em", "figcaption", "figure", "font", "footer", "h1", "h2", "h3", "h4", "h5", "h6", "header",
+    "hgroup", "hr", "i", "img", "ins", "kbd", "li", "main", "mark", "nav", "ol", "p", "picture", "pre",
+    "q", "rb", "rp", "rt", "rtc", "ruby", "s", "samp", "section", "small", "source", "span", "strike",
+    "strong", "style", "sub", "summary", "sup", "table", "tbody", "td", "tfoot", "th", "thead", "time",
+    "tr", "track", "tt", "u", "ul", "var", "video", "wbr",
+    // SVG (표지 이미지 래퍼 등)
+    "svg", "g", "defs", "image", "path", "rect", "circle", "ellipse", "line", "polyline", "polygon",
+    "text", "tspan", "title", "desc", "lineargradient", "radialgradient", "stop", "clippath", "mask",
+    "pattern", "symbol",
+    // MathML
+    "math", "mi", "mn", "mo", "ms", "mtext", "mspace", "mrow", "mfrac", "msqrt", "mroot", "msub",
+    "msup", "msubsup", "munder", "mover", "munderover", "mtable", "mtr", "mtd", "mstyle", "mpadded",
+    "mphantom", "menclose", "semantics", "annotation",
+];
+
+/// Elements removed together with their content.
+const DROPPED_WITH_CONTENT: &[&str] = &[
+    "script", "iframe", "object", "applet", "embed", "frame", "frameset", "noscript", "noembed",
+    "noframes", "template", "head",
+];
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "frame", "hr", "img", "input", "keygen", "link", "meta",
+    "param", "source", "track", "wbr",
+];
+
+/// Attributes kept on any allowed element (plus `aria-*` and `data-*`).
+const ALLOWED_ATTRIBUTES: &[&str] = &[
+    "id", "class", "style", "title", "lang", "xml:lang", "dir", "epub:type", "role", "hidden",
+    "xmlns", "xmlns:epub", "xmlns:xlink", "xmlns:svg", "xmlns:m",
+    // links and media
+    "href", "name", "src", "srcset", "sizes", "alt", "width", "height", "poster", "controls", "loop",
+    "muted", "type", "media", "kind", "label", "srclang", "datetime", "cite",
+    // lists and tables
+    "start", "reversed", "value", "span", "colspan", "rowspan", "headers", "scope", "align", "valign",
+    "border", "cellpadding", "cellspacing", "summary", "open",
+    // legacy presentation used by older EPUB 2 books
+    "color", "face", "size", "bgcolor",
+    // SVG
+    "viewbox", "preserveaspectratio", "version", "x", "y", "x1", "y1", "x2", "y2", "cx", "cy", "r",
+    "rx", "ry", "d", "points", "transform", "fill", "fill-opacity", "fill-rule", "stroke",
+    "stroke-width", "stroke-opacity", "opacity", "offset", "stop-color", "stop-opacity",
+    "gradientunits", "gradienttransform", "clip-path", "clippathunits", "font-size", "font-family",
+    "text-anchor", "xlink:href", "xlink:title",
+    // MathML
+    "display", "mathvariant", "mathsize", "displaystyle", "scriptlevel", "linethickness", "encoding",
+    "columnalign", "rowalign", "fence", "separator", "stretchy", "accent", "lspace", "rspace",
+    "notation",
+];
+
+/// Attributes holding a URL; removed unless the URL's scheme is safe (see `is_safe_url`).
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "xlink:href", "poster", "cite"];
+
+/// Remove everything but known content elements and attributes: scripts, embedded
+/// frames/objects, forms, `on*` handlers and `javascript:` (or other unsafe) URLs go.
+pub fn strip_active_content(html: &str) -> String {
+    let sanitizer = Sanitizer {
+        out: RefCell::new(String::with_capacity(html.len())),
+        skip: RefCell::new(None),
+        raw_text: Cell::new(false),
+        foreign_depth: Cell::new(0),
+    };
+    let input = BufferQueue::default();
+    input.push_back(StrTendril::from_slice(html));
+    let tokenizer = Tokenizer::new(sanitizer, TokenizerOpts::default());
+    // 싱크가 `Script`를 돌려주지 않으므로 한 번에 끝까지 읽는다
+    let _ = tokenizer.feed(&input);
+    tokenizer.end();
+    tokenizer.sink.out.into_inner()
+}
+
+struct Sanitizer {
+    out: RefCell<String>,
+    /// Element whose content is being dropped, and how deeply it is nested in itself.
+    skip: RefCell<Option<(String, usize)>>,
+    /// Inside an HTML `<style>` read as raw text: text is CSS, written as is. In SVG/MathML
+    /// the tokenizer decodes entities in it, so it is escaped like any other text.
+    raw_text: Cell<bool>,
+    /// Nesting of `<svg>`/`<math>`, where `<x/>` closes the element (unlike in HTML).
+    foreign_depth: Cell<usize>,
+}
+
+impl Sanitizer {
+    fn start_tag(&self, tag: &Tag) -> TokenSinkResult<()> {
+        let name = &*tag.name;
+        let void = VOID_ELEMENTS.contains(&name);
+        // SVG/MathML 안에서는 토크나이저 상태를 바꾸지 않는다
+        let raw = raw_kind(name).filter(|_| self.foreign_depth.get() == 0);
+        let result = raw.map_or(TokenSinkResult::Continue, TokenSinkResult::RawData);
+        // `<style/>`도 HTML에서는 여는 태그일 뿐이다
+        let self_closing = tag.self_closing && raw.is_none();
+        if DROPPED_WITH_CONTENT.contains(&name) {
+            if !void && (!tag.self_closing || raw.is_some()) {
+                *self.skip.borrow_mut() = Some((name.to_string(), 1));
+            }
+            return result;
+        }
+        if !ALLOWED_ELEMENTS.contains(&name) {
+            // 모르는 요소(form, input, button, ...)는 태그만 뺀다
+            return result;
+        }
+        let mut out = self.out.borrow_mut();
+        out.push('<');
+        out.push_str(name);
+        for attr in &tag.attrs {
+            // 토크나이저가 속성 이름을 소문자로 바꿔 준다
+            let attr_name = &*attr.name.local;
+            if !is_allowed_attribute(attr_name) {
+                continue;
+            }
+            let value = &*attr.value;
+            if URL_ATTRIBUTES.contains(&attr_name) && !is_safe_url(value) {
+                continue;
+            }
+            if attr_name == "srcset" && !value.split(',').all(|candidate| is_safe_url(candidate.trim())) {
+                continue;
+            }
+            if attr_name == "style" && !is_safe_style(value) {
+                continue;
+            }
+            out.push(' ');
+            out.push_str(attr_name);
+            out.push_str("=\"");
+            push_escaped(&mut out, value, true);
+            out.push('"');
+        }
+        let foreign = name == "svg" || name == "math";
+        if self_closing && (void || foreign || self.foreign_depth.get() > 0) {
+            out.push_str(" />");
+        } else if self_closing {
+            // HTML에서 `<div/>`는 닫히지 않은 `<div>`이므로 직접 닫는다
+            out.push_str("></");
+            out.push_str(name);
+            out.push('>');
+        } else {
+            out.push('>');
+            if foreign {
+                self.foreign_depth.set(self.foreign_depth.get() + 1);
+            }
+        }
+        if raw.is_some() && name == "style" {
+            self.raw_text.set(true);
+        }
+        result
+    }
+
+    fn end_tag(&self, tag: &Tag) {
+        let name = &*tag.name;
+        if name == "style" {
+            self.raw_text.set(false);
+        }
+        if name == "svg" || name == "math" {
+            self.foreign_depth.set(self.foreign_depth.get().saturating_sub(1));
+        }
+        if ALLOWED_ELEMENTS.contains(&name) && !VOID_ELEMENTS.contains(&name) {
+            let mut out = self.out.borrow_mut();
+            out.push_str("</");
+            out.push_str(name);
+            out.push('>');
+        }
+    }
+}
+
+impl TokenSink for Sanitizer {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        // 버리는 요소 안: 같은 이름의 중첩만 세면서 닫힐 때까지 건너뛴다
+        {
+            let mut skip = self.skip.borrow_mut();
+            if let Some((name, depth)) = skip.as_mut() {
+                if let Token::TagToken(tag) = &token {
+                    if *tag.name == **name {
+                        match tag.kind {
+                            TagKind::StartTag if !tag.self_closing => *depth += 1,
+                            TagKind::EndTag => *depth -= 1,
+                            _ => {}
+                        }
+                    }
+                }
+                if *depth == 0 {
+                    *skip = None;
+                }
+                return TokenSinkResult::Continue;
+            }
+        }
+        match token {
+            Token::TagToken(tag) => match tag.kind {
+                TagKind::StartTag => return self.start_tag(&tag),
+                TagKind::EndTag => self.end_tag(&tag),
+            },
+            Token::CharacterTokens(text) => {
+                let mut out = self.out.borrow_mut();
+                if self.raw_text.get() {
+                    out.push_str(&text);
+                } else {
+                    push_escaped(&mut out, &text, false);
+                }
+            }
+            // 주석, DOCTYPE, NUL 문자는 버린다
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+/// How the tokenizer must read an element's content (the tree builder normally tells it).
+fn raw_kind(name: &str) -> Option<RawKind> {
+    match name {
+        "script" => Some(RawKind::ScriptData),
+        "style" | "iframe" | "noembed" | "noframes" | "noscript" | "xmp" => Some(RawKind::Rawtext),
+        "textarea" | "title" => Some(RawKind::Rcdata),
+        _ => None,
+    }
+}
+
+fn is_allowed_attribute(name: &str) -> bool {
+    if name.starts_with("on") {
+        return false;
+    }
+    ALLOWED_ATTRIBUTES.contains(&name) || name.starts_with("aria-") || name.starts_with("data-")
+}
+
+/// Relative URLs, fragments and http(s)/mailto/`epublink:`/`data:image/` URLs.
+fn is_safe_url(url: &str) -> bool {
+    // 브라우저처럼 제어 문자와 공백을 무시하고 스킴을 본다 ("java\tscript:" 등)
+    let normalized: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let Some(colon) = normalized.find(':') else {
+        return true;
+    };
+    if normalized[..colon].contains(['/', '?', '#']) {
+        return true;
+    }
+    match &normalized[..colon] {
+        "http" | "https" | "mailto" => true,
+        "epublink" => true,
+        "data" => normalized.starts_with("data:image/"),
+        _ => false,
+    }
+}
+
+fn is_safe_style(style: &str) -> bool {
+    let lower = style.to_ascii_lowercase();
+    !lower.contains("expression(") && !lower.contains("javascript:") && !lower.contains("vbscript:")
+}
+
+fn push_escaped(out: &mut String, text: &str, attribute: bool) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '"' if attribute => out.push_str("&quot;"),
+            '<' if !attribute => out.push_str("&lt;"),
+            '>' if !attribute => out.push_str("&gt;"),
+            '\u{a0}' => out.push_str("&nbsp;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// CSS made safe to put between `<style>` and `</style>`: a `</style` inside it (any case)
+/// would end the element early, so it becomes `<\/style`.
+pub fn escape_style_text(css: &str) -> String {
+    let end_re = regex::Regex::new(r"(?i)</(style)").unwrap();
+    end_re.replace_all(css, r"<\/$1").into_owned()
+}
+
+/// Keep remote images and CSS resources from loading: `src="https://..."` becomes
+/// `data-blocked-src="https://..."`, remote `url(...)` in styles is emptied and
+/// `@import "https://..."` is dropped.
+pub fn block_remote_resources(html: &str) -> String {
+    let attr_re = regex::Regex::new(
+        r#"(?is)(\s)(src|srcset|poster|xlink:href|background)(\s*=\s*["']\s*)((?:https?:)?//[^"']*)"#,
+    )
+    .unwrap();
+    let css_url_re = regex::Regex::new(r#"(?i)url\(\s*['"]?\s*((?:https?:)?//[^'")]*)['"]?\s*\)"#).unwrap();
+    let css_import_re =
+        regex::Regex::new(r#"(?i)@import\s*['"]\s*((?:https?:)?//[^'"]*)['"][^;<]*;?"#).unwrap();
+
+    let html = rewrite_tags(html, |tag| {
+        // <a href>는 사용자가 누를 때만 열리므로 그대로 둔다
+        attr_re
+            .replace_all(tag, |caps: &regex::Captures| {
+                if is_local_host(&caps[4]) {
+                    caps[0].to_string()
+                } else {
+                    format!("{}data-blocked-{}{}{}", &caps[1], &caps[2], &caps[3], &caps[4])
+                }
+            })
+            .into_owned()
+    });
+    let html = css_url_re.replace_all(&html, |caps: &regex::Captures| {
+        if is_local_host(&caps[1]) {
+            caps[0].to_string()
+        } else {
+            "url()".to_string()
+        }
+    });
+    css_import_re
+        .replace_all(&html, |caps: &regex::Captures| {
+            if is_local_host(&caps[1]) {
+                caps[0].to_string()
+            } else {
+                String::new()
+            }
+        })
+        .into_owned()
+}
+
+/// Apply `rewrite` to every start tag, leaving text and end tags alone.
+fn rewrite_tags(html: &str, mut rewrite: impl FnMut(&str) -> String) -> String {
+    let tag_re = regex::Regex::new(r"(?s)<[a-zA-Z][^>]*>").unwrap();
+    tag_re
+        .replace_all(html, |caps: &regex::Captures| rewrite(&caps[0]))
+        .into_owned()
+}
+
+/// URLs served by the app itself (custom protocols on Windows use `http://<scheme>.localhost`).
+fn is_local_host(url: &str) -> bool {
+    let host = url
+        .trim_start_matches("https:")
+        .trim_start_matches("http:")
+        .trim_start_matches("//")
+        .split(['/', '?', '#', ':'])
+        .next()
+        .unwrap_or("");
+    host == "localhost" || host.ends_with(".localhost")
+}
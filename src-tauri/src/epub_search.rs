@@ -0,0 +1,59 @@
+//! Full-text search across EPUB chapters.
+//!
+//! 챕터 원본 HTML에서 태그를 걷어낸 텍스트를 검색하고, 일치 위치 바로 앞에 있는
+//! `id` 속성을 앵커로 돌려준다. 처리된 챕터 HTML(캐시)이 아니라 원본을 쓰므로
+//! 아직 열어 보지 않은 챕터도 렌더링하지 않고 검색된다.
+
+use crate::epub_reader::{extract_body_content, EpubChapter};
+use crate::html_text::{self, VisibleText};
+use crate::search::{self, Matcher};
+use serde::Serialize;
+
+/// Stop after this many matches.
+pub const MAX_EPUB_MATCHES: usize = 2000;
+
+/// Chars of context on each side of a match.
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EpubSearchMatch {
+    pub chapter_index: usize,
+    pub chapter_title: String,
+    /// Snippet split around the match (whitespace collapsed).
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+    /// `id` of the nearest element before the match, to scroll to.
+    pub anchor: Option<String>,
+    /// 0-based number of this match within the chapter (for highlighting the n-th hit).
+    pub occurrence: usize,
+}
+
+/// Search chapters in spine order (at most `MAX_EPUB_MATCHES` results).
+pub fn search_chapters(chapters: &[EpubChapter], matcher: &Matcher) -> Vec<EpubSearchMatch> {
+    let mut results = Vec::new();
+    for (chapter_index, chapter) in chapters.iter().enumerate() {
+        let VisibleText { text, anchors } = html_text::visible_text(&extract_body_content(&chapter.source));
+        for (occurrence, range) in search::find_in_text(&text, matcher).into_iter().enumerate() {
+            if results.len() >= MAX_EPUB_MATCHES {
+                return results;
+            }
+            let anchor_idx = anchors.partition_point(|(offset, _)| *offset <= range.start);
+            results.push(EpubSearchMatch {
+                chapter_index,
+                chapter_title: chapter.title.clone(),
+                before: tail_chars(&text[..range.start], SNIPPET_CONTEXT_CHARS),
+                matched: text[range.clone()].to_string(),
+                after: text[range.end..].chars().take(SNIPPET_CONTEXT_CHARS).collect(),
+                anchor: anchor_idx.checked_sub(1).map(|i| anchors[i].1.clone()),
+                occurrence,
+            });
+        }
+    }
+    results
+}
+
+fn tail_chars(s: &str, n: usize) -> String {
+    let start = s.char_indices().rev().nth(n.saturating_sub(1)).map_or(0, |(i, _)| i);
+    s[start..].to_string()
+}
@@ -0,0 +1,202 @@
+//! Minimal EPUB 3 writer (with an EPUB 2 NCX for older readers).
+
+use std::io::Write;
+use std::path::Path;
+
+use crate::exporter::escape_html;
+
+/// Book metadata written to the OPF.
+#[derive(Debug, Clone, Default)]
+pub struct BookMetadata {
+    pub title: String,
+    /// BCP 47 language tag.
+    pub language: String,
+    pub author: Option<String>,
+    pub publisher: Option<String>,
+    pub description: Option<String>,
+    pub subjects: Vec<String>,
+    /// Cover image (mime type, bytes).
+    pub cover: Option<(&'static str, Vec<u8>)>,
+}
+
+pub struct EpubSection {
+    pub title: String,
+    /// XHTML body content (already escaped/marked up).
+    pub body_html: String,
+}
+
+/// Plain text → XHTML paragraphs.
+pub fn text_to_xhtml_body(text: &str) -> String {
+    let mut body = String::with_capacity(text.len() + text.len() / 8);
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            body.push_str("<p class=\"blank\">&#160;</p>\n");
+        } else {
+            body.push_str("<p>");
+            body.push_str(&escape_html(line));
+            body.push_str("</p>\n");
+        }
+    }
+    body
+}
+
+pub fn write_epub(output: &Path, meta: &BookMetadata, sections: &[EpubSection]) -> anyhow::Result<()> {
+    if sections.is_empty() {
+        anyhow::bail!("EPUB needs at least one chapter");
+    }
+    let file = std::fs::File::create(output)?;
+    let mut zip = zip::ZipWriter::new(file);
+    // mimetype은 압축하지 않은 첫 항목이어야 한다
+    let stored = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let deflated = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file("mimetype", stored)?;
+    zip.write_all(b"application/epub+zip")?;
+
+    zip.start_file("META-INF/container.xml", deflated)?;
+    zip.write_all(
+        b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+          <container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">\n\
+          <rootfiles><rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\"/></rootfiles>\n\
+          </container>\n",
+    )?;
+
+    let title = escape_html(&meta.title);
+    let language = escape_html(&meta.language);
+    let identifier = format!(
+        "urn:simplereader:{}-{}",
+        std::process::id(),
+        chrono::Local::now().timestamp_millis()
+    );
+    let modified = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
+
+    let mut manifest = String::new();
+    let mut spine = String::new();
+    let mut nav_items = String::new();
+    let mut nav_points = String::new();
+    for (i, section) in sections.iter().enumerate() {
+        let id = format!("ch{:04}", i + 1);
+        let href = format!("{}.xhtml", id);
+        let section_title = escape_html(&section.title);
+        manifest.push_str(&format!(
+            "<item id=\"{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n",
+            id, href
+        ));
+        spine.push_str(&format!("<itemref idref=\"{}\"/>\n", id));
+        nav_items.push_str(&format!("<li><a href=\"{}\">{}</a></li>\n", href, section_title));
+        nav_points.push_str(&format!(
+            "<navPoint id=\"np{n}\" playOrder=\"{n}\"><navLabel><text>{t}</text></navLabel><content src=\"{h}\"/></navPoint>\n",
+            n = i + 1,
+            t = section_title,
+            h = href
+        ));
+
+        zip.start_file(format!("OEBPS/{}", href), deflated)?;
+        zip.write_all(
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n\
+                 <html xmlns=\"http://www.w3.org/1999/xhtml\" xml:lang=\"{lang}\" lang=\"{lang}\">\n\
+                 <head><meta charset=\"UTF-8\"/><title>{t}</title><link rel=\"stylesheet\" href=\"style.css\"/></head>\n\
+                 <body>\n<h2>{t}</h2>\n{body}</body>\n</html>\n",
+                lang = language,
+                t = section_title,
+                body = section.body_html
+            )
+            .as_bytes(),
+        )?;
+    }
+
+    let mut extra_meta = String::new();
+    for (tag, value) in [
+        ("creator", &meta.author),
+        ("publisher", &meta.publisher),
+        ("description", &meta.description),
+    ] {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            extra_meta.push_str(&format!("<dc:{0}>{1}</dc:{0}>\n", tag, escape_html(value)));
+        }
+    }
+    for subject in meta.subjects.iter().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        extra_meta.push_str(&format!("<dc:subject>{}</dc:subject>\n", escape_html(subject)));
+    }
+    if let Some((mime, data)) = &meta.cover {
+        let href = format!("cover.{}", cover_extension(mime));
+        zip.start_file(format!("OEBPS/{}", href), stored)?;
+        zip.write_all(data)?;
+        manifest.push_str(&format!(
+            "<item id=\"cover-image\" href=\"{}\" media-type=\"{}\" properties=\"cover-image\"/>\n",
+            href, mime
+        ));
+        // EPUB 2 리더용
+        extra_meta.push_str("<meta name=\"cover\" content=\"cover-image\"/>\n");
+    }
+
+    zip.start_file("OEBPS/style.css", deflated)?;
+    zip.write_all(b"p { margin: 0; text-indent: 0; }\np.blank { height: 1em; }\nh2 { margin: 1em 0; }\n")?;
+
+    zip.start_file("OEBPS/nav.xhtml", deflated)?;
+    zip.write_all(
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n\
+             <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" lang=\"{lang}\">\n\
+             <head><meta charset=\"UTF-8\"/><title>{t}</title></head>\n\
+             <body><nav epub:type=\"toc\"><ol>\n{items}</ol></nav></body>\n</html>\n",
+            lang = language,
+            t = title,
+            items = nav_items
+        )
+        .as_bytes(),
+    )?;
+
+    zip.start_file("OEBPS/toc.ncx", deflated)?;
+    zip.write_all(
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <ncx xmlns=\"http://www.daisy.org/z3986/2005/ncx/\" version=\"2005-1\">\n\
+             <head><meta name=\"dtb:uid\" content=\"{id}\"/></head>\n\
+             <docTitle><text>{t}</text></docTitle>\n<navMap>\n{points}</navMap>\n</ncx>\n",
+            id = identifier,
+            t = title,
+            points = nav_points
+        )
+        .as_bytes(),
+    )?;
+
+    zip.start_file("OEBPS/content.opf", deflated)?;
+    zip.write_all(
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"bookid\">\n\
+             <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n\
+             <dc:identifier id=\"bookid\">{id}</dc:identifier>\n<dc:title>{t}</dc:title>\n\
+             <dc:language>{lang}</dc:language>\n{extra}<meta property=\"dcterms:modified\">{modified}</meta>\n\
+             </metadata>\n<manifest>\n\
+             <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n\
+             <item id=\"ncx\" href=\"toc.ncx\" media-type=\"application/x-dtbncx+xml\"/>\n\
+             <item id=\"css\" href=\"style.css\" media-type=\"text/css\"/>\n{manifest}</manifest>\n\
+             <spine toc=\"ncx\">\n{spine}</spine>\n</package>\n",
+            id = identifier,
+            t = title,
+            lang = language,
+            extra = extra_meta,
+            modified = modified,
+            manifest = manifest,
+            spine = spine
+        )
+        .as_bytes(),
+    )?;
+
+    zip.finish()?;
+    Ok(())
+}
+
+fn cover_extension(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        _ => "jpg",
+    }
+}
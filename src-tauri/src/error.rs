@@ -5,6 +5,26 @@ pub enum AppError {
     #[error("File not found: {0}")]
     FileNotFound(String),
 
+    #[error("Network location is offline or unreachable: {0}")]
+    Offline(String),
+
+    #[error("Timed out accessing: {0}")]
+    Timeout(String),
+
+    #[error("Password required: {0}")]
+    PasswordRequired(String),
+
+    #[error("Wrong password: {0}")]
+    WrongPassword(String),
+
+    /// The book is encrypted with a DRM scheme (named in the message) we can't decrypt.
+    #[error("DRM protected: {0}")]
+    DrmProtected(String),
+
+    /// An archive entry failed its CRC check (named in the message).
+    #[error("Corrupt archive entry: {0}")]
+    CorruptEntry(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -0,0 +1,485 @@
+//! Export the reading view (text + formatting + font + theme) to standalone HTML or PDF.
+//!
+//! PDF는 직접 폰트를 임베드하지 않고, 생성한 HTML을 설치된 Chromium 계열 브라우저
+//! (Edge / Chrome / Chromium)의 headless 인쇄 기능으로 변환한다.
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+use crate::epub_writer::{BookMetadata, EpubSection};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportOptions {
+    pub output_path: String,
+    /// Formatter operation to apply before export (defaults to the one saved for the file).
+    #[serde(default)]
+    pub format_type: Option<String>,
+    #[serde(default)]
+    pub font_family: Option<String>,
+    #[serde(default)]
+    pub font_size: Option<u32>,
+    #[serde(default)]
+    pub font_bold: Option<bool>,
+    /// "dark" or "light" (defaults to the app theme).
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Render text as a standalone HTML document styled like the reading view.
+pub fn render_html(text: &str, title: &str, options: &ExportOptions, config: &AppConfig) -> String {
+    let font_family = options.font_family.as_deref().unwrap_or(&config.font_family);
+    let font_size = options.font_size.unwrap_or(config.font_size);
+    let font_weight = if options.font_bold.unwrap_or(config.font_bold) {
+        "bold"
+    } else {
+        "normal"
+    };
+    let (background, foreground) = match options.theme.as_deref().unwrap_or(&config.theme) {
+        "dark" => ("#1e1e1e", "#d4d4d4"),
+        _ => ("#ffffff", "#222222"),
+    };
+
+    let mut body = String::with_capacity(text.len() + text.len() / 8);
+    for paragraph in text.split('\n') {
+        let line = paragraph.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            body.push_str("<p class=\"blank\">&nbsp;</p>\n");
+        } else {
+            body.push_str("<p>");
+            body.push_str(&escape_html(line));
+            body.push_str("</p>\n");
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n\
+         body {{ background: {bg}; color: {fg}; font-family: '{font}', sans-serif; font-size: {size}px; \
+         font-weight: {weight}; line-height: 1.7; max-width: 48em; margin: 2em auto; padding: 0 1em; }}\n\
+         p {{ margin: 0; white-space: pre-wrap; word-break: break-word; }}\n\
+         @media print {{ body {{ max-width: none; margin: 0; }} }}\n\
+         </style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        title = escape_html(title),
+        bg = background,
+        fg = foreground,
+        font = font_family.replace('\'', ""),
+        size = font_size,
+        weight = font_weight,
+        body = body,
+    )
+}
+
+/// Candidate headless-capable browsers, in order of preference.
+fn browser_candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        for var in ["ProgramFiles(x86)", "ProgramFiles", "LOCALAPPDATA"] {
+            if let Ok(base) = std::env::var(var) {
+                let base = PathBuf::from(base);
+                candidates.push(base.join(r"Microsoft\Edge\Application\msedge.exe"));
+                candidates.push(base.join(r"Google\Chrome\Application\chrome.exe"));
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        candidates.push(PathBuf::from(
+            "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+        ));
+        candidates.push(PathBuf::from(
+            "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+        ));
+        candidates.push(PathBuf::from("/Applications/Chromium.app/Contents/MacOS/Chromium"));
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        if let Some(paths) = std::env::var_os("PATH") {
+            for dir in std::env::split_paths(&paths) {
+                for name in ["chromium", "chromium-browser", "google-chrome", "microsoft-edge"] {
+                    candidates.push(dir.join(name));
+                }
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Convert an HTML document to PDF using a headless browser.
+pub fn html_to_pdf(html: &str, output: &Path) -> anyhow::Result<()> {
+    let browser = browser_candidates()
+        .into_iter()
+        .find(|p| p.is_file())
+        .ok_or_else(|| {
+            anyhow::anyhow!("PDF export requires Microsoft Edge, Google Chrome or Chromium")
+        })?;
+
+    let temp_html = std::env::temp_dir().join(format!(
+        "simple-reader-export-{}-{}.html",
+        std::process::id(),
+        chrono::Local::now().timestamp_millis()
+    ));
+    std::fs::write(&temp_html, html)?;
+
+    let status = std::process::Command::new(&browser)
+        .arg("--headless")
+        .arg("--disable-gpu")
+        .arg("--no-pdf-header-footer")
+        .arg(format!("--print-to-pdf={}", output.display()))
+        .arg(&temp_html)
+        .status();
+    let _ = std::fs::remove_file(&temp_html);
+
+    let status = status.map_err(|e| anyhow::anyhow!("Failed to launch {}: {}", browser.display(), e))?;
+    if !status.success() || !output.exists() {
+        anyhow::bail!("PDF conversion failed ({})", status);
+    }
+    Ok(())
+}
+
+/// Export text to the requested format ("html" or "pdf") and return the written path.
+pub fn export_text(
+    text: &str,
+    default_title: &str,
+    format: &str,
+    options: &ExportOptions,
+    config: &AppConfig,
+) -> anyhow::Result<PathBuf> {
+    let formatted = match options.format_type.as_deref() {
+        Some(format_type) => crate::formatter::apply_format(text, format_type)?,
+        None => text.to_string(),
+    };
+    let title = options.title.as_deref().unwrap_or(default_title);
+    let html = render_html(&formatted, title, options, config);
+    let output = PathBuf::from(&options.output_path);
+
+    match format {
+        "html" => std::fs::write(&output, html)?,
+        "pdf" => html_to_pdf(&html, &output)?,
+        _ => anyhow::bail!("Unknown export format: {}", format),
+    }
+    Ok(output)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnkiDeckOptions {
+    pub output_path: String,
+    /// Deck name written to the file header (defaults to the book's file name).
+    #[serde(default)]
+    pub deck_name: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Lines of surrounding text included after the bookmarked line.
+    #[serde(default)]
+    pub context_lines: usize,
+}
+
+/// One flashcard: the bookmarked passage on the front, memo + source on the back.
+pub struct AnkiCard {
+    pub passage: String,
+    pub memo: String,
+    pub location: String,
+}
+
+/// TSV 필드 안의 탭/줄바꿈은 Anki의 HTML 모드로 보존한다.
+fn anki_field(text: &str) -> String {
+    escape_html(text.trim())
+        .replace('\t', "&#9;")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+/// Write cards as an Anki-importable TSV (File → Import, header directives set deck/tags).
+pub fn write_anki_tsv(cards: &[AnkiCard], deck_name: &str, options: &AnkiDeckOptions) -> anyhow::Result<PathBuf> {
+    let mut out = String::new();
+    out.push_str("#separator:tab\n#html:true\n");
+    out.push_str(&format!("#deck:{}\n", deck_name.replace(['\n', '\r'], " ")));
+    if !options.tags.is_empty() {
+        let tags: Vec<String> = options.tags.iter().map(|t| t.replace(char::is_whitespace, "_")).collect();
+        out.push_str(&format!("#tags:{}\n", tags.join(" ")));
+    }
+    for card in cards {
+        let back = if card.memo.trim().is_empty() {
+            anki_field(&card.location)
+        } else {
+            format!("{}<br><br>{}", anki_field(&card.memo), anki_field(&card.location))
+        };
+        out.push_str(&anki_field(&card.passage));
+        out.push('\t');
+        out.push_str(&back);
+        out.push('\n');
+    }
+
+    let output = PathBuf::from(&options.output_path);
+    std::fs::write(&output, out)?;
+    Ok(output)
+}
+
+/// Encode text for a device. `encoding` is an encoding_rs label, or "utf-8-bom".
+fn encode_text(text: &str, encoding: &str) -> anyhow::Result<Vec<u8>> {
+    let label = encoding.trim().to_lowercase();
+    if label == "utf-8-bom" {
+        let mut out = vec![0xEF, 0xBB, 0xBF];
+        out.extend_from_slice(text.as_bytes());
+        return Ok(out);
+    }
+    let enc = encoding_rs::Encoding::for_label(label.as_bytes())
+        .ok_or_else(|| anyhow::anyhow!("Unknown encoding: {}", encoding))?;
+    let (bytes, _, had_errors) = enc.encode(text);
+    if had_errors {
+        anyhow::bail!("Text contains characters that {} cannot represent", enc.name());
+    }
+    Ok(bytes.into_owned())
+}
+
+/// Copy/convert a book onto a mounted e-reader.
+///
+/// Series-like names ("작품 3권.txt") become `<target>/<series>/<series> - 003.<ext>`
+/// so devices that sort by file name keep the volumes in order.
+pub fn send_to_device(
+    source: &Path,
+    text: Option<&str>,
+    target_dir: &Path,
+    format: &str,
+    encoding: Option<&str>,
+) -> anyhow::Result<PathBuf> {
+    if !target_dir.is_dir() {
+        anyhow::bail!("Device folder not found: {}", target_dir.display());
+    }
+    let source_ext = source
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let format = format.to_lowercase();
+
+    let stem = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "book".to_string());
+    let (dir, file_stem) = match crate::series::parse_series_name(&stem) {
+        Some(name) => {
+            let series = crate::series::sanitize_file_name(&name.series);
+            (
+                target_dir.join(&series),
+                format!("{} - {:03}", series, name.number),
+            )
+        }
+        None => (target_dir.to_path_buf(), crate::series::sanitize_file_name(&stem)),
+    };
+    std::fs::create_dir_all(&dir)?;
+    let output = dir.join(format!("{}.{}", file_stem, format));
+
+    match (source_ext.as_str(), format.as_str()) {
+        (_, "txt") => {
+            let text = text.ok_or_else(|| anyhow::anyhow!("TXT export needs a text document"))?;
+            std::fs::write(&output, encode_text(text, encoding.unwrap_or("utf-8"))?)?;
+        }
+        ("epub", "azw3") => anyhow::bail!("EPUB → AZW3 conversion is not available yet"),
+        (src, dst) if src == dst => {
+            std::fs::copy(source, &output)?;
+        }
+        (src, dst) => anyhow::bail!("Cannot convert {} to {}", src, dst),
+    }
+    Ok(output)
+}
+
+/// Split a text document at detected chapter headings.
+/// `naming`: "number" (`001.txt`) or "title" (`001 - 제1화.txt`); `format`: "txt" or "epub"
+/// (one EPUB with a chapter per section, written into `target_dir`).
+/// Returns the written paths.
+pub fn split_by_chapters(
+    rope: &ropey::Rope,
+    book_title: &str,
+    target_dir: &Path,
+    naming: &str,
+    format: &str,
+    language: &str,
+) -> anyhow::Result<Vec<PathBuf>> {
+    if !matches!(naming, "number" | "title") {
+        anyhow::bail!("Unknown naming scheme: {}", naming);
+    }
+    let sections = chapter_sections(rope, book_title);
+    if sections.is_empty() {
+        anyhow::bail!("No chapter headings detected");
+    }
+
+    std::fs::create_dir_all(target_dir)?;
+    match format {
+        "txt" => {
+            let width = sections.len().to_string().len().max(3);
+            let mut written = Vec::with_capacity(sections.len());
+            for (i, section) in sections.iter().enumerate() {
+                let name = match naming {
+                    "title" => format!(
+                        "{:0width$} - {}.txt",
+                        i + 1,
+                        crate::series::sanitize_file_name(&section.title),
+                        width = width
+                    ),
+                    _ => format!("{:0width$}.txt", i + 1, width = width),
+                };
+                let path = target_dir.join(name);
+                std::fs::write(&path, &section.text)?;
+                written.push(path);
+            }
+            Ok(written)
+        }
+        "epub" => {
+            let output = target_dir.join(format!("{}.epub", crate::series::sanitize_file_name(book_title)));
+            let meta = BookMetadata {
+                title: book_title.to_string(),
+                language: language.to_string(),
+                ..Default::default()
+            };
+            crate::epub_writer::write_epub(&output, &meta, &epub_sections(&sections))?;
+            Ok(vec![output])
+        }
+        _ => anyhow::bail!("Unknown split format: {}", format),
+    }
+}
+
+/// A chunk of a text document that becomes one output file or EPUB chapter.
+struct TextSection {
+    title: String,
+    text: String,
+    /// `text` starts with its heading line. Sections made up by the exporter (text before
+    /// the first heading, or a document with no headings) don't have one.
+    has_heading: bool,
+}
+
+/// One section per detected chapter. Text before the first heading becomes a section
+/// titled `book_title`. Empty if no headings were found.
+fn chapter_sections(rope: &ropey::Rope, book_title: &str) -> Vec<TextSection> {
+    let chapters = crate::chapter_detect::detect_chapters(rope);
+    if chapters.is_empty() {
+        return Vec::new();
+    }
+    let mut sections = Vec::with_capacity(chapters.len() + 1);
+    // 첫 제목 앞에 본문이 있으면 별도 섹션으로 남긴다
+    let preamble_end = rope.line_to_char(chapters[0].line);
+    if rope.slice(..preamble_end).chars().any(|c| !c.is_whitespace()) {
+        sections.push(TextSection {
+            title: book_title.to_string(),
+            text: rope.slice(..preamble_end).to_string(),
+            has_heading: false,
+        });
+    }
+
+    let total_lines = rope.len_lines();
+    for (i, chapter) in chapters.iter().enumerate() {
+        let end_line = chapters.get(i + 1).map(|c| c.line).unwrap_or(total_lines);
+        let start = rope.line_to_char(chapter.line);
+        let end = rope.line_to_char(end_line);
+        sections.push(TextSection {
+            title: chapter.title.clone(),
+            text: rope.slice(start..end).to_string(),
+            has_heading: true,
+        });
+    }
+    sections
+}
+
+fn epub_sections(sections: &[TextSection]) -> Vec<EpubSection> {
+    sections
+        .iter()
+        .map(|section| {
+            // 제목 줄은 <h2>로 따로 들어가므로 본문에서 뺀다
+            let body = if section.has_heading {
+                section.text.split_once('\n').map(|(_, rest)| rest).unwrap_or("")
+            } else {
+                section.text.as_str()
+            };
+            EpubSection {
+                title: section.title.clone(),
+                body_html: crate::epub_writer::text_to_xhtml_body(body),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TextToEpubOptions {
+    pub output_path: String,
+    /// Defaults to the file name.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// BCP 47 tag (default: `AppConfig::epub_language`).
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub publisher: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub subjects: Vec<String>,
+    /// Image file embedded as the cover.
+    #[serde(default)]
+    pub cover_path: Option<String>,
+}
+
+/// Convert a plain-text novel to an EPUB, one chapter per detected heading
+/// (the whole text becomes a single chapter if none are found).
+pub fn text_to_epub(
+    rope: &ropey::Rope,
+    default_title: &str,
+    default_language: &str,
+    options: &TextToEpubOptions,
+) -> anyhow::Result<PathBuf> {
+    let title = options
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(default_title);
+    let mut sections = chapter_sections(rope, title);
+    if sections.is_empty() {
+        sections.push(TextSection {
+            title: title.to_string(),
+            text: rope.to_string(),
+            has_heading: false,
+        });
+    }
+    let cover = match options.cover_path.as_deref().filter(|p| !p.is_empty()) {
+        Some(path) => Some((crate::image_reader::image_mime(path), std::fs::read(path)?)),
+        None => None,
+    };
+    let meta = BookMetadata {
+        title: title.to_string(),
+        language: options
+            .language
+            .clone()
+            .filter(|l| !l.trim().is_empty())
+            .unwrap_or_else(|| default_language.to_string()),
+        author: options.author.clone(),
+        publisher: options.publisher.clone(),
+        description: options.description.clone(),
+        subjects: options.subjects.clone(),
+        cover,
+    };
+    let output = PathBuf::from(&options.output_path);
+    crate::epub_writer::write_epub(&output, &meta, &epub_sections(&sections))?;
+    Ok(output)
+}
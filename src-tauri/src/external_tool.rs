@@ -0,0 +1,66 @@
+//! Locating the external command-line tools (unrar, 7-Zip, ImageMagick, mutool, Tesseract).
+//!
+//! 설정에 경로가 있으면 그것만 쓰고, 없으면 PATH, 그다음 Windows의 Program Files 기본 설치 경로를 찾는다.
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Child, Output};
+
+/// Find a tool: the `configured` path (an error if it doesn't exist), else the first of
+/// `names` (without `.exe`) on PATH, else `program_files_subdirs` under `%ProgramFiles%`
+/// on Windows. Ok(None) when it isn't installed anywhere.
+pub fn find_tool(
+    configured: &str,
+    label: &str,
+    names: &[&str],
+    program_files_subdirs: &[&str],
+) -> anyhow::Result<Option<PathBuf>> {
+    if !configured.trim().is_empty() {
+        let path = PathBuf::from(configured.trim());
+        if path.is_file() {
+            return Ok(Some(path));
+        }
+        anyhow::bail!("{} not found at {}", label, path.display());
+    }
+
+    if let Some(paths) = std::env::var_os("PATH") {
+        for dir in std::env::split_paths(&paths) {
+            if let Some(found) = names
+                .iter()
+                .map(|name| dir.join(format!("{}{}", name, std::env::consts::EXE_SUFFIX)))
+                .find(|p| p.is_file())
+            {
+                return Ok(Some(found));
+            }
+        }
+    }
+
+    if cfg!(windows) {
+        if let Ok(base) = std::env::var("ProgramFiles") {
+            if let Some(found) = program_files_subdirs
+                .iter()
+                .map(|subdir| PathBuf::from(&base).join(subdir))
+                .find(|p| p.is_file())
+            {
+                return Ok(Some(found));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Read the first `max_bytes` a tool writes to stdout, then stop it. Returns the bytes and,
+/// if the tool finished before writing that much, its output (status and stderr) to check.
+pub fn read_stdout_head(mut child: Child, max_bytes: usize) -> anyhow::Result<(Vec<u8>, Option<Output>)> {
+    let mut head = Vec::with_capacity(max_bytes);
+    if let Some(stdout) = child.stdout.take() {
+        stdout.take(max_bytes as u64).read_to_end(&mut head)?;
+    }
+    if head.len() < max_bytes {
+        return Ok((head, Some(child.wait_with_output()?)));
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+    Ok((head, None))
+}
@@ -1,3 +1,7 @@
+use crate::search::Replacement;
+use crate::text_buffer::LineEnding;
+use ropey::Rope;
+
 /// Add newline after sentence-ending punctuation (. ? !).
 /// Only adds a break if the sentence terminator is followed by a space and another character.
 pub fn add_sentence_breaks(text: &str) -> String {
@@ -76,12 +80,108 @@ pub fn remove_blank_lines(text: &str) -> String {
     result
 }
 
+/// Remove spaces/tabs at the end of every line (line breaks are kept as they are).
+pub fn strip_trailing_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            result.push('\n');
+        }
+        let cr = line.ends_with('\r');
+        let content = line.strip_suffix('\r').unwrap_or(line);
+        result.push_str(content.trim_end_matches(|c: char| c.is_whitespace() && c != '\r'));
+        if cr {
+            result.push('\r');
+        }
+    }
+    result
+}
+
+/// Edits that convert every line break to `target` and/or strip trailing whitespace,
+/// collected in a single pass over the rope (for `TextBuffer::bulk_replace`).
+pub fn normalize_edits(rope: &Rope, target: Option<LineEnding>, strip_trailing: bool) -> Vec<Replacement> {
+    struct Scan {
+        edits: Vec<Replacement>,
+        target: Option<LineEnding>,
+        strip_trailing: bool,
+        /// Whitespace run that may turn out to end its line.
+        ws_start: Option<usize>,
+        ws_text: String,
+    }
+
+    impl Scan {
+        fn line_break(&mut self, pos: usize, found: &str) {
+            if self.strip_trailing {
+                if let Some(start) = self.ws_start.take() {
+                    self.edits.push(Replacement {
+                        char_start: start,
+                        old_text: std::mem::take(&mut self.ws_text),
+                        new_text: String::new(),
+                    });
+                }
+            }
+            self.ws_start = None;
+            self.ws_text.clear();
+            let wanted = match self.target {
+                Some(LineEnding::Lf) => "\n",
+                Some(LineEnding::Crlf) => "\r\n",
+                None => return,
+            };
+            if found != wanted && !found.is_empty() {
+                self.edits.push(Replacement {
+                    char_start: pos,
+                    old_text: found.to_string(),
+                    new_text: wanted.to_string(),
+                });
+            }
+        }
+    }
+
+    let mut scan = Scan {
+        edits: Vec::new(),
+        target,
+        strip_trailing,
+        ws_start: None,
+        ws_text: String::new(),
+    };
+    // 청크 경계에서 \r\n이 갈릴 수 있으므로 \r은 다음 글자를 볼 때까지 보류한다
+    let mut pending_cr: Option<usize> = None;
+    for (pos, c) in rope.chars().enumerate() {
+        if let Some(cr_pos) = pending_cr.take() {
+            if c == '\n' {
+                scan.line_break(cr_pos, "\r\n");
+                continue;
+            }
+            scan.line_break(cr_pos, "\r");
+        }
+        match c {
+            '\r' => pending_cr = Some(pos),
+            '\n' => scan.line_break(pos, "\n"),
+            c if c.is_whitespace() => {
+                scan.ws_start.get_or_insert(pos);
+                scan.ws_text.push(c);
+            }
+            _ => {
+                scan.ws_start = None;
+                scan.ws_text.clear();
+            }
+        }
+    }
+    match pending_cr {
+        Some(cr_pos) => scan.line_break(cr_pos, "\r"),
+        // 마지막 줄의 끝 공백
+        None => scan.line_break(rope.len_chars(), ""),
+    }
+    scan.edits
+}
+
 /// Apply a format operation by name to the given text.
 pub fn apply_format(text: &str, format_type: &str) -> anyhow::Result<String> {
     match format_type {
         "sentence_breaks" => Ok(add_sentence_breaks(text)),
         "compress_blank_lines" => Ok(compress_blank_lines(text)),
         "remove_blank_lines" => Ok(remove_blank_lines(text)),
+        "strip_trailing_whitespace" => Ok(strip_trailing_whitespace(text)),
         _ => anyhow::bail!("Unknown format type: {}", format_type),
     }
 }
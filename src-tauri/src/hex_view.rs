@@ -0,0 +1,92 @@
+//! Binary file detection and hex-dump rows for the hex viewer.
+//!
+//! 텍스트 경로로 실행 파일이나 이미지를 열면 깨진 글자만 보이므로, 앞부분을 보고
+//! 바이너리로 판단되면 16바이트씩 `오프셋  헥스  |ASCII|` 형식의 줄로 보여준다.
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+pub const BYTES_PER_ROW: usize = 16;
+
+/// Bytes inspected by `looks_binary`.
+pub const SNIFF_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HexChunk {
+    pub lines: Vec<String>,
+    pub start_row: usize,
+    pub end_row: usize,
+    pub total_rows: usize,
+    pub total_bytes: u64,
+}
+
+/// NUL bytes or many control characters in the sample (UTF-16/32 text with a BOM is not binary).
+pub fn looks_binary(sample: &[u8]) -> bool {
+    const UNICODE_BOMS: [&[u8]; 4] = [&[0x00, 0x00, 0xFE, 0xFF], &[0xFF, 0xFE], &[0xFE, 0xFF], &[0xEF, 0xBB, 0xBF]];
+    if sample.is_empty() || UNICODE_BOMS.iter().any(|bom| sample.starts_with(bom)) {
+        return false;
+    }
+    if sample.contains(&0) {
+        return true;
+    }
+    let control = sample
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C | 0x1B))
+        .count();
+    control * 10 > sample.len()
+}
+
+/// Check the first `SNIFF_BYTES` of a file.
+pub fn is_binary_file(path: &Path) -> anyhow::Result<bool> {
+    let mut sample = Vec::with_capacity(SNIFF_BYTES);
+    File::open(path)?.take(SNIFF_BYTES as u64).read_to_end(&mut sample)?;
+    Ok(looks_binary(&sample))
+}
+
+/// Hex rows `start_row..end_row` of a file.
+pub fn read_chunk(path: &Path, start_row: usize, end_row: usize) -> anyhow::Result<HexChunk> {
+    let mut file = File::open(path)?;
+    let total_bytes = file.metadata()?.len();
+    let total_rows = (total_bytes as usize).div_ceil(BYTES_PER_ROW);
+    let start_row = start_row.min(total_rows);
+    let end_row = end_row.clamp(start_row, total_rows);
+
+    let offset = (start_row * BYTES_PER_ROW) as u64;
+    let mut data = Vec::with_capacity((end_row - start_row) * BYTES_PER_ROW);
+    file.seek(SeekFrom::Start(offset))?;
+    file.take(((end_row - start_row) * BYTES_PER_ROW) as u64)
+        .read_to_end(&mut data)?;
+
+    let lines = data
+        .chunks(BYTES_PER_ROW)
+        .enumerate()
+        .map(|(i, row)| format_row(offset + (i * BYTES_PER_ROW) as u64, row))
+        .collect();
+    Ok(HexChunk {
+        lines,
+        start_row,
+        end_row,
+        total_rows,
+        total_bytes,
+    })
+}
+
+/// `00000010  48 65 6c 6c 6f 0a 00 00  00 00 00 00 00 00 00 00  |Hello...........|`
+fn format_row(offset: u64, row: &[u8]) -> String {
+    let mut line = format!("{:08x} ", offset);
+    for i in 0..BYTES_PER_ROW {
+        if i % 8 == 0 {
+            line.push(' ');
+        }
+        match row.get(i) {
+            Some(b) => line.push_str(&format!("{:02x} ", b)),
+            None => line.push_str("   "),
+        }
+    }
+    line.push_str(" |");
+    line.extend(row.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }));
+    line.push('|');
+    line
+}
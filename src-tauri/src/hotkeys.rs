@@ -0,0 +1,67 @@
+//! Global hotkeys: boss key (instant hide) and show/hide toggle.
+//!
+//! 단축키는 AppConfig에서 설정하며, 설정 저장 시 다시 등록된다.
+//! 빈 문자열이면 해당 단축키는 비활성화.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::config::AppConfig;
+
+#[derive(Clone, Serialize)]
+struct BossKeyPayload {
+    mute_audio: bool,
+}
+
+/// (Re-)register global hotkeys from config. Previously registered hotkeys are removed first.
+pub fn apply_hotkeys<R: Runtime>(app: &AppHandle<R>, config: &AppConfig) -> anyhow::Result<()> {
+    let shortcuts = app.global_shortcut();
+    shortcuts
+        .unregister_all()
+        .map_err(|e| anyhow::anyhow!("Failed to unregister hotkeys: {}", e))?;
+
+    let boss_key = config.boss_key.trim();
+    if !boss_key.is_empty() {
+        let mute_audio = config.boss_key_mute_audio;
+        shortcuts
+            .on_shortcut(boss_key, move |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    hide_for_boss_key(app, mute_audio);
+                }
+            })
+            .map_err(|e| anyhow::anyhow!("Invalid boss key '{}': {}", boss_key, e))?;
+    }
+
+    let toggle_key = config.toggle_window_key.trim();
+    if !toggle_key.is_empty() {
+        shortcuts
+            .on_shortcut(toggle_key, |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    toggle_main_window(app);
+                }
+            })
+            .map_err(|e| anyhow::anyhow!("Invalid show/hide key '{}': {}", toggle_key, e))?;
+    }
+
+    Ok(())
+}
+
+/// Hide the window immediately and tell the frontend to pause/mute audio (TTS).
+fn hide_for_boss_key<R: Runtime>(app: &AppHandle<R>, mute_audio: bool) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    let _ = app.emit("boss-key", BossKeyPayload { mute_audio });
+}
+
+fn toggle_main_window<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        crate::focus_main_window(app);
+    }
+}
@@ -0,0 +1,177 @@
+//! Text of (X)HTML chapters: one tag walker shared by plain-text extraction,
+//! EPUB full-text search and highlight offsets.
+//!
+//! `walk`는 브라우저의 `textContent`처럼 `<style>`/`<script>` 안쪽만 텍스트에서 뺀다
+//! (하이라이트 오프셋이 이 기준). `visible_text`는 그 위에서 루비 읽는 법(`<rt>`/`<rp>`)과
+//! `<head>`까지 빼고, 공백을 접고, 블록 태그를 줄바꿈으로 바꾼다.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+static ENTITY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z][a-zA-Z0-9]*);").unwrap());
+pub static ID_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?i)\bid\s*=\s*["']([^"']+)["']"#).unwrap());
+static DECODE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#[xX][0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap());
+static SPACE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+").unwrap());
+
+/// Elements that end a line of visible text.
+const BLOCK_TAGS: &[&str] = &[
+    "br", "p", "div", "h1", "h2", "h3", "h4", "h5", "h6", "li", "tr", "td", "blockquote", "section",
+    "article", "pre", "hr",
+];
+
+/// Elements whose content is not visible body text.
+const HIDDEN_TAGS: &[&str] = &["rt", "rp", "head"];
+
+pub enum Segment<'a> {
+    /// A tag and the text offset where it appears.
+    Tag(&'a str, usize),
+    /// Text between tags (entities not decoded) and its text offset.
+    Text(&'a str, usize),
+    /// Contents of `<style>`/`<script>`, which don't count as text.
+    Raw(&'a str),
+}
+
+/// Visit tags and text runs in order. Offsets count chars, an entity counting as one.
+pub fn walk<'a>(html: &'a str, mut visit: impl FnMut(Segment<'a>)) {
+    let mut offset = 0;
+    let mut last = 0;
+    let mut raw_text = false;
+    for tag in TAG_RE.find_iter(html) {
+        let text = &html[last..tag.start()];
+        if raw_text {
+            visit(Segment::Raw(text));
+        } else {
+            visit(Segment::Text(text, offset));
+            offset += count_units(text);
+        }
+        last = tag.end();
+        let tag = tag.as_str();
+        let (closing, name) = tag_name(tag);
+        if name == "style" || name == "script" {
+            raw_text = !closing && !tag.ends_with("/>");
+        }
+        visit(Segment::Tag(tag, offset));
+    }
+    let text = &html[last..];
+    if raw_text {
+        visit(Segment::Raw(text));
+    } else {
+        visit(Segment::Text(text, offset));
+    }
+}
+
+/// Byte length of the next text unit: an entity or a single char.
+pub fn unit_len(text: &str) -> usize {
+    if text.starts_with('&') {
+        if let Some(m) = ENTITY_RE.find(text) {
+            return m.end();
+        }
+    }
+    text.chars().next().map_or(1, char::len_utf8)
+}
+
+fn count_units(text: &str) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < text.len() {
+        i += unit_len(&text[i..]);
+        count += 1;
+    }
+    count
+}
+
+/// (is closing tag, lowercase element name) of a tag; the name is empty for comments etc.
+fn tag_name(tag: &str) -> (bool, String) {
+    let inner = tag.trim_start_matches('<');
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+    let name = inner
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    (closing, name)
+}
+
+pub struct VisibleText {
+    /// Whitespace collapsed; one line per block, a blank line at most between blocks.
+    pub text: String,
+    /// (byte offset in `text`, id) of every element carrying an `id`, in order.
+    pub anchors: Vec<(usize, String)>,
+}
+
+/// The text a reader sees in a chapter body.
+pub fn visible_text(html: &str) -> VisibleText {
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut anchors = Vec::new();
+    let mut hidden = 0usize;
+    walk(html, |segment| match segment {
+        Segment::Text(raw, _) if hidden == 0 && !raw.is_empty() => {
+            let decoded = decode_entities(raw);
+            let collapsed = SPACE_RE.replace_all(&decoded, " ");
+            // 줄 첫머리의 공백은 버린다
+            let collapsed = if text.is_empty() || text.ends_with('\n') || text.ends_with(' ') {
+                collapsed.trim_start()
+            } else {
+                &collapsed[..]
+            };
+            text.push_str(collapsed);
+        }
+        Segment::Tag(tag, _) => {
+            let (closing, name) = tag_name(tag);
+            if HIDDEN_TAGS.contains(&name.as_str()) {
+                if closing {
+                    hidden = hidden.saturating_sub(1);
+                } else if !tag.ends_with("/>") {
+                    hidden += 1;
+                }
+                return;
+            }
+            if hidden > 0 {
+                return;
+            }
+            if BLOCK_TAGS.contains(&name.as_str()) && !text.is_empty() && !text.ends_with("\n\n") {
+                text.truncate(text.trim_end_matches(' ').len());
+                text.push('\n');
+            }
+            if !closing {
+                if let Some(caps) = ID_RE.captures(tag) {
+                    anchors.push((text.len(), caps[1].to_string()));
+                }
+            }
+        }
+        _ => {}
+    });
+    VisibleText { text, anchors }
+}
+
+pub fn decode_entities(text: &str) -> String {
+    DECODE_RE
+        .replace_all(text, |caps: &regex::Captures| {
+            let name = &caps[1];
+            let decoded = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = name.strip_prefix('#') {
+                dec.parse::<u32>().ok().and_then(char::from_u32)
+            } else {
+                match name {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    _ => None,
+                }
+            };
+            decoded
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
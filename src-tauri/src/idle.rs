@@ -0,0 +1,91 @@
+//! System idle time (seconds since the last keyboard/mouse input).
+//!
+//! - Windows: GetLastInputInfo
+//! - macOS: CGEventSourceSecondsSinceLastEventType
+//! - Linux: GNOME Mutter IdleMonitor (gdbus), 없으면 xprintidle
+//!
+//! 화면 잠금/절전 중에는 입력이 없으므로 idle 시간이 계속 늘어난다.
+
+/// Seconds since the last user input, or None if the platform API is unavailable.
+pub fn idle_seconds() -> Option<u64> {
+    platform::idle_seconds()
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    pub fn idle_seconds() -> Option<u64> {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        // SAFETY: info is a properly sized LASTINPUTINFO.
+        let ok = unsafe { GetLastInputInfo(&mut info) };
+        if ok == 0 {
+            return None;
+        }
+        let now = unsafe { GetTickCount() };
+        Some(now.wrapping_sub(info.dwTime) as u64 / 1000)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    const COMBINED_SESSION_STATE: i32 = 0;
+    const ANY_INPUT_EVENT: u32 = !0;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+
+    pub fn idle_seconds() -> Option<u64> {
+        // SAFETY: plain C call without pointers.
+        let secs = unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT) };
+        (secs >= 0.0).then_some(secs as u64)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::process::Command;
+
+    pub fn idle_seconds() -> Option<u64> {
+        mutter_idle_ms().or_else(xprintidle_ms).map(|ms| ms / 1000)
+    }
+
+    /// `gdbus call ... GetIdletime` → "(uint64 12345,)"
+    fn mutter_idle_ms() -> Option<u64> {
+        let output = Command::new("gdbus")
+            .args([
+                "call",
+                "--session",
+                "--dest",
+                "org.gnome.Mutter.IdleMonitor",
+                "--object-path",
+                "/org/gnome/Mutter/IdleMonitor/Core",
+                "--method",
+                "org.gnome.Mutter.IdleMonitor.GetIdletime",
+            ])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8_lossy(&output.stdout)
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .nth(1) // skip the "64" of "uint64"
+            .and_then(|n| n.parse().ok())
+    }
+
+    fn xprintidle_ms() -> Option<u64> {
+        let output = Command::new("xprintidle").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    }
+}
@@ -1,12 +1,23 @@
-use std::collections::{HashMap, VecDeque};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 
+use image::DynamicImage;
+
+use crate::config::ImageFilter;
+use crate::image_process::DisplayOptions;
 use crate::zip_fast::ZipIndex;
 
-const MAX_CACHE_BYTES: usize = 100 * 1024 * 1024; // 100 MB
 const PREFETCH_AHEAD: usize = 2;
 const PREFETCH_BEHIND: usize = 1;
+/// Prefetch jobs further than this from the current index are dropped as stale.
+const PREFETCH_STALE_DISTANCE: usize = 8;
+/// Number of background prefetch workers (bounded, unlike one thread per call).
+const PREFETCH_WORKERS: usize = 2;
+/// Bytes read from the start of an image to get its size without reading all of it.
+const HEAD_BYTES: usize = 64 * 1024;
 
 /// Image source info needed by the cache to read images independently.
 #[derive(Clone)]
@@ -17,32 +28,90 @@ pub enum ImageSourceInfo {
     Zip {
         zip_path: PathBuf,
         entry_names: Vec<String>,
+        /// Central directory parsed by the tab.
+        index: Arc<ZipIndex>,
+    },
+    Rar {
+        rar_path: PathBuf,
+        entry_names: Vec<String>,
+        unrar: PathBuf,
+        solid: bool,
+    },
+    SevenZip {
+        archive_path: PathBuf,
+        entry_names: Vec<String>,
+        tool: PathBuf,
+        solid: bool,
     },
 }
 
-struct ZipHandle {
-    index: ZipIndex,
+struct PrefetchJob {
+    file_id: String,
+    index: usize,
 }
 
-/// LRU byte cache with a total memory budget.
+/// Where to read an image from, resolved under the lock and read outside it.
+enum ReadTarget {
+    File(PathBuf),
+    Zip {
+        handle: Arc<ZipIndex>,
+        zip_path: PathBuf,
+        entry_name: String,
+        password: Option<String>,
+    },
+    Rar {
+        unrar: PathBuf,
+        rar_path: PathBuf,
+        entry_name: String,
+        solid: bool,
+        password: Option<String>,
+    },
+    SevenZip {
+        tool: PathBuf,
+        archive_path: PathBuf,
+        entry_name: String,
+        solid: bool,
+        password: Option<String>,
+    },
+}
+
+impl ReadTarget {
+    /// File or entry name (decides whether the image needs transcoding).
+    fn name(&self) -> String {
+        match self {
+            ReadTarget::File(path) => path.to_string_lossy().to_string(),
+            ReadTarget::Zip { entry_name, .. }
+            | ReadTarget::Rar { entry_name, .. }
+            | ReadTarget::SevenZip { entry_name, .. } => entry_name.clone(),
+        }
+    }
+}
+
+/// (file id, index, max dimension the page was downscaled to).
+type CacheKey = (String, usize, Option<u32>);
+
+/// LRU byte cache with a total memory budget. Each page is cached per requested size.
 struct LruBytesCache {
     /// Ordered from oldest (front) to newest (back).
-    order: VecDeque<(String, usize)>,
-    data: HashMap<(String, usize), Vec<u8>>,
+    order: VecDeque<CacheKey>,
+    data: HashMap<CacheKey, Vec<u8>>,
     total_bytes: usize,
+    /// Inserts evict down to this size (the memory budget).
+    max_bytes: usize,
 }
 
 impl LruBytesCache {
-    fn new() -> Self {
+    fn new(max_bytes: usize) -> Self {
         Self {
             order: VecDeque::new(),
             data: HashMap::new(),
             total_bytes: 0,
+            max_bytes,
         }
     }
 
-    fn get(&mut self, file_id: &str, index: usize) -> Option<Vec<u8>> {
-        let key = (file_id.to_string(), index);
+    fn get(&mut self, file_id: &str, index: usize, max_dimension: Option<u32>) -> Option<Vec<u8>> {
+        let key = (file_id.to_string(), index, max_dimension);
         if let Some(bytes) = self.data.get(&key) {
             // Move to back (most recently used)
             self.order.retain(|k| k != &key);
@@ -53,12 +122,12 @@ impl LruBytesCache {
         }
     }
 
-    fn contains(&self, file_id: &str, index: usize) -> bool {
-        self.data.contains_key(&(file_id.to_string(), index))
+    fn contains(&self, file_id: &str, index: usize, max_dimension: Option<u32>) -> bool {
+        self.data.contains_key(&(file_id.to_string(), index, max_dimension))
     }
 
-    fn insert(&mut self, file_id: &str, index: usize, bytes: Vec<u8>) {
-        let key = (file_id.to_string(), index);
+    fn insert(&mut self, file_id: &str, index: usize, max_dimension: Option<u32>, bytes: Vec<u8>) {
+        let key = (file_id.to_string(), index, max_dimension);
         if self.data.contains_key(&key) {
             return;
         }
@@ -66,7 +135,7 @@ impl LruBytesCache {
         let size = bytes.len();
 
         // Evict until we have room
-        while self.total_bytes + size > MAX_CACHE_BYTES && !self.order.is_empty() {
+        while self.total_bytes + size > self.max_bytes && !self.order.is_empty() {
             if let Some(old_key) = self.order.pop_front() {
                 if let Some(old_bytes) = self.data.remove(&old_key) {
                     self.total_bytes -= old_bytes.len();
@@ -79,6 +148,33 @@ impl LruBytesCache {
         self.order.push_back(key);
     }
 
+    /// Evict oldest entries until total size fits within `max_bytes`.
+    fn evict_to(&mut self, max_bytes: usize) {
+        while self.total_bytes > max_bytes {
+            match self.order.pop_front() {
+                Some(old_key) => {
+                    if let Some(old_bytes) = self.data.remove(&old_key) {
+                        self.total_bytes -= old_bytes.len();
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Drop every cached size of one page.
+    fn remove(&mut self, file_id: &str, index: usize) {
+        self.order.retain(|k| k.0 != file_id || k.1 != index);
+        let total_bytes = &mut self.total_bytes;
+        self.data.retain(|k, bytes| {
+            let keep = k.0 != file_id || k.1 != index;
+            if !keep {
+                *total_bytes -= bytes.len();
+            }
+            keep
+        });
+    }
+
     fn remove_file(&mut self, file_id: &str) {
         self.order.retain(|k| k.0 != file_id);
         let keys_to_remove: Vec<_> = self
@@ -96,68 +192,304 @@ impl LruBytesCache {
 }
 
 struct CacheInner {
-    zip_handles: HashMap<String, ZipHandle>,
     /// Source info for all image tabs (both folder and zip)
     sources: HashMap<String, ImageSourceInfo>,
     lru: LruBytesCache,
+    /// Prefetch jobs queued or being read, to avoid duplicate work.
+    in_flight: HashSet<(String, usize)>,
+    /// Most recently viewed index per file; prefetch jobs far from it are stale.
+    current_index: HashMap<String, usize>,
+    /// Passwords for encrypted archives, by file id.
+    passwords: HashMap<String, String>,
+    /// Image sizes read from headers (None = unknown), for spread pairing.
+    dimensions: HashMap<(String, usize), Option<(u32, u32)>>,
+    /// Display processing per file id; cached bytes are already processed.
+    display_options: HashMap<String, DisplayOptions>,
+    /// Night-mode filter applied to every file.
+    filter: Option<ImageFilter>,
+    /// Clockwise page rotations per file id (index → degrees).
+    rotations: HashMap<String, BTreeMap<usize, u16>>,
+    /// Configured ImageMagick path for AVIF/HEIC/JPEG XL pages (empty = search for it).
+    imagemagick_path: String,
+    /// Last page decoded for webtoon tiles per file id: (index, options it was processed with, image).
+    decoded_pages: HashMap<String, (usize, DisplayOptions, Arc<DynamicImage>)>,
+}
+
+impl CacheInner {
+    fn decoded_bytes(&self) -> usize {
+        self.decoded_pages
+            .values()
+            .map(|(_, _, image)| image.as_bytes().len())
+            .sum()
+    }
+}
+
+/// Archive of a solid RAR / 7z source, which is read from an extraction folder.
+fn solid_archive(source: &ImageSourceInfo) -> Option<&Path> {
+    match source {
+        ImageSourceInfo::Rar {
+            rar_path, solid: true, ..
+        } => Some(rar_path),
+        ImageSourceInfo::SevenZip {
+            archive_path,
+            solid: true,
+            ..
+        } => Some(archive_path),
+        _ => None,
+    }
 }
 
 pub struct ImageCacheManager {
     inner: Arc<Mutex<CacheInner>>,
+    job_tx: Sender<PrefetchJob>,
 }
 
 impl ImageCacheManager {
-    pub fn new() -> Self {
-        Self {
-            inner: Arc::new(Mutex::new(CacheInner {
-                zip_handles: HashMap::new(),
-                sources: HashMap::new(),
-                lru: LruBytesCache::new(),
-            })),
+    /// `max_bytes` caps the LRU cache (the memory budget; see `set_max_bytes`).
+    pub fn new(max_bytes: usize) -> Self {
+        let inner = Arc::new(Mutex::new(CacheInner {
+            sources: HashMap::new(),
+            lru: LruBytesCache::new(max_bytes),
+            in_flight: HashSet::new(),
+            current_index: HashMap::new(),
+            passwords: HashMap::new(),
+            dimensions: HashMap::new(),
+            display_options: HashMap::new(),
+            filter: None,
+            rotations: HashMap::new(),
+            imagemagick_path: String::new(),
+            decoded_pages: HashMap::new(),
+        }));
+
+        let (job_tx, job_rx) = mpsc::channel::<PrefetchJob>();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        for _ in 0..PREFETCH_WORKERS {
+            let inner = Arc::clone(&inner);
+            let job_rx = Arc::clone(&job_rx);
+            std::thread::spawn(move || Self::worker_loop(inner, job_rx));
         }
+
+        Self { inner, job_tx }
     }
 
-    /// Register an image source when a tab is opened.
-    /// ZIP archive handle is opened lazily on first read_image call.
+    /// Register an image source when a tab is opened (ZIP tabs share their index with the cache).
     pub fn register(&self, file_id: &str, source: ImageSourceInfo) {
         let mut inner = self.inner.lock().unwrap();
-        inner.sources.insert(file_id.to_string(), source);
+        if let Some(archive) = solid_archive(&source) {
+            crate::archive_extract::retain(archive);
+        }
+        // 같은 탭을 다시 등록하면 이전 참조는 놓는다
+        let previous = inner.sources.insert(file_id.to_string(), source);
+        if let Some(archive) = previous.as_ref().and_then(solid_archive) {
+            crate::archive_extract::discard_extracted(archive);
+        }
     }
 
     /// Unregister when a tab is closed.
     pub fn unregister(&self, file_id: &str) {
+        let source = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.current_index.remove(file_id);
+            inner.passwords.remove(file_id);
+            inner.dimensions.retain(|(id, _), _| id != file_id);
+            inner.display_options.remove(file_id);
+            inner.rotations.remove(file_id);
+            inner.lru.remove_file(file_id);
+            inner.decoded_pages.remove(file_id);
+            inner.sources.remove(file_id)
+        };
+        // 솔리드 RAR/7z는 풀어 둔 임시 폴더도 정리한다 (다른 탭이 쓰고 있으면 남는다)
+        if let Some(archive) = source.as_ref().and_then(solid_archive) {
+            crate::archive_extract::discard_extracted(archive);
+        }
+    }
+
+    /// Set (or clear) the password used for encrypted entries of an archive tab.
+    pub fn set_password(&self, file_id: &str, password: Option<String>) {
+        let mut inner = self.inner.lock().unwrap();
+        match password {
+            Some(pw) => {
+                inner.passwords.insert(file_id.to_string(), pw);
+            }
+            None => {
+                inner.passwords.remove(file_id);
+            }
+        }
+    }
+
+    /// Set the largest side images of a file are downscaled to by `read_image` and
+    /// prefetching (None = original size). Pages cached at other sizes are kept.
+    pub fn set_max_dimension(&self, file_id: &str, max_dimension: Option<u32>) {
+        let max_dimension = max_dimension.map(crate::image_process::clamp_max_dimension);
+        let mut inner = self.inner.lock().unwrap();
+        let mut options = inner.display_options.get(file_id).cloned().unwrap_or_default();
+        options.max_dimension = max_dimension;
+        if options.is_identity() {
+            inner.display_options.remove(file_id);
+        } else {
+            inner.display_options.insert(file_id.to_string(), options);
+        }
+    }
+
+    /// Enable margin cropping with the given tolerance (None = off).
+    pub fn set_crop_threshold(&self, file_id: &str, threshold: Option<u8>) {
+        self.update_options(file_id, |options| options.crop_threshold = threshold);
+    }
+
+    /// Set the night-mode filter for all files. Cached pages are dropped when it changes.
+    pub fn set_filter(&self, filter: Option<ImageFilter>) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.filter != filter {
+            inner.filter = filter;
+            inner.lru.evict_to(0);
+        }
+    }
+
+    /// Update the LRU size cap (after the memory budget changes); evicts if over it.
+    pub fn set_max_bytes(&self, max_bytes: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.lru.max_bytes = max_bytes;
+        inner.lru.evict_to(max_bytes);
+    }
+
+    /// Set the ImageMagick path used to transcode pages the webview can't display.
+    pub fn set_imagemagick_path(&self, path: &str) {
+        self.inner.lock().unwrap().imagemagick_path = path.to_string();
+    }
+
+    pub fn imagemagick_path(&self) -> String {
+        self.inner.lock().unwrap().imagemagick_path.clone()
+    }
+
+    /// Set the saved page rotations of a file (when its tab opens).
+    pub fn set_rotations(&self, file_id: &str, rotations: BTreeMap<usize, u16>) {
         let mut inner = self.inner.lock().unwrap();
-        inner.zip_handles.remove(file_id);
-        inner.sources.remove(file_id);
         inner.lru.remove_file(file_id);
+        if rotations.is_empty() {
+            inner.rotations.remove(file_id);
+        } else {
+            inner.rotations.insert(file_id.to_string(), rotations);
+        }
     }
 
-    /// Read an image, using LRU cache first, then the appropriate source.
-    pub fn read_image(&self, file_id: &str, index: usize) -> anyhow::Result<Vec<u8>> {
+    /// Rotate one page (clockwise degrees, 0 = original). Only that page is re-read.
+    pub fn set_rotation(&self, file_id: &str, index: usize, degrees: u16) {
         let mut inner = self.inner.lock().unwrap();
+        let rotations = inner.rotations.entry(file_id.to_string()).or_default();
+        if degrees == 0 {
+            rotations.remove(&index);
+        } else {
+            rotations.insert(index, degrees);
+        }
+        inner.lru.remove(file_id, index);
+    }
+
+    /// Clockwise rotation of a page in degrees (0 = original).
+    pub fn rotation(&self, file_id: &str, index: usize) -> u16 {
+        Self::options_of(&self.inner.lock().unwrap(), file_id, index).rotation
+    }
 
+    fn update_options(&self, file_id: &str, update: impl FnOnce(&mut DisplayOptions)) {
+        let mut inner = self.inner.lock().unwrap();
+        let mut options = inner.display_options.get(file_id).cloned().unwrap_or_default();
+        let previous = options.clone();
+        update(&mut options);
+        if options == previous {
+            return;
+        }
+        inner.lru.remove_file(file_id);
+        if options.is_identity() {
+            inner.display_options.remove(file_id);
+        } else {
+            inner.display_options.insert(file_id.to_string(), options);
+        }
+    }
+
+    /// Read an image at the file's current max dimension (see `set_max_dimension`).
+    pub fn read_image(&self, file_id: &str, index: usize) -> anyhow::Result<Vec<u8>> {
+        let max_dimension = Self::max_dimension_of(&self.inner.lock().unwrap(), file_id);
+        self.read_image_sized(file_id, index, max_dimension)
+    }
+
+    /// Read an image downscaled to `max_dimension`, using LRU cache first, then the appropriate source.
+    /// The lock is only held for the cache lookup and insert, not for disk/zip I/O.
+    pub fn read_image_sized(&self, file_id: &str, index: usize, max_dimension: Option<u32>) -> anyhow::Result<Vec<u8>> {
+        let max_dimension = max_dimension.map(crate::image_process::clamp_max_dimension);
         // Check LRU cache first
-        if let Some(bytes) = inner.lru.get(file_id, index) {
+        if let Some(bytes) = self.inner.lock().unwrap().lru.get(file_id, index, max_dimension) {
             return Ok(bytes);
         }
 
         // Read from source
-        let bytes = Self::read_from_source(&mut inner, file_id, index)?;
+        let (bytes, options) = Self::read_uncached(&self.inner, file_id, index, max_dimension)?;
 
-        // Cache the result
-        inner.lru.insert(file_id, index, bytes.clone());
+        // Cache the result (unless the display options changed while reading)
+        let mut inner = self.inner.lock().unwrap();
+        if Self::processed_as_current(&inner, file_id, index, &options) {
+            inner.lru.insert(file_id, index, max_dimension, bytes.clone());
+        }
 
         Ok(bytes)
     }
 
-    /// Prefetch images around the current index in a background thread.
+    /// Total bytes currently held by the LRU cache and the decoded webtoon pages.
+    pub fn memory_usage(&self) -> usize {
+        let inner = self.inner.lock().unwrap();
+        inner.lru.total_bytes + inner.decoded_bytes()
+    }
+
+    /// Evict least recently used images until the cache fits within `max_bytes`;
+    /// the decoded webtoon pages go too when they alone don't fit.
+    pub fn shrink_to(&self, max_bytes: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        let decoded = inner.decoded_bytes();
+        if decoded > max_bytes {
+            inner.decoded_pages.clear();
+            inner.lru.evict_to(max_bytes);
+        } else {
+            inner.lru.evict_to(max_bytes - decoded);
+        }
+    }
+
+    /// A page decoded as displayed (EXIF orientation, night filter and rotation applied,
+    /// not resized or cropped), for cutting webtoon tiles. The last one is kept per file.
+    pub fn decoded_page(&self, file_id: &str, index: usize) -> anyhow::Result<Arc<DynamicImage>> {
+        let options = {
+            let inner = self.inner.lock().unwrap();
+            let options = Self::options_of(&inner, file_id, index);
+            if let Some((i, o, image)) = inner.decoded_pages.get(file_id) {
+                if *i == index && *o == options {
+                    return Ok(Arc::clone(image));
+                }
+            }
+            options
+        };
+        let (name, bytes) = self.read_image_raw(file_id, index)?;
+        let bytes = crate::image_transcode::for_display(&name, bytes, &self.imagemagick_path())?;
+        let image = crate::image_process::decode_oriented(&bytes)?;
+        let image = Arc::new(crate::image_process::filter_and_rotate(image, &options));
+        let mut inner = self.inner.lock().unwrap();
+        if inner.sources.contains_key(file_id) {
+            inner
+                .decoded_pages
+                .insert(file_id.to_string(), (index, options, Arc::clone(&image)));
+        }
+        Ok(image)
+    }
+
+    /// Queue prefetch of images around the current index on the worker pool.
+    /// Indices are in reading order for both reading directions, so RTL books need
+    /// no special handling here.
     pub fn prefetch(&self, file_id: &str, current_index: usize, total: usize) {
-        let inner_arc = Arc::clone(&self.inner);
-        let file_id = file_id.to_string();
+        self.prefetch_ahead(file_id, current_index, total, PREFETCH_AHEAD);
+    }
 
+    /// Like `prefetch`, with a custom look-ahead (e.g. slideshows read further ahead).
+    pub fn prefetch_ahead(&self, file_id: &str, current_index: usize, total: usize, ahead: usize) {
+        let ahead = ahead.min(PREFETCH_STALE_DISTANCE);
         // Collect indices to prefetch
         let mut indices = Vec::new();
-        for i in 1..=PREFETCH_AHEAD {
+        for i in 1..=ahead {
             if current_index + i < total {
                 indices.push(current_index + i);
             }
@@ -168,73 +500,331 @@ impl ImageCacheManager {
             }
         }
 
-        // Filter out already cached
-        {
-            let inner = inner_arc.lock().unwrap();
-            indices.retain(|&idx| !inner.lru.contains(&file_id, idx));
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .current_index
+            .insert(file_id.to_string(), current_index);
+        let max_dimension = Self::max_dimension_of(&inner, file_id);
+
+        for idx in indices {
+            // Skip already cached or already queued
+            if inner.lru.contains(file_id, idx, max_dimension) {
+                continue;
+            }
+            if !inner.in_flight.insert((file_id.to_string(), idx)) {
+                continue;
+            }
+            let job = PrefetchJob {
+                file_id: file_id.to_string(),
+                index: idx,
+            };
+            if self.job_tx.send(job).is_err() {
+                inner.in_flight.remove(&(file_id.to_string(), idx));
+            }
         }
+    }
 
-        if indices.is_empty() {
-            return;
+    fn worker_loop(inner: Arc<Mutex<CacheInner>>, job_rx: Arc<Mutex<Receiver<PrefetchJob>>>) {
+        loop {
+            let job = match job_rx.lock().unwrap().recv() {
+                Ok(job) => job,
+                Err(_) => return,
+            };
+            let key = (job.file_id.clone(), job.index);
+
+            let (should_read, max_dimension) = {
+                let inner = inner.lock().unwrap();
+                let max_dimension = Self::max_dimension_of(&inner, &job.file_id);
+                // Tab closed, already cached, or user has flipped far away since queuing
+                let is_stale = inner
+                    .current_index
+                    .get(&job.file_id)
+                    .map(|&cur| cur.abs_diff(job.index) > PREFETCH_STALE_DISTANCE)
+                    .unwrap_or(true);
+                let should_read = inner.sources.contains_key(&job.file_id)
+                    && !inner.lru.contains(&job.file_id, job.index, max_dimension)
+                    && !is_stale;
+                (should_read, max_dimension)
+            };
+
+            let bytes = if should_read {
+                Self::read_uncached(&inner, &job.file_id, job.index, max_dimension).ok()
+            } else {
+                None
+            };
+
+            let mut inner = inner.lock().unwrap();
+            inner.in_flight.remove(&key);
+            if let Some((bytes, options)) = bytes {
+                // Check source still registered (tab might have been closed while reading)
+                // and the bytes were processed with the current options
+                if inner.sources.contains_key(&job.file_id)
+                    && Self::processed_as_current(&inner, &job.file_id, job.index, &options)
+                {
+                    inner.lru.insert(&job.file_id, job.index, max_dimension, bytes);
+                }
+            }
         }
+    }
 
-        std::thread::spawn(move || {
-            for idx in indices {
-                let mut inner = inner_arc.lock().unwrap();
-                // Double-check not cached (another thread might have added it)
-                if inner.lru.contains(&file_id, idx) {
-                    continue;
+    /// (width, height) of a page as displayed, from the first bytes of the image (EXIF
+    /// orientation applied), with the page rotation applied. The header size is remembered per page.
+    pub fn image_dimensions(&self, file_id: &str, index: usize) -> Option<(u32, u32)> {
+        let key = (file_id.to_string(), index);
+        let cached = self.inner.lock().unwrap().dimensions.get(&key).copied();
+        let dimensions = match cached {
+            Some(dimensions) => dimensions,
+            None => {
+                let dimensions = self.read_dimensions(file_id, index);
+                let mut inner = self.inner.lock().unwrap();
+                if inner.sources.contains_key(file_id) {
+                    inner.dimensions.insert(key, dimensions);
                 }
-                // Check source still registered (tab might have been closed)
-                if !inner.sources.contains_key(&file_id) {
-                    break;
+                dimensions
+            }
+        };
+        let rotation = self.rotation(file_id, index);
+        dimensions.map(|(w, h)| if rotation % 180 == 90 { (h, w) } else { (w, h) })
+    }
+
+    /// Size from the header prefix; the whole image when the header is longer than that.
+    fn read_dimensions(&self, file_id: &str, index: usize) -> Option<(u32, u32)> {
+        let (name, head) = Self::read_source_head(&self.inner, file_id, index, Some(HEAD_BYTES)).ok()?;
+        if let Some(dimensions) = crate::image_reader::display_dimensions(&name, &head) {
+            return Some(dimensions);
+        }
+        if head.len() < HEAD_BYTES {
+            return None;
+        }
+        let (name, bytes) = self.read_image_raw(file_id, index).ok()?;
+        crate::image_reader::display_dimensions(&name, &bytes)
+    }
+
+    /// File or entry name of an image, None if the index is out of range.
+    pub fn image_name(&self, file_id: &str, index: usize) -> Option<String> {
+        Self::resolve_target(&self.inner.lock().unwrap(), file_id, index)
+            .ok()
+            .map(|target| target.name())
+    }
+
+    /// Read the stored bytes of an image (no transcoding, bypassing the LRU cache),
+    /// together with its file or entry name.
+    pub fn read_image_raw(&self, file_id: &str, index: usize) -> anyhow::Result<(String, Vec<u8>)> {
+        Self::read_source(&self.inner, file_id, index)
+    }
+
+    fn options_of(inner: &CacheInner, file_id: &str, index: usize) -> DisplayOptions {
+        let mut options = inner.display_options.get(file_id).cloned().unwrap_or_default();
+        options.filter = inner.filter.clone();
+        options.rotation = inner
+            .rotations
+            .get(file_id)
+            .and_then(|rotations| rotations.get(&index))
+            .copied()
+            .unwrap_or(0);
+        options
+    }
+
+    fn max_dimension_of(inner: &CacheInner, file_id: &str) -> Option<u32> {
+        inner.display_options.get(file_id).and_then(|options| options.max_dimension)
+    }
+
+    /// Bytes processed with `options` still match the file's settings, apart from the
+    /// size (which is part of the cache key).
+    fn processed_as_current(inner: &CacheInner, file_id: &str, index: usize, options: &DisplayOptions) -> bool {
+        let mut current = Self::options_of(inner, file_id, index);
+        current.max_dimension = options.max_dimension;
+        current == *options
+    }
+
+    /// Read, transcode and process an image downscaled to `max_dimension`;
+    /// returns the options it was processed with.
+    fn read_uncached(
+        inner: &Mutex<CacheInner>,
+        file_id: &str,
+        index: usize,
+        max_dimension: Option<u32>,
+    ) -> anyhow::Result<(Vec<u8>, DisplayOptions)> {
+        let (name, bytes) = Self::read_source(inner, file_id, index)?;
+        let (options, magick) = {
+            let inner = inner.lock().unwrap();
+            let mut options = Self::options_of(&inner, file_id, index);
+            options.max_dimension = max_dimension;
+            (options, inner.imagemagick_path.clone())
+        };
+        let bytes = crate::image_transcode::for_display(&name, bytes, &magick)?;
+        Ok((crate::image_process::apply(bytes, &options)?, options))
+    }
+
+    /// Resolve the read target under the lock, then read without holding it.
+    fn read_source(
+        inner: &Mutex<CacheInner>,
+        file_id: &str,
+        index: usize,
+    ) -> anyhow::Result<(String, Vec<u8>)> {
+        Self::read_source_head(inner, file_id, index, None)
+    }
+
+    /// Like `read_source`, but with `head` only the first that many bytes (or a little
+    /// more) are read, for image headers.
+    fn read_source_head(
+        inner: &Mutex<CacheInner>,
+        file_id: &str,
+        index: usize,
+        head: Option<usize>,
+    ) -> anyhow::Result<(String, Vec<u8>)> {
+        let target = Self::resolve_target(&inner.lock().unwrap(), file_id, index)?;
+        let name = target.name();
+
+        let bytes = match target {
+            ReadTarget::File(path) => Self::read_file(&path, head),
+            ReadTarget::Zip {
+                handle,
+                zip_path,
+                entry_name,
+                password,
+            } => Self::read_zip_entry(&handle, &zip_path, &entry_name, password.as_deref(), head),
+            ReadTarget::Rar {
+                unrar,
+                rar_path,
+                entry_name,
+                solid,
+                password,
+            } => match head {
+                Some(max_bytes) => crate::rar_reader::read_entry_head(
+                    &unrar,
+                    &rar_path,
+                    &entry_name,
+                    solid,
+                    password.as_deref(),
+                    max_bytes,
+                ),
+                None => crate::rar_reader::read_entry(&unrar, &rar_path, &entry_name, solid, password.as_deref()),
+            },
+            ReadTarget::SevenZip {
+                tool,
+                archive_path,
+                entry_name,
+                solid,
+                password,
+            } => match head {
+                Some(max_bytes) => crate::sevenz_reader::read_entry_head(
+                    &tool,
+                    &archive_path,
+                    &entry_name,
+                    solid,
+                    password.as_deref(),
+                    max_bytes,
+                ),
+                None => {
+                    crate::sevenz_reader::read_entry(&tool, &archive_path, &entry_name, solid, password.as_deref())
                 }
-                match Self::read_from_source(&mut inner, &file_id, idx) {
-                    Ok(bytes) => {
-                        inner.lru.insert(&file_id, idx, bytes);
-                    }
-                    Err(_) => break,
+            },
+        }?;
+        Ok((name, bytes))
+    }
+
+    fn read_file(path: &Path, head: Option<usize>) -> anyhow::Result<Vec<u8>> {
+        let read = || -> std::io::Result<Vec<u8>> {
+            match head {
+                Some(max_bytes) => {
+                    let mut bytes = Vec::new();
+                    std::fs::File::open(path)?.take(max_bytes as u64).read_to_end(&mut bytes)?;
+                    Ok(bytes)
                 }
+                None => std::fs::read(path),
             }
-        });
+        };
+        read().map_err(|e| anyhow::anyhow!("Failed to read image: {}", e))
+    }
+
+    /// Encrypted entries go through the `zip` crate (with the stored password);
+    /// everything else uses the fast mmap reader. With `head`, plain entries are only
+    /// decompressed that far.
+    fn read_zip_entry(
+        handle: &ZipIndex,
+        zip_path: &std::path::Path,
+        entry_name: &str,
+        password: Option<&str>,
+        head: Option<usize>,
+    ) -> anyhow::Result<Vec<u8>> {
+        if !handle.is_entry_encrypted(entry_name) {
+            return match head {
+                Some(max_bytes) => handle.read_entry_head(entry_name, max_bytes),
+                None => handle.read_entry(entry_name),
+            };
+        }
+        let password = password.ok_or_else(|| {
+            crate::error::AppError::PasswordRequired(zip_path.to_string_lossy().to_string())
+        })?;
+        crate::archive_password::read_encrypted_entry(zip_path, entry_name, password)
     }
 
-    fn read_from_source(
-        inner: &mut CacheInner,
+    fn resolve_target(
+        inner: &CacheInner,
         file_id: &str,
         index: usize,
-    ) -> anyhow::Result<Vec<u8>> {
+    ) -> anyhow::Result<ReadTarget> {
         let source = inner
             .sources
             .get(file_id)
-            .ok_or_else(|| anyhow::anyhow!("Image source not registered: {}", file_id))?
-            .clone();
+            .ok_or_else(|| anyhow::anyhow!("Image source not registered: {}", file_id))?;
 
         match source {
             ImageSourceInfo::Folder { image_paths } => {
                 let path = image_paths
                     .get(index)
                     .ok_or_else(|| anyhow::anyhow!("Image index out of range: {}", index))?;
-                std::fs::read(path).map_err(|e| anyhow::anyhow!("Failed to read image: {}", e))
+                Ok(ReadTarget::File(path.clone()))
             }
             ImageSourceInfo::Zip {
-                ref zip_path,
-                ref entry_names,
+                zip_path,
+                entry_names,
+                index: handle,
             } => {
                 let entry_name = entry_names
                     .get(index)
                     .ok_or_else(|| anyhow::anyhow!("Image index out of range: {}", index))?;
-
-                // Lazily open and cache ZipIndex handle on first access
-                if !inner.zip_handles.contains_key(file_id) {
-                    let zip_index = ZipIndex::open(zip_path)?;
-                    inner
-                        .zip_handles
-                        .insert(file_id.to_string(), ZipHandle { index: zip_index });
-                }
-
-                let handle = inner.zip_handles.get(file_id).unwrap();
-                handle.index.read_entry(entry_name)
+                Ok(ReadTarget::Zip {
+                    handle: Arc::clone(handle),
+                    zip_path: zip_path.clone(),
+                    entry_name: entry_name.clone(),
+                    password: inner.passwords.get(file_id).cloned(),
+                })
+            }
+            ImageSourceInfo::Rar {
+                rar_path,
+                entry_names,
+                unrar,
+                solid,
+            } => {
+                let entry_name = entry_names
+                    .get(index)
+                    .ok_or_else(|| anyhow::anyhow!("Image index out of range: {}", index))?;
+                Ok(ReadTarget::Rar {
+                    unrar: unrar.clone(),
+                    rar_path: rar_path.clone(),
+                    entry_name: entry_name.clone(),
+                    solid: *solid,
+                    password: inner.passwords.get(file_id).cloned(),
+                })
+            }
+            ImageSourceInfo::SevenZip {
+                archive_path,
+                entry_names,
+                tool,
+                solid,
+            } => {
+                let entry_name = entry_names
+                    .get(index)
+                    .ok_or_else(|| anyhow::anyhow!("Image index out of range: {}", index))?;
+                Ok(ReadTarget::SevenZip {
+                    tool: tool.clone(),
+                    archive_path: archive_path.clone(),
+                    entry_name: entry_name.clone(),
+                    solid: *solid,
+                    password: inner.passwords.get(file_id).cloned(),
+                })
             }
         }
     }
@@ -0,0 +1,329 @@
+//! Processing applied to images before they are sent to the webview.
+//!
+//! 큰 스캔본(4000×6000 등)은 WebView에서 디코드된 비트맵이 페이지당 수십 MB를 차지하므로,
+//! 요청한 최대 크기에 맞게 Rust에서 줄여서 보낸다. 스캔 여백(흰색/검은색 테두리) 자르기도
+//! 여기서 한다. 야간 모드 필터(반전/밝기/대비/세피아)는 설정 전체에 적용된다.
+//! 페이지별 회전은 회전만 필요한 JPEG이면 EXIF 방향 값만 바꿔 무손실로 처리한다.
+//! 이미지 캐시에는 처리된 결과만 들어간다.
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, Rgb, Rgba};
+
+use crate::config::ImageFilter;
+
+/// Smallest accepted max dimension, so a bad value can't produce a useless page.
+const MIN_MAX_DIMENSION: u32 = 256;
+const JPEG_QUALITY: u8 = 90;
+/// Share of pixels (per mille) in a margin line allowed to differ (dust, scan noise).
+const MARGIN_NOISE_PER_MILLE: u32 = 10;
+/// Crops that would leave less than this share of a side are skipped (blank pages).
+const MIN_CROPPED_PERCENT: u32 = 50;
+/// EXIF orientation values for clockwise rotations of 0/90/180/270 degrees.
+const EXIF_ROTATIONS: [(u16, u16); 4] = [(0, 1), (90, 6), (180, 3), (270, 8)];
+const EXIF_ORIENTATION_TAG: u16 = 0x0112;
+
+/// Display processing of one page. The default leaves images untouched.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// Longest side (px) images are downscaled to fit.
+    pub max_dimension: Option<u32>,
+    /// Trim uniform white/black margins; the value is the colour tolerance (0–255).
+    pub crop_threshold: Option<u8>,
+    /// Night-mode filter (from the config, same for every file).
+    pub filter: Option<ImageFilter>,
+    /// Clockwise rotation of the page (0, 90, 180 or 270).
+    pub rotation: u16,
+}
+
+impl DisplayOptions {
+    pub fn is_identity(&self) -> bool {
+        self.max_dimension.is_none()
+            && self.crop_threshold.is_none()
+            && self.filter.is_none()
+            && self.rotation == 0
+    }
+}
+
+pub fn clamp_max_dimension(max_dimension: u32) -> u32 {
+    max_dimension.max(MIN_MAX_DIMENSION)
+}
+
+/// Apply `options` to displayable image bytes. Images that need no change (or can't be
+/// decoded, or are GIFs that may be animated) are returned as is.
+pub fn apply(bytes: Vec<u8>, options: &DisplayOptions) -> anyhow::Result<Vec<u8>> {
+    if options.is_identity() {
+        return Ok(bytes);
+    }
+    let Ok(format) = image::guess_format(&bytes) else {
+        return Ok(bytes);
+    };
+    if format == ImageFormat::Gif {
+        return Ok(bytes);
+    }
+    // 헤더만 읽어 이미 작은 이미지는 디코드하지 않는다
+    let needs_resize = options.max_dimension.is_some_and(|max| {
+        image::ImageReader::with_format(std::io::Cursor::new(&bytes), format)
+            .into_dimensions()
+            .is_ok_and(|(w, h)| w > max || h > max)
+    });
+    let needs_decode = needs_resize || options.crop_threshold.is_some() || options.filter.is_some();
+    if !needs_decode {
+        if options.rotation == 0 {
+            return Ok(bytes);
+        }
+        if format == ImageFormat::Jpeg {
+            if let Some(rotated) = rotate_jpeg_exif(&bytes, options.rotation) {
+                return Ok(rotated);
+            }
+        }
+    }
+
+    let mut image = decode_oriented(&bytes)?;
+    let mut changed = false;
+    if let Some(cropped) = options.crop_threshold.and_then(|t| crop_margins(&image, t)) {
+        image = cropped;
+        changed = true;
+    }
+    if let Some(max) = options.max_dimension {
+        if image.width() > max || image.height() > max {
+            image = image.resize(max, max, FilterType::Triangle);
+            changed = true;
+        }
+    }
+    // 줄인 뒤에 걸어야 픽셀 수가 적다
+    if options.filter.is_some() || options.rotation != 0 {
+        image = filter_and_rotate(image, options);
+        changed = true;
+    }
+    if !changed {
+        return Ok(bytes);
+    }
+    encode(&image)
+}
+
+/// Decode image bytes with the EXIF orientation applied to the pixels (re-encoding drops
+/// the EXIF block).
+pub fn decode_oriented(bytes: &[u8]) -> anyhow::Result<DynamicImage> {
+    let mut decoder = image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// The night-mode filter and the page rotation of `options`, the steps that keep every
+/// pixel (webtoon tiles are cut from the result by header coordinates).
+pub fn filter_and_rotate(mut image: DynamicImage, options: &DisplayOptions) -> DynamicImage {
+    if let Some(filter) = &options.filter {
+        image = apply_filter(image, filter);
+    }
+    match options.rotation {
+        90 => image.rotate90(),
+        180 => image.rotate180(),
+        270 => image.rotate270(),
+        _ => image,
+    }
+}
+
+fn exif_orientation_for(degrees: u16) -> Option<u16> {
+    EXIF_ROTATIONS
+        .iter()
+        .find(|(d, _)| *d == degrees % 360)
+        .map(|(_, tag)| *tag)
+}
+
+/// Rotate a JPEG without re-encoding by rewriting (or adding) its EXIF orientation,
+/// which the webview applies. None when the file has a mirrored orientation or an
+/// EXIF block without an orientation entry.
+fn rotate_jpeg_exif(bytes: &[u8], degrees: u16) -> Option<Vec<u8>> {
+    if bytes.get(0..2)? != [0xFF, 0xD8] {
+        return None;
+    }
+    let mut pos = 2;
+    while pos + 4 <= bytes.len() && bytes[pos] == 0xFF {
+        let marker = bytes[pos + 1];
+        // SOS: 여기부터는 이미지 데이터
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+        let len = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let segment = bytes.get(pos + 4..pos + 2 + len)?;
+        if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+            return patch_exif_orientation(bytes, pos + 10, &segment[6..], degrees);
+        }
+        pos += 2 + len;
+    }
+
+    // EXIF가 없으면 방향 값 하나만 있는 APP1 세그먼트를 SOI 뒤에 넣는다
+    let tag = exif_orientation_for(degrees)?;
+    let mut app1 = vec![0xFF, 0xE1, 0, 34];
+    app1.extend_from_slice(b"Exif\0\0");
+    app1.extend_from_slice(b"MM\0\x2A\0\0\0\x08"); // big-endian TIFF header, IFD0 at 8
+    app1.extend_from_slice(&1u16.to_be_bytes());
+    app1.extend_from_slice(&EXIF_ORIENTATION_TAG.to_be_bytes());
+    app1.extend_from_slice(&[0, 3, 0, 0, 0, 1]); // SHORT, count 1
+    app1.extend_from_slice(&tag.to_be_bytes());
+    app1.extend_from_slice(&[0; 6]); // value padding, no next IFD
+
+    let mut out = Vec::with_capacity(bytes.len() + app1.len());
+    out.extend_from_slice(&bytes[..2]);
+    out.extend_from_slice(&app1);
+    out.extend_from_slice(&bytes[2..]);
+    Some(out)
+}
+
+/// Combine the existing orientation entry of IFD0 with `degrees`. `tiff_start` is the
+/// offset of the TIFF header (`tiff`) within `bytes`.
+fn patch_exif_orientation(bytes: &[u8], tiff_start: usize, tiff: &[u8], degrees: u16) -> Option<Vec<u8>> {
+    let big_endian = match tiff.get(0..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let read16 = |at: usize| -> Option<u16> {
+        let b: [u8; 2] = tiff.get(at..at + 2)?.try_into().ok()?;
+        Some(if big_endian { u16::from_be_bytes(b) } else { u16::from_le_bytes(b) })
+    };
+    let read32 = |at: usize| -> Option<u32> {
+        let b: [u8; 4] = tiff.get(at..at + 4)?.try_into().ok()?;
+        Some(if big_endian { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+    };
+
+    let ifd = read32(4)? as usize;
+    for i in 0..read16(ifd)? as usize {
+        let entry = ifd + 2 + i * 12;
+        if read16(entry)? != EXIF_ORIENTATION_TAG {
+            continue;
+        }
+        let current = read16(entry + 8)?;
+        let current_degrees = EXIF_ROTATIONS.iter().find(|(_, tag)| *tag == current)?.0;
+        let tag = exif_orientation_for(current_degrees + degrees)?;
+        let value = if big_endian { tag.to_be_bytes() } else { tag.to_le_bytes() };
+        let at = tiff_start + entry + 8;
+        let mut out = bytes.to_vec();
+        out.get_mut(at..at + 2)?.copy_from_slice(&value);
+        return Some(out);
+    }
+    None
+}
+
+fn apply_filter(mut image: DynamicImage, filter: &ImageFilter) -> DynamicImage {
+    if filter.invert {
+        image.invert();
+    }
+    if filter.brightness != 0 {
+        image = image.brighten(filter.brightness.clamp(-100, 100) * 255 / 100);
+    }
+    if filter.contrast != 0 {
+        image = image.adjust_contrast(filter.contrast.clamp(-100, 100) as f32);
+    }
+    if filter.sepia {
+        image = sepia(image);
+    }
+    image
+}
+
+fn sepia_pixel(r: u8, g: u8, b: u8) -> [u8; 3] {
+    let (r, g, b) = (r as f32, g as f32, b as f32);
+    [
+        (0.393 * r + 0.769 * g + 0.189 * b).min(255.0) as u8,
+        (0.349 * r + 0.686 * g + 0.168 * b).min(255.0) as u8,
+        (0.272 * r + 0.534 * g + 0.131 * b).min(255.0) as u8,
+    ]
+}
+
+/// Sepia tone, keeping the alpha channel only if the image had one.
+fn sepia(image: DynamicImage) -> DynamicImage {
+    if image.color().has_alpha() {
+        let mut rgba = image.to_rgba8();
+        for pixel in rgba.pixels_mut() {
+            let Rgba([r, g, b, a]) = *pixel;
+            let [r, g, b] = sepia_pixel(r, g, b);
+            *pixel = Rgba([r, g, b, a]);
+        }
+        DynamicImage::ImageRgba8(rgba)
+    } else {
+        let mut rgb = image.to_rgb8();
+        for pixel in rgb.pixels_mut() {
+            let Rgb([r, g, b]) = *pixel;
+            *pixel = Rgb(sepia_pixel(r, g, b));
+        }
+        DynamicImage::ImageRgb8(rgb)
+    }
+}
+
+/// A row or column is margin when (almost) every pixel is near white, or near black.
+fn is_margin_line(values: impl Iterator<Item = u8>, threshold: u8) -> bool {
+    let (mut total, mut white, mut black) = (0u32, 0u32, 0u32);
+    for value in values {
+        total += 1;
+        if value >= 255 - threshold {
+            white += 1;
+        } else if value <= threshold {
+            black += 1;
+        }
+    }
+    let needed = total - total * MARGIN_NOISE_PER_MILLE / 1000;
+    white >= needed || black >= needed
+}
+
+/// Trim uniform borders. None when there is nothing worth cropping, or when the crop
+/// would remove most of the page (blank or nearly blank pages).
+fn crop_margins(image: &DynamicImage, threshold: u8) -> Option<DynamicImage> {
+    let luma = &image.to_luma8();
+    let (width, height) = luma.dimensions();
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let row = |y: u32, left: u32, right: u32| (left..right).map(move |x| luma.get_pixel(x, y)[0]);
+    let column = |x: u32, top: u32, bottom: u32| (top..bottom).map(move |y| luma.get_pixel(x, y)[0]);
+
+    let mut top = 0;
+    while top < height && is_margin_line(row(top, 0, width), threshold) {
+        top += 1;
+    }
+    let mut bottom = height;
+    while bottom > top && is_margin_line(row(bottom - 1, 0, width), threshold) {
+        bottom -= 1;
+    }
+    if bottom <= top {
+        return None;
+    }
+    let mut left = 0;
+    while left < width && is_margin_line(column(left, top, bottom), threshold) {
+        left += 1;
+    }
+    let mut right = width;
+    while right > left && is_margin_line(column(right - 1, top, bottom), threshold) {
+        right -= 1;
+    }
+
+    let (cropped_width, cropped_height) = (right - left, bottom - top);
+    if cropped_width * 100 < width * MIN_CROPPED_PERCENT
+        || cropped_height * 100 < height * MIN_CROPPED_PERCENT
+    {
+        return None;
+    }
+    // 1% 미만으로 줄어드는 경우는 다시 인코딩할 가치가 없다
+    if (width - cropped_width) * 100 < width && (height - cropped_height) * 100 < height {
+        return None;
+    }
+    Some(image.crop_imm(left, top, cropped_width, cropped_height))
+}
+
+/// JPEG for opaque images, PNG when there is an alpha channel.
+pub(crate) fn encode(image: &DynamicImage) -> anyhow::Result<Vec<u8>> {
+    let mut out = std::io::Cursor::new(Vec::new());
+    if image.color().has_alpha() {
+        image.write_to(&mut out, ImageFormat::Png)?;
+    } else {
+        image
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY))?;
+    }
+    Ok(out.into_inner())
+}
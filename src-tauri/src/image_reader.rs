@@ -1,13 +1,61 @@
 use std::cmp::Ordering;
 use std::path::{Path, PathBuf};
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock, RwLock};
 
-const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp", "svg"];
+use crate::zip_fast::ZipIndex;
+
+/// AVIF/HEIC/HEIF/JXL are converted to PNG before display (see `image_transcode`).
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "webp", "bmp", "svg", "avif", "heic", "heif", "jxl",
+];
+
+/// Junk entry names (lowercase), see `AppConfig::image_ignore_patterns`.
+static IGNORE_PATTERNS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+pub fn set_ignore_patterns(patterns: &[String]) {
+    let patterns = patterns
+        .iter()
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+    *IGNORE_PATTERNS.write().unwrap_or_else(|e| e.into_inner()) = patterns;
+}
+
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+    if let Some(prefix) = pattern.strip_suffix('*') {
+        name.starts_with(prefix)
+    } else if let Some(suffix) = pattern.strip_prefix('*') {
+        name.ends_with(suffix)
+    } else {
+        name == pattern
+    }
+}
+
+/// Entries that show up as broken pages: macOS resource forks (`__MACOSX/`, `._*`),
+/// NAS/Windows thumbnail caches, and anything inside a hidden (dot) folder.
+pub(crate) fn is_junk_entry(path: &str) -> bool {
+    let patterns = IGNORE_PATTERNS.read().unwrap_or_else(|e| e.into_inner());
+    let mut components = path
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != "." && *c != "..");
+    components.any(|component| {
+        let component = component.to_lowercase();
+        component.starts_with('.') || patterns.iter().any(|p| matches_pattern(&component, p))
+    })
+}
+
+/// Image files of a folder listing (not junk, not empty).
+fn is_listed_image(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    is_image_file(&name)
+        && !is_junk_entry(&name)
+        && std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() > 0)
+}
 
 // ── Natural Sort ──
 
 #[derive(Eq, PartialEq)]
-enum SortChunk {
+pub(crate) enum SortChunk {
     Text(String),
     Num(u64),
 }
@@ -29,7 +77,7 @@ impl PartialOrd for SortChunk {
     }
 }
 
-fn natural_sort_key(s: &str) -> Vec<SortChunk> {
+pub(crate) fn natural_sort_key(s: &str) -> Vec<SortChunk> {
     let mut chunks = Vec::new();
     let lower = s.to_lowercase();
     let mut chars = lower.char_indices().peekable();
@@ -37,14 +85,14 @@ fn natural_sort_key(s: &str) -> Vec<SortChunk> {
     while chars.peek().is_some() {
         let (start, ch) = *chars.peek().unwrap();
         if ch.is_ascii_digit() {
-            while chars.peek().map_or(false, |(_, c)| c.is_ascii_digit()) {
+            while chars.peek().is_some_and(|(_, c)| c.is_ascii_digit()) {
                 chars.next();
             }
             let end = chars.peek().map_or(lower.len(), |(i, _)| *i);
             chunks.push(SortChunk::Num(lower[start..end].parse().unwrap_or(0)));
         } else {
             chars.next();
-            while chars.peek().map_or(false, |(_, c)| !c.is_ascii_digit()) {
+            while chars.peek().is_some_and(|(_, c)| !c.is_ascii_digit()) {
                 chars.next();
             }
             let end = chars.peek().map_or(lower.len(), |(i, _)| *i);
@@ -77,83 +125,113 @@ fn extract_series_prefix(stem: &str) -> String {
     }
 }
 
-/// 같은 디렉토리에서 인접한 ZIP 파일 경로를 찾는다.
-/// (이전 ZIP, 다음 ZIP) 튜플을 반환.
-pub fn find_adjacent_zips(current_zip: &Path) -> anyhow::Result<(Option<PathBuf>, Option<PathBuf>)> {
-    let dir = current_zip
-        .parent()
-        .ok_or_else(|| anyhow::anyhow!("Cannot determine parent directory"))?;
-
-    let current_name = current_zip
-        .file_name()
-        .ok_or_else(|| anyhow::anyhow!("Cannot get filename"))?
-        .to_string_lossy();
-
-    // 같은 디렉토리의 ZIP 파일 수집 + natural sort
-    let mut zips: Vec<PathBuf> = std::fs::read_dir(dir)?
-        .filter_map(|e| e.ok())
-        .map(|e| e.path())
-        .filter(|p| {
-            p.is_file()
-                && p.extension()
-                    .map(|ext| ext.to_string_lossy().to_lowercase() == "zip")
-                    .unwrap_or(false)
-        })
-        .collect();
-
-    zips.sort_by(|a, b| natural_sort_cmp(a, b));
+fn path_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
 
-    // 현재 파일의 접두사 추출
-    let current_stem = current_zip
-        .file_stem()
+fn path_name(path: &Path) -> String {
+    path.file_name()
         .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_default();
-    let current_prefix = extract_series_prefix(&current_stem);
+        .unwrap_or_default()
+}
+
+/// `paths`를 natural sort한 뒤 `current`와 같은 시리즈(접두사)에 속하는 목록과 그 안의 현재 위치.
+/// 같은 접두사가 하나뿐이면 전체 목록으로 폴백한다. `current`가 없으면 None.
+fn series_group(
+    mut paths: Vec<PathBuf>,
+    current: &Path,
+    series_name: fn(&Path) -> String,
+) -> Option<(Vec<PathBuf>, usize)> {
+    paths.sort_by(|a, b| natural_sort_cmp(a, b));
 
     // 같은 접두사의 파일들로 그룹핑
-    let group: Vec<&PathBuf> = zips
+    let current_prefix = extract_series_prefix(&series_name(current));
+    let group: Vec<PathBuf> = paths
         .iter()
-        .filter(|p| {
-            let stem = p.file_stem()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_default();
-            extract_series_prefix(&stem) == current_prefix
-        })
+        .filter(|p| extract_series_prefix(&series_name(p)) == current_prefix)
+        .cloned()
         .collect();
 
     // 그룹 크기 > 1이면 그룹 내 탐색, 아니면 전체 목록으로 폴백
-    let search_list: Vec<&PathBuf> = if group.len() > 1 {
-        group
-    } else {
-        zips.iter().collect()
-    };
+    let search_list = if group.len() > 1 { group } else { paths };
 
     // 현재 위치 찾기
+    let current_name = path_name(current).to_lowercase();
     let current_pos = search_list
         .iter()
-        .position(|p| {
-            p.file_name()
-                .map(|n| n.to_string_lossy().to_lowercase())
-                == Some(current_name.to_lowercase().into())
-        });
+        .position(|p| path_name(p).to_lowercase() == current_name)?;
+    Some((search_list, current_pos))
+}
 
-    let current_pos = match current_pos {
-        Some(pos) => pos,
-        None => return Ok((None, None)),
-    };
+fn has_images(dir: &Path) -> bool {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .any(|e| is_listed_image(&e.path()))
+        })
+        .unwrap_or(false)
+}
 
-    let prev = if current_pos > 0 {
-        Some(search_list[current_pos - 1].clone())
-    } else {
-        None
-    };
-    let next = if current_pos + 1 < search_list.len() {
-        Some(search_list[current_pos + 1].clone())
+/// 시리즈 비교용 이름: 폴더는 이름 전체, 파일은 확장자를 뺀 이름.
+fn volume_name(path: &Path) -> String {
+    if path.is_dir() {
+        path_name(path)
     } else {
-        None
-    };
+        path_stem(path)
+    }
+}
 
-    Ok((prev, next))
+/// The archive or image folder a tab belongs to: the folder itself, the folder of a
+/// single opened image, or the archive file.
+pub fn volume_path(tab_path: &Path) -> PathBuf {
+    let is_folder_image = !tab_path.is_dir()
+        && tab_path
+            .file_name()
+            .is_some_and(|name| is_image_file(&name.to_string_lossy()));
+    match tab_path.parent() {
+        Some(dir) if is_folder_image => dir.to_path_buf(),
+        _ => tab_path.to_path_buf(),
+    }
+}
+
+/// 같은 디렉토리에서 `volume`과 같은 시리즈의 권(이미지 아카이브, 이미지가 있는 폴더)을
+/// natural sort 순서로 찾는다. (목록, 현재 위치)를 반환하고, 현재 권이 목록에 없으면 None.
+/// 숨김 파일/폴더는 건너뛴다.
+pub fn find_series_volumes(volume: &Path) -> anyhow::Result<Option<(Vec<PathBuf>, usize)>> {
+    let dir = volume
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Cannot determine parent directory"))?;
+
+    let volumes: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| !is_junk_entry(&path_name(p)))
+        .filter(|p| {
+            if p.is_dir() {
+                has_images(p)
+            } else {
+                p.extension()
+                    .is_some_and(|ext| is_image_archive_extension(&ext.to_string_lossy().to_lowercase()))
+            }
+        })
+        .collect();
+
+    Ok(series_group(volumes, volume, volume_name))
+}
+
+/// Number of pages of a ZIP/CBZ or image folder; None for formats that need an external
+/// tool to list (RAR, 7z).
+pub fn volume_page_count(volume: &Path) -> Option<usize> {
+    if volume.is_dir() {
+        return scan_directory_images(volume).ok().map(|(_, images)| images.len());
+    }
+    let ext = volume.extension()?.to_string_lossy().to_lowercase();
+    matches!(ext.as_str(), "zip" | "cbz")
+        .then(|| list_zip_images(volume).ok().map(|entries| entries.len()))
+        .flatten()
 }
 
 #[allow(dead_code)]
@@ -165,6 +243,22 @@ pub enum ImageSource {
     Zip {
         zip_path: PathBuf,
         entry_names: Vec<String>,
+        /// Central directory parsed when the tab opened, shared with the image cache.
+        index: Arc<ZipIndex>,
+    },
+    Rar {
+        rar_path: PathBuf,
+        entry_names: Vec<String>,
+        /// Resolved unrar executable.
+        unrar: PathBuf,
+        solid: bool,
+    },
+    SevenZip {
+        archive_path: PathBuf,
+        entry_names: Vec<String>,
+        /// Resolved 7-Zip executable.
+        tool: PathBuf,
+        solid: bool,
     },
 }
 
@@ -172,7 +266,9 @@ impl ImageSource {
     pub fn len(&self) -> usize {
         match self {
             ImageSource::Folder { image_paths, .. } => image_paths.len(),
-            ImageSource::Zip { entry_names, .. } => entry_names.len(),
+            ImageSource::Zip { entry_names, .. }
+            | ImageSource::Rar { entry_names, .. }
+            | ImageSource::SevenZip { entry_names, .. } => entry_names.len(),
         }
     }
 
@@ -186,11 +282,33 @@ impl ImageSource {
                         .unwrap_or_default()
                 })
                 .collect(),
-            ImageSource::Zip { entry_names, .. } => entry_names.clone(),
+            ImageSource::Zip { entry_names, .. }
+            | ImageSource::Rar { entry_names, .. }
+            | ImageSource::SevenZip { entry_names, .. } => entry_names.clone(),
         }
     }
 
-    pub fn read_bytes(&self, index: usize) -> anyhow::Result<Vec<u8>> {
+    fn name_at(&self, index: usize) -> Option<String> {
+        match self {
+            ImageSource::Folder { image_paths, .. } => image_paths
+                .get(index)
+                .map(|p| p.to_string_lossy().to_string()),
+            ImageSource::Zip { entry_names, .. }
+            | ImageSource::Rar { entry_names, .. }
+            | ImageSource::SevenZip { entry_names, .. } => entry_names.get(index).cloned(),
+        }
+    }
+
+    /// Read image `index`, converted to a format the webview can display.
+    pub fn read_bytes(&self, index: usize, imagemagick_path: &str) -> anyhow::Result<Vec<u8>> {
+        let name = self
+            .name_at(index)
+            .ok_or_else(|| anyhow::anyhow!("Image index out of range: {}", index))?;
+        let bytes = self.read_raw(index)?;
+        crate::image_transcode::for_display(&name, bytes, imagemagick_path)
+    }
+
+    fn read_raw(&self, index: usize) -> anyhow::Result<Vec<u8>> {
         match self {
             ImageSource::Folder { image_paths, .. } => {
                 let path = image_paths
@@ -199,14 +317,36 @@ impl ImageSource {
                 std::fs::read(path).map_err(|e| anyhow::anyhow!("Failed to read image: {}", e))
             }
             ImageSource::Zip {
-                zip_path,
                 entry_names,
+                index: zip_index,
                 ..
             } => {
                 let entry_name = entry_names
                     .get(index)
                     .ok_or_else(|| anyhow::anyhow!("Image index out of range: {}", index))?;
-                read_zip_image(zip_path, entry_name)
+                zip_index.read_entry(entry_name)
+            }
+            ImageSource::Rar {
+                rar_path,
+                entry_names,
+                unrar,
+                solid,
+            } => {
+                let entry_name = entry_names
+                    .get(index)
+                    .ok_or_else(|| anyhow::anyhow!("Image index out of range: {}", index))?;
+                crate::rar_reader::read_entry(unrar, rar_path, entry_name, *solid, None)
+            }
+            ImageSource::SevenZip {
+                archive_path,
+                entry_names,
+                tool,
+                solid,
+            } => {
+                let entry_name = entry_names
+                    .get(index)
+                    .ok_or_else(|| anyhow::anyhow!("Image index out of range: {}", index))?;
+                crate::sevenz_reader::read_entry(tool, archive_path, entry_name, *solid, None)
             }
         }
     }
@@ -216,7 +356,19 @@ pub fn is_image_extension(ext: &str) -> bool {
     IMAGE_EXTENSIONS.contains(&ext)
 }
 
-fn is_image_file(name: &str) -> bool {
+/// Comic book archives (.cbz / .cbr / .cb7).
+pub fn is_comic_extension(ext: &str) -> bool {
+    matches!(ext, "cbz" | "cbr" | "cb7")
+}
+
+/// Archives opened in the image viewer (ZIP, RAR, 7z and their comic variants).
+pub fn is_image_archive_extension(ext: &str) -> bool {
+    matches!(ext, "zip" | "cbz")
+        || crate::rar_reader::is_rar_extension(ext)
+        || crate::sevenz_reader::is_7z_extension(ext)
+}
+
+pub(crate) fn is_image_file(name: &str) -> bool {
     let lower = name.to_lowercase();
     IMAGE_EXTENSIONS
         .iter()
@@ -233,7 +385,7 @@ pub fn scan_directory_images(dir_path: &Path) -> anyhow::Result<(PathBuf, Vec<Pa
     let mut images: Vec<PathBuf> = std::fs::read_dir(dir_path)?
         .filter_map(|entry| entry.ok())
         .map(|entry| entry.path())
-        .filter(|p| p.is_file() && is_image_file(&p.to_string_lossy()))
+        .filter(|p| is_listed_image(p))
         .collect();
 
     images.sort_by(|a, b| natural_sort_cmp(a, b));
@@ -251,7 +403,7 @@ pub fn scan_folder_images(file_path: &Path) -> anyhow::Result<(PathBuf, Vec<Path
     let mut images: Vec<PathBuf> = std::fs::read_dir(dir)?
         .filter_map(|entry| entry.ok())
         .map(|entry| entry.path())
-        .filter(|p| p.is_file() && is_image_file(&p.to_string_lossy()))
+        .filter(|p| is_listed_image(p))
         .collect();
 
     images.sort_by(|a, b| natural_sort_cmp(a, b));
@@ -271,35 +423,44 @@ pub fn scan_folder_images(file_path: &Path) -> anyhow::Result<(PathBuf, Vec<Path
     Ok((dir.to_path_buf(), images, current_index))
 }
 
-/// List image entries in a ZIP file, sorted depth-first alphabetically.
+/// List image entries in a ZIP file with their uncompressed sizes, sorted depth-first alphabetically.
 /// Uses custom fast parser: only reads EOCD + Central Directory (no local header validation).
-pub fn list_zip_images(zip_path: &Path) -> anyhow::Result<Vec<String>> {
-    let index = crate::zip_fast::ZipIndex::open(zip_path)?;
+pub fn list_zip_images(zip_path: &Path) -> anyhow::Result<Vec<(String, u64)>> {
+    let index = ZipIndex::open(zip_path)?;
+    Ok(zip_image_entries(&index))
+}
 
-    let mut entries: Vec<String> = index
-        .entry_names()
-        .filter(|name| !name.ends_with('/') && is_image_file(name))
-        .map(|name| name.to_string())
+/// Image entries of an already opened ZIP, sorted like `list_zip_images`.
+pub fn zip_image_entries(index: &ZipIndex) -> Vec<(String, u64)> {
+    let mut entries: Vec<(String, u64)> = index
+        .entry_sizes()
+        .filter(|(name, size)| {
+            !name.ends_with('/') && *size > 0 && is_image_file(name) && !is_junk_entry(name)
+        })
+        .map(|(name, size)| (name.to_string(), size))
         .collect();
 
-    entries.sort_by(|a, b| {
-        let a_parts: Vec<&str> = a.split('/').collect();
-        let b_parts: Vec<&str> = b.split('/').collect();
-        // 디렉토리 경로 깊이가 같으면 natural sort로 비교
-        let depth = a_parts.len().cmp(&b_parts.len());
-        if depth != Ordering::Equal {
-            return depth;
-        }
-        for (ap, bp) in a_parts.iter().zip(b_parts.iter()) {
-            let cmp = natural_sort_key(ap).cmp(&natural_sort_key(bp));
-            if cmp != Ordering::Equal {
-                return cmp;
-            }
-        }
-        Ordering::Equal
-    });
+    entries.sort_by(|(a, _), (b, _)| archive_entry_cmp(a, b));
+
+    entries
+}
 
-    Ok(entries)
+/// Order archive entry paths ('/'-separated): shallower first, then natural sort per component.
+pub(crate) fn archive_entry_cmp(a: &str, b: &str) -> Ordering {
+    let a_parts: Vec<&str> = a.split('/').collect();
+    let b_parts: Vec<&str> = b.split('/').collect();
+    // 디렉토리 경로 깊이가 같으면 natural sort로 비교
+    let depth = a_parts.len().cmp(&b_parts.len());
+    if depth != Ordering::Equal {
+        return depth;
+    }
+    for (ap, bp) in a_parts.iter().zip(b_parts.iter()) {
+        let cmp = natural_sort_key(ap).cmp(&natural_sort_key(bp));
+        if cmp != Ordering::Equal {
+            return cmp;
+        }
+    }
+    Ordering::Equal
 }
 
 /// Read a single image entry from a ZIP file using the fast parser.
@@ -307,3 +468,184 @@ pub fn read_zip_image(zip_path: &Path, entry_name: &str) -> anyhow::Result<Vec<u
     let index = crate::zip_fast::ZipIndex::open(zip_path)?;
     index.read_entry(entry_name)
 }
+
+// ── Archive preview (library grid) ──
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ArchivePreview {
+    pub page_count: usize,
+    pub cover_name: Option<String>,
+    /// Downscaled first page as a data URI (None if it couldn't be read).
+    pub cover: Option<String>,
+    /// Archive needs a password to read its images.
+    pub encrypted: bool,
+}
+
+/// Downscale an image to fit in `max_size` × `max_size` and encode it as JPEG.
+pub fn encode_thumbnail(bytes: &[u8], max_size: u32) -> Option<Vec<u8>> {
+    encode_rotated_thumbnail(bytes, max_size, 0)
+}
+
+/// `encode_thumbnail`, turned clockwise by `rotation` degrees (0, 90, 180 or 270) after
+/// the EXIF orientation is applied (the JPEG written has no EXIF).
+pub fn encode_rotated_thumbnail(bytes: &[u8], max_size: u32, rotation: u16) -> Option<Vec<u8>> {
+    use image::metadata::Orientation;
+    use image::ImageDecoder;
+
+    let mut decoder = image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = image::DynamicImage::from_decoder(decoder).ok()?;
+    image.apply_orientation(orientation);
+    let thumb = if image.width() > max_size || image.height() > max_size {
+        image.thumbnail(max_size, max_size)
+    } else {
+        image
+    };
+    let thumb = match rotation {
+        90 => thumb.rotate90(),
+        180 => thumb.rotate180(),
+        270 => thumb.rotate270(),
+        _ => thumb,
+    };
+    let mut out = std::io::Cursor::new(Vec::new());
+    thumb
+        .to_rgb8()
+        .write_to(&mut out, image::ImageFormat::Jpeg)
+        .ok()?;
+    Some(out.into_inner())
+}
+
+/// Size and format of one image, read from its header.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ImageInfo {
+    /// None when the header can't be parsed (e.g. SVG, JPEG XL).
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Size of the stored file in bytes (before any transcoding).
+    pub file_size: u64,
+    /// Lowercase format name ("jpeg", "png", "avif", ...).
+    pub format: String,
+}
+
+/// Parse dimensions and format from the image header without decoding the pixels.
+pub fn image_info(name: &str, bytes: &[u8]) -> ImageInfo {
+    let ext = name.rsplit('.').next().unwrap_or_default().to_lowercase();
+    let (format, dimensions) = match image::guess_format(bytes) {
+        Ok(format) => {
+            let dimensions = image::ImageReader::with_format(std::io::Cursor::new(bytes), format)
+                .into_dimensions()
+                .ok();
+            let name = format.extensions_str().first().copied().unwrap_or_default();
+            // "jpg" 대신 "jpeg"
+            let name = if format == image::ImageFormat::Jpeg { "jpeg" } else { name };
+            (name.to_string(), dimensions)
+        }
+        Err(_) if matches!(ext.as_str(), "avif" | "heic" | "heif") => (ext.clone(), isobmff_dimensions(bytes)),
+        Err(_) if ext == "jpg" => ("jpeg".to_string(), None),
+        Err(_) => (ext.clone(), None),
+    };
+    ImageInfo {
+        width: dimensions.map(|(w, _)| w),
+        height: dimensions.map(|(_, h)| h),
+        file_size: bytes.len() as u64,
+        format,
+    }
+}
+
+/// (width, height) as the image is shown: the stored size from the header, swapped when the
+/// EXIF orientation turns it by 90°. None when the header can't be parsed.
+pub fn display_dimensions(name: &str, bytes: &[u8]) -> Option<(u32, u32)> {
+    use image::metadata::Orientation;
+    use image::ImageDecoder;
+
+    let info = image_info(name, bytes);
+    let (width, height) = info.width.zip(info.height)?;
+    let orientation = image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_decoder().ok())
+        .and_then(|mut decoder| decoder.orientation().ok());
+    match orientation {
+        Some(
+            Orientation::Rotate90
+            | Orientation::Rotate270
+            | Orientation::Rotate90FlipH
+            | Orientation::Rotate270FlipH,
+        ) => Some((height, width)),
+        _ => Some((width, height)),
+    }
+}
+
+/// Width/height from the `ispe` property box of an AVIF/HEIF file (the first one is the
+/// primary image in practice).
+fn isobmff_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let pos = bytes.windows(4).position(|w| w == b"ispe")?;
+    // "ispe" + version/flags(4) + width(4) + height(4)
+    let data = bytes.get(pos + 8..pos + 16)?;
+    let width = u32::from_be_bytes(data[0..4].try_into().ok()?);
+    let height = u32::from_be_bytes(data[4..8].try_into().ok()?);
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+pub(crate) fn image_mime(name: &str) -> &'static str {
+    let lower = name.to_lowercase();
+    match lower.rsplit('.').next().unwrap_or_default() {
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        _ => "image/jpeg",
+    }
+}
+
+/// Page count and a small cover (first image) of a ZIP/CBZ or image folder, without opening a tab.
+pub fn archive_preview(path: &Path, max_size: u32) -> anyhow::Result<ArchivePreview> {
+    use base64::Engine;
+
+    let (names, first, encrypted): (Vec<String>, Option<anyhow::Result<Vec<u8>>>, bool) = if path.is_dir() {
+        let (_, images) = scan_directory_images(path)?;
+        let names = images
+            .iter()
+            .map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default())
+            .collect();
+        let first = images.first().map(|p| std::fs::read(p).map_err(Into::into));
+        (names, first, false)
+    } else {
+        let index = crate::zip_fast::ZipIndex::open(path)?;
+        let names: Vec<String> = list_zip_images(path)?.into_iter().map(|(name, _)| name).collect();
+        let encrypted = names.first().is_some_and(|n| index.is_entry_encrypted(n));
+        let first = (!encrypted)
+            .then(|| names.first().map(|n| index.read_entry(n)))
+            .flatten();
+        (names, first, encrypted)
+    };
+
+    let cover_name = names.first().cloned();
+    let cover = match (first, cover_name.as_deref()) {
+        (Some(Ok(bytes)), Some(name)) => {
+            // 디코딩할 수 없는 형식(svg 등)은 원본 그대로 보낸다
+            let (mime, data) = match encode_thumbnail(&bytes, max_size) {
+                Some(thumb) => ("image/jpeg", thumb),
+                None => (image_mime(name), bytes),
+            };
+            Some(format!(
+                "data:{};base64,{}",
+                mime,
+                base64::engine::general_purpose::STANDARD.encode(data)
+            ))
+        }
+        _ => None,
+    };
+
+    Ok(ArchivePreview {
+        page_count: names.len(),
+        cover_name,
+        cover,
+        encrypted,
+    })
+}
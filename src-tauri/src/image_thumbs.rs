@@ -0,0 +1,112 @@
+//! Page thumbnails of image tabs (folders and archives) for the page-grid overview.
+//!
+//! 썸네일은 (원본 경로, 크기, 수정 시각, 페이지, 엔트리 이름, 최대 크기, 회전) 단위로
+//! `disk_cache::IMAGE_THUMBS`에 JPEG로 캐시하므로, 1000쪽짜리 아카이브도 두 번째부터는
+//! 디코드 없이 바로 읽힌다. 처음 만들 때는 몇 개의 스레드로 나눠 디코드한다.
+//! EXIF 방향과 페이지 회전은 썸네일 픽셀에 반영한다.
+
+use base64::Engine;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::disk_cache;
+use crate::image_cache::ImageCacheManager;
+
+/// Most thumbnails made by one `thumbnails` call.
+pub const MAX_THUMBNAILS_PER_CALL: usize = 100;
+/// Threads decoding missing thumbnails.
+const THUMBNAIL_WORKERS: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageThumbnail {
+    pub index: usize,
+    /// JPEG data URI.
+    pub data: String,
+}
+
+fn thumb_cache_name(source: &Path, index: usize, name: &str, max_size: u32, rotation: u16) -> String {
+    let key = disk_cache::source_key(
+        source,
+        &[
+            &index.to_le_bytes(),
+            name.as_bytes(),
+            &max_size.to_le_bytes(),
+            &rotation.to_le_bytes(),
+        ],
+    );
+    format!("{}.jpg", key)
+}
+
+fn make_thumbnail(
+    cache: &ImageCacheManager,
+    file_id: &str,
+    index: usize,
+    max_size: u32,
+    rotation: u16,
+    cache_name: &str,
+) {
+    let Ok((name, bytes)) = cache.read_image_raw(file_id, index) else {
+        return;
+    };
+    let Ok(bytes) = crate::image_transcode::for_display(&name, bytes, &cache.imagemagick_path()) else {
+        return;
+    };
+    if let Some(thumb) = crate::image_reader::encode_rotated_thumbnail(&bytes, max_size, rotation) {
+        if let Err(e) = disk_cache::IMAGE_THUMBS.write(cache_name, &thumb) {
+            log::warn!("Failed to cache thumbnail: {}", e);
+        }
+    }
+}
+
+/// Previews of pages `start..start + count` (at most `MAX_THUMBNAILS_PER_CALL`) of an image
+/// tab, each fitting in `max_size` × `max_size`. `source` is the folder or archive path.
+/// Pages past the end, or that can't be decoded, are left out.
+pub fn thumbnails(
+    cache: &ImageCacheManager,
+    file_id: &str,
+    source: &Path,
+    total: usize,
+    start: usize,
+    count: usize,
+    max_size: u32,
+) -> anyhow::Result<Vec<ImageThumbnail>> {
+    let end = start.saturating_add(count.min(MAX_THUMBNAILS_PER_CALL)).min(total);
+    let cache_names: Vec<(usize, u16, String)> = (start..end)
+        .filter_map(|index| {
+            let name = cache.image_name(file_id, index)?;
+            let rotation = cache.rotation(file_id, index);
+            Some((index, rotation, thumb_cache_name(source, index, &name, max_size, rotation)))
+        })
+        .collect();
+
+    let missing: Vec<&(usize, u16, String)> = cache_names
+        .iter()
+        .filter(|(_, _, name)| !disk_cache::IMAGE_THUMBS.contains(name))
+        .collect();
+    if !missing.is_empty() {
+        let chunk_size = missing.len().div_ceil(THUMBNAIL_WORKERS);
+        std::thread::scope(|scope| {
+            for chunk in missing.chunks(chunk_size) {
+                scope.spawn(move || {
+                    for (index, rotation, name) in chunk {
+                        make_thumbnail(cache, file_id, *index, max_size, *rotation, name);
+                    }
+                });
+            }
+        });
+    }
+
+    Ok(cache_names
+        .into_iter()
+        .filter_map(|(index, _, name)| {
+            let bytes = disk_cache::IMAGE_THUMBS.read(&name)?;
+            Some(ImageThumbnail {
+                index,
+                data: format!(
+                    "data:image/jpeg;base64,{}",
+                    base64::engine::general_purpose::STANDARD.encode(bytes)
+                ),
+            })
+        })
+        .collect())
+}
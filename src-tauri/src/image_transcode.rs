@@ -0,0 +1,68 @@
+//! Transcoding images the webview can't display (AVIF, HEIC/HEIF, JPEG XL) to PNG.
+//!
+//! 디코더를 앱에 넣지 않고 ImageMagick(`magick`, 구버전은 `convert`)에 표준 입력으로
+//! 넘겨 PNG로 받는다. 변환된 결과가 이미지 캐시에 들어가므로 페이지마다 한 번만 변환된다.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Extensions (lowercase) that are converted before display, with ImageMagick's format name.
+const TRANSCODED_FORMATS: &[(&str, &str)] = &[
+    ("avif", "avif"),
+    ("heic", "heic"),
+    ("heif", "heif"),
+    ("jxl", "jxl"),
+];
+
+fn transcoded_format(name: &str) -> Option<&'static str> {
+    let ext = name.rsplit('.').next()?.to_lowercase();
+    TRANSCODED_FORMATS
+        .iter()
+        .find(|(e, _)| *e == ext)
+        .map(|(_, format)| *format)
+}
+
+/// Locate ImageMagick: configured path, then `magick` (v7) or `convert` (v6) on PATH.
+fn find_magick(configured: &str) -> anyhow::Result<PathBuf> {
+    // Windows의 convert.exe는 디스크 변환 도구라 magick만 찾는다
+    let names: &[&str] = if cfg!(windows) { &["magick"] } else { &["magick", "convert"] };
+    crate::external_tool::find_tool(configured, "ImageMagick", names, &[])?.ok_or_else(|| {
+        anyhow::anyhow!("AVIF/HEIC/JPEG XL images need ImageMagick (install it or set its path in settings)")
+    })
+}
+
+/// Return `bytes` ready for the webview: converted to PNG if `name` is a transcoded format,
+/// otherwise unchanged. `imagemagick_path` is the configured tool path (empty = search for it).
+pub fn for_display(name: &str, bytes: Vec<u8>, imagemagick_path: &str) -> anyhow::Result<Vec<u8>> {
+    let Some(format) = transcoded_format(name) else {
+        return Ok(bytes);
+    };
+    let magick = find_magick(imagemagick_path)?;
+
+    let mut child = Command::new(&magick)
+        .arg(format!("{}:-", format))
+        .arg("png:-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run ImageMagick: {}", e))?;
+    // 출력 파이프가 차서 멈추지 않도록 입력은 별도 스레드에서 쓴다
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("Failed to open ImageMagick stdin"))?;
+    let writer = std::thread::spawn(move || stdin.write_all(&bytes));
+    let output = child.wait_with_output()?;
+    let _ = writer.join();
+
+    if !output.status.success() || output.stdout.is_empty() {
+        anyhow::bail!(
+            "Failed to convert {}: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
@@ -0,0 +1,288 @@
+//! Read-only, memory-mapped text for files too large to load into a Rope.
+//!
+//! 줄 오프셋은 `INDEX_STRIDE`줄마다 하나씩만 저장하고, 요청된 줄까지만 점진적으로
+//! 색인한다. 디코딩은 읽어 가는 줄 단위로 한다 (CP949/Shift_JIS 등 `\n`이 다른 바이트와
+//! 섞이지 않는 인코딩만 해당).
+
+use crate::text_buffer::{LineEnding, WideEncoding};
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Files larger than this open in lazy (read-only) mode.
+pub const LAZY_THRESHOLD_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Store one checkpoint every this many lines.
+const INDEX_STRIDE: usize = 128;
+
+/// Bytes scanned per indexing step.
+const INDEX_STEP_BYTES: usize = 16 * 1024 * 1024;
+
+/// Approximate size of the pieces `LazyTextReader::for_each_chunk` decodes.
+const READ_CHUNK_BYTES: usize = 16 * 1024 * 1024;
+
+/// Bytes fed to the encoding detector.
+const DETECT_SAMPLE_BYTES: usize = 64 * 1024;
+
+struct LineIndex {
+    /// Byte offset of line `i * INDEX_STRIDE`.
+    checkpoints: Vec<usize>,
+    /// Lines whose start is known (the last one may still be growing).
+    lines_indexed: usize,
+    /// Byte position up to which `\n`s have been counted.
+    scanned: usize,
+    /// Chars (bytes for non-UTF-8 encodings) in `start..scanned`.
+    chars: usize,
+    complete: bool,
+}
+
+pub struct LazyText {
+    map: Arc<Mmap>,
+    /// Offset of the first content byte (after a UTF-8 BOM).
+    start: usize,
+    encoding: &'static Encoding,
+    index: Mutex<LineIndex>,
+    /// (lines, chars) from an earlier complete scan (warm cache), used until the index catches up.
+    known_totals: OnceLock<(usize, usize)>,
+}
+
+impl LazyText {
+    /// Map `path`, decoding with `forced` or a detected encoding.
+    pub fn open(path: &Path, forced: Option<&'static Encoding>) -> anyhow::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: read-only mapping; the file may change underneath, which only garbles the view
+        let map = unsafe { Mmap::map(&file)? };
+
+        if forced.is_none() && WideEncoding::detect_bom(&map).is_some() {
+            anyhow::bail!("UTF-16/UTF-32 files this large are not supported");
+        }
+        let utf8_bom = map.starts_with(&[0xEF, 0xBB, 0xBF]) && forced.map_or(true, |e| e == UTF_8);
+        let start = if utf8_bom { 3 } else { 0 };
+        let sample = &map[start..map.len().min(start + DETECT_SAMPLE_BYTES)];
+        let encoding = match forced {
+            Some(encoding) => encoding,
+            None if is_utf8_prefix(sample) => UTF_8,
+            None => {
+                let mut detector = EncodingDetector::new();
+                detector.feed(sample, false);
+                detector.guess(None, true)
+            }
+        };
+        if !encoding.is_ascii_compatible() {
+            anyhow::bail!("{} is not supported for files this large", encoding.name());
+        }
+
+        Ok(Self {
+            map: Arc::new(map),
+            start,
+            encoding,
+            index: Mutex::new(LineIndex {
+                checkpoints: vec![start],
+                lines_indexed: 1,
+                scanned: start,
+                chars: 0,
+                complete: false,
+            }),
+            known_totals: OnceLock::new(),
+        })
+    }
+
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+
+    pub fn line_ending(&self) -> LineEnding {
+        LineEnding::detect(&self.map[self.start..])
+    }
+
+    /// Get lines `start_line..end_line`, each including its trailing newline (like `Rope::line`).
+    pub fn get_chunk(&self, start_line: usize, end_line: usize) -> Vec<String> {
+        let Some(mut pos) = self.line_start(start_line) else {
+            return Vec::new();
+        };
+        let data = &self.map[..];
+        let mut lines = Vec::with_capacity(end_line.saturating_sub(start_line));
+        for _ in start_line..end_line {
+            if pos >= data.len() {
+                break;
+            }
+            let end = next_line_start(data, pos);
+            lines.push(self.decode(&data[pos..end]));
+            pos = end;
+        }
+        lines
+    }
+
+    /// Seed (lines, chars) remembered from a previous session for the same file.
+    pub fn set_known_totals(&self, lines: usize, chars: usize) {
+        let _ = self.known_totals.set((lines, chars));
+    }
+
+    /// (lines, chars) once the index has scanned the whole file, unless they were seeded.
+    pub fn learned_totals(&self) -> Option<(usize, usize)> {
+        if self.known_totals.get().is_some() {
+            return None;
+        }
+        let index = self.lock_index();
+        index.complete.then_some((index.lines_indexed, index.chars))
+    }
+
+    /// Total line count. Until the index has reached the end of the file this is an
+    /// estimate from the line density so far; each call indexes one more step, so repeated
+    /// calls (get_file_info) converge without one multi-GB scan up front.
+    pub fn total_lines(&self) -> usize {
+        if let Some(&(lines, _)) = self.known_totals.get() {
+            return lines;
+        }
+        let mut index = self.lock_index();
+        if !index.complete {
+            self.index_step(&mut index);
+        }
+        self.extrapolate(&index, index.lines_indexed)
+    }
+
+    /// Char count for UTF-8 files; the byte length for other encodings.
+    /// Estimated like `total_lines` while the index is incomplete.
+    pub fn total_chars(&self) -> usize {
+        if let Some(&(_, chars)) = self.known_totals.get() {
+            return chars;
+        }
+        let mut index = self.lock_index();
+        if !index.complete {
+            self.index_step(&mut index);
+        }
+        self.extrapolate(&index, index.chars)
+    }
+
+    /// Scale a count over the scanned part of the file to the whole file.
+    fn extrapolate(&self, index: &LineIndex, counted: usize) -> usize {
+        let scanned = index.scanned - self.start;
+        let total = self.map.len() - self.start;
+        if index.complete || scanned == 0 {
+            return counted;
+        }
+        ((counted as u128 * total as u128) / scanned as u128) as usize
+    }
+
+    /// A reader over the mapped file that doesn't borrow this buffer, so whole-file scans
+    /// can run after the tab lock is released.
+    pub fn reader(&self) -> LazyTextReader {
+        LazyTextReader {
+            map: Arc::clone(&self.map),
+            start: self.start,
+            encoding: self.encoding,
+        }
+    }
+
+    /// Decode the whole file (for features that need the full text).
+    pub fn to_string_full(&self) -> String {
+        self.decode(&self.map[self.start..])
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        self.encoding
+            .decode_without_bom_handling(bytes)
+            .0
+            .into_owned()
+    }
+
+    fn lock_index(&self) -> std::sync::MutexGuard<'_, LineIndex> {
+        self.index.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Byte offset where `line` starts, indexing as far as needed.
+    fn line_start(&self, line: usize) -> Option<usize> {
+        let checkpoint = {
+            let mut index = self.lock_index();
+            while index.lines_indexed <= line && !index.complete {
+                self.index_step(&mut index);
+            }
+            if line >= index.lines_indexed {
+                return None;
+            }
+            index.checkpoints[line / INDEX_STRIDE]
+        };
+        let data = &self.map[..];
+        let mut pos = checkpoint;
+        for _ in 0..line % INDEX_STRIDE {
+            pos = next_line_start(data, pos);
+        }
+        Some(pos)
+    }
+
+    fn index_step(&self, index: &mut LineIndex) {
+        let data = &self.map[..];
+        let end = (index.scanned + INDEX_STEP_BYTES).min(data.len());
+        let utf8 = self.encoding == UTF_8;
+        for (i, &b) in data[index.scanned..end].iter().enumerate() {
+            if !utf8 || (b & 0xC0) != 0x80 {
+                index.chars += 1;
+            }
+            if b == b'\n' {
+                if index.lines_indexed % INDEX_STRIDE == 0 {
+                    index.checkpoints.push(index.scanned + i + 1);
+                }
+                index.lines_indexed += 1;
+            }
+        }
+        index.scanned = end;
+        index.complete = end >= data.len();
+    }
+}
+
+/// The text of a lazy buffer, decoded a piece at a time.
+pub struct LazyTextReader {
+    map: Arc<Mmap>,
+    start: usize,
+    encoding: &'static Encoding,
+}
+
+impl LazyTextReader {
+    /// Call `f` with the decoded text in pieces of about `READ_CHUNK_BYTES`. Pieces end after
+    /// a newline (or, in a very long line, after ASCII whitespace), so no char, word or line
+    /// is split between two of them.
+    pub fn for_each_chunk(&self, mut f: impl FnMut(&str)) {
+        let data = &self.map[..];
+        let mut pos = self.start;
+        while pos < data.len() {
+            let end = chunk_end(data, pos);
+            f(&self.encoding.decode_without_bom_handling(&data[pos..end]).0);
+            pos = end;
+        }
+    }
+}
+
+/// End of the piece starting at `pos`: after the first `\n` past `READ_CHUNK_BYTES`, else
+/// after whitespace within another `READ_CHUNK_BYTES` (never a trail byte in the
+/// supported encodings), else the end of data.
+fn chunk_end(data: &[u8], pos: usize) -> usize {
+    let from = pos + READ_CHUNK_BYTES;
+    if from >= data.len() {
+        return data.len();
+    }
+    let window = &data[from..data.len().min(from + READ_CHUNK_BYTES)];
+    window
+        .iter()
+        .position(|&b| b == b'\n')
+        .or_else(|| window.iter().position(|&b| matches!(b, b' ' | b'\t' | b'\r')))
+        .map_or(data.len(), |i| from + i + 1)
+}
+
+/// Start of the line after the one starting at `pos` (or the end of data).
+fn next_line_start(data: &[u8], pos: usize) -> usize {
+    data[pos..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(data.len(), |i| pos + i + 1)
+}
+
+/// Valid UTF-8, allowing a multi-byte char cut off at the end of the sample.
+fn is_utf8_prefix(sample: &[u8]) -> bool {
+    match std::str::from_utf8(sample) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    }
+}
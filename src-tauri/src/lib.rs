@@ -1,26 +1,116 @@
+mod archive_extract;
+mod archive_password;
+mod archive_tools;
+mod atomic_write;
+mod autosave;
+mod backup;
 mod bookmark;
+mod bookmark_import;
+mod chapter_detect;
 mod commands;
 mod config;
+mod covers;
+mod deep_link;
+mod disk_cache;
+mod duplicates;
+mod epub_edit;
+mod epub_fonts;
+mod epub_highlights;
 mod epub_reader;
+mod epub_sanitize;
+mod epub_search;
+mod epub_writer;
 mod error;
+mod exporter;
+mod external_tool;
 mod formatter;
+mod hex_view;
+mod hotkeys;
+mod html_text;
+mod idle;
 mod image_cache;
 mod image_reader;
+mod image_process;
+mod image_thumbs;
+mod image_transcode;
+mod lazy_text;
+mod library_watcher;
+mod memory_budget;
+mod ocr;
+mod pagination;
+mod pdf_render;
+mod pdf_stream;
+mod quick_open;
+mod rar_reader;
+mod reading_stats;
+mod remote_io;
 mod search;
+mod series;
+mod sevenz_reader;
+mod slideshow;
+mod spread;
+mod store_recovery;
+mod sync;
 mod tab_manager;
+mod tail_follow;
 mod text_buffer;
+mod text_fold;
+mod text_stats;
+mod translation;
+mod vocabulary;
+mod warm_cache;
+mod webtoon;
 mod zip_fast;
 
-use std::sync::Mutex;
+use std::sync::{Mutex, RwLock};
 
 pub struct AppState {
+    /// Current settings: loaded once at startup and replaced by `save_config`.
+    pub config: RwLock<config::AppConfig>,
     pub tab_manager: Mutex<tab_manager::TabManager>,
     pub bookmark_store: Mutex<bookmark::BookmarkStore>,
     pub image_cache: image_cache::ImageCacheManager,
+    pub memory_budget: memory_budget::MemoryBudget,
+    pub library_watcher: library_watcher::LibraryWatcher,
+    pub reading_stats: Mutex<reading_stats::ReadingStats>,
+    pub translation_cache: Mutex<translation::TranslationCache>,
+    pub slideshow: slideshow::SlideshowManager,
+    pub search_jobs: search::SearchJobs,
+    pub search_cursors: search::SearchCursors,
+    pub ocr_batches: ocr::OcrBatches,
+    pub follow_watcher: tail_follow::FollowWatcher,
+}
+
+/// Push the settings read outside `AppState::config` (caches, budget, readers, writers,
+/// watched folders, global hotkeys) into the running app: at startup, after `save_config`
+/// and after a restore. In `--safe-mode` folders aren't watched and hotkeys aren't registered.
+pub(crate) fn apply_runtime_config(
+    config: &config::AppConfig,
+    state: &AppState,
+    app: &tauri::AppHandle,
+) -> anyhow::Result<()> {
+    state.memory_budget.set_budget_mb(config.memory_budget_mb);
+    state.image_cache.set_max_bytes(state.memory_budget.budget_bytes());
+    state.image_cache.set_filter(config.image_filter.active());
+    state.image_cache.set_imagemagick_path(&config.imagemagick_path);
+    image_reader::set_ignore_patterns(&config.image_ignore_patterns);
+    zip_fast::set_crc_check_limit(config.zip_crc_check_max_mb.saturating_mul(1024 * 1024));
+    remote_io::set_read_cache_enabled(config.remote_read_cache);
+    atomic_write::set_keep_backup(config.keep_backup_on_save);
+    if store_recovery::is_safe_mode() {
+        return Ok(());
+    }
+    // 한쪽이 실패해도 다른 쪽은 적용한다
+    let watched = state.library_watcher.watch(app, &config.watched_folders);
+    let hotkeys = hotkeys::apply_hotkeys(app, config);
+    watched.and(hotkeys)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // --safe-mode: start without global hotkeys, folder watching and deep-link registration
+    store_recovery::set_safe_mode(std::env::args().skip(1).any(|a| a == "--safe-mode"));
+
     let bookmark_store = bookmark::BookmarkStore::new()
         .unwrap_or_else(|e| {
             eprintln!("Failed to load bookmark store: {}. Using empty store.", e);
@@ -28,10 +118,23 @@ pub fn run() {
             bookmark::BookmarkStore::new().expect("Failed to create bookmark store")
         });
 
+    let app_config = config::AppConfig::load().unwrap_or_default();
+
+    let memory_budget = memory_budget::MemoryBudget::new(app_config.memory_budget_mb);
     let app_state = AppState {
+        config: RwLock::new(app_config.clone()),
         tab_manager: Mutex::new(tab_manager::TabManager::new()),
         bookmark_store: Mutex::new(bookmark_store),
-        image_cache: image_cache::ImageCacheManager::new(),
+        image_cache: image_cache::ImageCacheManager::new(memory_budget.budget_bytes()),
+        memory_budget,
+        library_watcher: library_watcher::LibraryWatcher::new(),
+        reading_stats: Mutex::new(reading_stats::ReadingStats::load()),
+        translation_cache: Mutex::new(translation::TranslationCache::new()),
+        slideshow: slideshow::SlideshowManager::new(),
+        search_jobs: search::SearchJobs::new(),
+        search_cursors: search::SearchCursors::new(),
+        ocr_batches: ocr::OcrBatches::new(),
+        follow_watcher: tail_follow::FollowWatcher::new(),
     };
 
     tauri::Builder::default()
@@ -39,26 +142,9 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
             // When a second instance is launched, bring existing window to front
-            // and open the file passed as argument
-            use tauri::Manager;
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
-                // Windows: SetForegroundWindow로 확실하게 전면 표시
-                #[cfg(target_os = "windows")]
-                {
-                    use windows_sys::Win32::UI::WindowsAndMessaging::{
-                        SetForegroundWindow, ShowWindow, SW_RESTORE,
-                    };
-                    if let Ok(hwnd) = window.hwnd() {
-                        unsafe {
-                            ShowWindow(hwnd.0 as _, SW_RESTORE);
-                            SetForegroundWindow(hwnd.0 as _);
-                        }
-                    }
-                }
-            }
+            // and open the file passed as argument.
+            // (simplereader:// links are forwarded to the deep-link plugin automatically)
+            focus_main_window(app);
             if args.len() > 1 {
                 let file_path = args[1].clone();
                 let path = std::path::Path::new(&file_path);
@@ -68,7 +154,23 @@ pub fn run() {
                 }
             }
         }))
-        .setup(|app| {
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .register_asynchronous_uri_scheme_protocol(epub_fonts::SCHEME, |ctx, request, responder| {
+            // Fonts may trigger a lazy EPUB parse, so keep it off the webview thread
+            let app = ctx.app_handle().clone();
+            std::thread::spawn(move || {
+                responder.respond(epub_fonts::handle_request(&app, &request));
+            });
+        })
+        .register_asynchronous_uri_scheme_protocol(pdf_stream::SCHEME, |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            std::thread::spawn(move || {
+                responder.respond(pdf_stream::handle_request(&app, &request));
+            });
+        })
+        .setup(move |app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
                     tauri_plugin_log::Builder::default()
@@ -77,8 +179,64 @@ pub fn run() {
                 )?;
             }
 
+            let safe_mode = store_recovery::is_safe_mode();
+
+            // Settings, watched library folders and global hotkeys (boss key / show-hide)
+            {
+                use tauri::Manager;
+                let state = app.state::<AppState>();
+                if let Err(e) = apply_runtime_config(&app_config, &state, app.handle()) {
+                    log::warn!("Failed to apply settings: {}", e);
+                }
+            }
+
+            // Keep the cover/thumbnail/OCR caches within their size limits
+            std::thread::spawn(disk_cache::prune_all);
+            // Remove solid-archive extractions left behind by a crash
+            std::thread::spawn(archive_extract::sweep_stale);
+
+            // Write modified buffers to ~/.simple-reader/recovery/ periodically
+            autosave::spawn_autosave(app.handle().clone());
+
+            // Pause reading timers while the system is idle/locked
+            reading_stats::spawn_idle_sampler(app.handle().clone());
+
+            // Deep links: simplereader://open?path=...&line=...
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                #[cfg(any(target_os = "windows", target_os = "linux"))]
+                if !safe_mode {
+                    if let Err(e) = app.deep_link().register_all() {
+                        log::warn!("Failed to register deep link scheme: {}", e);
+                    }
+                }
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    deep_link::handle_urls(&handle, event.urls());
+                });
+                if let Ok(Some(urls)) = app.deep_link().get_current() {
+                    let handle = app.handle().clone();
+                    // Emit after a short delay to ensure frontend is ready
+                    std::thread::spawn(move || {
+                        std::thread::sleep(std::time::Duration::from_millis(500));
+                        deep_link::handle_urls(&handle, urls);
+                    });
+                }
+            }
+
+            // Report safe mode / recovered data files once the frontend is ready
+            let report = store_recovery::startup_report();
+            if report.safe_mode || !report.issues.is_empty() {
+                use tauri::Emitter;
+                let handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(std::time::Duration::from_millis(500));
+                    let _ = handle.emit("startup-issues", report);
+                });
+            }
+
             // Check if a file path was passed as CLI argument (file association)
-            let args: Vec<String> = std::env::args().collect();
+            let args: Vec<String> = std::env::args().filter(|a| a != "--safe-mode").collect();
             if args.len() > 1 {
                 let file_path = args[1].clone();
                 let path = std::path::Path::new(&file_path);
@@ -99,26 +257,47 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // File commands
             commands::open_file,
+            commands::open_file_full,
+            commands::quick_open,
             commands::close_file,
             commands::save_file,
+            commands::save_file_as,
+            commands::reopen_with_encoding,
             commands::get_text_chunk,
+            commands::get_text_range,
+            commands::get_hex_chunk,
+            commands::get_page,
+            commands::exit_page_mode,
             commands::get_open_tabs,
             commands::switch_tab,
             commands::get_total_lines,
             commands::get_full_text,
+            commands::get_text_chapters,
+            // Follow mode commands
+            commands::start_follow,
+            commands::stop_follow,
             // Edit commands
             commands::insert_text,
             commands::replace_line,
             commands::delete_text,
+            commands::insert_at,
+            commands::delete_range,
             commands::undo,
             commands::redo,
+            commands::begin_edit_group,
+            commands::end_edit_group,
             // Bookmark commands
             commands::add_bookmark,
             commands::remove_bookmark,
             commands::get_bookmarks,
             commands::get_all_bookmarks,
+            commands::add_epub_highlight,
+            commands::remove_epub_highlight,
+            commands::get_epub_highlights,
             commands::search_bookmarks,
             commands::save_last_position,
+            commands::save_pdf_view,
+            commands::save_reading_direction,
             commands::track_file_open,
             commands::get_file_list,
             commands::remove_file_entry,
@@ -128,13 +307,27 @@ pub fn run() {
             commands::update_bookmark,
             commands::save_format_type,
             commands::get_format_type,
+            commands::import_bookmarks,
             // Search commands
             commands::search_text,
+            commands::start_search,
+            commands::cancel_search,
+            commands::find_next,
+            commands::find_prev,
             commands::replace_text,
             commands::replace_all_text,
+            commands::preview_replace_all,
             // Format commands
             commands::preview_format,
             commands::apply_format,
+            commands::normalize_line_endings,
+            // Export commands
+            commands::export_document,
+            commands::export_epub_text,
+            commands::export_highlights_to_anki,
+            commands::send_to_device,
+            commands::split_text_by_chapters,
+            commands::convert_text_to_epub,
             // Config commands
             commands::get_config,
             commands::save_config,
@@ -148,15 +341,144 @@ pub fn run() {
             commands::get_epub_chapters,
             commands::get_epub_chapter,
             commands::get_epub_font_styles,
+            commands::get_epub_metadata,
+            commands::search_epub,
+            commands::resolve_epub_link,
+            commands::get_epub_progress,
+            commands::get_epub_book_style,
+            commands::set_epub_book_style,
+            commands::open_epub_chapter_editor,
             // PDF commands
             commands::read_pdf_bytes,
+            commands::get_pdf_size,
+            commands::read_pdf_range,
+            commands::get_pdf_stream_url,
+            commands::get_pdf_page_count,
+            commands::render_pdf_page,
+            commands::get_pdf_outline,
+            commands::get_pdf_metadata,
+            commands::get_pdf_page_text,
+            commands::search_pdf,
+            commands::get_pdf_thumbnails,
             // Image commands
             commands::get_image_list,
             commands::get_image_bytes,
+            commands::get_image_info,
+            commands::get_spread_plan,
+            commands::set_crop_margins,
+            commands::get_crop_margins,
+            commands::set_image_rotation,
+            commands::get_image_rotations,
+            commands::get_image_thumbnails,
+            commands::get_strip_layout,
+            commands::get_image_tile,
             commands::get_adjacent_zips,
+            commands::get_series_info,
+            commands::get_archive_preview,
+            // Slideshow commands
+            commands::start_slideshow,
+            commands::pause_slideshow,
+            commands::resume_slideshow,
+            commands::stop_slideshow,
+            // Recovery commands
+            commands::recover_unsaved_files,
+            commands::restore_recovered_file,
+            commands::discard_recovered_file,
+            // Archive password commands
+            commands::test_archive_password,
+            commands::save_archive_password,
+            commands::clear_archive_password,
+            commands::has_archive_password,
+            // Archive tools
+            commands::archive_folder_to_cbz,
+            commands::extract_archive,
+            // Clipboard commands
+            commands::copy_text_range,
+            commands::copy_epub_chapter_text,
+            commands::copy_image_to_clipboard,
+            // Library commands
+            commands::get_watched_folders,
+            commands::add_watched_folder,
+            commands::remove_watched_folder,
+            commands::find_duplicate_files,
+            commands::get_cover_thumbnail,
+            // OCR commands
+            commands::ocr_image,
+            commands::ocr_image_batch,
+            commands::cancel_ocr_batch,
+            commands::ocr_pdf_page,
+            // Memory commands
+            commands::trim_memory,
+            // Stats commands
+            commands::get_stats_summary,
+            commands::get_idle_seconds,
+            // Vocabulary commands
+            commands::analyze_vocabulary,
+            commands::get_text_stats,
+            // Goal commands
+            commands::set_reading_goal,
+            commands::clear_reading_goal,
+            commands::get_goal_status,
+            // Translation commands
+            commands::translate_text,
+            // Backup commands
+            commands::backup_app_data,
+            commands::restore_app_data,
             // App lifecycle
             commands::exit_app,
+            commands::get_startup_issues,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, _event| {
+            if let tauri::RunEvent::Exit = _event {
+                use tauri::Manager;
+                if let Ok(mut stats) = _app.state::<AppState>().reading_stats.lock() {
+                    stats.flush();
+                }
+                if let Ok(mut store) = _app.state::<AppState>().bookmark_store.lock() {
+                    if let Err(e) = store.flush_progress() {
+                        log::warn!("Failed to save reading progress: {}", e);
+                    }
+                }
+            }
+
+            // macOS delivers file associations as an Opened event instead of CLI args
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = _event {
+                use tauri::Emitter;
+                for url in urls {
+                    if url.scheme() != "file" {
+                        continue; // simplereader:// links are handled by the deep-link plugin
+                    }
+                    if let Ok(path) = url.to_file_path() {
+                        focus_main_window(_app);
+                        let _ = _app.emit("open-file-from-args", path.to_string_lossy().to_string());
+                    }
+                }
+            }
+        });
+}
+
+/// Bring the main window to front (used for second-instance launches and deep links).
+pub(crate) fn focus_main_window<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    use tauri::Manager;
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        // Windows: SetForegroundWindow로 확실하게 전면 표시
+        #[cfg(target_os = "windows")]
+        {
+            use windows_sys::Win32::UI::WindowsAndMessaging::{
+                SetForegroundWindow, ShowWindow, SW_RESTORE,
+            };
+            if let Ok(hwnd) = window.hwnd() {
+                unsafe {
+                    ShowWindow(hwnd.0 as _, SW_RESTORE);
+                    SetForegroundWindow(hwnd.0 as _);
+                }
+            }
+        }
+    }
 }
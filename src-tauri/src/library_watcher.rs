@@ -0,0 +1,149 @@
+//! Watched library folders.
+//!
+//! 등록한 폴더를 notify로 감시해서 새로 생긴 지원 파일을 파일 목록(bookmark store)에
+//! 자동으로 추가하고 `library-updated` 이벤트를 보낸다. 연재물의 새 화가 폴더에
+//! 들어오면 따로 열지 않아도 목록에 나타난다.
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::AppState;
+
+/// Events arriving within this window are reported together.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+const LIBRARY_EXTENSIONS: &[&str] = &["txt", "md", "log", "epub", "pdf", "zip", "cbz", "rar", "cbr", "7z", "cb7"];
+
+#[derive(Clone, Serialize)]
+pub struct LibraryUpdate {
+    pub added: Vec<String>,
+}
+
+pub struct LibraryWatcher {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+pub fn is_library_file(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| LIBRARY_EXTENSIONS.contains(&ext.as_str()))
+}
+
+impl LibraryWatcher {
+    pub fn new() -> Self {
+        Self {
+            watcher: Mutex::new(None),
+        }
+    }
+
+    /// Replace the watched folder set. Passing an empty list stops watching.
+    pub fn watch<R: Runtime>(&self, app: &AppHandle<R>, folders: &[String]) -> anyhow::Result<()> {
+        let mut slot = self
+            .watcher
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Watcher lock poisoned: {}", e))?;
+        // 기존 watcher를 drop하면 이벤트 채널이 닫혀 처리 스레드도 종료된다
+        *slot = None;
+        if folders.is_empty() {
+            return Ok(());
+        }
+
+        let (tx, rx) = mpsc::channel::<PathBuf>();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+        })?;
+
+        for folder in folders {
+            if let Err(e) = watcher.watch(Path::new(folder), RecursiveMode::Recursive) {
+                log::warn!("Failed to watch {}: {}", folder, e);
+            }
+        }
+
+        let handle = app.clone();
+        std::thread::spawn(move || process_events(&handle, rx));
+
+        *slot = Some(watcher);
+        Ok(())
+    }
+}
+
+/// Collect events in debounced batches until the watcher is dropped.
+fn process_events<R: Runtime>(app: &AppHandle<R>, rx: mpsc::Receiver<PathBuf>) {
+    while let Ok(first) = rx.recv() {
+        let mut batch = vec![first];
+        while let Ok(path) = rx.recv_timeout(DEBOUNCE) {
+            batch.push(path);
+        }
+        batch.sort();
+        batch.dedup();
+        let files: Vec<PathBuf> = batch
+            .into_iter()
+            .filter(|p| p.is_file() && is_library_file(p))
+            .collect();
+        add_to_library(app, &files);
+    }
+}
+
+/// Add files missing from the file list and emit `library-updated` for the new ones.
+pub fn add_to_library<R: Runtime>(app: &AppHandle<R>, files: &[PathBuf]) -> Vec<String> {
+    let state = app.state::<AppState>();
+    let added: Vec<String> = {
+        let Ok(mut store) = state.bookmark_store.lock() else {
+            return Vec::new();
+        };
+        let added: Vec<String> = files
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .filter(|p| store.add_file_entry(p))
+            .collect();
+        if !added.is_empty() {
+            if let Err(e) = store.save_to_disk() {
+                log::warn!("Failed to save file list: {}", e);
+            }
+        }
+        added
+    };
+
+    if !added.is_empty() {
+        let _ = app.emit(
+            "library-updated",
+            LibraryUpdate {
+                added: added.clone(),
+            },
+        );
+    }
+    added
+}
+
+/// Recursively list supported files under a folder (used when a folder is first registered).
+pub fn scan_library_folder(folder: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![folder.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if is_library_file(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
@@ -0,0 +1,101 @@
+//! Global memory budget shared by the image LRU, EPUB chapter caches and text buffers.
+//!
+//! Each subsystem keeps its own storage; this module only measures them together
+//! and decides what to evict once the combined size exceeds the configured cap.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::Serialize;
+
+use crate::image_cache::ImageCacheManager;
+use crate::tab_manager::TabManager;
+
+const MB: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryUsage {
+    pub image_cache_bytes: usize,
+    pub epub_cache_bytes: usize,
+    pub text_buffer_bytes: usize,
+    /// Undo/redo history of all text buffers.
+    pub undo_history_bytes: usize,
+    pub total_bytes: usize,
+    pub budget_bytes: usize,
+}
+
+pub struct MemoryBudget {
+    budget_bytes: AtomicUsize,
+}
+
+impl MemoryBudget {
+    pub fn new(budget_mb: usize) -> Self {
+        Self {
+            budget_bytes: AtomicUsize::new(budget_mb * MB),
+        }
+    }
+
+    /// Update the cap (e.g. after the user changes it in settings).
+    pub fn set_budget_mb(&self, budget_mb: usize) {
+        self.budget_bytes.store(budget_mb * MB, Ordering::Relaxed);
+    }
+
+    pub fn budget_bytes(&self) -> usize {
+        self.budget_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Measure current usage across all subsystems.
+    pub fn usage(&self, tab_manager: &TabManager, image_cache: &ImageCacheManager) -> MemoryUsage {
+        let (text_buffer_bytes, undo_history_bytes, epub_cache_bytes) = tab_manager.memory_usage();
+        let image_cache_bytes = image_cache.memory_usage();
+        MemoryUsage {
+            image_cache_bytes,
+            epub_cache_bytes,
+            text_buffer_bytes,
+            undo_history_bytes,
+            total_bytes: image_cache_bytes + epub_cache_bytes + text_buffer_bytes + undo_history_bytes,
+            budget_bytes: self.budget_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Evict across subsystems until total usage fits within the budget.
+    /// Cheapest-to-rebuild data goes first: cached images, then inactive tabs,
+    /// then the active tab's EPUB chapter cache.
+    pub fn enforce(
+        &self,
+        tab_manager: &mut TabManager,
+        image_cache: &ImageCacheManager,
+    ) -> MemoryUsage {
+        let usage = self.usage(tab_manager, image_cache);
+        if usage.total_bytes <= usage.budget_bytes {
+            return usage;
+        }
+
+        // 1. 이미지 LRU는 다른 서브시스템이 쓰고 남은 만큼만 유지
+        let others = usage.epub_cache_bytes + usage.text_buffer_bytes + usage.undo_history_bytes;
+        image_cache.shrink_to(usage.budget_bytes.saturating_sub(others));
+        let usage = self.usage(tab_manager, image_cache);
+        if usage.total_bytes <= usage.budget_bytes {
+            return usage;
+        }
+
+        // 2. 비활성 탭의 텍스트 버퍼 / 챕터 캐시 해제
+        tab_manager.release_inactive();
+        let usage = self.usage(tab_manager, image_cache);
+        if usage.total_bytes <= usage.budget_bytes {
+            return usage;
+        }
+
+        // 3. 활성 탭의 챕터 캐시까지 비움
+        tab_manager.clear_epub_caches();
+        self.usage(tab_manager, image_cache)
+    }
+
+    /// Release everything that can be rebuilt on demand, regardless of the budget.
+    /// Used by the `trim_memory` command on low-RAM systems.
+    pub fn trim(&self, tab_manager: &mut TabManager, image_cache: &ImageCacheManager) -> MemoryUsage {
+        image_cache.shrink_to(0);
+        tab_manager.release_inactive();
+        tab_manager.clear_epub_caches();
+        self.usage(tab_manager, image_cache)
+    }
+}
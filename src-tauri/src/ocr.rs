@@ -0,0 +1,200 @@
+//! OCR for scanned pages via the tesseract CLI (sidecar process).
+//!
+//! tesseract의 TSV 출력에서 단어 단위 좌표를 읽어, 프론트엔드가 이미지 위에 선택 가능한
+//! 텍스트 레이어를 그릴 수 있게 한다. 결과는 (파일, 페이지, 언어, mtime) 단위로
+//! `disk_cache::OCR`에 캐시한다.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+use crate::disk_cache;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrWord {
+    pub text: String,
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrPage {
+    pub text: String,
+    pub words: Vec<OcrWord>,
+}
+
+/// Locate the tesseract executable: configured path, then PATH, then the default Windows install.
+fn find_tesseract(configured: &str) -> anyhow::Result<PathBuf> {
+    crate::external_tool::find_tool(configured, "Tesseract", &["tesseract"], &[r"Tesseract-OCR\tesseract.exe"])?
+        .ok_or_else(|| anyhow::anyhow!("Tesseract OCR is not installed (set its path in settings)"))
+}
+
+/// Cache key for one page of a source file.
+pub fn cache_key(source: &Path, page: usize, lang: &str) -> String {
+    disk_cache::source_key(source, &[&page.to_le_bytes(), lang.as_bytes()])
+}
+
+pub fn load_cached(key: &str) -> Option<OcrPage> {
+    let content = disk_cache::OCR.read(&format!("{}.json", key))?;
+    serde_json::from_slice(&content).ok()
+}
+
+fn store_cached(key: &str, page: &OcrPage) -> anyhow::Result<()> {
+    disk_cache::OCR.write(&format!("{}.json", key), &serde_json::to_vec(page)?)
+}
+
+/// OCR encoded image bytes (any format tesseract/leptonica reads), using the cache.
+pub fn ocr_bytes(image: &[u8], lang: &str, key: &str, tesseract_path: &str) -> anyhow::Result<OcrPage> {
+    if let Some(page) = load_cached(key) {
+        return Ok(page);
+    }
+
+    let tesseract = find_tesseract(tesseract_path)?;
+    let input = std::env::temp_dir().join(format!("simple-reader-ocr-{}.img", key));
+    std::fs::write(&input, image)?;
+    let output = std::process::Command::new(&tesseract)
+        .arg(&input)
+        .arg("stdout")
+        .arg("-l")
+        .arg(lang)
+        .arg("tsv")
+        .output();
+    let _ = std::fs::remove_file(&input);
+
+    let output = output.map_err(|e| anyhow::anyhow!("Failed to run tesseract: {}", e))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Tesseract failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let page = parse_tsv(&String::from_utf8_lossy(&output.stdout));
+    if let Err(e) = store_cached(key, &page) {
+        log::warn!("Failed to cache OCR result: {}", e);
+    }
+    Ok(page)
+}
+
+/// Parse tesseract TSV output. Words (level 5) are kept with their boxes; text is rebuilt
+/// line by line, with a blank line between paragraphs.
+fn parse_tsv(tsv: &str) -> OcrPage {
+    let mut words = Vec::new();
+    let mut text = String::new();
+    let mut current_line: Option<(u32, u32, u32)> = None;
+    let mut current_par: Option<(u32, u32)> = None;
+
+    for row in tsv.lines().skip(1) {
+        let cols: Vec<&str> = row.split('\t').collect();
+        if cols.len() < 12 || cols[0] != "5" {
+            continue;
+        }
+        let word = cols[11].trim();
+        if word.is_empty() {
+            continue;
+        }
+        let num = |i: usize| cols[i].parse::<u32>().unwrap_or(0);
+        let par = (num(2), num(3));
+        let line = (num(2), num(3), num(4));
+
+        if current_line != Some(line) {
+            if current_line.is_some() {
+                text.push('\n');
+                if current_par != Some(par) {
+                    text.push('\n');
+                }
+            }
+            current_line = Some(line);
+            current_par = Some(par);
+        } else {
+            text.push(' ');
+        }
+        text.push_str(word);
+
+        words.push(OcrWord {
+            text: word.to_string(),
+            left: num(6),
+            top: num(7),
+            width: num(8),
+            height: num(9),
+            confidence: cols[10].parse().unwrap_or(0.0),
+        });
+    }
+
+    OcrPage { text, words }
+}
+
+type OcrJob = Box<dyn FnOnce() + Send>;
+
+/// Background OCR batches, run one after another on a single worker thread.
+/// Each batch has a cancellation flag, by file id.
+pub struct OcrBatches {
+    worker: Mutex<Option<mpsc::Sender<OcrJob>>>,
+    jobs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl OcrBatches {
+    pub fn new() -> Self {
+        Self {
+            worker: Mutex::new(None),
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Queue a batch for `file_id`; a pending or running batch of the same file is cancelled.
+    /// The batch should stop once its flag is set and call `finish` when done.
+    pub fn submit(&self, file_id: &str, batch: impl FnOnce(Arc<AtomicBool>) + Send + 'static) -> anyhow::Result<()> {
+        let flag = Arc::new(AtomicBool::new(false));
+        if let Ok(mut jobs) = self.jobs.lock() {
+            if let Some(old) = jobs.insert(file_id.to_string(), flag.clone()) {
+                old.store(true, Ordering::Relaxed);
+            }
+        }
+        let job: OcrJob = {
+            let flag = flag.clone();
+            Box::new(move || batch(flag))
+        };
+        let mut worker = self.worker.lock().map_err(|e| anyhow::anyhow!("{}", e))?;
+        if let Some(job) = match worker.as_ref() {
+            Some(sender) => sender.send(job).err().map(|e| e.0),
+            None => Some(job),
+        } {
+            // 워커가 없거나 끝났으면 새로 띄운다
+            let (sender, receiver) = mpsc::channel::<OcrJob>();
+            std::thread::Builder::new()
+                .name("ocr-batch".into())
+                .spawn(move || {
+                    for job in receiver {
+                        job();
+                    }
+                })?;
+            sender.send(job).map_err(|_| anyhow::anyhow!("OCR worker stopped"))?;
+            *worker = Some(sender);
+        }
+        Ok(())
+    }
+
+    pub fn cancel(&self, file_id: &str) -> bool {
+        match self.jobs.lock().ok().and_then(|jobs| jobs.get(file_id).cloned()) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop a finished batch (unless it was replaced by a newer one for the same file).
+    pub fn finish(&self, file_id: &str, flag: &Arc<AtomicBool>) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            if jobs.get(file_id).is_some_and(|f| Arc::ptr_eq(f, flag)) {
+                jobs.remove(file_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Char-offset page map for the "book page" reading mode.
+//!
+//! 페이지는 대략 `chars_per_page` 글자씩 나누되, 끝부분에서 줄바꿈이나 공백을 찾아
+//! 그 뒤에서 끊어 단어가 두 페이지에 걸치지 않게 한다. 띄어쓰기가 없는 긴 구간
+//! (중국어·일본어 등)은 글자 수 그대로 끊는다.
+
+use ropey::Rope;
+use serde::Serialize;
+
+/// Smallest page size accepted (keeps the map from exploding).
+pub const MIN_CHARS_PER_PAGE: usize = 100;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Page {
+    pub index: usize,
+    pub text: String,
+    pub start_char: usize,
+    pub end_char: usize,
+    /// Line of `start_char` (for syncing with the line-based reading position).
+    pub start_line: usize,
+    pub total_pages: usize,
+}
+
+pub struct PageMap {
+    pub chars_per_page: usize,
+    /// Tab revision the map was built for.
+    pub revision: u64,
+    /// Char offset where each page starts.
+    starts: Vec<usize>,
+    total_chars: usize,
+}
+
+impl PageMap {
+    pub fn build(rope: &Rope, chars_per_page: usize, revision: u64) -> Self {
+        let chars_per_page = chars_per_page.max(MIN_CHARS_PER_PAGE);
+        let total_chars = rope.len_chars();
+        // 페이지 끝 1/5 안에서만 끊을 곳을 찾는다
+        let look_back = chars_per_page / 5;
+
+        let mut starts = vec![0];
+        let mut pos = 0;
+        while total_chars - pos > chars_per_page {
+            let limit = pos + chars_per_page;
+            let mut line_break = None;
+            let mut space = None;
+            let mut chars = rope.chars_at(limit);
+            for back in 0..look_back {
+                let Some(c) = chars.prev() else { break };
+                if c == '\n' {
+                    line_break = Some(limit - back);
+                    break;
+                }
+                if space.is_none() && c.is_whitespace() && c != '\r' {
+                    space = Some(limit - back);
+                }
+            }
+            pos = line_break.or(space).unwrap_or(limit);
+            starts.push(pos);
+        }
+
+        Self {
+            chars_per_page,
+            revision,
+            starts,
+            total_chars,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Char range of a page.
+    pub fn range(&self, index: usize) -> Option<(usize, usize)> {
+        let start = *self.starts.get(index)?;
+        let end = self.starts.get(index + 1).copied().unwrap_or(self.total_chars);
+        Some((start, end))
+    }
+}
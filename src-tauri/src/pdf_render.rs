@@ -0,0 +1,366 @@
+//! Native PDF page rendering via the MuPDF `mutool` CLI (sidecar process).
+//!
+//! 스캔본처럼 큰 PDF를 통째로 webview(pdf.js)에 넘기지 않고, 필요한 페이지만 PNG로
+//! 렌더링해서 보낸다. `mutool`은 설정의 경로, PATH, 기본 설치 경로 순서로 찾는다.
+//! 목차/메타데이터/텍스트처럼 구조화된 정보는 `mutool run`으로 MuPDF JavaScript를 실행해 JSON으로 받는다.
+//! 페이지 썸네일은 (파일, 크기, 수정 시각, 페이지) 단위로 `disk_cache::PDF_THUMBS`에 JPEG로 캐시한다.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::disk_cache;
+
+/// PDF user space is 72 units per inch, so scale 1.0 renders at 72 DPI.
+const POINTS_PER_INCH: f64 = 72.0;
+
+/// Allowed render scale range (8.0 = 576 DPI).
+const MIN_SCALE: f64 = 0.1;
+const MAX_SCALE: f64 = 8.0;
+
+/// Locate the mutool executable: configured path, then PATH, then the default Windows install.
+fn find_mutool(configured: &str) -> anyhow::Result<PathBuf> {
+    crate::external_tool::find_tool(configured, "mutool", &["mutool"], &[r"MuPDF\mutool.exe"])?
+        .ok_or_else(|| anyhow::anyhow!("MuPDF (mutool) is not installed (set its path in settings)"))
+}
+
+fn run(mutool: &Path, args: &[&std::ffi::OsStr]) -> anyhow::Result<Vec<u8>> {
+    let output = Command::new(mutool)
+        .args(args)
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run mutool: {}", e))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "mutool failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// Run a MuPDF JavaScript program with `args` (scriptArgs) and parse the JSON it prints.
+fn run_script<T: serde::de::DeserializeOwned>(
+    mutool: &Path,
+    name: &str,
+    script: &str,
+    args: &[&std::ffi::OsStr],
+) -> anyhow::Result<T> {
+    // 동시에 여러 스크립트가 돌아도 이름이 겹치지 않는다 (drop될 때 지워진다)
+    let mut script_file = tempfile::Builder::new()
+        .prefix(&format!("simple-reader-pdf-{}-", name))
+        .suffix(".js")
+        .tempfile()?;
+    script_file.write_all(script.as_bytes())?;
+    script_file.flush()?;
+    let mut full_args: Vec<&std::ffi::OsStr> = vec!["run".as_ref(), script_file.path().as_os_str()];
+    full_args.extend_from_slice(args);
+    let stdout = run(mutool, &full_args)?;
+    serde_json::from_slice(&stdout)
+        .map_err(|e| anyhow::anyhow!("Unexpected mutool output: {}", e))
+}
+
+/// One outline (bookmark) entry, flattened in document order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfOutlineItem {
+    pub title: String,
+    /// 0-based target page; None for external links or unresolvable targets.
+    pub page: Option<usize>,
+    /// Nesting level (0 = top level).
+    pub depth: usize,
+}
+
+const OUTLINE_SCRIPT: &str = r#"
+var doc = Document.openDocument(scriptArgs[0]);
+var out = [];
+function walk(items, depth) {
+    for (var i = 0; i < items.length; ++i) {
+        var item = items[i];
+        var page = null;
+        try {
+            if (item.uri) {
+                var target = doc.resolveLink(item.uri);
+                if (typeof target === "number" && target >= 0) page = target;
+            }
+        } catch (e) {}
+        out.push({ title: item.title || "", page: page, depth: depth });
+        if (item.down) walk(item.down, depth + 1);
+    }
+}
+var outline = doc.loadOutline();
+if (outline) walk(outline, 0);
+print(JSON.stringify(out));
+"#;
+
+/// The bookmark tree, flattened with depths (empty if the PDF has no outline).
+pub fn outline(pdf: &Path, mutool_path: &str) -> anyhow::Result<Vec<PdfOutlineItem>> {
+    let mutool = find_mutool(mutool_path)?;
+    run_script(&mutool, "outline", OUTLINE_SCRIPT, &[pdf.as_os_str()])
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub page_count: usize,
+    /// Size of the first page in points (1/72 inch).
+    pub page_width: f64,
+    pub page_height: f64,
+}
+
+const METADATA_SCRIPT: &str = r#"
+var doc = Document.openDocument(scriptArgs[0]);
+function info(key) {
+    var value = doc.getMetaData("info:" + key);
+    return value ? value : null;
+}
+var count = doc.countPages();
+var width = 0, height = 0;
+if (count > 0) {
+    var bounds = doc.loadPage(0).getBounds();
+    width = bounds[2] - bounds[0];
+    height = bounds[3] - bounds[1];
+}
+print(JSON.stringify({
+    title: info("Title"),
+    author: info("Author"),
+    subject: info("Subject"),
+    creator: info("Creator"),
+    producer: info("Producer"),
+    page_count: count,
+    page_width: width,
+    page_height: height
+}));
+"#;
+
+/// Document info (title, author, ...) plus page count and first-page size.
+pub fn metadata(pdf: &Path, mutool_path: &str) -> anyhow::Result<PdfMetadata> {
+    let mutool = find_mutool(mutool_path)?;
+    run_script(&mutool, "metadata", METADATA_SCRIPT, &[pdf.as_os_str()])
+}
+
+/// Maximum hits returned by `search`.
+pub const MAX_PDF_MATCHES: usize = 2000;
+
+/// Rectangle in page coordinates (points, origin at the top-left of the page).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// One search hit. A hit that wraps across lines has one rect per line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfSearchHit {
+    /// 0-based page.
+    pub page: usize,
+    pub rects: Vec<PdfRect>,
+}
+
+const PAGE_TEXT_SCRIPT: &str = r#"
+var doc = Document.openDocument(scriptArgs[0]);
+var page = parseInt(scriptArgs[1], 10);
+if (page < 0 || page >= doc.countPages()) throw new Error("Page " + page + " not found");
+print(JSON.stringify(doc.loadPage(page).toStructuredText("preserve-whitespace").asText()));
+"#;
+
+/// Text of page `page` (0-based), in reading order as MuPDF extracts it.
+pub fn page_text(pdf: &Path, page: usize, mutool_path: &str) -> anyhow::Result<String> {
+    let mutool = find_mutool(mutool_path)?;
+    let page_arg = page.to_string();
+    run_script(&mutool, "text", PAGE_TEXT_SCRIPT, &[pdf.as_os_str(), page_arg.as_ref()])
+}
+
+// MuPDF 버전에 따라 search()가 [x0,y0,x1,y1] 사각형, 쿼드 8개 값, 또는 히트별 쿼드 배열을 돌려준다
+const SEARCH_SCRIPT: &str = r#"
+var doc = Document.openDocument(scriptArgs[0]);
+var needle = scriptArgs[1];
+var limit = parseInt(scriptArgs[2], 10);
+function toRect(q) {
+    var xs = [], ys = [];
+    for (var i = 0; i + 1 < q.length; i += 2) { xs.push(q[i]); ys.push(q[i + 1]); }
+    var x0 = Math.min.apply(null, xs), y0 = Math.min.apply(null, ys);
+    return { x: x0, y: y0, width: Math.max.apply(null, xs) - x0, height: Math.max.apply(null, ys) - y0 };
+}
+var out = [];
+var count = doc.countPages();
+for (var p = 0; p < count && out.length < limit; ++p) {
+    var hits = doc.loadPage(p).search(needle, limit - out.length);
+    for (var i = 0; i < hits.length && out.length < limit; ++i) {
+        var hit = hits[i];
+        var quads = typeof hit[0] === "number" ? [hit] : hit;
+        var rects = [];
+        for (var j = 0; j < quads.length; ++j) rects.push(toRect(quads[j]));
+        out.push({ page: p, rects: rects });
+    }
+}
+print(JSON.stringify(out));
+"#;
+
+/// Find `query` (case-insensitive, as MuPDF searches) on every page, in page order.
+pub fn search(pdf: &Path, query: &str, mutool_path: &str) -> anyhow::Result<Vec<PdfSearchHit>> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mutool = find_mutool(mutool_path)?;
+    let limit = MAX_PDF_MATCHES.to_string();
+    run_script(
+        &mutool,
+        "search",
+        SEARCH_SCRIPT,
+        &[pdf.as_os_str(), query.as_ref(), limit.as_ref()],
+    )
+}
+
+/// Number of pages in the PDF.
+pub fn page_count(pdf: &Path, mutool_path: &str) -> anyhow::Result<usize> {
+    let mutool = find_mutool(mutool_path)?;
+    let stdout = run(
+        &mutool,
+        &["show".as_ref(), pdf.as_os_str(), "trailer/Root/Pages/Count".as_ref()],
+    )?;
+    let text = String::from_utf8_lossy(&stdout);
+    text.split(|c: char| !c.is_ascii_digit())
+        .find(|s| !s.is_empty())
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("Could not read page count: {}", text.trim()))
+}
+
+/// Render page `page` (0-based) to PNG at `scale` (1.0 = 72 DPI).
+pub fn render_page(pdf: &Path, page: usize, scale: f64, mutool_path: &str) -> anyhow::Result<Vec<u8>> {
+    if !scale.is_finite() || scale <= 0.0 {
+        anyhow::bail!("Invalid render scale: {}", scale);
+    }
+    let dpi = (POINTS_PER_INCH * scale.clamp(MIN_SCALE, MAX_SCALE)).round() as u32;
+    let mutool = find_mutool(mutool_path)?;
+
+    let out_dir = tempfile::Builder::new().prefix("simple-reader-pdf-").tempdir()?;
+    let output = out_dir.path().join(format!("page-{}.png", page + 1));
+    let resolution = dpi.to_string();
+    let page_arg = (page + 1).to_string();
+    run(
+        &mutool,
+        &[
+            "draw".as_ref(),
+            "-F".as_ref(),
+            "png".as_ref(),
+            "-r".as_ref(),
+            resolution.as_ref(),
+            "-o".as_ref(),
+            output.as_os_str(),
+            pdf.as_os_str(),
+            page_arg.as_ref(),
+        ],
+    )
+    .and_then(|_| {
+        // 범위를 벗어난 페이지는 mutool이 경고만 하고 파일을 만들지 않는다
+        std::fs::read(&output).map_err(|_| anyhow::anyhow!("Page {} not found", page))
+    })
+}
+
+/// Most thumbnails rendered by one `thumbnails` call.
+pub const MAX_THUMBNAILS_PER_CALL: usize = 50;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PdfThumbnail {
+    /// 0-based page.
+    pub page: usize,
+    /// JPEG data URI.
+    pub data: String,
+}
+
+fn thumb_cache_name(pdf: &Path, page: usize, max_size: u32) -> String {
+    let key = disk_cache::source_key(pdf, &[&page.to_le_bytes(), &max_size.to_le_bytes()]);
+    format!("{}.jpg", key)
+}
+
+/// Low-resolution previews of pages `start..start + count` (at most
+/// `MAX_THUMBNAILS_PER_CALL`), each fitting in `max_size` × `max_size`.
+/// Pages past the end are left out.
+pub fn thumbnails(
+    pdf: &Path,
+    start: usize,
+    count: usize,
+    max_size: u32,
+    mutool_path: &str,
+) -> anyhow::Result<Vec<PdfThumbnail>> {
+    let pages = start..start.saturating_add(count.min(MAX_THUMBNAILS_PER_CALL));
+    let cache_names: Vec<(usize, String)> = pages
+        .map(|page| (page, thumb_cache_name(pdf, page, max_size)))
+        .collect();
+
+    let missing: Vec<usize> = cache_names
+        .iter()
+        .filter(|(_, name)| !disk_cache::PDF_THUMBS.contains(name))
+        .map(|(page, _)| *page)
+        .collect();
+    if let (Some(&first), Some(&last)) = (missing.first(), missing.last()) {
+        let last = last.min(page_count(pdf, mutool_path)?.saturating_sub(1));
+        if first <= last {
+            render_thumbnails(pdf, first, last, max_size, mutool_path)?;
+        }
+    }
+
+    Ok(cache_names
+        .into_iter()
+        .filter_map(|(page, name)| {
+            let bytes = disk_cache::PDF_THUMBS.read(&name)?;
+            Some(PdfThumbnail {
+                page,
+                data: format!(
+                    "data:image/jpeg;base64,{}",
+                    base64::engine::general_purpose::STANDARD.encode(bytes)
+                ),
+            })
+        })
+        .collect())
+}
+
+/// Render pages `first..=last` in one mutool run and store them in the cache as JPEG.
+fn render_thumbnails(
+    pdf: &Path,
+    first: usize,
+    last: usize,
+    max_size: u32,
+    mutool_path: &str,
+) -> anyhow::Result<()> {
+    let mutool = find_mutool(mutool_path)?;
+    // 중간에 실패해도 남은 PNG는 폴더와 함께 지워진다
+    let out_dir = tempfile::Builder::new().prefix("simple-reader-pdf-thumb-").tempdir()?;
+    // %d는 mutool이 1부터 세는 페이지 번호로 바꾼다
+    let pattern = out_dir.path().join("page-%d.png");
+    let size = max_size.to_string();
+    let range = format!("{}-{}", first + 1, last + 1);
+    let result = run(
+        &mutool,
+        &[
+            "draw".as_ref(),
+            "-F".as_ref(),
+            "png".as_ref(),
+            "-w".as_ref(),
+            size.as_ref(),
+            "-h".as_ref(),
+            size.as_ref(),
+            "-o".as_ref(),
+            pattern.as_os_str(),
+            pdf.as_os_str(),
+            range.as_ref(),
+        ],
+    );
+
+    for page in first..=last {
+        let png = out_dir.path().join(format!("page-{}.png", page + 1));
+        if let Ok(bytes) = std::fs::read(&png) {
+            if let Some(jpeg) = crate::image_reader::encode_thumbnail(&bytes, max_size) {
+                disk_cache::PDF_THUMBS.write(&thumb_cache_name(pdf, page, max_size), &jpeg)?;
+            }
+        }
+    }
+    result.map(|_| ())
+}
@@ -0,0 +1,144 @@
+//! Serving PDF tabs over the `pdfstream` URI scheme with HTTP Range support.
+//!
+//! pdf.js가 URL로 문서를 열면 필요한 구간만 Range 요청으로 가져가므로, 1GB가 넘는 PDF도
+//! 파일 전체를 메모리에 올리거나 IPC 한 번에 보내지 않는다. 한 번에 내려주는 양은
+//! `MAX_PDF_RANGE_BYTES`로 제한한다 (더 큰 Range는 잘라서 206으로 응답).
+
+use std::io::{Read, Seek, SeekFrom};
+
+use tauri::http::{header, Method, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::AppState;
+
+pub const SCHEME: &str = "pdfstream";
+
+/// Upper bound for a single ranged read, so a bad request can't pull the whole file.
+pub const MAX_PDF_RANGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// URL of a PDF tab's contents.
+pub fn stream_url(file_id: &str) -> String {
+    let encoded: String = url::form_urlencoded::byte_serialize(file_id.as_bytes()).collect();
+    // Windows(WebView2)는 커스텀 스킴을 http://<scheme>.localhost 로 노출한다
+    #[cfg(windows)]
+    let origin = format!("http://{}.localhost", SCHEME);
+    #[cfg(not(windows))]
+    let origin = format!("{}://localhost", SCHEME);
+    format!("{}/?id={}", origin, encoded)
+}
+
+fn error_response(status: StatusCode) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(Vec::new())
+        .unwrap_or_default()
+}
+
+/// Parse a `Range: bytes=...` header into an inclusive byte range within `len`.
+/// Only the first range of a multi-range request is used.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    if len == 0 {
+        return None;
+    }
+    let (start, end) = match (start.trim(), end.trim()) {
+        // bytes=-500: the last 500 bytes
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            if suffix == 0 {
+                return None;
+            }
+            (len.saturating_sub(suffix), len - 1)
+        }
+        (start, "") => (start.parse().ok()?, len - 1),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(len - 1)),
+    };
+    (start <= end && start < len).then_some((start, end))
+}
+
+/// Handle `pdfstream://localhost/?id=<file_id>` (GET/HEAD with optional Range).
+pub fn handle_request<R: Runtime>(app: &AppHandle<R>, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    if request.method() == Method::OPTIONS {
+        return Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .header(header::ACCESS_CONTROL_ALLOW_HEADERS, "Range")
+            .header(header::ACCESS_CONTROL_ALLOW_METHODS, "GET, HEAD")
+            .body(Vec::new())
+            .unwrap_or_default();
+    }
+
+    let Ok(url) = url::Url::parse(&request.uri().to_string()) else {
+        return error_response(StatusCode::BAD_REQUEST);
+    };
+    let Some(file_id) = url
+        .query_pairs()
+        .find(|(key, _)| key == "id")
+        .map(|(_, value)| value.to_string())
+    else {
+        return error_response(StatusCode::BAD_REQUEST);
+    };
+
+    let state = app.state::<AppState>();
+    let path = match state.tab_manager.lock() {
+        Ok(tab_manager) => tab_manager.get_pdf_source_path(&file_id),
+        Err(_) => return error_response(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    let Ok(path) = path else {
+        return error_response(StatusCode::NOT_FOUND);
+    };
+    let Ok(mut file) = std::fs::File::open(&path) else {
+        return error_response(StatusCode::NOT_FOUND);
+    };
+    let Ok(len) = file.metadata().map(|m| m.len()) else {
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR);
+    };
+
+    let range_header = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok());
+    let (status, start, end) = match range_header {
+        Some(value) => match parse_range(value, len) {
+            Some((start, end)) => (StatusCode::PARTIAL_CONTENT, start, end),
+            None => {
+                return Response::builder()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+                    .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                    .body(Vec::new())
+                    .unwrap_or_default();
+            }
+        },
+        // Range 없이 큰 파일을 요청하면 첫 구간만 206으로 보낸다 (전체 크기는 Content-Range로 알림)
+        None if len > MAX_PDF_RANGE_BYTES as u64 => (StatusCode::PARTIAL_CONTENT, 0, len - 1),
+        None => (StatusCode::OK, 0, len.saturating_sub(1)),
+    };
+    let end = end.min(start + MAX_PDF_RANGE_BYTES as u64 - 1);
+    let body_len = if len == 0 { 0 } else { end - start + 1 };
+
+    let mut builder = Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/pdf")
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CONTENT_LENGTH, body_len.to_string())
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header(
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            "Accept-Ranges, Content-Length, Content-Range",
+        );
+    if status == StatusCode::PARTIAL_CONTENT {
+        builder = builder.header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len));
+    }
+    if request.method() == Method::HEAD {
+        return builder.body(Vec::new()).unwrap_or_default();
+    }
+
+    let mut body = vec![0u8; body_len as usize];
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_exact(&mut body).is_err() {
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    builder.body(body).unwrap_or_default()
+}
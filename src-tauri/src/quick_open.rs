@@ -0,0 +1,133 @@
+//! Ctrl+P style quick-open: fuzzy matching over open tabs, favorites, recent files
+//! and bookmark memos, merged into one ranked list.
+
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::bookmark::{BookmarkStore, FileListEntry};
+use crate::tab_manager::TabInfo;
+
+const MAX_RESULTS: usize = 50;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickOpenKind {
+    Tab,
+    Favorite,
+    Recent,
+    Bookmark,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickOpenEntry {
+    pub kind: QuickOpenKind,
+    pub title: String,
+    pub subtitle: String,
+    pub file_path: String,
+    /// Bookmark position (line / page / chapter) to jump to.
+    pub position: Option<usize>,
+    pub score: i64,
+}
+
+/// Subsequence fuzzy score (case-insensitive). None if `query` isn't a subsequence of `text`.
+/// Consecutive matches and matches at word starts score higher; earlier matches beat later ones.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let text_chars: Vec<char> = text.chars().flat_map(|c| c.to_lowercase()).collect();
+    let mut score = 0i64;
+    let mut ti = 0;
+    let mut prev_match: Option<usize> = None;
+
+    for qc in query.chars().flat_map(|c| c.to_lowercase()) {
+        if qc.is_whitespace() {
+            continue;
+        }
+        let found = (ti..text_chars.len()).find(|&i| text_chars[i] == qc)?;
+        score += 10;
+        if prev_match.is_some_and(|p| p + 1 == found) {
+            score += 15;
+        }
+        let at_word_start = found == 0
+            || !text_chars[found - 1].is_alphanumeric()
+            || (text_chars[found - 1].is_ascii() != text_chars[found].is_ascii());
+        if at_word_start {
+            score += 10;
+        }
+        score -= (found - ti).min(10) as i64;
+        prev_match = Some(found);
+        ti = found + 1;
+    }
+    // 짧은 이름일수록 정확한 매치
+    Some(score - (text_chars.len() as i64 / 10))
+}
+
+fn file_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Rank everything matching `query`. Each file appears once, under its most relevant kind
+/// (open tab > favorite > recent); bookmark memos are separate entries.
+pub fn quick_open(query: &str, tabs: &[TabInfo], store: &BookmarkStore) -> Vec<QuickOpenEntry> {
+    let query = query.trim();
+    let mut results = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    let mut push_file = |kind: QuickOpenKind, boost: i64, path: &str, title: String, results: &mut Vec<QuickOpenEntry>| {
+        if !seen.insert(path.to_string()) {
+            return;
+        }
+        let score = fuzzy_score(query, &title).or_else(|| fuzzy_score(query, path).map(|s| s / 2));
+        if let Some(score) = score {
+            results.push(QuickOpenEntry {
+                kind,
+                subtitle: path.to_string(),
+                title,
+                file_path: path.to_string(),
+                position: None,
+                score: score + boost,
+            });
+        }
+    };
+
+    for tab in tabs {
+        push_file(QuickOpenKind::Tab, 30, &tab.path, tab.name.clone(), &mut results);
+    }
+    let file_list: Vec<FileListEntry> = store.get_file_list();
+    for entry in file_list.iter().filter(|e| e.favorite) {
+        push_file(QuickOpenKind::Favorite, 20, &entry.file_path, entry.file_name.clone(), &mut results);
+    }
+    for entry in &file_list {
+        push_file(QuickOpenKind::Recent, 0, &entry.file_path, entry.file_name.clone(), &mut results);
+    }
+
+    // 책갈피 메모는 검색어가 있을 때만
+    if !query.is_empty() {
+        for (path, file) in store.get_all_bookmarks() {
+            for bookmark in &file.bookmarks {
+                if bookmark.memo.trim().is_empty() {
+                    continue;
+                }
+                if let Some(score) = fuzzy_score(query, &bookmark.memo) {
+                    results.push(QuickOpenEntry {
+                        kind: QuickOpenKind::Bookmark,
+                        title: bookmark.memo.clone(),
+                        subtitle: format!("{} · {}", file_name(path), bookmark.line + 1),
+                        file_path: path.clone(),
+                        position: Some(bookmark.line),
+                        score: score - 5,
+                    });
+                }
+            }
+        }
+    }
+
+    // 안정 정렬: 점수가 같으면 탭 → 즐겨찾기 → 최근 순서 유지
+    results.sort_by_key(|r| std::cmp::Reverse(r.score));
+    results.truncate(MAX_RESULTS);
+    results
+}
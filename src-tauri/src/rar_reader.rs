@@ -0,0 +1,228 @@
+//! RAR / CBR archives via the `unrar` CLI (sidecar process).
+//!
+//! ZIP과 같은 방식으로 목록은 열 때 한 번만 읽고, 페이지마다 `unrar p`로 해당 엔트리만
+//! 표준 출력으로 풀어 읽는다. 솔리드 아카이브는 7z처럼 `archive_extract`로 한 번만 푼다.
+//! 비밀번호는 프로세스 목록에 보이지 않게 표준 입력으로 넘기고, 없으면 `-p-`를 넘겨
+//! unrar가 묻지 않게 한다.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+
+use crate::error::AppError;
+
+/// unrar exit code for a wrong (or missing) password.
+const EXIT_BAD_PASSWORD: i32 = 11;
+/// unrar exit code for a CRC error, which is how RAR4 reports a wrong password.
+const EXIT_CRC_ERROR: i32 = 3;
+
+pub fn is_rar_extension(ext: &str) -> bool {
+    matches!(ext, "rar" | "cbr")
+}
+
+/// Locate the unrar executable: configured path, then PATH, then the default Windows install.
+pub fn find_unrar(configured: &str) -> anyhow::Result<PathBuf> {
+    crate::external_tool::find_tool(configured, "unrar", &["unrar"], &[r"WinRAR\UnRAR.exe"])?
+        .ok_or_else(|| anyhow::anyhow!("RAR archives need unrar (install it or set its path in settings)"))
+}
+
+/// unrar `command` with the password switch: a bare `-p` makes unrar read the password
+/// from stdin (see `run`), `-p-` tells it there is none.
+fn unrar_command(unrar: &Path, command: &str, password: Option<&str>) -> Command {
+    let mut cmd = Command::new(unrar);
+    cmd.arg(command).arg(if password.is_some() { "-p" } else { "-p-" });
+    cmd
+}
+
+fn spawn(mut cmd: Command, password: Option<&str>) -> anyhow::Result<Child> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run unrar: {}", e))?;
+    if let (Some(password), Some(mut stdin)) = (password, child.stdin.take()) {
+        let _ = writeln!(stdin, "{}", password);
+    }
+    Ok(child)
+}
+
+fn run(cmd: Command, password: Option<&str>) -> anyhow::Result<Output> {
+    spawn(cmd, password)?
+        .wait_with_output()
+        .map_err(|e| anyhow::anyhow!("Failed to run unrar: {}", e))
+}
+
+fn password_error(archive: &Path, password: Option<&str>) -> anyhow::Error {
+    let name = archive.to_string_lossy().to_string();
+    match password {
+        Some(_) => AppError::WrongPassword(name).into(),
+        None => AppError::PasswordRequired(name).into(),
+    }
+}
+
+pub struct RarListing {
+    /// Image entries, sorted like ZIP entries.
+    pub entry_names: Vec<String>,
+    pub solid: bool,
+}
+
+/// Whether the archive is solid, and its files in archive order with their sizes.
+fn list_files(unrar: &Path, archive: &Path) -> anyhow::Result<(bool, Vec<(String, u64)>)> {
+    let mut cmd = unrar_command(unrar, "lt", None);
+    cmd.arg("--").arg(archive);
+    let output = run(cmd, None)?;
+    if output.status.code() == Some(EXIT_BAD_PASSWORD) {
+        // 파일 이름까지 암호화된 아카이브는 목록을 읽을 수 없다
+        return Err(password_error(archive, None));
+    }
+    if !output.status.success() {
+        anyhow::bail!(
+            "unrar failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let text = String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n");
+
+    // "Details: RAR 5, solid" 같은 아카이브 줄 다음에 빈 줄로 구분된 엔트리 블록이 온다
+    let solid = text.lines().any(|line| {
+        line.trim_start()
+            .strip_prefix("Details:")
+            .is_some_and(|details| details.split(',').any(|part| part.trim() == "solid"))
+    });
+    let mut files = Vec::new();
+    for block in text.split("\n\n") {
+        let mut name = None;
+        let mut is_file = false;
+        let mut size = 0;
+        for line in block.lines() {
+            let line = line.trim_start();
+            if let Some(value) = line.strip_prefix("Name: ") {
+                name = Some(value.to_string());
+            } else if line == "Type: File" {
+                is_file = true;
+            } else if let Some(value) = line.strip_prefix("Size: ") {
+                size = value.trim().parse().unwrap_or(0);
+            }
+        }
+        if let Some(name) = name.filter(|_| is_file) {
+            files.push((name, size));
+        }
+    }
+    Ok((solid, files))
+}
+
+/// List image entries (depth first, natural order) and whether the archive is solid.
+/// Empty entries (placeholders, broken uploads) are left out.
+pub fn list_images(unrar: &Path, archive: &Path) -> anyhow::Result<RarListing> {
+    let (solid, files) = list_files(unrar, archive)?;
+    let mut names: Vec<String> = files
+        .into_iter()
+        .filter(|(name, size)| {
+            *size > 0 && crate::image_reader::is_image_file(name) && !crate::image_reader::is_junk_entry(name)
+        })
+        .map(|(name, _)| name)
+        .collect();
+    names.sort_by(|a, b| {
+        crate::image_reader::archive_entry_cmp(&a.replace('\\', "/"), &b.replace('\\', "/"))
+    });
+    Ok(RarListing {
+        entry_names: names,
+        solid,
+    })
+}
+
+/// Map unrar's exit status to the password errors the frontend prompts on.
+fn check_status(archive: &Path, password: Option<&str>, output: &Output, what: &str) -> anyhow::Result<()> {
+    match output.status.code() {
+        Some(0) => Ok(()),
+        Some(EXIT_BAD_PASSWORD) => Err(password_error(archive, password)),
+        Some(EXIT_CRC_ERROR) if password.is_some() => Err(password_error(archive, password)),
+        code => anyhow::bail!("unrar failed to extract {} (exit code {:?})", what, code),
+    }
+}
+
+/// Read a single entry. Solid archives are extracted once and read from disk afterwards.
+pub fn read_entry(
+    unrar: &Path,
+    archive: &Path,
+    entry_name: &str,
+    solid: bool,
+    password: Option<&str>,
+) -> anyhow::Result<Vec<u8>> {
+    if solid {
+        return crate::archive_extract::read_extracted(archive, entry_name, || {
+            extract_stream(unrar, archive, password)
+        });
+    }
+
+    let mut cmd = unrar_command(unrar, "p", password);
+    cmd.arg("-inul").arg("--").arg(archive).arg(entry_name);
+    let output = run(cmd, password)?;
+    check_status(archive, password, &output, entry_name)?;
+    if output.stdout.is_empty() {
+        anyhow::bail!("RAR entry not found: {}", entry_name);
+    }
+    Ok(output.stdout)
+}
+
+/// The first `max_bytes` of an entry (for reading image headers), stopping unrar once
+/// they are read. Solid archives are read from their extraction.
+pub fn read_entry_head(
+    unrar: &Path,
+    archive: &Path,
+    entry_name: &str,
+    solid: bool,
+    password: Option<&str>,
+    max_bytes: usize,
+) -> anyhow::Result<Vec<u8>> {
+    if solid {
+        let mut bytes = read_entry(unrar, archive, entry_name, true, password)?;
+        bytes.truncate(max_bytes);
+        return Ok(bytes);
+    }
+    let mut cmd = unrar_command(unrar, "p", password);
+    cmd.arg("-inul").arg("--").arg(archive).arg(entry_name);
+    let (head, output) = crate::external_tool::read_stdout_head(spawn(cmd, password)?, max_bytes)?;
+    if let Some(output) = output {
+        check_status(archive, password, &output, entry_name)?;
+    }
+    if head.is_empty() {
+        anyhow::bail!("RAR entry not found: {}", entry_name);
+    }
+    Ok(head)
+}
+
+/// Start writing every file of the archive, in archive order, to stdout.
+fn extract_stream(
+    unrar: &Path,
+    archive: &Path,
+    password: Option<&str>,
+) -> anyhow::Result<crate::archive_extract::ExtractStream> {
+    let (_, entries) = list_files(unrar, archive)?;
+    let mut cmd = unrar_command(unrar, "p", password);
+    cmd.arg("-inul").arg("--").arg(archive);
+    let child = spawn(cmd, password)?;
+    let archive = archive.to_path_buf();
+    let password = password.map(str::to_string);
+    Ok(crate::archive_extract::ExtractStream {
+        child,
+        entries,
+        check: Box::new(move |output| {
+            check_status(&archive, password.as_deref(), output, &archive.display().to_string())
+        }),
+    })
+}
+
+/// Check a password by extracting the first image. Archives without encryption accept any password.
+pub fn test_password(unrar: &Path, archive: &Path, password: &str) -> anyhow::Result<bool> {
+    let Some(first) = list_images(unrar, archive)?.entry_names.into_iter().next() else {
+        return Ok(true);
+    };
+    // 솔리드 아카이브라도 확인은 엔트리 하나만 풀어서 한다
+    match read_entry(unrar, archive, &first, false, Some(password)) {
+        Ok(_) => Ok(true),
+        Err(e) if matches!(e.downcast_ref::<AppError>(), Some(AppError::WrongPassword(_))) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
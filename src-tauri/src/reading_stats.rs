@@ -0,0 +1,434 @@
+//! Reading statistics.
+//!
+//! 위치 갱신(페이지/챕터 이동, 스크롤 위치 저장)을 활동 신호로 보고, 연속된 신호 사이의
+//! 시간을 독서 시간으로 누적한다. 신호 간격이 IDLE_GAP보다 길면 자리를 비운 것으로 보고
+//! 합산하지 않는다. 시스템 idle 시간을 알 수 있으면(idle.rs) idle/잠금 시점에
+//! 바로 멈춘다. 원시 로그는 남기지 않고 날짜 × 파일 단위로만 집계해서 저장한다.
+
+use chrono::{Duration as ChronoDuration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::bookmark::ReadingGoal;
+
+/// Longer gaps between activity signals are not counted as reading time.
+const IDLE_GAP: Duration = Duration::from_secs(5 * 60);
+/// Persist at most this often while reading.
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+/// Forward jumps larger than this (slider drags, "go to end") don't count as progress.
+const MAX_PAGE_STEP: usize = 10;
+const MAX_LINE_STEP: usize = 500;
+const MOST_READ_LIMIT: usize = 10;
+/// System idle time after which the timers pause.
+pub const IDLE_PAUSE_SECS: u64 = 60;
+/// How often the idle sampler checks the system idle time.
+pub const IDLE_SAMPLE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailyFileStats {
+    pub seconds: u64,
+    /// Forward progress in the file's unit (pages, chapters or lines).
+    pub units: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayMinutes {
+    pub date: String,
+    pub minutes: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BookStats {
+    pub file_path: String,
+    pub file_name: String,
+    pub minutes: f64,
+    /// "page", "chapter" or "line".
+    pub unit: String,
+    pub units: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSummary {
+    pub total_minutes: f64,
+    pub minutes_per_day: Vec<DayMinutes>,
+    pub books: Vec<BookStats>,
+    pub most_read: Vec<BookStats>,
+    pub current_streak: usize,
+    pub longest_streak: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalStatus {
+    /// "page", "chapter" or "line".
+    pub unit: String,
+    pub target_date: Option<String>,
+    pub units_per_day: Option<u64>,
+    pub position: usize,
+    pub total_units: usize,
+    pub remaining_units: usize,
+    /// Days left including today (None without a target date).
+    pub days_left: Option<i64>,
+    /// Units per day needed to finish by the target date.
+    pub required_per_day: Option<f64>,
+    /// Where the reader should be by now to finish on time.
+    pub expected_position: Option<usize>,
+    pub today_units: u64,
+    pub average_per_day: f64,
+    pub finished: bool,
+    pub on_track: bool,
+}
+
+struct Activity {
+    last_seen: Instant,
+    last_position: usize,
+    /// Paused by idle detection; the gap before the next signal is not reading time.
+    paused: bool,
+}
+
+pub struct ReadingStats {
+    /// date (YYYY-MM-DD) → file path → stats
+    days: BTreeMap<String, HashMap<String, DailyFileStats>>,
+    active: HashMap<String, Activity>,
+    last_save: Instant,
+    dirty: bool,
+    store_path: Option<PathBuf>,
+}
+
+/// Progress unit for a file, derived from its extension.
+pub fn unit_for_path(path: &str) -> &'static str {
+    let ext = Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "epub" => "chapter",
+        "pdf" | "zip" | "cbz" | "rar" | "cbr" | "7z" | "cb7" => "page",
+        _ if crate::image_reader::is_image_extension(&ext) || Path::new(path).is_dir() => "page",
+        _ => "line",
+    }
+}
+
+impl ReadingStats {
+    /// Load stats from disk. Missing or unreadable stats start empty.
+    pub fn load() -> Self {
+        let store_path = Self::default_path().ok();
+        let (days, recovered) = store_path
+            .as_ref()
+            .and_then(|p| crate::store_recovery::load_map::<HashMap<String, DailyFileStats>, _>(p).ok().flatten())
+            .unwrap_or_default();
+        Self {
+            days,
+            active: HashMap::new(),
+            last_save: Instant::now(),
+            // 복구한 데이터는 다음 저장 때 다시 쓴다
+            dirty: recovered,
+            store_path,
+        }
+    }
+
+    fn default_path() -> anyhow::Result<PathBuf> {
+        let home = dirs::home_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+        Ok(home.join(".simple-reader").join("reading_stats.json"))
+    }
+
+    /// Record that `file_path` is being read at `position`.
+    pub fn record_activity(&mut self, file_path: &str, position: usize) {
+        let now = Instant::now();
+        let (elapsed, advanced) = match self.active.get(file_path) {
+            Some(prev) => {
+                let gap = now.duration_since(prev.last_seen);
+                let elapsed = if gap <= IDLE_GAP && !prev.paused {
+                    gap.as_secs()
+                } else {
+                    0
+                };
+                let max_step = if unit_for_path(file_path) == "line" {
+                    MAX_LINE_STEP
+                } else {
+                    MAX_PAGE_STEP
+                };
+                let advanced = position
+                    .checked_sub(prev.last_position)
+                    .filter(|step| *step <= max_step)
+                    .unwrap_or(0);
+                (elapsed, advanced as u64)
+            }
+            None => (0, 0),
+        };
+        self.active.insert(
+            file_path.to_string(),
+            Activity {
+                last_seen: now,
+                last_position: position,
+                paused: false,
+            },
+        );
+
+        if elapsed == 0 && advanced == 0 {
+            return;
+        }
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let entry = self
+            .days
+            .entry(today)
+            .or_default()
+            .entry(file_path.to_string())
+            .or_default();
+        entry.seconds += elapsed;
+        entry.units += advanced;
+        self.dirty = true;
+
+        if now.duration_since(self.last_save) >= SAVE_INTERVAL {
+            self.flush();
+        }
+    }
+
+    /// The system has been idle (or locked/asleep) since `idle_since`: credit reading time
+    /// up to that point and stop the clocks until the next activity signal.
+    pub fn pause_idle(&mut self, idle_since: Instant) {
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let paused: Vec<(String, u64)> = self
+            .active
+            .iter()
+            .map(|(path, activity)| {
+                let credited = idle_since
+                    .checked_duration_since(activity.last_seen)
+                    .filter(|d| *d <= IDLE_GAP)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                (path.clone(), credited)
+            })
+            .collect();
+
+        for (path, seconds) in paused {
+            if seconds > 0 {
+                self.days
+                    .entry(today.clone())
+                    .or_default()
+                    .entry(path.clone())
+                    .or_default()
+                    .seconds += seconds;
+                self.dirty = true;
+            }
+            if let Some(activity) = self.active.get_mut(&path) {
+                activity.paused = true;
+            }
+        }
+        self.flush();
+    }
+
+    /// Stop timing a file (tab closed).
+    pub fn end_activity(&mut self, file_path: &str) {
+        self.active.remove(file_path);
+        self.flush();
+    }
+
+    /// Persist pending changes.
+    pub fn flush(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        match self.save_to_disk() {
+            Ok(()) => self.dirty = false,
+            Err(e) => log::warn!("Failed to save reading stats: {}", e),
+        }
+        self.last_save = Instant::now();
+    }
+
+    fn save_to_disk(&self) -> anyhow::Result<()> {
+        let Some(store_path) = self.store_path.as_ref() else {
+            return Ok(());
+        };
+        if let Some(parent) = store_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(store_path, serde_json::to_string(&self.days)?)?;
+        Ok(())
+    }
+
+    /// Aggregate stats over `range`: "week", "month", "year" or "all".
+    pub fn summary(&self, range: &str) -> anyhow::Result<StatsSummary> {
+        let today = Local::now().date_naive();
+        let start = match range {
+            "week" => Some(today - ChronoDuration::days(6)),
+            "month" => Some(today - ChronoDuration::days(29)),
+            "year" => Some(today - ChronoDuration::days(364)),
+            "all" => None,
+            _ => anyhow::bail!("Unknown stats range: {}", range),
+        };
+
+        let mut minutes_per_day = Vec::new();
+        let mut per_book: HashMap<&str, DailyFileStats> = HashMap::new();
+        for (date, files) in &self.days {
+            let Ok(day) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+                continue;
+            };
+            if start.is_some_and(|s| day < s) {
+                continue;
+            }
+            let seconds: u64 = files.values().map(|f| f.seconds).sum();
+            minutes_per_day.push(DayMinutes {
+                date: date.clone(),
+                minutes: seconds as f64 / 60.0,
+            });
+            for (path, stats) in files {
+                let book = per_book.entry(path.as_str()).or_default();
+                book.seconds += stats.seconds;
+                book.units += stats.units;
+            }
+        }
+
+        let mut books: Vec<BookStats> = per_book
+            .into_iter()
+            .map(|(path, stats)| BookStats {
+                file_path: path.to_string(),
+                file_name: Path::new(path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                minutes: stats.seconds as f64 / 60.0,
+                unit: unit_for_path(path).to_string(),
+                units: stats.units,
+            })
+            .collect();
+        books.sort_by(|a, b| b.minutes.total_cmp(&a.minutes));
+        let most_read = books.iter().take(MOST_READ_LIMIT).cloned().collect();
+        let total_minutes = minutes_per_day.iter().map(|d| d.minutes).sum();
+        let (current_streak, longest_streak) = self.streaks(today);
+
+        Ok(StatsSummary {
+            total_minutes,
+            minutes_per_day,
+            books,
+            most_read,
+            current_streak,
+            longest_streak,
+        })
+    }
+
+    /// Forward progress on `file_path` from `since` through today.
+    fn units_since(&self, file_path: &str, since: NaiveDate) -> u64 {
+        let since = since.format("%Y-%m-%d").to_string();
+        self.days
+            .range(since..)
+            .filter_map(|(_, files)| files.get(file_path))
+            .map(|f| f.units)
+            .sum()
+    }
+
+    /// Whether the reader is on pace for a book's goal.
+    pub fn goal_status(&self, file_path: &str, goal: &ReadingGoal, position: usize) -> GoalStatus {
+        let today = Local::now().date_naive();
+        let created = chrono::DateTime::parse_from_rfc3339(&goal.created)
+            .map(|dt| dt.with_timezone(&Local).date_naive())
+            .unwrap_or(today);
+        let total_units = goal.total_units.max(1);
+        let remaining_units = total_units.saturating_sub(position + 1);
+        let finished = remaining_units == 0;
+
+        let today_units = self.units_since(file_path, today);
+        let days_elapsed = ((today - created).num_days() + 1).max(1);
+        let average_per_day = self.units_since(file_path, created) as f64 / days_elapsed as f64;
+
+        let target = goal
+            .target_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        let days_left = target.map(|t| (t - today).num_days() + 1);
+        let required_per_day = days_left.map(|d| remaining_units as f64 / d.max(1) as f64);
+        // 목표일까지 시작 위치 → 끝을 일정한 속도로 읽는다고 보고, 어제까지 왔어야 할 위치
+        let expected_position = target.map(|t| {
+            let total_days = ((t - created).num_days() + 1).max(1) as f64;
+            let done_days = (today - created).num_days().clamp(0, total_days as i64) as f64;
+            let span = total_units.saturating_sub(goal.start_position) as f64;
+            goal.start_position + (span * done_days / total_days) as usize
+        });
+
+        let on_pace_for_date = match (expected_position, days_left) {
+            (_, Some(d)) if d < 1 => finished,
+            (Some(expected), _) => position >= expected,
+            _ => true,
+        };
+        let on_pace_daily = goal
+            .units_per_day
+            .map_or(true, |per_day| today_units >= per_day || average_per_day >= per_day as f64);
+
+        GoalStatus {
+            unit: unit_for_path(file_path).to_string(),
+            target_date: goal.target_date.clone(),
+            units_per_day: goal.units_per_day,
+            position,
+            total_units,
+            remaining_units,
+            days_left,
+            required_per_day,
+            expected_position,
+            today_units,
+            average_per_day,
+            finished,
+            on_track: finished || (on_pace_for_date && on_pace_daily),
+        }
+    }
+
+    /// (current, longest) runs of consecutive reading days.
+    /// The current streak stays alive until the end of the day after the last reading day.
+    fn streaks(&self, today: NaiveDate) -> (usize, usize) {
+        let days: Vec<NaiveDate> = self
+            .days
+            .iter()
+            .filter(|(_, files)| files.values().any(|f| f.seconds > 0))
+            .filter_map(|(date, _)| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .collect();
+
+        let mut longest = 0;
+        let mut run = 0;
+        let mut prev: Option<NaiveDate> = None;
+        for day in &days {
+            run = match prev {
+                Some(p) if *day - p == ChronoDuration::days(1) => run + 1,
+                _ => 1,
+            };
+            longest = longest.max(run);
+            prev = Some(*day);
+        }
+
+        let current = match prev {
+            Some(last) if today - last <= ChronoDuration::days(1) => run,
+            _ => 0,
+        };
+        (current, longest)
+    }
+}
+
+/// Background sampler: pause the timers once the system has been idle for IDLE_PAUSE_SECS.
+pub fn spawn_idle_sampler<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
+    use tauri::Manager;
+    std::thread::spawn(move || {
+        let mut was_idle = false;
+        loop {
+            std::thread::sleep(IDLE_SAMPLE_INTERVAL);
+            let Some(idle) = crate::idle::idle_seconds() else {
+                continue;
+            };
+            let is_idle = idle >= IDLE_PAUSE_SECS;
+            if is_idle && !was_idle {
+                let idle_since = Instant::now()
+                    .checked_sub(Duration::from_secs(idle))
+                    .unwrap_or_else(Instant::now);
+                if let Ok(mut stats) = app.state::<crate::AppState>().reading_stats.lock() {
+                    stats.pause_idle(idle_since);
+                }
+                // 스크롤 중에 미뤄 둔 읽기 진행률도 이때 쓴다
+                if let Ok(mut store) = app.state::<crate::AppState>().bookmark_store.lock() {
+                    if let Err(e) = store.flush_progress() {
+                        log::warn!("Failed to save reading progress: {}", e);
+                    }
+                }
+            }
+            was_idle = is_idle;
+        }
+    });
+}
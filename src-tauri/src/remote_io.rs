@@ -0,0 +1,248 @@
+//! Network path (SMB/NAS) and slow-storage resilience.
+//!
+//! 원격 경로는 타임아웃이 걸린 스레드에서 먼저 확인해서, 오프라인 NAS 때문에
+//! UI가 수 초간 멈추는 대신 `AppError::Offline` / `AppError::Timeout`을 돌려준다.
+//! 선택적으로 원격 파일을 로컬 캐시(~/.simple-reader/remote_cache, `disk_cache::REMOTE_FILES`)에
+//! 복사해서 읽는다. 캐시는 다른 디스크 캐시처럼 용량을 넘으면 오래 안 쓴 복사본부터 지운다.
+//! 저장은 항상 원격 경로에 직접 쓰고, 그 뒤 캐시 복사본을 새로 만든다.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::error::AppError;
+
+/// Timeout for metadata probes on remote paths.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Timeout for whole-file transfers: copying into the read cache, reading or saving.
+const COPY_TIMEOUT: Duration = Duration::from_secs(120);
+/// Files larger than this are read directly instead of being copied.
+const MAX_CACHED_FILE_BYTES: u64 = 512 * 1024 * 1024;
+
+static READ_CACHE_ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_read_cache_enabled(enabled: bool) {
+    READ_CACHE_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Run `f` on a helper thread and give up after `timeout`.
+/// A stalled thread is left to finish (or hang) on its own; the caller is never blocked longer.
+pub fn with_timeout<T, F>(timeout: Duration, what: &Path, f: F) -> anyhow::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(f());
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(_) => Err(AppError::Timeout(what.to_string_lossy().to_string()).into()),
+    }
+}
+
+/// Read or write all of `path` in `f`; remote paths get the transfer timeout so a dead
+/// share fails with `AppError::Timeout` instead of hanging the caller.
+pub fn transfer<T, F>(path: &Path, f: F) -> anyhow::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+{
+    if is_remote_path(path) {
+        with_timeout(COPY_TIMEOUT, path, f)
+    } else {
+        f()
+    }
+}
+
+/// Whether a path lives on network storage.
+pub fn is_remote_path(path: &Path) -> bool {
+    let s = path.to_string_lossy();
+
+    #[cfg(target_os = "windows")]
+    {
+        if s.starts_with(r"\\?\UNC\") {
+            return true;
+        }
+        if s.starts_with(r"\\?\") {
+            return false;
+        }
+        if s.starts_with(r"\\") || s.starts_with("//") {
+            return true;
+        }
+        windows_drive_is_remote(&s)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // 네트워크 볼륨과 외장 디스크는 /Volumes 아래에 마운트된다
+        s.starts_with("//") || s.starts_with("/Volumes/")
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        s.starts_with("//") || linux_mount_is_remote(path)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn windows_drive_is_remote(path: &str) -> bool {
+    use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+    const DRIVE_REMOTE: u32 = 4;
+
+    let bytes = path.as_bytes();
+    if bytes.len() < 2 || bytes[1] != b':' || !bytes[0].is_ascii_alphabetic() {
+        return false;
+    }
+    let root: Vec<u16> = format!("{}:\\", bytes[0] as char)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    unsafe { GetDriveTypeW(root.as_ptr()) == DRIVE_REMOTE }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn linux_mount_is_remote(path: &Path) -> bool {
+    const REMOTE_FS: &[&str] = &[
+        "cifs", "smb3", "smbfs", "nfs", "nfs4", "fuse.sshfs", "9p", "afs", "davfs", "fuse.rclone",
+    ];
+    let Ok(mounts) = std::fs::read_to_string("/proc/mounts") else {
+        return false;
+    };
+    // 가장 긴 mount point가 실제 파일시스템
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then(|| (mount_point.len(), fs_type.to_string()))
+        })
+        .max_by_key(|(len, _)| *len)
+        .is_some_and(|(_, fs_type)| REMOTE_FS.contains(&fs_type.as_str()))
+}
+
+/// Resolve the path that should actually be read for `path`.
+///
+/// Local paths are returned unchanged. Remote paths are probed with a timeout
+/// (surfacing Offline / FileNotFound) and, when the read cache is enabled,
+/// copied into the local cache.
+pub fn resolve_source(path: &Path) -> anyhow::Result<PathBuf> {
+    if !is_remote_path(path) {
+        return Ok(path.to_path_buf());
+    }
+
+    let probe_path = path.to_path_buf();
+    let meta = match with_timeout(PROBE_TIMEOUT, path, move || {
+        Ok(std::fs::metadata(&probe_path)?)
+    }) {
+        Ok(meta) => meta,
+        Err(e) => {
+            if matches!(e.downcast_ref::<AppError>(), Some(AppError::Timeout(_))) {
+                return Err(AppError::Offline(path.to_string_lossy().to_string()).into());
+            }
+            if is_unreachable(path) {
+                return Err(AppError::Offline(path.to_string_lossy().to_string()).into());
+            }
+            return Err(AppError::FileNotFound(path.to_string_lossy().to_string()).into());
+        }
+    };
+
+    // 낱장 이미지는 같은 폴더의 다른 이미지와 함께 열리므로 복사하지 않는다
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !meta.is_file()
+        || !READ_CACHE_ENABLED.load(Ordering::Relaxed)
+        || meta.len() > MAX_CACHED_FILE_BYTES
+        || crate::image_reader::is_image_extension(&ext)
+    {
+        return Ok(path.to_path_buf());
+    }
+
+    cache_copy(path, &meta)
+}
+
+/// Call after `path` was saved while being read from `source`: the stale cached copy is
+/// removed and the path resolved again, so a reload sees the saved content.
+pub fn refresh_after_save(path: &Path, source: &Path) -> anyhow::Result<PathBuf> {
+    if source == path {
+        return Ok(path.to_path_buf());
+    }
+    let root = cache_root()?;
+    if let Some(dir) = source.parent().filter(|dir| dir.starts_with(&root) && *dir != root) {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    resolve_source(path)
+}
+
+/// The file is missing — check whether its share/parent is reachable at all.
+fn is_unreachable(path: &Path) -> bool {
+    let Some(parent) = path.parent().map(|p| p.to_path_buf()) else {
+        return true;
+    };
+    let parent_for_probe = parent.clone();
+    with_timeout(PROBE_TIMEOUT, &parent, move || {
+        Ok(std::fs::metadata(&parent_for_probe).is_ok())
+    })
+    .map(|reachable| !reachable)
+    .unwrap_or(true)
+}
+
+/// Copy a remote file into the read cache (reusing an up-to-date copy).
+/// The copy keeps the original file name so tab names and extensions stay intact.
+fn cache_copy(path: &Path, meta: &std::fs::Metadata) -> anyhow::Result<PathBuf> {
+    use sha1::{Digest, Sha1};
+
+    let mtime_ms = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let mut hasher = Sha1::new();
+    hasher.update(path.to_string_lossy().as_bytes());
+    hasher.update(mtime_ms.to_le_bytes());
+    hasher.update(meta.len().to_le_bytes());
+    let key: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid file path: {}", path.display()))?;
+    let cache_dir = cache_root()?.join(key);
+    let cached = cache_dir.join(file_name);
+    if std::fs::metadata(&cached).is_ok_and(|m| m.len() == meta.len()) {
+        // 최근에 쓴 복사본은 정리 대상에서 뒤로 민다
+        if let Ok(file) = std::fs::File::options().write(true).open(&cached) {
+            let _ = file.set_modified(std::time::SystemTime::now());
+        }
+        return Ok(cached);
+    }
+
+    std::fs::create_dir_all(&cache_dir)?;
+    // 시간 초과로 버려진 복사 스레드와 재시도가 같은 파일에 쓰지 않게 복사마다 임시 이름을 쓴다.
+    // 버려진 스레드의 임시 파일은 그 스레드가 끝날 때 지워진다.
+    let partial = tempfile::Builder::new().prefix(".partial-").tempfile_in(&cache_dir)?;
+    let src = path.to_path_buf();
+    let partial = with_timeout(COPY_TIMEOUT, path, move || {
+        let mut partial = partial;
+        std::io::copy(&mut std::fs::File::open(&src)?, partial.as_file_mut())?;
+        Ok(partial)
+    })?;
+    partial.persist(&cached).map_err(|e| e.error)?;
+    crate::disk_cache::REMOTE_FILES.prune();
+    Ok(cached)
+}
+
+fn cache_root() -> anyhow::Result<PathBuf> {
+    crate::disk_cache::REMOTE_FILES.dir()
+}
@@ -1,5 +1,16 @@
+use crate::text_fold::{self, FoldMode, Folded};
+use regex::{Regex, RegexBuilder};
 use ropey::Rope;
 use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Lines searched between progress events / cancellation checks.
+pub const SEARCH_CHUNK_LINES: usize = 50_000;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SearchMatch {
@@ -11,6 +22,217 @@ pub struct SearchMatch {
     pub context: String,
 }
 
+/// Matching options shared by search and replace.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    /// Only match at word boundaries (Unicode-aware, so Hangul/CJK letters count as word chars).
+    pub whole_word: bool,
+    /// Treat the query as a regular expression (replacement may use `$1` / `${name}`).
+    pub regex: bool,
+    /// Ignore diacritics and full/half-width differences (café = cafe, ＡＢＣ = ABC).
+    pub ignore_diacritics: bool,
+}
+
+impl SearchOptions {
+    fn fold_mode(&self, lowercase: bool) -> FoldMode {
+        FoldMode {
+            lowercase,
+            diacritics_width: self.ignore_diacritics,
+        }
+    }
+}
+
+/// A compiled query, built once per search/replace call.
+pub struct Matcher {
+    options: SearchOptions,
+    kind: MatcherKind,
+    /// The query can match across line breaks (searched over joined lines).
+    multiline: bool,
+    /// Expand `$1` / `${name}` in replacements (only for user regexes).
+    expand_captures: bool,
+}
+
+/// Extra lines read past a line range so cross-line matches starting inside it are complete.
+const MULTILINE_LOOKAHEAD_LINES: usize = 64;
+
+enum MatcherKind {
+    /// Folded query, searched against text folded the same way.
+    Literal(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    pub fn new(query: &str, options: SearchOptions) -> anyhow::Result<Self> {
+        let (kind, multiline, expand_captures) = if options.regex {
+            let pattern = text_fold::fold_str(query, options.fold_mode(false));
+            let regex = build_regex(&pattern, &options)
+                .map_err(|e| anyhow::anyhow!("Invalid regular expression: {}", e))?;
+            (MatcherKind::Regex(regex), regex_may_span_lines(&pattern), true)
+        } else if query.contains('\n') {
+            // 줄바꿈이 든 일반 검색어는 \n이 CRLF에도 맞도록 정규식으로 바꿔 찾는다
+            let query = text_fold::fold_str(&query.replace("\r\n", "\n"), options.fold_mode(false));
+            let pattern = regex::escape(&query).replace('\n', r"\r?\n");
+            (MatcherKind::Regex(build_regex(&pattern, &options)?), true, false)
+        } else {
+            let needle = text_fold::fold_str(query, options.fold_mode(!options.case_sensitive));
+            (MatcherKind::Literal(needle), false, false)
+        };
+        Ok(Self {
+            options,
+            kind,
+            multiline,
+            expand_captures,
+        })
+    }
+
+    pub fn options(&self) -> SearchOptions {
+        self.options
+    }
+
+    fn is_empty(&self) -> bool {
+        match &self.kind {
+            MatcherKind::Literal(needle) => needle.is_empty(),
+            MatcherKind::Regex(regex) => regex.as_str().is_empty(),
+        }
+    }
+
+    /// The text to run `find_at` on (regexes handle case themselves).
+    fn prepare<'a>(&self, text: &'a str) -> Folded<'a> {
+        let lowercase = matches!(self.kind, MatcherKind::Literal(_)) && !self.options.case_sensitive;
+        Folded::new(text, self.options.fold_mode(lowercase))
+    }
+
+    /// Next non-empty match in prepared text at or after byte `from`, as a byte range.
+    fn find_at(&self, haystack: &str, from: usize) -> Option<(usize, usize)> {
+        let mut start = from;
+        while start <= haystack.len() {
+            let (s, e) = match &self.kind {
+                MatcherKind::Literal(needle) => {
+                    let pos = start + haystack[start..].find(needle.as_str())?;
+                    (pos, pos + needle.len())
+                }
+                MatcherKind::Regex(regex) => {
+                    let m = regex.find_at(haystack, start)?;
+                    (m.start(), m.end())
+                }
+            };
+            if e > s && (!self.options.whole_word || is_whole_word(haystack, s, e)) {
+                return Some((s, e));
+            }
+            // 다음 글자부터 다시 찾는다
+            start = s + haystack[s..].chars().next().map_or(1, char::len_utf8);
+        }
+        None
+    }
+
+    /// Replacement text for the match starting at folded offset `start` (captures expanded
+    /// in regex mode). Capture spans are mapped back to `original`, so `$1` inserts what the
+    /// document contains ("Café") rather than its folded form ("Cafe").
+    fn expand<'a>(&self, original: &str, folded: &Folded, start: usize, replacement: &'a str) -> Cow<'a, str> {
+        match &self.kind {
+            MatcherKind::Regex(regex) if self.expand_captures => match regex.captures_at(folded.as_str(), start) {
+                Some(caps) => {
+                    let group = |m: Option<regex::Match>| match m {
+                        Some(m) if !m.is_empty() => {
+                            &original[folded.start_to_original(m.start())..folded.end_to_original(m.end())]
+                        }
+                        _ => "",
+                    };
+                    Cow::Owned(interpolate(replacement, |name| match name.parse::<usize>() {
+                        Ok(index) => group(caps.get(index)),
+                        Err(_) => group(caps.name(name)),
+                    }))
+                }
+                None => Cow::Borrowed(replacement),
+            },
+            _ => Cow::Borrowed(replacement),
+        }
+    }
+}
+
+/// Expand `$1`, `$name`, `${name}` and `$$` the way `regex::Captures::expand` does,
+/// with group text supplied by `group`.
+fn interpolate<'t>(template: &str, group: impl Fn(&str) -> &'t str) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(dollar) = rest.find('$') {
+        out.push_str(&rest[..dollar]);
+        rest = &rest[dollar + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        let (name, after) = match rest.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(close) => (&braced[..close], &braced[close + 1..]),
+                None => ("", rest),
+            },
+            None => {
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                (&rest[..len], &rest[len..])
+            }
+        };
+        if name.is_empty() {
+            // 그룹 이름이 없으면 $를 그대로 둔다
+            out.push('$');
+            continue;
+        }
+        out.push_str(group(name));
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+fn build_regex(pattern: &str, options: &SearchOptions) -> Result<Regex, regex::Error> {
+    // ^/$는 줄 단위로, CRLF 파일에서도 동작하도록
+    RegexBuilder::new(pattern)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .crlf(true)
+        .build()
+}
+
+/// Whether a user regex can consume a line break (`\n`, `\s`, negated classes, `(?s)`).
+fn regex_may_span_lines(pattern: &str) -> bool {
+    ["\n", "\\n", "\\r", "\\s", "\\v", "[^", "(?s"]
+        .iter()
+        .any(|token| pattern.contains(token))
+}
+
+/// Context (bytes) on each side of a position used to decide a word boundary there.
+/// UAX#29 rules only look a few characters around a position (regional indicator runs
+/// longer than this are the only exception).
+const WORD_BOUNDARY_CONTEXT: usize = 64;
+
+/// Whether `pos` is a UAX#29 word boundary in `haystack`.
+/// 한·중·일 문장처럼 띄어쓰기가 없는 경우에도 유니코드 규칙으로 단어 경계를 판단한다.
+fn is_word_boundary(haystack: &str, pos: usize) -> bool {
+    if pos == 0 || pos == haystack.len() {
+        return true;
+    }
+    let mut from = pos.saturating_sub(WORD_BOUNDARY_CONTEXT);
+    while !haystack.is_char_boundary(from) {
+        from -= 1;
+    }
+    let mut to = (pos + WORD_BOUNDARY_CONTEXT).min(haystack.len());
+    while !haystack.is_char_boundary(to) {
+        to += 1;
+    }
+    haystack[from..to]
+        .split_word_bound_indices()
+        .any(|(offset, _)| from + offset == pos)
+}
+
+/// Whether `haystack[start..end]` starts and ends on word boundaries.
+fn is_whole_word(haystack: &str, start: usize, end: usize) -> bool {
+    is_word_boundary(haystack, start) && is_word_boundary(haystack, end)
+}
+
 /// Count UTF-16 code units for a string (matches JavaScript's string indexing).
 fn utf16_len(s: &str) -> usize {
     s.chars().map(|c| c.len_utf16()).sum()
@@ -19,43 +241,99 @@ fn utf16_len(s: &str) -> usize {
 /// Search for all occurrences of a query in a Rope.
 /// Searches line-by-line to avoid byte/char position mismatches.
 /// line_char_start/line_char_end use UTF-16 code unit offsets (for JS compatibility).
-pub fn search_in_rope(rope: &Rope, query: &str, case_sensitive: bool) -> Vec<SearchMatch> {
-    if query.is_empty() {
+pub fn search_in_rope(rope: &Rope, matcher: &Matcher) -> Vec<SearchMatch> {
+    search_lines_threaded(rope, matcher, 0..rope.len_lines())
+}
+
+/// Byte ranges (in `text`) of every match in a plain string, e.g. an EPUB chapter's text.
+pub fn find_in_text(text: &str, matcher: &Matcher) -> Vec<Range<usize>> {
+    if matcher.is_empty() {
         return Vec::new();
     }
+    let folded = matcher.prepare(text);
+    let haystack = folded.as_str();
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    while let Some((s, e)) = matcher.find_at(haystack, from) {
+        ranges.push(folded.start_to_original(s)..folded.end_to_original(e));
+        from = e;
+    }
+    ranges
+}
+
+/// Below this many lines a search runs on the calling thread.
+const PARALLEL_MIN_LINES: usize = 20_000;
+
+/// Split the line range across CPU cores and merge the results in document order.
+///
+/// Scoped std threads rather than a thread pool: the workers borrow the rope and matcher
+/// directly, and spawning a handful of threads is noise next to the ≥20k lines each call
+/// searches (`start_search` calls this per `SEARCH_CHUNK_LINES` chunk).
+pub fn search_lines_threaded(
+    rope: &Rope,
+    matcher: &Matcher,
+    lines: Range<usize>,
+) -> Vec<SearchMatch> {
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let len = lines.end.saturating_sub(lines.start);
+    if threads < 2 || len < PARALLEL_MIN_LINES {
+        return search_lines(rope, matcher, lines);
+    }
+
+    let per_thread = len.div_ceil(threads.min(len / (PARALLEL_MIN_LINES / 4)).max(1));
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (lines.start..lines.end)
+            .step_by(per_thread)
+            .map(|start| {
+                let end = (start + per_thread).min(lines.end);
+                scope.spawn(move || search_lines(rope, matcher, start..end))
+            })
+            .collect();
+        // 스레드 순서대로 이어 붙이면 문서 순서가 유지된다
+        let mut matches = Vec::new();
+        for handle in handles {
+            match handle.join() {
+                Ok(part) => matches.extend(part),
+                Err(e) => std::panic::resume_unwind(e),
+            }
+        }
+        matches
+    })
+}
+
+/// Search only the given line range (char offsets stay document-absolute).
+pub fn search_lines(rope: &Rope, matcher: &Matcher, lines: Range<usize>) -> Vec<SearchMatch> {
+    let lines = lines.start.min(rope.len_lines())..lines.end.min(rope.len_lines());
+    if matcher.is_empty() || lines.is_empty() {
+        return Vec::new();
+    }
+    if matcher.multiline {
+        return search_lines_multiline(rope, matcher, lines);
+    }
 
     let mut results = Vec::new();
-    let search_query = if case_sensitive {
-        query.to_string()
-    } else {
-        query.to_lowercase()
-    };
-    let query_chars = query.chars().count();
-    let query_utf16_len = utf16_len(query);
 
-    let mut global_char_offset: usize = 0;
+    let mut global_char_offset: usize = rope.line_to_char(lines.start);
 
-    for line_idx in 0..rope.len_lines() {
+    for line_idx in lines {
         let line = rope.line(line_idx);
         let line_text = line.to_string();
-        let search_line = if case_sensitive {
-            line_text.clone()
-        } else {
-            line_text.to_lowercase()
-        };
+        let search_line = matcher.prepare(&line_text);
 
         let mut byte_start = 0;
-        while let Some(byte_pos) = search_line[byte_start..].find(&search_query) {
-            let abs_byte_pos = byte_start + byte_pos;
+        while let Some((folded_start, match_end)) = matcher.find_at(search_line.as_str(), byte_start) {
+            let abs_byte_pos = search_line.start_to_original(folded_start);
+            let matched = &line_text[abs_byte_pos..search_line.end_to_original(match_end)];
+
             // Count Unicode chars for Rope operations (char_start/char_end)
             let line_char_start_unicode = line_text[..abs_byte_pos].chars().count();
 
             // Count UTF-16 code units for JS substring (line_char_start/line_char_end)
             let line_char_start = utf16_len(&line_text[..abs_byte_pos]);
-            let line_char_end = line_char_start + query_utf16_len;
+            let line_char_end = line_char_start + utf16_len(matched);
 
             let char_start = global_char_offset + line_char_start_unicode;
-            let char_end = char_start + query_chars;
+            let char_end = char_start + matched.chars().count();
 
             let context = line_text
                 .trim_end_matches('\n')
@@ -71,7 +349,7 @@ pub fn search_in_rope(rope: &Rope, query: &str, case_sensitive: bool) -> Vec<Sea
                 context,
             });
 
-            byte_start = abs_byte_pos + search_query.len();
+            byte_start = match_end;
         }
 
         global_char_offset += line_text.chars().count();
@@ -80,98 +358,339 @@ pub fn search_in_rope(rope: &Rope, query: &str, case_sensitive: bool) -> Vec<Sea
     results
 }
 
-/// Replace the next occurrence of query after the given char position.
-/// Returns the char position where the replacement was made, or None.
-pub fn replace_next(
-    rope: &mut Rope,
-    query: &str,
-    replacement: &str,
-    from_position: usize,
-    case_sensitive: bool,
-) -> Option<usize> {
-    if query.is_empty() {
-        return None;
+/// Cross-line search: matches must start inside `lines` but may run past it.
+/// Positions are anchored to the first line of each match.
+fn search_lines_multiline(rope: &Rope, matcher: &Matcher, lines: Range<usize>) -> Vec<SearchMatch> {
+    let last_line = rope.len_lines() - 1;
+    let start_char = rope.line_to_char(lines.start);
+    let range_end_char = line_start_of_next(rope, lines.end - 1);
+    let end_char = line_start_of_next(rope, (lines.end - 1 + MULTILINE_LOOKAHEAD_LINES).min(last_line));
+    let text = rope.slice(start_char..end_char).to_string();
+    let search_text = matcher.prepare(&text);
+
+    let mut results = Vec::new();
+    let mut byte_start = 0;
+    let mut byte_cursor = 0;
+    let mut char_cursor = 0;
+    while let Some((folded_start, match_end)) = matcher.find_at(search_text.as_str(), byte_start) {
+        let abs_byte_pos = search_text.start_to_original(folded_start);
+        char_cursor += text[byte_cursor..abs_byte_pos].chars().count();
+        byte_cursor = abs_byte_pos;
+        let char_start = start_char + char_cursor;
+        if char_start >= range_end_char {
+            break;
+        }
+        let matched = &text[abs_byte_pos..search_text.end_to_original(match_end)];
+        let line = rope.char_to_line(char_start);
+        let line_text = rope.line(line).to_string();
+        let offset_in_line = char_start - rope.line_to_char(line);
+        let line_char_start: usize = line_text.chars().take(offset_in_line).map(char::len_utf16).sum();
+        // 첫 줄에 걸친 부분까지만 하이라이트
+        let first_line_part = matched.split(['\r', '\n']).next().unwrap_or("");
+
+        results.push(SearchMatch {
+            line,
+            char_start,
+            char_end: char_start + matched.chars().count(),
+            line_char_start,
+            line_char_end: line_char_start + utf16_len(first_line_part),
+            context: line_text.trim_end_matches(['\n', '\r']).to_string(),
+        });
+        byte_start = match_end;
     }
+    results
+}
 
-    let text = rope.to_string();
-    let search_text;
-    let search_query;
+/// First match starting at or after char `from`, wrapping to the top if needed
+/// (`true` = wrapped). Scans chunk by chunk so it stops at the first hit.
+pub fn find_next_match(rope: &Rope, matcher: &Matcher, from: usize) -> Option<(SearchMatch, bool)> {
+    let total = rope.len_lines();
+    let from = from.min(rope.len_chars());
+    let start_line = rope.char_to_line(from);
 
-    if case_sensitive {
-        search_text = text.clone();
-        search_query = query.to_string();
-    } else {
-        search_text = text.to_lowercase();
-        search_query = query.to_lowercase();
-    };
+    let mut line = start_line;
+    while line < total {
+        let end = (line + SEARCH_CHUNK_LINES).min(total);
+        if let Some(m) = search_lines(rope, matcher, line..end).into_iter().find(|m| m.char_start >= from) {
+            return Some((m, false));
+        }
+        line = end;
+    }
+    // 끝까지 없으면 처음부터 시작 줄까지
+    let mut line = 0;
+    while line <= start_line {
+        let end = (line + SEARCH_CHUNK_LINES).min(start_line + 1);
+        if let Some(m) = search_lines(rope, matcher, line..end).into_iter().next() {
+            return Some((m, true));
+        }
+        line = end;
+    }
+    None
+}
 
-    // Convert from_position (char index) to byte index for searching
-    let byte_start: usize = text.chars().take(from_position).map(|c| c.len_utf8()).sum();
+/// Last match starting before char `before`, wrapping to the bottom if needed.
+pub fn find_prev_match(rope: &Rope, matcher: &Matcher, before: usize) -> Option<(SearchMatch, bool)> {
+    let total = rope.len_lines();
+    let before = before.min(rope.len_chars());
+    let start_line = rope.char_to_line(before);
 
-    if let Some(byte_pos) = search_text[byte_start..].find(&search_query) {
-        let abs_byte_pos = byte_start + byte_pos;
-        let char_start = text[..abs_byte_pos].chars().count();
-        let char_end = char_start + query.chars().count();
+    let mut end = start_line + 1;
+    while end > 0 {
+        let start = end.saturating_sub(SEARCH_CHUNK_LINES);
+        if let Some(m) = search_lines(rope, matcher, start..end)
+            .into_iter()
+            .rev()
+            .find(|m| m.char_start < before)
+        {
+            return Some((m, false));
+        }
+        end = start;
+    }
+    // 처음까지 없으면 끝에서부터 시작 줄까지
+    let mut end = total;
+    while end > start_line {
+        let start = end.saturating_sub(SEARCH_CHUNK_LINES).max(start_line);
+        if let Some(m) = search_lines(rope, matcher, start..end).into_iter().next_back() {
+            return Some((m, true));
+        }
+        end = start;
+    }
+    None
+}
 
-        rope.remove(char_start..char_end);
-        rope.insert(char_start, replacement);
+struct SearchCursor {
+    query: String,
+    options: SearchOptions,
+    /// Char range of the last match returned by find_next/find_prev.
+    last: Option<(usize, usize)>,
+}
 
-        Some(char_start)
-    } else {
-        None
+/// Per-tab query and position for find_next / find_prev, so the frontend
+/// doesn't have to hold the full match list.
+pub struct SearchCursors {
+    cursors: Mutex<HashMap<String, SearchCursor>>,
+}
+
+impl SearchCursors {
+    pub fn new() -> Self {
+        Self {
+            cursors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Char position to continue from. An explicit `from`, or a changed query/options, restarts there.
+    pub fn anchor(&self, file_id: &str, query: &str, options: SearchOptions, from: Option<usize>, forward: bool) -> usize {
+        let last = self.cursors.lock().ok().and_then(|cursors| {
+            cursors
+                .get(file_id)
+                .filter(|c| c.query == query && c.options == options)
+                .and_then(|c| c.last)
+        });
+        match (from, last) {
+            (Some(pos), _) => pos,
+            (None, Some((start, end))) => {
+                if forward {
+                    end
+                } else {
+                    start
+                }
+            }
+            (None, None) => {
+                if forward {
+                    0
+                } else {
+                    usize::MAX
+                }
+            }
+        }
+    }
+
+    pub fn record(&self, file_id: &str, query: &str, options: SearchOptions, last: Option<(usize, usize)>) {
+        if let Ok(mut cursors) = self.cursors.lock() {
+            cursors.insert(
+                file_id.to_string(),
+                SearchCursor {
+                    query: query.to_string(),
+                    options,
+                    last,
+                },
+            );
+        }
+    }
+
+    pub fn clear(&self, file_id: &str) {
+        if let Ok(mut cursors) = self.cursors.lock() {
+            cursors.remove(file_id);
+        }
+    }
+}
+
+/// Cancellation flags of running background searches, by search id.
+pub struct SearchJobs {
+    jobs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl SearchJobs {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a search; a previous search with the same id is cancelled.
+    pub fn register(&self, search_id: &str) -> Arc<AtomicBool> {
+        let flag = Arc::new(AtomicBool::new(false));
+        if let Ok(mut jobs) = self.jobs.lock() {
+            if let Some(old) = jobs.insert(search_id.to_string(), flag.clone()) {
+                old.store(true, Ordering::Relaxed);
+            }
+        }
+        flag
+    }
+
+    pub fn cancel(&self, search_id: &str) -> bool {
+        match self.jobs.lock().ok().and_then(|jobs| jobs.get(search_id).cloned()) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop a finished search (unless it was replaced by a newer one with the same id).
+    pub fn finish(&self, search_id: &str, flag: &Arc<AtomicBool>) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            if jobs.get(search_id).is_some_and(|f| Arc::ptr_eq(f, flag)) {
+                jobs.remove(search_id);
+            }
+        }
     }
 }
 
-/// Replace all occurrences of query in the Rope.
-/// Returns the number of replacements made.
-/// String 기반 일괄 치환으로 O(n) 성능.
-pub fn replace_all_in_rope(
-    rope: &mut Rope,
-    query: &str,
+/// The next replacement at or after char `from_position` (no wrap-around), without
+/// touching the rope. Only the chunk holding the match is converted to a string.
+pub fn find_next_replacement(
+    rope: &Rope,
+    matcher: &Matcher,
     replacement: &str,
-    case_sensitive: bool,
-) -> usize {
-    if query.is_empty() {
-        return 0;
+    from_position: usize,
+) -> Option<Replacement> {
+    let total = rope.len_lines();
+    let from = from_position.min(rope.len_chars());
+    let mut line = rope.char_to_line(from);
+    while line < total {
+        let end = (line + SEARCH_CHUNK_LINES).min(total);
+        if let Some(m) = search_lines(rope, matcher, line..end).into_iter().find(|m| m.char_start >= from) {
+            return Some(replacement_for(rope, matcher, &m, replacement));
+        }
+        line = end;
     }
+    None
+}
 
-    let text = rope.to_string();
-    let search_query = if case_sensitive {
-        query.to_string()
+/// Old and new text of one match; captures are expanded over the lines the match was found in.
+fn replacement_for(rope: &Rope, matcher: &Matcher, m: &SearchMatch, replacement: &str) -> Replacement {
+    let last_line = if matcher.multiline {
+        (m.line + MULTILINE_LOOKAHEAD_LINES).min(rope.len_lines() - 1)
     } else {
-        query.to_lowercase()
+        m.line
     };
+    let slice_start = rope.line_to_char(m.line);
+    let slice = rope.slice(slice_start..line_start_of_next(rope, last_line));
+    let text = slice.to_string();
+    let search_text = matcher.prepare(&text);
+    let byte_start = slice.char_to_byte(m.char_start - slice_start);
+    let folded_start = search_text.original_to_folded(byte_start);
+    Replacement {
+        char_start: m.char_start,
+        old_text: rope.slice(m.char_start..m.char_end).to_string(),
+        new_text: matcher.expand(&text, &search_text, folded_start, replacement).into_owned(),
+    }
+}
 
-    // 매칭 위치를 한 번에 수집
-    let search_text = if case_sensitive {
-        text.clone()
-    } else {
-        text.to_lowercase()
-    };
+/// One pending replacement, in char offsets of the unmodified text.
+#[derive(Debug, Clone)]
+pub struct Replacement {
+    pub char_start: usize,
+    pub old_text: String,
+    pub new_text: String,
+}
 
-    let mut match_positions = Vec::new();
-    let mut byte_start = 0;
-    while let Some(byte_pos) = search_text[byte_start..].find(&search_query) {
-        let abs_byte_pos = byte_start + byte_pos;
-        match_positions.push(abs_byte_pos);
-        byte_start = abs_byte_pos + query.len();
+/// Collect every replacement `replace_all` would make, without touching the rope.
+pub fn find_replacements(rope: &Rope, matcher: &Matcher, replacement: &str) -> Vec<Replacement> {
+    if matcher.is_empty() {
+        return Vec::new();
     }
 
-    let count = match_positions.len();
-    if count == 0 {
-        return 0;
-    }
+    let text = rope.to_string();
+    let search_text = matcher.prepare(&text);
 
-    // String 상에서 한 번에 조립
-    let mut result = String::with_capacity(text.len());
-    let mut last_end = 0;
-    for &pos in &match_positions {
-        result.push_str(&text[last_end..pos]);
-        result.push_str(replacement);
-        last_end = pos + query.len();
+    let mut replacements = Vec::new();
+    let mut byte_start = 0;
+    // 매치마다 처음부터 세지 않도록 char 위치를 누적
+    let mut byte_cursor = 0;
+    let mut char_cursor = 0;
+    while let Some((folded_start, match_end)) = matcher.find_at(search_text.as_str(), byte_start) {
+        let abs_byte_pos = search_text.start_to_original(folded_start);
+        char_cursor += text[byte_cursor..abs_byte_pos].chars().count();
+        byte_cursor = abs_byte_pos;
+        replacements.push(Replacement {
+            char_start: char_cursor,
+            old_text: text[abs_byte_pos..search_text.end_to_original(match_end)].to_string(),
+            new_text: matcher.expand(&text, &search_text, folded_start, replacement).into_owned(),
+        });
+        byte_start = match_end;
     }
-    result.push_str(&text[last_end..]);
+    replacements
+}
+
+/// Chars of surrounding text shown on each side of a previewed replacement.
+const PREVIEW_CONTEXT_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplacePreview {
+    pub line: usize,
+    pub char_start: usize,
+    pub char_end: usize,
+    /// The surrounding text as it is now.
+    pub before: String,
+    /// The same text with this replacement applied.
+    pub after: String,
+}
 
-    *rope = Rope::from_str(&result);
-    count
+/// Before/after snippets for the first `limit` replacements (same line only).
+pub fn preview_replacements(rope: &Rope, replacements: &[Replacement], limit: usize) -> Vec<ReplacePreview> {
+    replacements
+        .iter()
+        .take(limit)
+        .map(|r| {
+            let char_end = r.char_start + r.old_text.chars().count();
+            let line = rope.char_to_line(r.char_start);
+            let line_start = rope.line_to_char(line);
+            let end_line = rope.char_to_line(char_end.min(rope.len_chars()));
+            let line_end = line_start_of_next(rope, end_line);
+
+            let prefix = rope.slice(r.char_start.saturating_sub(PREVIEW_CONTEXT_CHARS).max(line_start)..r.char_start);
+            let suffix = rope.slice(char_end..(char_end + PREVIEW_CONTEXT_CHARS).min(line_end).max(char_end));
+            let prefix = prefix.to_string();
+            let suffix = suffix.to_string();
+            let suffix = suffix.trim_end_matches(['\n', '\r']);
+            ReplacePreview {
+                line,
+                char_start: r.char_start,
+                char_end,
+                before: format!("{}{}{}", prefix, r.old_text, suffix),
+                after: format!("{}{}{}", prefix, r.new_text, suffix),
+            }
+        })
+        .collect()
+}
+
+fn line_start_of_next(rope: &Rope, line: usize) -> usize {
+    if line + 1 < rope.len_lines() {
+        rope.line_to_char(line + 1)
+    } else {
+        rope.len_chars()
+    }
 }
@@ -0,0 +1,126 @@
+//! Series detection from file/folder names ("작품명 3권", "Title v02", "Title - 015화" …).
+
+use serde::Serialize;
+use std::path::Path;
+use std::sync::LazyLock;
+
+static SERIES_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(
+        r"(?i)^(?P<series>.*?)[\s._\-\[\(]*(?:vol\.?|volume|v|ch\.?|chapter|ep\.?|#|제)?\s*(?P<num>\d{1,4})\s*(?:권|화|장|부|巻|話)?[\s\]\)]*(?:[\(\[][^\)\]]*[\)\]]\s*)*$",
+    )
+    .unwrap()
+});
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesName {
+    pub series: String,
+    pub number: u32,
+}
+
+/// Split a name (without extension) into series title and volume/chapter number.
+pub fn parse_series_name(stem: &str) -> Option<SeriesName> {
+    let caps = SERIES_RE.captures(stem.trim())?;
+    let series = caps["series"]
+        .trim_end_matches(|c: char| c.is_whitespace() || "-_.[(".contains(c))
+        .trim()
+        .to_string();
+    if series.is_empty() {
+        return None;
+    }
+    let number = caps["num"].parse().ok()?;
+    Some(SeriesName { series, number })
+}
+
+/// Replace characters that FAT32/exFAT (typical e-reader storage) and Windows reject.
+pub fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if "<>:\"/\\|?*".contains(c) || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect::<String>()
+        .trim_end_matches(['.', ' '])
+        .to_string()
+}
+
+// ── Series overview ──
+//
+// 권 목록은 `image_reader::find_series_volumes`(시리즈 접두사 그룹핑)로 찾고, 진행률은
+// 북마크 저장소의 마지막 위치와 권의 페이지 수로 계산한다. RAR/7z는 목록을 읽으려면 외부
+// 도구를 실행해야 하므로, 열려 있는 권이 아니면 페이지 수를 세지 않는다.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesVolume {
+    pub path: String,
+    pub name: String,
+    pub is_current: bool,
+    /// The volume has been opened before (tracked in the bookmark store).
+    pub opened: bool,
+    /// 0-based page where reading stopped.
+    pub last_position: usize,
+    pub page_count: Option<usize>,
+    pub progress_percent: Option<f64>,
+    /// Read up to the last page.
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesInfo {
+    /// Index of the open volume in `volumes`.
+    pub current_index: usize,
+    pub volumes: Vec<SeriesVolume>,
+}
+
+fn volume_entry(path: &Path, is_current: bool, last_position: Option<usize>, page_count: Option<usize>) -> SeriesVolume {
+    let progress = last_position.zip(page_count.filter(|&count| count > 0));
+    SeriesVolume {
+        path: path.to_string_lossy().to_string(),
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        is_current,
+        opened: last_position.is_some(),
+        last_position: last_position.unwrap_or(0),
+        page_count,
+        progress_percent: progress
+            .map(|(position, count)| (position + 1).min(count) as f64 / count as f64 * 100.0),
+        finished: progress.is_some_and(|(position, count)| position + 1 >= count),
+    }
+}
+
+/// Volumes of the series `current` belongs to. The open volume's position and page count
+/// come from its tab; `saved_position` looks up other volumes in the bookmark store.
+pub fn series_info(
+    current: &Path,
+    current_position: usize,
+    current_pages: usize,
+    saved_position: impl Fn(&str) -> Option<usize>,
+) -> anyhow::Result<SeriesInfo> {
+    let Some((paths, current_index)) = crate::image_reader::find_series_volumes(current)? else {
+        return Ok(SeriesInfo {
+            current_index: 0,
+            volumes: vec![volume_entry(current, true, Some(current_position), Some(current_pages))],
+        });
+    };
+
+    let volumes = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            if i == current_index {
+                volume_entry(path, true, Some(current_position), Some(current_pages))
+            } else {
+                let last_position = saved_position(&path.to_string_lossy());
+                volume_entry(path, false, last_position, crate::image_reader::volume_page_count(path))
+            }
+        })
+        .collect();
+    Ok(SeriesInfo {
+        current_index,
+        volumes,
+    })
+}
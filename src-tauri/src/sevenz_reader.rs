@@ -0,0 +1,213 @@
+//! 7z / CB7 archives via the 7-Zip CLI (sidecar process).
+//!
+//! 일반 아카이브는 RAR과 같게 페이지마다 해당 엔트리만 표준 출력으로 풀어 읽는다.
+//! 솔리드 아카이브는 `archive_extract`로 한 번만 (필요한 페이지까지 차례로) 풀어서 읽는다.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::error::AppError;
+
+pub fn is_7z_extension(ext: &str) -> bool {
+    matches!(ext, "7z" | "cb7")
+}
+
+/// Locate 7-Zip: configured path, then PATH (7z / 7zz / 7za), then the default Windows install.
+pub fn find_7z(configured: &str) -> anyhow::Result<PathBuf> {
+    let names: &[&str] = if cfg!(windows) { &["7z", "7za"] } else { &["7z", "7zz", "7za"] };
+    crate::external_tool::find_tool(configured, "7-Zip", names, &[r"7-Zip\7z.exe"])?
+        .ok_or_else(|| anyhow::anyhow!("7z archives need 7-Zip (install it or set its path in settings)"))
+}
+
+pub struct SevenZipListing {
+    /// Image entries, sorted like ZIP entries.
+    pub entry_names: Vec<String>,
+    pub solid: bool,
+}
+
+fn password_arg(password: Option<&str>) -> String {
+    format!("-p{}", password.unwrap_or_default())
+}
+
+/// Map 7-Zip's "Wrong password" failure to the password errors the frontend prompts on.
+fn check_output(archive: &Path, password: Option<&str>, output: &std::process::Output) -> anyhow::Result<()> {
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("Wrong password") || stderr.contains("Can not open encrypted archive") {
+        let name = archive.to_string_lossy().to_string();
+        return Err(match password {
+            Some(_) => AppError::WrongPassword(name).into(),
+            None => AppError::PasswordRequired(name).into(),
+        });
+    }
+    anyhow::bail!("7-Zip failed: {}", stderr.trim())
+}
+
+/// Whether the archive is solid, and its files (not folders) in archive order with their sizes.
+fn list_files(tool: &Path, archive: &Path) -> anyhow::Result<(bool, Vec<(String, u64)>)> {
+    let output = Command::new(tool)
+        .arg("l")
+        .arg("-slt")
+        .arg("-p")
+        .arg("--")
+        .arg(archive)
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run 7-Zip: {}", e))?;
+    check_output(archive, None, &output)?;
+    let text = String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n");
+
+    // "----------" 앞은 아카이브 속성, 뒤는 빈 줄로 구분된 엔트리 블록
+    let (header, body) = text.split_once("\n----------").unwrap_or(("", &text));
+    let solid = header.lines().any(|line| line.trim() == "Solid = +");
+
+    let mut files = Vec::new();
+    for block in body.split("\n\n") {
+        let mut path = None;
+        let mut is_dir = false;
+        let mut size = 0;
+        for line in block.lines() {
+            if let Some(value) = line.strip_prefix("Path = ") {
+                path = Some(value.to_string());
+            } else if line == "Folder = +" {
+                is_dir = true;
+            } else if let Some(value) = line.strip_prefix("Size = ") {
+                size = value.trim().parse().unwrap_or(0);
+            }
+        }
+        if let Some(path) = path.filter(|_| !is_dir) {
+            files.push((path, size));
+        }
+    }
+    Ok((solid, files))
+}
+
+/// List image entries and whether the archive is solid.
+pub fn list_images(tool: &Path, archive: &Path) -> anyhow::Result<SevenZipListing> {
+    let (solid, files) = list_files(tool, archive)?;
+    let mut names: Vec<String> = files
+        .into_iter()
+        .filter(|(path, size)| {
+            *size > 0 && crate::image_reader::is_image_file(path) && !crate::image_reader::is_junk_entry(path)
+        })
+        .map(|(path, _)| path)
+        .collect();
+    names.sort_by(|a, b| {
+        crate::image_reader::archive_entry_cmp(&a.replace('\\', "/"), &b.replace('\\', "/"))
+    });
+    Ok(SevenZipListing {
+        entry_names: names,
+        solid,
+    })
+}
+
+/// Read a single entry. Solid archives are extracted once and read from disk afterwards.
+pub fn read_entry(
+    tool: &Path,
+    archive: &Path,
+    entry_name: &str,
+    solid: bool,
+    password: Option<&str>,
+) -> anyhow::Result<Vec<u8>> {
+    if solid {
+        return crate::archive_extract::read_extracted(archive, entry_name, || {
+            extract_stream(tool, archive, password)
+        });
+    }
+
+    let output = Command::new(tool)
+        .arg("e")
+        .arg("-so")
+        .arg("-spd")
+        .arg(password_arg(password))
+        .arg("--")
+        .arg(archive)
+        .arg(entry_name)
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run 7-Zip: {}", e))?;
+    check_output(archive, password, &output)?;
+    if output.stdout.is_empty() {
+        anyhow::bail!("7z entry not found: {}", entry_name);
+    }
+    Ok(output.stdout)
+}
+
+/// The first `max_bytes` of an entry (for reading image headers), stopping 7-Zip once
+/// they are read. Solid archives are read from their extraction.
+pub fn read_entry_head(
+    tool: &Path,
+    archive: &Path,
+    entry_name: &str,
+    solid: bool,
+    password: Option<&str>,
+    max_bytes: usize,
+) -> anyhow::Result<Vec<u8>> {
+    if solid {
+        let mut bytes = read_entry(tool, archive, entry_name, true, password)?;
+        bytes.truncate(max_bytes);
+        return Ok(bytes);
+    }
+    let child = Command::new(tool)
+        .arg("e")
+        .arg("-so")
+        .arg("-spd")
+        .arg(password_arg(password))
+        .arg("--")
+        .arg(archive)
+        .arg(entry_name)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run 7-Zip: {}", e))?;
+    let (head, output) = crate::external_tool::read_stdout_head(child, max_bytes)?;
+    if let Some(output) = output {
+        check_output(archive, password, &output)?;
+    }
+    if head.is_empty() {
+        anyhow::bail!("7z entry not found: {}", entry_name);
+    }
+    Ok(head)
+}
+
+/// Check a password by reading the first image. Archives without encryption accept any password.
+pub fn test_password(tool: &Path, archive: &Path, password: &str) -> anyhow::Result<bool> {
+    let listing = list_images(tool, archive)?;
+    let Some(first) = listing.entry_names.first() else {
+        return Ok(true);
+    };
+    // 솔리드 아카이브라도 확인은 엔트리 하나만 풀어서 한다
+    match read_entry(tool, archive, first, false, Some(password)) {
+        Ok(_) => Ok(true),
+        Err(e) if matches!(e.downcast_ref::<AppError>(), Some(AppError::WrongPassword(_))) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Start writing every file of the archive, in archive order, to stdout.
+fn extract_stream(
+    tool: &Path,
+    archive: &Path,
+    password: Option<&str>,
+) -> anyhow::Result<crate::archive_extract::ExtractStream> {
+    let (_, entries) = list_files(tool, archive)?;
+    let child = Command::new(tool)
+        .arg("x")
+        .arg("-so")
+        .arg(password_arg(password))
+        .arg("--")
+        .arg(archive)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run 7-Zip: {}", e))?;
+    let archive = archive.to_path_buf();
+    let password = password.map(str::to_string);
+    Ok(crate::archive_extract::ExtractStream {
+        child,
+        entries,
+        check: Box::new(move |output| check_output(&archive, password.as_deref(), output)),
+    })
+}
@@ -0,0 +1,186 @@
+//! Backend-driven image slideshow.
+//!
+//! 타이머를 백엔드 스레드에서 돌려서 webview가 렌더링으로 바쁠 때도 간격이 밀리지 않는다.
+//! 다음 tick 시각은 시작 시각 기준으로 계산하므로 오차가 누적되지 않는다.
+//! 매 tick마다 last_position을 갱신하고 앞쪽 이미지를 넉넉히 prefetch한 뒤
+//! `slideshow-tick` 이벤트를 보낸다.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::AppState;
+
+const MIN_INTERVAL_MS: u64 = 200;
+/// Images prefetched ahead of the current slide.
+const SLIDESHOW_PREFETCH_AHEAD: usize = 5;
+
+enum Control {
+    Pause,
+    Resume,
+    Stop,
+}
+
+#[derive(Clone, Serialize)]
+struct SlideshowTick {
+    file_id: String,
+    index: usize,
+    total: usize,
+}
+
+#[derive(Clone, Serialize)]
+struct SlideshowEnded {
+    file_id: String,
+}
+
+pub struct SlideshowManager {
+    /// Running shows by file id: (generation, control channel).
+    shows: Mutex<HashMap<String, (u64, Sender<Control>)>>,
+    /// Incremented per start, so a finished thread only unregisters its own show.
+    next_generation: AtomicU64,
+}
+
+impl SlideshowManager {
+    pub fn new() -> Self {
+        Self {
+            shows: Mutex::new(HashMap::new()),
+            next_generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Start (or restart) a slideshow for an image tab from its current position.
+    pub fn start<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        file_id: &str,
+        interval_ms: u64,
+        looping: bool,
+    ) -> anyhow::Result<()> {
+        let (start_index, total) = {
+            let state = app.state::<AppState>();
+            let tab_manager = state
+                .tab_manager
+                .lock()
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            let position = tab_manager
+                .get_last_position(file_id)
+                .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
+            let total = tab_manager.get_image_count(file_id);
+            if total == 0 {
+                anyhow::bail!("Not an image tab: {}", file_id);
+            }
+            (position.min(total - 1), total)
+        };
+
+        self.stop(file_id);
+        let (tx, rx) = mpsc::channel();
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        self.shows
+            .lock()
+            .map_err(|e| anyhow::anyhow!("{}", e))?
+            .insert(file_id.to_string(), (generation, tx));
+
+        let interval = Duration::from_millis(interval_ms.max(MIN_INTERVAL_MS));
+        let app = app.clone();
+        let file_id = file_id.to_string();
+        std::thread::spawn(move || {
+            let state = app.state::<AppState>();
+            state
+                .image_cache
+                .prefetch_ahead(&file_id, start_index, total, SLIDESHOW_PREFETCH_AHEAD);
+
+            let mut index = start_index;
+            let mut next_tick = Instant::now() + interval;
+            let mut paused_remaining: Option<Duration> = None;
+
+            loop {
+                let control = match paused_remaining {
+                    Some(_) => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                    None => rx.recv_timeout(next_tick.saturating_duration_since(Instant::now())),
+                };
+                match control {
+                    Ok(Control::Pause) => {
+                        if paused_remaining.is_none() {
+                            paused_remaining = Some(next_tick.saturating_duration_since(Instant::now()));
+                        }
+                        continue;
+                    }
+                    Ok(Control::Resume) => {
+                        if let Some(remaining) = paused_remaining.take() {
+                            next_tick = Instant::now() + remaining;
+                        }
+                        continue;
+                    }
+                    Ok(Control::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+                    Err(RecvTimeoutError::Timeout) => {}
+                }
+
+                // tick
+                index += 1;
+                if index >= total {
+                    if !looping {
+                        let _ = app.emit("slideshow-ended", SlideshowEnded { file_id: file_id.clone() });
+                        break;
+                    }
+                    index = 0;
+                }
+                if let Ok(mut tab_manager) = state.tab_manager.lock() {
+                    tab_manager.set_last_position(&file_id, index, 0);
+                }
+                state
+                    .image_cache
+                    .prefetch_ahead(&file_id, index, total, SLIDESHOW_PREFETCH_AHEAD);
+                let _ = app.emit(
+                    "slideshow-tick",
+                    SlideshowTick {
+                        file_id: file_id.clone(),
+                        index,
+                        total,
+                    },
+                );
+
+                next_tick += interval;
+                // 시스템이 오래 멈췄다면 밀린 tick을 몰아서 보내지 않는다
+                if next_tick < Instant::now() {
+                    next_tick = Instant::now() + interval;
+                }
+            }
+
+            // 재시작된 경우 등록된 것은 새 슬라이드쇼이므로 건드리지 않는다
+            if let Ok(mut shows) = state.slideshow.shows.lock() {
+                if shows.get(&file_id).is_some_and(|(g, _)| *g == generation) {
+                    shows.remove(&file_id);
+                }
+            };
+        });
+        Ok(())
+    }
+
+    fn send(&self, file_id: &str, control: Control) -> bool {
+        self.shows
+            .lock()
+            .ok()
+            .and_then(|shows| shows.get(file_id).map(|(_, tx)| tx.send(control).is_ok()))
+            .unwrap_or(false)
+    }
+
+    pub fn pause(&self, file_id: &str) -> bool {
+        self.send(file_id, Control::Pause)
+    }
+
+    pub fn resume(&self, file_id: &str) -> bool {
+        self.send(file_id, Control::Resume)
+    }
+
+    pub fn stop(&self, file_id: &str) {
+        if let Ok(mut shows) = self.shows.lock() {
+            if let Some((_, tx)) = shows.remove(file_id) {
+                let _ = tx.send(Control::Stop);
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Two-page spread pairing for the image viewer (manga mode).
+//!
+//! 세로 페이지는 두 장씩 묶고, 가로로 넓은 페이지(이미 펼침면으로 스캔된 것)는 혼자 둔다.
+//! 넓은 페이지 다음부터 다시 짝을 맞추므로 펼침면 앞뒤에서 좌우가 어긋나지 않는다.
+
+use serde::Serialize;
+
+/// Pages wider than this (width / height) are shown alone.
+const WIDE_ASPECT_RATIO: f64 = 1.1;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpreadPlan {
+    /// Right-to-left: the first page of each spread goes on the right.
+    pub rtl: bool,
+    /// Page indices of each spread in reading order (one or two pages).
+    pub spreads: Vec<Vec<usize>>,
+}
+
+fn is_wide(dimensions: Option<(u32, u32)>) -> bool {
+    match dimensions {
+        Some((width, height)) if height > 0 => width as f64 / height as f64 > WIDE_ASPECT_RATIO,
+        // 크기를 모르면 세로 페이지로 본다
+        _ => false,
+    }
+}
+
+/// Pair pages given their (width, height). With `cover_alone`, the first page is shown by itself.
+pub fn pair_pages(dimensions: &[Option<(u32, u32)>], cover_alone: bool) -> Vec<Vec<usize>> {
+    let mut spreads = Vec::new();
+    let mut i = 0;
+    if cover_alone && !dimensions.is_empty() {
+        spreads.push(vec![0]);
+        i = 1;
+    }
+    while i < dimensions.len() {
+        if !is_wide(dimensions[i]) && i + 1 < dimensions.len() && !is_wide(dimensions[i + 1]) {
+            spreads.push(vec![i, i + 1]);
+            i += 2;
+        } else {
+            spreads.push(vec![i]);
+            i += 1;
+        }
+    }
+    spreads
+}
@@ -0,0 +1,204 @@
+//! Safe loading of the JSON data files (config.json, books.json, reading_stats.json).
+//!
+//! 파싱에 실패한 파일을 기본값으로 덮어쓰지 않도록, 먼저 타임스탬프를 붙여 격리
+//! (`books.corrupt-20250101-120000.json`)한 뒤 읽을 수 있는 항목만 골라 복구한다.
+//! 복구 내역은 `startup-issues` 이벤트와 `get_startup_issues` 명령으로 알린다.
+//!
+//! `--safe-mode`로 시작하면 전역 단축키, 폴더 감시 같은 부가 기능을 켜지 않는다.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+static ISSUES: Mutex<Vec<StoreIssue>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StoreIssue {
+    /// File name, e.g. "books.json".
+    pub file: String,
+    /// Where the corrupt original was moved (None if it couldn't be moved).
+    pub quarantined_to: Option<String>,
+    pub recovered_entries: usize,
+    /// Entries that were readable JSON but invalid for this version.
+    pub lost_entries: usize,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupReport {
+    pub safe_mode: bool,
+    pub issues: Vec<StoreIssue>,
+}
+
+pub fn set_safe_mode(enabled: bool) {
+    SAFE_MODE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_safe_mode() -> bool {
+    SAFE_MODE.load(Ordering::Relaxed)
+}
+
+pub fn startup_report() -> StartupReport {
+    StartupReport {
+        safe_mode: is_safe_mode(),
+        issues: ISSUES.lock().map(|issues| issues.clone()).unwrap_or_default(),
+    }
+}
+
+/// Load a JSON object keyed by string (books.json, reading_stats.json).
+/// Returns None if the file doesn't exist, and `true` alongside the data when it was
+/// recovered from a corrupt file (the caller should save it back).
+pub fn load_map<V, M>(path: &Path) -> anyhow::Result<Option<(M, bool)>>
+where
+    V: DeserializeOwned,
+    M: DeserializeOwned + FromIterator<(String, V)>,
+{
+    let Some((content, parsed)) = read_checked::<M>(path)? else {
+        return Ok(None);
+    };
+    let error = match parsed {
+        Ok(data) => return Ok(Some((data, false))),
+        Err(error) => error,
+    };
+
+    let mut lost = 0;
+    let entries: Vec<(String, V)> = salvage_object(&content)
+        .into_iter()
+        .filter_map(|(key, value)| match serde_json::from_value(value) {
+            Ok(v) => Some((key, v)),
+            Err(_) => {
+                lost += 1;
+                None
+            }
+        })
+        .collect();
+    report(path, entries.len(), lost, error);
+    Ok(Some((entries.into_iter().collect(), true)))
+}
+
+/// Load a JSON struct (config.json). Fields that can't be recovered keep their defaults.
+pub fn load_struct<T>(path: &Path) -> anyhow::Result<Option<(T, bool)>>
+where
+    T: DeserializeOwned + Serialize + Default,
+{
+    let Some((content, parsed)) = read_checked::<T>(path)? else {
+        return Ok(None);
+    };
+    let error = match parsed {
+        Ok(data) => return Ok(Some((data, false))),
+        Err(error) => error,
+    };
+
+    let mut merged = match serde_json::to_value(T::default())? {
+        Value::Object(map) => map,
+        _ => anyhow::bail!("Cannot recover non-object data: {}", path.display()),
+    };
+    let mut recovered = 0;
+    let mut lost = 0;
+    for (key, value) in salvage_object(&content) {
+        // 필드 하나씩 덮어써 보고, 타입이 맞는 것만 남긴다
+        let mut candidate = merged.clone();
+        candidate.insert(key, value);
+        if serde_json::from_value::<T>(Value::Object(candidate.clone())).is_ok() {
+            merged = candidate;
+            recovered += 1;
+        } else {
+            lost += 1;
+        }
+    }
+    let data = serde_json::from_value(Value::Object(merged)).unwrap_or_default();
+    report(path, recovered, lost, error);
+    Ok(Some((data, true)))
+}
+
+/// Read a data file and try a normal parse. The inner `Err` carries the parse error.
+#[allow(clippy::type_complexity)]
+fn read_checked<T: DeserializeOwned>(path: &Path) -> anyhow::Result<Option<(String, Result<T, String>)>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(path)?;
+    Ok(Some(match String::from_utf8(bytes) {
+        Ok(content) => {
+            let parsed = serde_json::from_str(&content).map_err(|e| e.to_string());
+            (content, parsed)
+        }
+        Err(e) => {
+            let content = String::from_utf8_lossy(e.as_bytes()).into_owned();
+            (content, Err(format!("Invalid UTF-8: {}", e.utf8_error())))
+        }
+    }))
+}
+
+/// Pull complete top-level `"key": value` pairs out of a (possibly truncated) JSON object.
+fn salvage_object(content: &str) -> Vec<(String, Value)> {
+    let mut entries = Vec::new();
+    let Some(start) = content.find('{') else {
+        return entries;
+    };
+    let mut rest = &content[start + 1..];
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if !rest.starts_with('"') {
+            break;
+        }
+        let mut keys = serde_json::Deserializer::from_str(rest).into_iter::<String>();
+        let Some(Ok(key)) = keys.next() else {
+            break;
+        };
+        let Some(after) = rest[keys.byte_offset()..].trim_start().strip_prefix(':') else {
+            break;
+        };
+        let mut values = serde_json::Deserializer::from_str(after).into_iter::<Value>();
+        match values.next() {
+            Some(Ok(value)) => {
+                entries.push((key, value));
+                rest = &after[values.byte_offset()..];
+            }
+            _ => break,
+        }
+    }
+    entries
+}
+
+/// Move a corrupt file aside so it isn't overwritten by the next save.
+fn quarantine(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_string_lossy();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let target = path.with_file_name(format!("{}.corrupt-{}{}", stem, stamp, ext));
+    std::fs::rename(path, &target)
+        .or_else(|_| std::fs::copy(path, &target).map(|_| ()))
+        .ok()?;
+    Some(target)
+}
+
+fn report(path: &Path, recovered_entries: usize, lost_entries: usize, error: String) {
+    let quarantined_to = quarantine(path);
+    log::warn!(
+        "Recovered corrupt {} ({} entries kept, {} lost): {}",
+        path.display(),
+        recovered_entries,
+        lost_entries,
+        error
+    );
+    if let Ok(mut issues) = ISSUES.lock() {
+        issues.push(StoreIssue {
+            file: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            quarantined_to: quarantined_to.map(|p| p.to_string_lossy().to_string()),
+            recovered_entries,
+            lost_entries,
+            error,
+        });
+    }
+}
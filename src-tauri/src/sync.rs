@@ -0,0 +1,61 @@
+//! Position / edit broadcasting between windows.
+//!
+//! 모든 창이 같은 TabManager를 공유하므로, 한 창에서 위치나 내용이 바뀌면 이벤트로
+//! 알려서 다른 창이 다시 읽어가게 한다. payload의 `source`는 변경을 일으킨 창의
+//! label이며, 해당 창은 자기 이벤트를 무시하면 된다.
+
+use serde::Serialize;
+use tauri::{Emitter, Runtime, Window};
+
+use crate::tab_manager::TabManager;
+
+#[derive(Clone, Serialize)]
+pub struct PositionChanged {
+    pub file_path: String,
+    pub position: usize,
+    pub scroll_offset: usize,
+    pub source: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DocumentChanged {
+    pub file_id: String,
+    /// Increases with every edit; windows holding an older revision should refetch.
+    pub revision: u64,
+    pub is_modified: bool,
+    pub total_lines: usize,
+    pub source: String,
+}
+
+pub fn emit_position_changed<R: Runtime>(
+    window: &Window<R>,
+    file_path: &str,
+    position: usize,
+    scroll_offset: usize,
+) {
+    let _ = window.emit(
+        "position-changed",
+        PositionChanged {
+            file_path: file_path.to_string(),
+            position,
+            scroll_offset,
+            source: window.label().to_string(),
+        },
+    );
+}
+
+pub fn emit_document_changed<R: Runtime>(window: &Window<R>, tab_manager: &TabManager, file_id: &str) {
+    let Some((revision, is_modified)) = tab_manager.get_revision(file_id) else {
+        return;
+    };
+    let _ = window.emit(
+        "document-changed",
+        DocumentChanged {
+            file_id: file_id.to_string(),
+            revision,
+            is_modified,
+            total_lines: tab_manager.get_total_lines(file_id).unwrap_or(0),
+            source: window.label().to_string(),
+        },
+    );
+}
@@ -1,29 +1,84 @@
-use crate::epub_reader::EpubBook;
+use crate::bookmark::{PdfViewState, ReadingDirection};
+use crate::config::AppConfig;
+use crate::epub_reader::{ChapterInfo, ChapterRenderJob, EpubBook, EpubChapter, EpubLayout, EpubLinkTarget};
+use crate::hex_view::HexChunk;
 use crate::image_reader::ImageSource;
-use crate::text_buffer::TextBuffer;
+use crate::pagination::{self, Page, PageMap};
+use crate::text_buffer::{LineEnding, TextBuffer};
+use encoding_rs::Encoding;
+use ropey::Rope;
+use crate::warm_cache::{ImageEntryMeta, WarmCache, WarmMeta};
 use serde::Serialize;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 pub enum FileType {
     Text,
     Epub,
     Pdf,
     Image,
+    /// Comic book archive (.cbz/.cbr/.cb7): the image viewer plus comic-specific features.
+    Comic,
+    /// Non-text file shown in the hex viewer (see `get_hex_chunk`).
+    Binary,
 }
 
 pub struct Tab {
     pub path: PathBuf,
+    /// Path actually read from: a local copy for cached remote files, otherwise `path`.
+    pub source_path: PathBuf,
     pub buffer: Option<TextBuffer>,
     pub epub_book: Option<EpubBook>,
+    /// EPUB chapter list; available before the book itself is parsed (warm start).
+    pub chapter_infos: Vec<ChapterInfo>,
     pub image_source: Option<ImageSource>,
     pub last_position: usize,
     pub last_scroll_offset: usize,
     pub is_modified: bool,
+    /// Edit counter, bumped on every change (used to sync windows).
+    pub revision: u64,
     pub file_type: FileType,
+    /// Encoding chosen with `reopen_with_encoding` (None = auto-detect), kept across reloads.
+    pub encoding_override: Option<&'static Encoding>,
+    /// Set while the book page mode is on (see `get_page`).
+    pub page_map: Option<PageMap>,
+    /// Writing mode / page direction of an EPUB (default for other files).
+    pub epub_layout: EpubLayout,
+    /// Zip entry being edited when this text tab is an EPUB chapter editor
+    /// (`path` is then the .epub the chapter is saved back into).
+    pub epub_entry: Option<String>,
+    /// Zoom and fit mode of a PDF tab (the page is `last_position`).
+    pub pdf_view: Option<PdfViewState>,
+    pub reading_direction: ReadingDirection,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl Tab {
+    /// An unmodified tab reading `path`; the caller fills in the buffer / book / images.
+    fn new(path: &Path, file_type: FileType, last_position: usize, last_scroll_offset: usize) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            source_path: path.to_path_buf(),
+            buffer: None,
+            epub_book: None,
+            chapter_infos: Vec::new(),
+            image_source: None,
+            last_position,
+            last_scroll_offset,
+            is_modified: false,
+            revision: 0,
+            file_type,
+            encoding_override: None,
+            page_map: None,
+            epub_layout: EpubLayout::default(),
+            epub_entry: None,
+            pdf_view: None,
+            reading_direction: ReadingDirection::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct FileInfo {
     pub id: String,
     pub name: String,
@@ -37,6 +92,24 @@ pub struct FileInfo {
     pub total_chapters: usize,
     pub total_images: usize,
     pub initial_image_name: String,
+    /// Huge text file opened via mmap: edits and saving are disabled.
+    pub read_only: bool,
+    /// Detected text encoding ("UTF-8", "EUC-KR", ...; empty for non-text files).
+    pub encoding: String,
+    /// "LF" or "CRLF" (empty for non-text files).
+    pub line_ending: String,
+    /// Page count while the book page mode is on.
+    pub total_pages: Option<usize>,
+    /// EPUB `writing_mode` / `page_direction` (both None for other files).
+    #[serde(flatten)]
+    pub epub_layout: EpubLayout,
+    /// Saved zoom / fit mode of a PDF (page index is `last_position`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf_view: Option<PdfViewState>,
+    /// Comic archive. Comics use the image viewer, so `file_type` stays "image".
+    pub is_comic: bool,
+    /// Saved per-file page direction ("ltr" / "rtl").
+    pub reading_direction: ReadingDirection,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -57,9 +130,21 @@ pub struct TextChunk {
     pub total_lines: usize,
 }
 
+/// An arbitrary char range of a text buffer (clamped to the text).
+#[derive(Debug, Clone, Serialize)]
+pub struct TextRange {
+    pub text: String,
+    pub start_char: usize,
+    pub end_char: usize,
+    pub start_line: usize,
+    /// Line containing the last char of the range (== start_line when empty).
+    pub end_line: usize,
+}
+
 pub struct TabManager {
     tabs: HashMap<String, Tab>,
     pub active_tab: Option<String>,
+    warm_cache: WarmCache,
 }
 
 impl TabManager {
@@ -67,12 +152,27 @@ impl TabManager {
         Self {
             tabs: HashMap::new(),
             active_tab: None,
+            warm_cache: WarmCache::load(),
         }
     }
 
+    /// Whether `path` is open in a tab.
+    pub fn has_tab(&self, path: &str) -> bool {
+        self.tabs.contains_key(path)
+    }
+
     /// Open a file in a new tab (or switch to it if already open).
     /// Returns FileInfo about the opened file.
-    pub fn open_file(&mut self, path: &str, last_position: usize, last_scroll_offset: usize) -> anyhow::Result<FileInfo> {
+    /// `source_path` is where to read it from (`remote_io::resolve_source`, resolved by the
+    /// caller without holding the tab lock); `config` supplies the external tool paths (unrar, 7-Zip).
+    pub fn open_file(
+        &mut self,
+        path: &str,
+        source_path: &Path,
+        last_position: usize,
+        last_scroll_offset: usize,
+        config: &AppConfig,
+    ) -> anyhow::Result<FileInfo> {
         // If already open, update last_position and switch to it
         if self.tabs.contains_key(path) {
             self.set_last_position(path, last_position, last_scroll_offset);
@@ -80,51 +180,61 @@ impl TabManager {
         }
 
         let file_path = PathBuf::from(path);
-        if !file_path.exists() {
+        if !source_path.exists() {
             anyhow::bail!("File not found: {}", path);
         }
 
         // Directory → open as image folder
-        if file_path.is_dir() {
-            return self.open_image_directory(path, &file_path, last_position, last_scroll_offset);
+        if source_path.is_dir() {
+            return self.open_image_directory(path, source_path, last_position, last_scroll_offset);
         }
 
-        let ext = file_path
+        let ext = source_path
             .extension()
             .map(|e| e.to_string_lossy().to_lowercase())
             .unwrap_or_default();
 
-        if ext == "epub" {
-            self.open_epub(path, &file_path, last_position, last_scroll_offset)
+        let info = if ext == "epub" {
+            self.open_epub(path, source_path, last_position, last_scroll_offset)
         } else if ext == "pdf" {
-            self.open_pdf(path, &file_path, last_position, last_scroll_offset)
-        } else if crate::image_reader::is_image_extension(&ext) || ext == "zip" {
-            self.open_image(path, &file_path, last_position, last_scroll_offset)
+            self.open_pdf(path, source_path, last_position, last_scroll_offset)
+        } else if crate::image_reader::is_image_extension(&ext)
+            || crate::image_reader::is_image_archive_extension(&ext)
+        {
+            self.open_image(path, source_path, last_position, last_scroll_offset, config)
+        } else if crate::hex_view::is_binary_file(source_path)? {
+            self.open_binary(path, source_path, last_position, last_scroll_offset)
         } else {
-            self.open_text(path, &file_path, last_position, last_scroll_offset)
+            self.open_text(path, source_path, last_position, last_scroll_offset)
+        }?;
+
+        // Read from the local copy, but keep the original path as the tab's identity
+        if source_path != file_path {
+            if let Some(tab) = self.tabs.get_mut(path) {
+                tab.path = file_path;
+            }
         }
+        Ok(info)
     }
 
     fn open_text(
         &mut self,
         path: &str,
-        file_path: &PathBuf,
+        file_path: &Path,
         last_position: usize,
         last_scroll_offset: usize,
     ) -> anyhow::Result<FileInfo> {
-        let buffer = TextBuffer::from_file(file_path)?;
+        let buffer = load_text_buffer(&mut self.warm_cache, file_path, None)?;
         let total_lines = buffer.get_total_lines();
         let total_chars = buffer.get_total_chars();
+        let read_only = buffer.is_read_only();
+        let encoding = buffer.encoding_name().to_string();
+        let line_ending = buffer.line_ending().label().to_string();
+        let total_pages = None;
 
         let tab = Tab {
-            path: file_path.clone(),
             buffer: Some(buffer),
-            epub_book: None,
-            image_source: None,
-            last_position,
-            last_scroll_offset,
-            is_modified: false,
-            file_type: FileType::Text,
+            ..Tab::new(file_path, FileType::Text, last_position, last_scroll_offset)
         };
 
         let file_name = file_path
@@ -143,23 +253,56 @@ impl TabManager {
             total_chars,
             last_position,
             last_scroll_offset,
-            is_modified: false,
             file_type: "text".to_string(),
-            total_chapters: 0,
-            total_images: 0,
-            initial_image_name: String::new(),
+            read_only,
+            encoding,
+            line_ending,
+            total_pages,
+            ..Default::default()
         })
     }
 
     fn open_epub(
         &mut self,
         path: &str,
-        file_path: &PathBuf,
+        file_path: &Path,
         last_position: usize,
         last_scroll_offset: usize,
     ) -> anyhow::Result<FileInfo> {
-        let epub_book = crate::epub_reader::parse_epub(file_path)?;
-        let total_chapters = epub_book.total_chapters();
+        // Warm start: reuse the cached chapter list and defer parsing until
+        // the first chapter is actually requested.
+        let (epub_book, chapter_infos, epub_layout) = match self.warm_cache.get(file_path) {
+            Some(WarmMeta::Epub {
+                chapters,
+                writing_mode,
+                page_direction,
+            }) => (
+                None,
+                chapters,
+                EpubLayout {
+                    writing_mode,
+                    page_direction,
+                },
+            ),
+            _ => {
+                let book = crate::epub_reader::parse_epub(file_path)?;
+                let chapters = book.get_chapter_infos();
+                let layout = EpubLayout {
+                    writing_mode: book.writing_mode.clone(),
+                    page_direction: book.page_direction.clone(),
+                };
+                self.warm_cache.put(
+                    file_path,
+                    WarmMeta::Epub {
+                        chapters: chapters.clone(),
+                        writing_mode: layout.writing_mode.clone(),
+                        page_direction: layout.page_direction.clone(),
+                    },
+                );
+                (Some(book), chapters, layout)
+            }
+        };
+        let total_chapters = chapter_infos.len();
 
         let file_name = file_path
             .file_name()
@@ -167,14 +310,10 @@ impl TabManager {
             .unwrap_or_else(|| path.to_string());
 
         let tab = Tab {
-            path: file_path.clone(),
-            buffer: None,
-            epub_book: Some(epub_book),
-            image_source: None,
-            last_position,
-            last_scroll_offset,
-            is_modified: false,
-            file_type: FileType::Epub,
+            epub_book,
+            chapter_infos,
+            epub_layout: epub_layout.clone(),
+            ..Tab::new(file_path, FileType::Epub, last_position, last_scroll_offset)
         };
 
         self.tabs.insert(path.to_string(), tab);
@@ -184,22 +323,19 @@ impl TabManager {
             id: path.to_string(),
             name: file_name,
             path: path.to_string(),
-            total_lines: 0,
-            total_chars: 0,
             last_position,
             last_scroll_offset,
-            is_modified: false,
             file_type: "epub".to_string(),
             total_chapters,
-            total_images: 0,
-            initial_image_name: String::new(),
+            epub_layout,
+            ..Default::default()
         })
     }
 
     fn open_pdf(
         &mut self,
         path: &str,
-        file_path: &PathBuf,
+        file_path: &Path,
         last_position: usize,
         last_scroll_offset: usize,
     ) -> anyhow::Result<FileInfo> {
@@ -208,16 +344,37 @@ impl TabManager {
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_else(|| path.to_string());
 
-        let tab = Tab {
-            path: file_path.clone(),
-            buffer: None,
-            epub_book: None,
-            image_source: None,
+        let tab = Tab::new(file_path, FileType::Pdf, last_position, last_scroll_offset);
+
+        self.tabs.insert(path.to_string(), tab);
+        self.active_tab = Some(path.to_string());
+
+        Ok(FileInfo {
+            id: path.to_string(),
+            name: file_name,
+            path: path.to_string(),
             last_position,
             last_scroll_offset,
-            is_modified: false,
-            file_type: FileType::Pdf,
-        };
+            file_type: "pdf".to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn open_binary(
+        &mut self,
+        path: &str,
+        file_path: &Path,
+        last_position: usize,
+        last_scroll_offset: usize,
+    ) -> anyhow::Result<FileInfo> {
+        let file_name = file_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        // 헥스 뷰에서는 줄 = 16바이트 행
+        let (total_lines, total_chars) = binary_size(file_path)?;
+
+        let tab = Tab::new(file_path, FileType::Binary, last_position, last_scroll_offset);
 
         self.tabs.insert(path.to_string(), tab);
         self.active_tab = Some(path.to_string());
@@ -226,15 +383,13 @@ impl TabManager {
             id: path.to_string(),
             name: file_name,
             path: path.to_string(),
-            total_lines: 0,
-            total_chars: 0,
+            total_lines,
+            total_chars,
             last_position,
             last_scroll_offset,
-            is_modified: false,
-            file_type: "pdf".to_string(),
-            total_chapters: 0,
-            total_images: 0,
-            initial_image_name: String::new(),
+            file_type: "binary".to_string(),
+            read_only: true,
+            ..Default::default()
         })
     }
 
@@ -244,6 +399,9 @@ impl TabManager {
             .tabs
             .remove(id)
             .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", id))?;
+        if let Some(buffer) = tab.buffer.as_ref() {
+            remember_text_totals(&mut self.warm_cache, &tab.source_path, buffer);
+        }
 
         let last_position = tab.last_position;
         let last_scroll_offset = tab.last_scroll_offset;
@@ -263,8 +421,14 @@ impl TabManager {
             if prev_id != id {
                 let prev_id_clone = prev_id.clone();
                 if let Some(prev_tab) = self.tabs.get_mut(&prev_id_clone) {
-                    if matches!(prev_tab.file_type, FileType::Text) && !prev_tab.is_modified {
-                        prev_tab.buffer = None;
+                    // 챕터 편집 탭은 파일에서 다시 읽을 수 없으므로 버퍼를 유지한다
+                    if matches!(prev_tab.file_type, FileType::Text)
+                        && !prev_tab.is_modified
+                        && prev_tab.epub_entry.is_none()
+                    {
+                        if let Some(buffer) = prev_tab.buffer.take() {
+                            remember_text_totals(&mut self.warm_cache, &prev_tab.source_path, &buffer);
+                        }
                     }
                 }
             }
@@ -277,7 +441,7 @@ impl TabManager {
 
         // Lazy-load rope if needed (text files only)
         if matches!(tab.file_type, FileType::Text) && tab.buffer.is_none() {
-            tab.buffer = Some(TextBuffer::from_file(&tab.path)?);
+            tab.buffer = Some(load_text_buffer(&mut self.warm_cache, &tab.source_path, tab.encoding_override)?);
         }
 
         let (total_lines, total_chars, total_chapters, total_images, file_type_str) = match tab.file_type {
@@ -291,16 +455,13 @@ impl TabManager {
                     "text".to_string(),
                 )
             }
-            FileType::Epub => {
-                let chapters = tab
-                    .epub_book
-                    .as_ref()
-                    .map(|b| b.total_chapters())
-                    .unwrap_or(0);
-                (0, 0, chapters, 0, "epub".to_string())
-            }
+            FileType::Epub => (0, 0, tab.chapter_infos.len(), 0, "epub".to_string()),
             FileType::Pdf => (0, 0, 0, 0, "pdf".to_string()),
-            FileType::Image => {
+            FileType::Binary => {
+                let (rows, bytes) = binary_size(&tab.source_path)?;
+                (rows, bytes, 0, 0, "binary".to_string())
+            }
+            FileType::Image | FileType::Comic => {
                 let count = tab
                     .image_source
                     .as_ref()
@@ -313,6 +474,14 @@ impl TabManager {
         let last_position = tab.last_position;
         let last_scroll_offset = tab.last_scroll_offset;
         let is_modified = tab.is_modified;
+        let read_only = matches!(tab.file_type, FileType::Binary)
+            || tab.buffer.as_ref().is_some_and(|b| b.is_read_only());
+        let (encoding, line_ending) = tab
+            .buffer
+            .as_ref()
+            .map(|b| (b.encoding_name().to_string(), b.line_ending().label().to_string()))
+            .unwrap_or_default();
+        let total_pages = refresh_page_map(tab).map(PageMap::len);
         let path_str = tab.path.to_string_lossy().to_string();
         let name = tab
             .path
@@ -334,10 +503,39 @@ impl TabManager {
             file_type: file_type_str,
             total_chapters,
             total_images,
-            initial_image_name: String::new(),
+            read_only,
+            encoding,
+            line_ending,
+            total_pages,
+            epub_layout: tab.epub_layout.clone(),
+            pdf_view: tab.pdf_view.clone(),
+            is_comic: matches!(tab.file_type, FileType::Comic),
+            reading_direction: tab.reading_direction,
+            ..Default::default()
         })
     }
 
+    /// Reload a text tab decoded with `encoding`, keeping its id and reading position.
+    pub fn reopen_with_encoding(&mut self, id: &str, encoding: &'static Encoding) -> anyhow::Result<FileInfo> {
+        if self.tabs.get(id).is_some_and(|tab| tab.epub_entry.is_some()) {
+            anyhow::bail!("EPUB chapters are always edited as UTF-8");
+        }
+        let tab = self
+            .tabs
+            .get_mut(id)
+            .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", id))?;
+        if !matches!(tab.file_type, FileType::Text) {
+            anyhow::bail!("Not a text file: {}", id);
+        }
+        if tab.is_modified {
+            anyhow::bail!("Save or discard changes before reopening with another encoding");
+        }
+        tab.buffer = Some(load_text_buffer(&mut self.warm_cache, &tab.source_path, Some(encoding))?);
+        tab.encoding_override = Some(encoding);
+        tab.revision += 1;
+        self.switch_tab(id)
+    }
+
     /// Get info about all open tabs.
     pub fn get_open_tabs(&self) -> Vec<TabInfo> {
         self.tabs
@@ -352,7 +550,8 @@ impl TabManager {
                     FileType::Text => "text",
                     FileType::Epub => "epub",
                     FileType::Pdf => "pdf",
-                    FileType::Image => "image",
+                    FileType::Image | FileType::Comic => "image",
+                    FileType::Binary => "binary",
                 };
                 TabInfo {
                     id: id.clone(),
@@ -394,6 +593,101 @@ impl TabManager {
         })
     }
 
+    /// Get the text between two char offsets with the lines it spans.
+    pub fn get_text_range(&self, file_id: &str, start_char: usize, end_char: usize) -> anyhow::Result<TextRange> {
+        let buffer = self.get_buffer(file_id)?;
+        if buffer.is_read_only() {
+            anyhow::bail!("Char ranges are not available for files opened read-only");
+        }
+        let rope = buffer.rope();
+        let end = end_char.min(rope.len_chars());
+        let start = start_char.min(end);
+        let start_line = rope.char_to_line(start);
+        let end_line = if end > start { rope.char_to_line(end - 1) } else { start_line };
+        Ok(TextRange {
+            text: rope.slice(start..end).to_string(),
+            start_char: start,
+            end_char: end,
+            start_line,
+            end_line,
+        })
+    }
+
+    /// Hex-dump rows of a tab's file (16 bytes per row).
+    pub fn get_hex_chunk(&self, file_id: &str, start_row: usize, end_row: usize) -> anyhow::Result<HexChunk> {
+        let tab = self
+            .tabs
+            .get(file_id)
+            .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
+        crate::hex_view::read_chunk(&tab.source_path, start_row, end_row)
+    }
+
+    /// Get a page of about `chars_per_page` chars, turning on the book page mode for the tab.
+    pub fn get_page(&mut self, file_id: &str, page_index: usize, chars_per_page: usize) -> anyhow::Result<Page> {
+        let tab = self
+            .tabs
+            .get_mut(file_id)
+            .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
+        let buffer = tab
+            .buffer
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Buffer not loaded for tab: {}", file_id))?;
+        if buffer.is_read_only() {
+            anyhow::bail!("Page mode is not available for files opened read-only");
+        }
+        let chars_per_page = chars_per_page.max(pagination::MIN_CHARS_PER_PAGE);
+        let stale = tab
+            .page_map
+            .as_ref()
+            .map_or(true, |map| map.chars_per_page != chars_per_page || map.revision != tab.revision);
+        if stale {
+            tab.page_map = Some(PageMap::build(buffer.rope(), chars_per_page, tab.revision));
+        }
+        let map = tab.page_map.as_ref().ok_or_else(|| anyhow::anyhow!("Page map not built"))?;
+        let (start, end) = map
+            .range(page_index)
+            .ok_or_else(|| anyhow::anyhow!("Page index out of range: {}", page_index))?;
+        let rope = buffer.rope();
+        Ok(Page {
+            index: page_index,
+            text: rope.slice(start..end).to_string(),
+            start_char: start,
+            end_char: end,
+            start_line: rope.char_to_line(start),
+            total_pages: map.len(),
+        })
+    }
+
+    /// Turn the book page mode off again (FileInfo stops reporting `total_pages`).
+    pub fn exit_page_mode(&mut self, file_id: &str) {
+        if let Some(tab) = self.tabs.get_mut(file_id) {
+            tab.page_map = None;
+        }
+    }
+
+    /// Append text read from disk in follow mode. Returns the changed lines, or None when
+    /// the buffer is unloaded (it will be re-read with the new data anyway).
+    pub fn append_from_disk(&mut self, file_id: &str, text: &str) -> anyhow::Result<Option<TextChunk>> {
+        let tab = self
+            .tabs
+            .get_mut(file_id)
+            .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
+        let Some(buffer) = tab.buffer.as_mut() else {
+            return Ok(None);
+        };
+        // 마지막 줄이 줄바꿈 없이 끝났으면 그 줄부터 바뀐다
+        let start_line = buffer.get_total_lines().saturating_sub(1);
+        buffer.append_from_disk(text);
+        tab.revision += 1;
+        let total_lines = buffer.get_total_lines();
+        Ok(Some(TextChunk {
+            lines: buffer.get_chunk(start_line, total_lines),
+            start_line,
+            end_line: total_lines,
+            total_lines,
+        }))
+    }
+
     /// Get total lines for a file.
     pub fn get_total_lines(&self, file_id: &str) -> anyhow::Result<usize> {
         let tab = self
@@ -413,9 +707,14 @@ impl TabManager {
             .tabs
             .get_mut(file_id)
             .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
-        tab.buffer
+        let buffer = tab
+            .buffer
             .as_mut()
-            .ok_or_else(|| anyhow::anyhow!("Buffer not loaded for tab: {}", file_id))
+            .ok_or_else(|| anyhow::anyhow!("Buffer not loaded for tab: {}", file_id))?;
+        if buffer.is_read_only() {
+            anyhow::bail!("File is too large to edit and was opened read-only: {}", file_id);
+        }
+        Ok(buffer)
     }
 
     /// Get an immutable reference to a tab's buffer.
@@ -429,13 +728,29 @@ impl TabManager {
             .ok_or_else(|| anyhow::anyhow!("Buffer not loaded for tab: {}", file_id))
     }
 
-    /// Mark a tab as modified.
+    /// The rope of a text tab. Huge files opened read-only have an empty rope, so
+    /// rope-based features (search, chapters, export...) fail for them instead of finding nothing.
+    pub fn get_rope(&self, file_id: &str) -> anyhow::Result<&Rope> {
+        let buffer = self.get_buffer(file_id)?;
+        if buffer.is_read_only() {
+            anyhow::bail!("Not available for files opened read-only (too large): {}", file_id);
+        }
+        Ok(buffer.rope())
+    }
+
+    /// Record an edit: sets the modified flag and bumps the revision.
     pub fn set_modified(&mut self, file_id: &str, modified: bool) {
         if let Some(tab) = self.tabs.get_mut(file_id) {
             tab.is_modified = modified;
+            tab.revision += 1;
         }
     }
 
+    /// (revision, is_modified) for a tab.
+    pub fn get_revision(&self, file_id: &str) -> Option<(u64, bool)> {
+        self.tabs.get(file_id).map(|tab| (tab.revision, tab.is_modified))
+    }
+
     /// Save the file for a tab.
     pub fn save_file(&mut self, file_id: &str) -> anyhow::Result<()> {
         let tab = self
@@ -443,15 +758,127 @@ impl TabManager {
             .get_mut(file_id)
             .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
         let path = tab.path.clone();
+        if let Some(entry) = tab.epub_entry.clone() {
+            let buffer = tab
+                .buffer
+                .as_mut()
+                .ok_or_else(|| anyhow::anyhow!("Buffer not loaded for tab: {}", file_id))?;
+            let data = buffer.to_string_full().into_bytes();
+            let epub_path = path.clone();
+            crate::remote_io::transfer(&path, move || {
+                crate::epub_edit::replace_entry(&epub_path, &entry, &data)
+            })?;
+            buffer.mark_saved();
+            tab.is_modified = false;
+            // 같은 책을 보고 있는 탭은 (새 캐시 복사본에서) 다시 파싱해서 챕터 목록·글자 수·
+            // 세로쓰기 여부와 웜 캐시를 새로 고친다
+            let mut refreshed: Option<(PathBuf, Option<EpubBook>)> = None;
+            for other in self.tabs.values_mut() {
+                if !matches!(other.file_type, FileType::Epub) || other.path != path {
+                    continue;
+                }
+                let (source, book) = refreshed.get_or_insert_with(|| {
+                    let source = refreshed_source(&path, &other.source_path);
+                    let book = crate::epub_reader::parse_epub(&source)
+                        .map_err(|e| log::warn!("Failed to re-parse {}: {}", source.display(), e))
+                        .ok();
+                    (source, book)
+                });
+                other.source_path = source.clone();
+                other.epub_book = None;
+                if let Some(book) = book.as_ref() {
+                    other.chapter_infos = book.get_chapter_infos();
+                    other.epub_layout = EpubLayout {
+                        writing_mode: book.writing_mode.clone(),
+                        page_direction: book.page_direction.clone(),
+                    };
+                }
+            }
+            if let Some((source, Some(book))) = refreshed {
+                self.warm_cache.put(
+                    &source,
+                    WarmMeta::Epub {
+                        chapters: book.get_chapter_infos(),
+                        writing_mode: book.writing_mode.clone(),
+                        page_direction: book.page_direction.clone(),
+                    },
+                );
+                // 탭 id가 경로이므로 책 탭은 보통 하나다: 파싱한 책을 그대로 넘긴다
+                if let Some(other) = self
+                    .tabs
+                    .values_mut()
+                    .find(|other| matches!(other.file_type, FileType::Epub) && other.path == path)
+                {
+                    other.epub_book = Some(book);
+                }
+            }
+            return Ok(());
+        }
         if let Some(buffer) = tab.buffer.as_mut() {
-            buffer.save(&path)?;
+            save_text(buffer, &path)?;
             tab.is_modified = false;
+            tab.source_path = refreshed_source(&path, &tab.source_path);
         } else {
             anyhow::bail!("Buffer not loaded for tab: {}", file_id);
         }
         Ok(())
     }
 
+    /// Save a text tab to a new path and rebind the tab to it. Returns the new tab id.
+    pub fn save_file_as(
+        &mut self,
+        file_id: &str,
+        new_path: &Path,
+        encoding: Option<&'static Encoding>,
+        bom: Option<bool>,
+        line_ending: Option<LineEnding>,
+    ) -> anyhow::Result<String> {
+        let new_id = new_path.to_string_lossy().to_string();
+        if new_id != file_id && self.tabs.contains_key(&new_id) {
+            anyhow::bail!("File is already open in another tab: {}", new_id);
+        }
+        let tab = self
+            .tabs
+            .get_mut(file_id)
+            .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
+        let buffer = tab
+            .buffer
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Buffer not loaded for tab: {}", file_id))?;
+        let previous = buffer.save_format();
+        buffer.set_save_format(encoding, bom, line_ending);
+        // 기존 줄바꿈도 고른 형식으로 바꿔야 CRLF 파일을 LF로 저장할 수 있다
+        let converted = line_ending.map_or(0, |target| {
+            buffer.bulk_replace(crate::formatter::normalize_edits(buffer.rope(), Some(target), false))
+        });
+        if let Err(e) = save_text(buffer, new_path) {
+            buffer.restore_save_format(previous);
+            if converted > 0 {
+                buffer.undo();
+            }
+            return Err(e);
+        }
+        // "다른 이름으로 저장"한 챕터는 평범한 텍스트 파일이 된다
+        tab.epub_entry = None;
+        if tab.encoding_override.is_some() || encoding.is_some() {
+            // 다시 읽을 때도 저장한 인코딩으로 읽는다
+            tab.encoding_override = Some(buffer.encoding());
+        }
+        tab.is_modified = false;
+        tab.path = new_path.to_path_buf();
+        tab.source_path = refreshed_source(new_path, &tab.source_path);
+
+        if new_id != file_id {
+            if let Some(tab) = self.tabs.remove(file_id) {
+                self.tabs.insert(new_id.clone(), tab);
+            }
+            if self.active_tab.as_deref() == Some(file_id) {
+                self.active_tab = Some(new_id.clone());
+            }
+        }
+        Ok(new_id)
+    }
+
     /// Update the last reading position for a tab.
     pub fn set_last_position(&mut self, file_id: &str, position: usize, scroll_offset: usize) {
         if let Some(tab) = self.tabs.get_mut(file_id) {
@@ -460,7 +887,41 @@ impl TabManager {
         }
     }
 
+    /// Set the zoom / fit mode of a PDF tab.
+    pub fn set_pdf_view(&mut self, file_id: &str, view: Option<PdfViewState>) {
+        if let Some(tab) = self.tabs.get_mut(file_id) {
+            tab.pdf_view = view;
+        }
+    }
+
+    pub fn get_pdf_view(&self, file_id: &str) -> Option<PdfViewState> {
+        self.tabs.get(file_id).and_then(|tab| tab.pdf_view.clone())
+    }
+
+    pub fn set_reading_direction(&mut self, file_id: &str, direction: ReadingDirection) {
+        if let Some(tab) = self.tabs.get_mut(file_id) {
+            tab.reading_direction = direction;
+        }
+    }
+
+    pub fn get_reading_direction(&self, file_id: &str) -> ReadingDirection {
+        self.tabs
+            .get(file_id)
+            .map(|tab| tab.reading_direction)
+            .unwrap_or_default()
+    }
+
+    /// Current reading position of a tab (line, chapter or image index).
+    pub fn get_last_position(&self, file_id: &str) -> Option<usize> {
+        self.tabs.get(file_id).map(|tab| tab.last_position)
+    }
+
     /// Get the file path for a tab.
+    /// Whether the tab edits a chapter of an EPUB (its path is the .epub, not a text file).
+    pub fn is_epub_chapter_editor(&self, file_id: &str) -> bool {
+        self.tabs.get(file_id).is_some_and(|tab| tab.epub_entry.is_some())
+    }
+
     pub fn get_file_path(&self, file_id: &str) -> anyhow::Result<PathBuf> {
         let tab = self
             .tabs
@@ -469,62 +930,195 @@ impl TabManager {
         Ok(tab.path.clone())
     }
 
-    /// Get EPUB chapter HTML by index.
-    pub fn get_epub_chapter_html(
-        &self,
-        file_id: &str,
-        chapter_index: usize,
-    ) -> anyhow::Result<String> {
+    /// Get the path to read file contents from (local copy for cached remote files).
+    pub fn get_source_path(&self, file_id: &str) -> anyhow::Result<PathBuf> {
         let tab = self
             .tabs
             .get(file_id)
             .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
-        let epub_book = tab
-            .epub_book
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Not an EPUB file: {}", file_id))?;
-        epub_book
-            .get_chapter_html(chapter_index)
-            .ok_or_else(|| anyhow::anyhow!("Chapter {} not found", chapter_index))
+        Ok(tab.source_path.clone())
     }
 
-    /// Get EPUB font styles (@font-face CSS).
-    pub fn get_epub_font_styles(&self, file_id: &str) -> anyhow::Result<String> {
+    /// Source path of a PDF tab (errors for other file types).
+    pub fn get_pdf_source_path(&self, file_id: &str) -> anyhow::Result<PathBuf> {
         let tab = self
             .tabs
             .get(file_id)
             .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
-        let epub_book = tab
-            .epub_book
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Not an EPUB file: {}", file_id))?;
-        Ok(epub_book.font_styles.clone())
+        if !matches!(tab.file_type, FileType::Pdf) {
+            anyhow::bail!("Not a PDF file: {}", file_id);
+        }
+        Ok(tab.source_path.clone())
     }
 
-    /// Get EPUB chapter info list.
-    pub fn get_epub_chapter_infos(
-        &self,
+    /// Get the parsed EPUB book for a tab, parsing it first if it was opened warm.
+    fn epub_book_mut(&mut self, file_id: &str) -> anyhow::Result<&mut EpubBook> {
+        let tab = self
+            .tabs
+            .get_mut(file_id)
+            .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
+        if !matches!(tab.file_type, FileType::Epub) {
+            anyhow::bail!("Not an EPUB file: {}", file_id);
+        }
+        if tab.epub_book.is_none() {
+            let book = crate::epub_reader::parse_epub(&tab.source_path)?;
+            tab.chapter_infos = book.get_chapter_infos();
+            tab.epub_book = Some(book);
+        }
+        Ok(tab.epub_book.as_mut().unwrap())
+    }
+
+    /// Get EPUB chapter HTML by index.
+    pub fn get_epub_chapter_html(
+        &mut self,
         file_id: &str,
-    ) -> anyhow::Result<Vec<crate::epub_reader::ChapterInfo>> {
+        chapter_index: usize,
+    ) -> anyhow::Result<String> {
+        self.epub_book_mut(file_id)?
+            .get_chapter_html(chapter_index)
+            .ok_or_else(|| anyhow::anyhow!("Chapter {} not found", chapter_index))
+    }
+
+    /// Open chapter `index` of an EPUB tab as an editable text tab holding the chapter's
+    /// XHTML source (id `<epub path>#chapter<index>`). Saving writes it back into the .epub.
+    pub fn open_epub_chapter_editor(&mut self, file_id: &str, index: usize) -> anyhow::Result<FileInfo> {
+        let path = self.get_file_path(file_id)?;
+        let (entry, title) = {
+            let chapter = self
+                .epub_book_mut(file_id)?
+                .chapters
+                .get(index)
+                .ok_or_else(|| anyhow::anyhow!("Chapter index out of range: {}", index))?;
+            (chapter.path.replace('\\', "/"), chapter.title.clone())
+        };
+        let id = format!("{}#chapter{}", path.to_string_lossy(), index);
+
+        if !self.tabs.contains_key(&id) {
+            let text = crate::epub_edit::read_entry_text(&path, &entry)?;
+            let tab = Tab {
+                buffer: Some(TextBuffer::from_string(&text)),
+                epub_entry: Some(entry),
+                ..Tab::new(&path, FileType::Text, 0, 0)
+            };
+            self.tabs.insert(id.clone(), tab);
+        }
+
+        let mut info = self.switch_tab(&id)?;
+        let book_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        info.name = format!("{} - {}", book_name, title);
+        Ok(info)
+    }
+
+    /// Render jobs for the chapters next to `index` (N+1, then N-1) that aren't cached yet.
+    pub fn epub_prefetch_jobs(&self, file_id: &str, index: usize) -> Vec<ChapterRenderJob> {
+        let Some(book) = self.tabs.get(file_id).and_then(|tab| tab.epub_book.as_ref()) else {
+            return Vec::new();
+        };
+        [Some(index + 1), index.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .filter_map(|i| book.render_job(i))
+            .collect()
+    }
+
+    /// Put a prefetched chapter into the book's cache (no-op if the tab was closed).
+    pub fn store_prefetched_chapter(&mut self, file_id: &str, job: &ChapterRenderJob, html: String) {
+        if let Some(book) = self.tabs.get_mut(file_id).and_then(|tab| tab.epub_book.as_mut()) {
+            book.insert_rendered(job, html);
+        }
+    }
+
+    /// Get EPUB chapter as plain text by index.
+    pub fn get_epub_chapter_text(
+        &mut self,
+        file_id: &str,
+        chapter_index: usize,
+    ) -> anyhow::Result<String> {
+        self.epub_book_mut(file_id)?
+            .get_chapter_text(chapter_index)
+            .ok_or_else(|| anyhow::anyhow!("Chapter {} not found", chapter_index))
+    }
+
+    /// Shared handle to the book's chapters (raw sources), parsing the book if needed.
+    pub fn get_epub_chapters(&mut self, file_id: &str) -> anyhow::Result<Arc<Vec<EpubChapter>>> {
+        Ok(Arc::clone(&self.epub_book_mut(file_id)?.chapters))
+    }
+
+    /// Resolve an internal link to its chapter index and fragment.
+    pub fn resolve_epub_link(
+        &mut self,
+        file_id: &str,
+        href: &str,
+        from_chapter: Option<usize>,
+    ) -> anyhow::Result<EpubLinkTarget> {
+        self.epub_book_mut(file_id)?
+            .resolve_link(href, from_chapter)
+            .ok_or_else(|| anyhow::anyhow!("Link target not found in book: {}", href))
+    }
+
+    /// Plain text of the whole book (see `EpubBook::to_plain_text`).
+    pub fn get_epub_plain_text(&mut self, file_id: &str) -> anyhow::Result<String> {
+        Ok(self.epub_book_mut(file_id)?.to_plain_text())
+    }
+
+    /// Get EPUB font styles (@font-face CSS). Fonts are referenced by URL, not inlined.
+    pub fn get_epub_font_styles(&mut self, file_id: &str) -> anyhow::Result<String> {
+        let base_url = crate::epub_fonts::font_base_url(file_id);
+        Ok(self.epub_book_mut(file_id)?.font_styles_for(&base_url))
+    }
+
+    /// Get an embedded EPUB font (mime, bytes) for the font protocol.
+    pub fn get_epub_font(&mut self, file_id: &str, index: usize) -> anyhow::Result<(&'static str, Vec<u8>)> {
+        let font = self
+            .epub_book_mut(file_id)?
+            .get_font(index)
+            .ok_or_else(|| anyhow::anyhow!("Font {} not found", index))?;
+        Ok((font.mime, font.data.clone()))
+    }
+
+    /// Get EPUB chapter info list (served from the warm cache if the book isn't parsed yet).
+    pub fn get_epub_chapter_infos(&self, file_id: &str) -> anyhow::Result<Vec<ChapterInfo>> {
         let tab = self
             .tabs
             .get(file_id)
             .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", file_id))?;
-        let epub_book = tab
-            .epub_book
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Not an EPUB file: {}", file_id))?;
-        Ok(epub_book.get_chapter_infos())
+        if !matches!(tab.file_type, FileType::Epub) {
+            anyhow::bail!("Not an EPUB file: {}", file_id);
+        }
+        Ok(tab.chapter_infos.clone())
     }
 
     fn open_image_directory(
         &mut self,
         path: &str,
-        dir_path: &PathBuf,
+        dir_path: &Path,
         last_position: usize,
         last_scroll_offset: usize,
     ) -> anyhow::Result<FileInfo> {
-        let (dir, image_paths) = crate::image_reader::scan_directory_images(dir_path)?;
+        let (dir, image_paths) = match self.warm_cache.get(dir_path) {
+            // 캐시된 목록에도 현재 제외 패턴을 다시 적용한다 (이전 버전이 남긴 목록, 바뀐 설정)
+            Some(WarmMeta::ImageFolder { images }) => (
+                dir_path.to_path_buf(),
+                images
+                    .iter()
+                    .filter(|m| !crate::image_reader::is_junk_entry(&m.name))
+                    .map(|m| dir_path.join(&m.name))
+                    .collect(),
+            ),
+            _ => {
+                let (dir, image_paths) = crate::image_reader::scan_directory_images(dir_path)?;
+                self.warm_cache.put(
+                    dir_path,
+                    WarmMeta::ImageFolder {
+                        images: crate::warm_cache::folder_image_metas(&image_paths),
+                    },
+                );
+                (dir, image_paths)
+            }
+        };
         if image_paths.is_empty() {
             anyhow::bail!("No image files found in directory: {}", dir_path.display());
         }
@@ -541,14 +1135,8 @@ impl TabManager {
             .unwrap_or_else(|| path.to_string());
 
         let tab = Tab {
-            path: dir_path.clone(),
-            buffer: None,
-            epub_book: None,
             image_source: Some(image_source),
-            last_position,
-            last_scroll_offset,
-            is_modified: false,
-            file_type: FileType::Image,
+            ..Tab::new(dir_path, FileType::Image, last_position, last_scroll_offset)
         };
 
         self.tabs.insert(path.to_string(), tab);
@@ -558,42 +1146,87 @@ impl TabManager {
             id: path.to_string(),
             name: dir_name,
             path: path.to_string(),
-            total_lines: 0,
-            total_chars: 0,
             last_position,
             last_scroll_offset,
-            is_modified: false,
             file_type: "image".to_string(),
-            total_chapters: 0,
             total_images,
-            initial_image_name: String::new(),
+            ..Default::default()
         })
     }
 
     fn open_image(
         &mut self,
         path: &str,
-        file_path: &PathBuf,
+        file_path: &Path,
         last_position: usize,
         last_scroll_offset: usize,
+        config: &AppConfig,
     ) -> anyhow::Result<FileInfo> {
         let ext = file_path
             .extension()
             .map(|e| e.to_string_lossy().to_lowercase())
             .unwrap_or_default();
 
-        let (image_source, initial_position) = if ext == "zip" {
-            let entries = crate::image_reader::list_zip_images(file_path)?;
+        let (image_source, initial_position) = if ext == "zip" || ext == "cbz" {
+            // 목록이 웜 캐시에 있어도 central directory는 탭이 닫힐 때까지 한 번 열어 둔다
+            let index = std::sync::Arc::new(crate::zip_fast::ZipIndex::open(file_path)?);
+            let entries = match self.warm_cache.get(file_path) {
+                Some(WarmMeta::ImageZip { entries }) => entries
+                    .into_iter()
+                    .map(|m| m.name)
+                    .filter(|name| !crate::image_reader::is_junk_entry(name))
+                    .collect(),
+                _ => {
+                    let entries = crate::image_reader::zip_image_entries(&index);
+                    self.warm_cache.put(
+                        file_path,
+                        WarmMeta::ImageZip {
+                            entries: entries
+                                .iter()
+                                .map(|(name, size)| ImageEntryMeta {
+                                    name: name.clone(),
+                                    size: *size,
+                                })
+                                .collect(),
+                        },
+                    );
+                    entries.into_iter().map(|(name, _)| name).collect()
+                }
+            };
             (
                 ImageSource::Zip {
-                    zip_path: file_path.clone(),
+                    zip_path: file_path.to_path_buf(),
                     entry_names: entries,
+                    index,
+                },
+                last_position,
+            )
+        } else if crate::rar_reader::is_rar_extension(&ext) {
+            let unrar = crate::rar_reader::find_unrar(&config.unrar_path)?;
+            let listing = crate::rar_reader::list_images(&unrar, file_path)?;
+            (
+                ImageSource::Rar {
+                    rar_path: file_path.to_path_buf(),
+                    entry_names: listing.entry_names,
+                    unrar,
+                    solid: listing.solid,
+                },
+                last_position,
+            )
+        } else if crate::sevenz_reader::is_7z_extension(&ext) {
+            let tool = crate::sevenz_reader::find_7z(&config.sevenzip_path)?;
+            let listing = crate::sevenz_reader::list_images(&tool, file_path)?;
+            (
+                ImageSource::SevenZip {
+                    archive_path: file_path.to_path_buf(),
+                    entry_names: listing.entry_names,
+                    tool,
+                    solid: listing.solid,
                 },
                 last_position,
             )
         } else {
-            let (dir_path, image_paths, current_index) =
-                crate::image_reader::scan_folder_images(file_path)?;
+            let (dir_path, image_paths, current_index) = self.scan_folder_warm(file_path)?;
             (
                 ImageSource::Folder {
                     dir_path,
@@ -604,20 +1237,20 @@ impl TabManager {
         };
 
         let total_images = image_source.len();
+        let is_comic = crate::image_reader::is_comic_extension(&ext);
         let file_name = file_path
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_else(|| path.to_string());
 
         let tab = Tab {
-            path: file_path.clone(),
-            buffer: None,
-            epub_book: None,
             image_source: Some(image_source),
-            last_position: initial_position,
-            last_scroll_offset,
-            is_modified: false,
-            file_type: FileType::Image,
+            ..Tab::new(
+                file_path,
+                if is_comic { FileType::Comic } else { FileType::Image },
+                initial_position,
+                last_scroll_offset,
+            )
         };
 
         self.tabs.insert(path.to_string(), tab);
@@ -627,18 +1260,48 @@ impl TabManager {
             id: path.to_string(),
             name: file_name.clone(),
             path: path.to_string(),
-            total_lines: 0,
-            total_chars: 0,
             last_position: initial_position,
             last_scroll_offset,
-            is_modified: false,
             file_type: "image".to_string(),
-            total_chapters: 0,
             total_images,
             initial_image_name: file_name,
+            is_comic,
+            ..Default::default()
         })
     }
 
+    /// Scan the parent folder of an image file, reusing the warm cache when the
+    /// folder hasn't changed since the last scan.
+    fn scan_folder_warm(&mut self, file_path: &Path) -> anyhow::Result<(PathBuf, Vec<PathBuf>, usize)> {
+        let dir = file_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Cannot determine parent directory"))?;
+
+        if let Some(WarmMeta::ImageFolder { mut images }) = self.warm_cache.get(dir) {
+            images.retain(|m| !crate::image_reader::is_junk_entry(&m.name));
+            let target_name = file_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let current_index = images
+                .iter()
+                .position(|m| m.name.to_lowercase() == target_name)
+                .unwrap_or(0);
+            let image_paths = images.iter().map(|m| dir.join(&m.name)).collect();
+            return Ok((dir.to_path_buf(), image_paths, current_index));
+        }
+
+        let (dir_path, image_paths, current_index) =
+            crate::image_reader::scan_folder_images(file_path)?;
+        self.warm_cache.put(
+            &dir_path,
+            WarmMeta::ImageFolder {
+                images: crate::warm_cache::folder_image_metas(&image_paths),
+            },
+        );
+        Ok((dir_path, image_paths, current_index))
+    }
+
     /// Get image filename list for the image viewer.
     pub fn get_image_list(&self, file_id: &str) -> anyhow::Result<Vec<String>> {
         let tab = self
@@ -653,7 +1316,7 @@ impl TabManager {
     }
 
     /// Read image bytes at a given index.
-    pub fn get_image_bytes(&self, file_id: &str, index: usize) -> anyhow::Result<Vec<u8>> {
+    pub fn get_image_bytes(&self, file_id: &str, index: usize, imagemagick_path: &str) -> anyhow::Result<Vec<u8>> {
         let tab = self
             .tabs
             .get(file_id)
@@ -662,7 +1325,7 @@ impl TabManager {
             .image_source
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("Not an image file: {}", file_id))?;
-        source.read_bytes(index)
+        source.read_bytes(index, imagemagick_path)
     }
 
     /// Get image source info for cache registration.
@@ -681,9 +1344,33 @@ impl TabManager {
             ImageSource::Zip {
                 zip_path,
                 entry_names,
+                index,
             } => crate::image_cache::ImageSourceInfo::Zip {
                 zip_path: zip_path.clone(),
                 entry_names: entry_names.clone(),
+                index: std::sync::Arc::clone(index),
+            },
+            ImageSource::Rar {
+                rar_path,
+                entry_names,
+                unrar,
+                solid,
+            } => crate::image_cache::ImageSourceInfo::Rar {
+                rar_path: rar_path.clone(),
+                entry_names: entry_names.clone(),
+                unrar: unrar.clone(),
+                solid: *solid,
+            },
+            ImageSource::SevenZip {
+                archive_path,
+                entry_names,
+                tool,
+                solid,
+            } => crate::image_cache::ImageSourceInfo::SevenZip {
+                archive_path: archive_path.clone(),
+                entry_names: entry_names.clone(),
+                tool: tool.clone(),
+                solid: *solid,
             },
         })
     }
@@ -696,4 +1383,135 @@ impl TabManager {
             .map(|s| s.len())
             .unwrap_or(0)
     }
+
+    /// Memory held by text buffers, their undo history and EPUB chapter caches:
+    /// (text_bytes, undo_bytes, epub_cache_bytes).
+    pub fn memory_usage(&self) -> (usize, usize, usize) {
+        let mut text_bytes = 0;
+        let mut undo_bytes = 0;
+        let mut epub_bytes = 0;
+        for tab in self.tabs.values() {
+            if let Some(buffer) = tab.buffer.as_ref() {
+                text_bytes += buffer.rope().len_bytes();
+                undo_bytes += buffer.undo_memory_bytes();
+            }
+            if let Some(book) = tab.epub_book.as_ref() {
+                epub_bytes += book.cache_bytes();
+            }
+        }
+        (text_bytes, undo_bytes, epub_bytes)
+    }
+
+    /// (id, rope, revision) of every modified text tab, for autosave.
+    pub fn modified_text_snapshots(&self) -> Vec<(String, ropey::Rope, u64)> {
+        self.tabs
+            .iter()
+            .filter(|(_, tab)| tab.is_modified)
+            .filter_map(|(id, tab)| {
+                let buffer = tab.buffer.as_ref()?;
+                Some((id.clone(), buffer.rope().clone(), tab.revision))
+            })
+            .collect()
+    }
+
+    /// Release memory held by inactive tabs: unmodified text buffers are unloaded
+    /// (lazy-reloaded on switch) and EPUB chapter caches are cleared.
+    pub fn release_inactive(&mut self) {
+        for (id, tab) in self.tabs.iter_mut() {
+            if self.active_tab.as_deref() == Some(id.as_str()) {
+                continue;
+            }
+            if matches!(tab.file_type, FileType::Text) && !tab.is_modified && tab.epub_entry.is_none() {
+                if let Some(buffer) = tab.buffer.take() {
+                    remember_text_totals(&mut self.warm_cache, &tab.source_path, &buffer);
+                }
+            }
+            if let Some(book) = tab.epub_book.as_mut() {
+                book.clear_chapter_cache();
+            }
+        }
+    }
+
+    /// Clear EPUB chapter caches for all tabs, including the active one.
+    pub fn clear_epub_caches(&mut self) {
+        for tab in self.tabs.values_mut() {
+            if let Some(book) = tab.epub_book.as_mut() {
+                book.clear_chapter_cache();
+            }
+        }
+    }
+}
+
+/// Load a text file; a huge (lazy) file gets its line/char totals from the warm cache
+/// so reopening it does not rescan the whole file.
+fn load_text_buffer(
+    warm_cache: &mut WarmCache,
+    path: &Path,
+    forced: Option<&'static Encoding>,
+) -> anyhow::Result<TextBuffer> {
+    let source = path.to_path_buf();
+    let buffer = crate::remote_io::transfer(path, move || TextBuffer::from_file_with_encoding(&source, forced))?;
+    if let Some(lazy) = buffer.lazy() {
+        if let Some(WarmMeta::Text { encoding, total_lines, total_chars }) = warm_cache.get(path) {
+            if encoding == lazy.encoding().name() {
+                lazy.set_known_totals(total_lines, total_chars);
+            }
+        }
+    }
+    Ok(buffer)
+}
+
+/// Save a text buffer to `path`; network paths are written with a timeout.
+fn save_text(buffer: &mut TextBuffer, path: &Path) -> anyhow::Result<()> {
+    if !crate::remote_io::is_remote_path(path) {
+        return buffer.save(path);
+    }
+    let bytes = buffer.to_file_bytes()?;
+    let target = path.to_path_buf();
+    crate::remote_io::transfer(path, move || {
+        crate::atomic_write::write_atomic(&target, |writer| std::io::Write::write_all(writer, &bytes))
+    })?;
+    buffer.mark_saved();
+    Ok(())
+}
+
+/// Where to read `path` from after it was saved (see `remote_io::refresh_after_save`).
+fn refreshed_source(path: &Path, old_source: &Path) -> PathBuf {
+    crate::remote_io::refresh_after_save(path, old_source).unwrap_or_else(|e| {
+        log::warn!("Failed to refresh the cached copy of {}: {}", path.display(), e);
+        path.to_path_buf()
+    })
+}
+
+/// Store a lazy buffer's totals in the warm cache once its line index has been completed.
+fn remember_text_totals(warm_cache: &mut WarmCache, path: &Path, buffer: &TextBuffer) {
+    let Some(lazy) = buffer.lazy() else {
+        return;
+    };
+    if let Some((total_lines, total_chars)) = lazy.learned_totals() {
+        warm_cache.put(
+            path,
+            WarmMeta::Text {
+                encoding: lazy.encoding().name().to_string(),
+                total_lines,
+                total_chars,
+            },
+        );
+    }
+}
+
+/// (hex rows, bytes) of a binary file.
+fn binary_size(path: &Path) -> anyhow::Result<(usize, usize)> {
+    let bytes = std::fs::metadata(path)?.len() as usize;
+    Ok((bytes.div_ceil(crate::hex_view::BYTES_PER_ROW), bytes))
+}
+
+/// The tab's page map, rebuilt first if the text changed since it was made.
+fn refresh_page_map(tab: &mut Tab) -> Option<&PageMap> {
+    let map = tab.page_map.as_ref()?;
+    if map.revision != tab.revision {
+        let buffer = tab.buffer.as_ref()?;
+        tab.page_map = Some(PageMap::build(buffer.rope(), map.chars_per_page, tab.revision));
+    }
+    tab.page_map.as_ref()
 }
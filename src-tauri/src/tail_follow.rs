@@ -0,0 +1,182 @@
+//! Log-follow mode: watch an open text file and append what other programs write to it.
+//!
+//! 파일 끝에 덧붙은 바이트만 읽어 완성된 줄까지 버퍼 끝에 붙이고 `follow-appended`
+//! 이벤트로 바뀐 줄을 보낸다. 파일이 줄어들면(로그 회전 등) `follow-truncated`를 보낸다.
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::tab_manager::TextChunk;
+use crate::AppState;
+use encoding_rs::Encoding;
+
+/// Writes arriving within this window are read together.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Serialize)]
+pub struct FollowUpdate {
+    pub file_id: String,
+    /// Lines from the first changed line (the old last line may have grown) to the end.
+    #[serde(flatten)]
+    pub chunk: TextChunk,
+}
+
+#[derive(Clone, Serialize)]
+struct FollowTruncated {
+    file_id: String,
+}
+
+pub struct FollowWatcher {
+    watchers: Mutex<HashMap<String, RecommendedWatcher>>,
+}
+
+impl FollowWatcher {
+    pub fn new() -> Self {
+        Self {
+            watchers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start following a text tab from the file's current end.
+    pub fn start<R: Runtime>(&self, app: &AppHandle<R>, file_id: &str) -> anyhow::Result<()> {
+        let (path, encoding) = {
+            let state = app.state::<AppState>();
+            let tab_manager = state
+                .tab_manager
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Tab manager lock poisoned: {}", e))?;
+            if tab_manager.is_epub_chapter_editor(file_id) {
+                anyhow::bail!("Follow mode is not available for EPUB chapters");
+            }
+            let buffer = tab_manager.get_buffer(file_id)?;
+            if buffer.is_read_only() {
+                anyhow::bail!("Follow mode is not available for files opened read-only");
+            }
+            if !buffer.has_byte_line_breaks() {
+                anyhow::bail!("Follow mode is not supported for {} files", buffer.encoding_name());
+            }
+            (tab_manager.get_file_path(file_id)?, buffer.encoding())
+        };
+        let offset = std::fs::metadata(&path)?.len();
+
+        // 파일을 새로 만드는 로그 회전도 잡히도록 상위 폴더를 감시한다
+        let (tx, rx) = mpsc::channel::<()>();
+        let target = path.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                && event.paths.iter().any(|p| p == &target)
+            {
+                let _ = tx.send(());
+            }
+        })?;
+        let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+        let handle = app.clone();
+        let id = file_id.to_string();
+        std::thread::spawn(move || follow_loop(&handle, &id, path, offset, encoding, rx));
+
+        self.watchers
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Watcher lock poisoned: {}", e))?
+            .insert(file_id.to_string(), watcher);
+        Ok(())
+    }
+
+    /// Stop following. Returns false if the tab wasn't being followed.
+    pub fn stop(&self, file_id: &str) -> bool {
+        // watcher를 drop하면 채널이 닫혀 읽기 스레드도 끝난다
+        self.watchers
+            .lock()
+            .map(|mut watchers| watchers.remove(file_id).is_some())
+            .unwrap_or(false)
+    }
+}
+
+/// Read appended data after each batch of change events until the watcher is dropped.
+fn follow_loop<R: Runtime>(
+    app: &AppHandle<R>,
+    file_id: &str,
+    path: PathBuf,
+    mut offset: u64,
+    encoding: &'static Encoding,
+    rx: mpsc::Receiver<()>,
+) {
+    while rx.recv().is_ok() {
+        // 계속 쓰이는 로그라도 DEBOUNCE마다 한 번은 읽도록 기다린 뒤 쌓인 이벤트를 비운다
+        std::thread::sleep(DEBOUNCE);
+        while rx.try_recv().is_ok() {}
+
+        let Ok(len) = std::fs::metadata(&path).map(|m| m.len()) else {
+            continue;
+        };
+        if len < offset {
+            offset = len;
+            let _ = app.emit(
+                "follow-truncated",
+                FollowTruncated {
+                    file_id: file_id.to_string(),
+                },
+            );
+            continue;
+        }
+        let appended = match read_complete_lines(&path, offset, len) {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("Failed to read appended data from {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        offset += appended.len() as u64;
+        let text = encoding.decode_without_bom_handling(&appended).0;
+
+        let state = app.state::<AppState>();
+        let Ok(mut tab_manager) = state.tab_manager.lock() else {
+            break;
+        };
+        match tab_manager.append_from_disk(file_id, &text) {
+            Ok(Some(chunk)) => {
+                let _ = app.emit(
+                    "follow-appended",
+                    FollowUpdate {
+                        file_id: file_id.to_string(),
+                        chunk,
+                    },
+                );
+            }
+            // 비활성 탭이라 버퍼가 내려가 있으면 다시 열 때 새 내용까지 읽힌다
+            Ok(None) => {}
+            Err(_) => break, // tab closed
+        }
+    }
+}
+
+/// Bytes `offset..len` up to the last line break (a line still being written waits).
+fn read_complete_lines(path: &Path, offset: u64, len: u64) -> std::io::Result<Option<Vec<u8>>> {
+    if len == offset {
+        return Ok(None);
+    }
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut bytes = Vec::with_capacity((len - offset) as usize);
+    file.take(len - offset).read_to_end(&mut bytes)?;
+    match bytes.iter().rposition(|&b| b == b'\n') {
+        Some(last) => {
+            bytes.truncate(last + 1);
+            Ok(Some(bytes))
+        }
+        None => Ok(None),
+    }
+}
@@ -1,64 +1,299 @@
+use crate::atomic_write;
+use crate::hex_view;
+use crate::lazy_text::{self, LazyText};
+use crate::search::Replacement;
 use ropey::Rope;
+use serde::Deserialize;
+use std::io::Write;
 use std::path::Path;
 use chardetng::EncodingDetector;
-use encoding_rs::Encoding;
+use encoding_rs::{Encoding, UTF_8};
 
 #[derive(Debug, Clone)]
 pub enum EditOperation {
     Insert { position: usize, text: String },
     Delete { position: usize, text: String },
     Replace { position: usize, old_text: String, new_text: String },
+    /// Replace-all: edits sorted by position, in char offsets of the text before the replacement.
+    BulkReplace { edits: Vec<Replacement> },
+    /// Edits made between `begin_group` / `end_group`, undone together.
+    Group(Vec<EditOperation>),
+}
+
+impl EditOperation {
+    /// Approximate heap size of the stored text.
+    fn bytes(&self) -> usize {
+        match self {
+            EditOperation::Insert { text, .. } | EditOperation::Delete { text, .. } => text.len(),
+            EditOperation::Replace { old_text, new_text, .. } => old_text.len() + new_text.len(),
+            EditOperation::BulkReplace { edits } => edits
+                .iter()
+                .map(|e| std::mem::size_of::<Replacement>() + e.old_text.len() + e.new_text.len())
+                .sum(),
+            EditOperation::Group(ops) => ops.iter().map(EditOperation::bytes).sum(),
+        }
+    }
 }
 
 const MAX_UNDO: usize = 100;
 
+/// Undo + redo history is trimmed (oldest first) above this size.
+const MAX_UNDO_BYTES: usize = 64 * 1024 * 1024;
+
+/// Bytes inspected to decide the line ending style.
+const EOL_SAMPLE_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    /// The more common style in the sample (LF when there are no line breaks).
+    pub fn detect(bytes: &[u8]) -> Self {
+        let sample = &bytes[..bytes.len().min(EOL_SAMPLE_BYTES)];
+        let lf = sample.iter().filter(|&&b| b == b'\n').count();
+        let crlf = sample.windows(2).filter(|w| w == b"\r\n").count();
+        if crlf * 2 > lf {
+            LineEnding::Crlf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.to_ascii_uppercase().as_str() {
+            "LF" => Some(LineEnding::Lf),
+            "CRLF" => Some(LineEnding::Crlf),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::Crlf => "CRLF",
+        }
+    }
+}
+
+/// What a column passed to `insert_at` / `delete_range` counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnUnit {
+    /// UTF-16 code units, i.e. JavaScript string indices (search results use these too).
+    #[default]
+    Utf16,
+    /// Unicode scalar values (Rust chars).
+    Char,
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// Unicode encodings recognised by their BOM (Windows Notepad's "Unicode" is UTF-16LE).
+/// encoding_rs has no UTF-32 and can only decode UTF-16, so both directions are done here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WideEncoding {
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+impl WideEncoding {
+    pub fn detect_bom(bytes: &[u8]) -> Option<Self> {
+        // UTF-32LE BOM은 UTF-16LE BOM으로 시작하므로 먼저 확인
+        [Self::Utf32Le, Self::Utf32Be, Self::Utf16Le, Self::Utf16Be]
+            .into_iter()
+            .find(|wide| bytes.starts_with(wide.bom()))
+    }
+
+    fn for_encoding(encoding: &'static Encoding) -> Option<Self> {
+        if encoding == encoding_rs::UTF_16LE {
+            Some(Self::Utf16Le)
+        } else if encoding == encoding_rs::UTF_16BE {
+            Some(Self::Utf16Be)
+        } else {
+            None
+        }
+    }
+
+    fn bom(self) -> &'static [u8] {
+        match self {
+            Self::Utf16Le => &[0xFF, 0xFE],
+            Self::Utf16Be => &[0xFE, 0xFF],
+            Self::Utf32Le => &[0xFF, 0xFE, 0x00, 0x00],
+            Self::Utf32Be => &[0x00, 0x00, 0xFE, 0xFF],
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf16Be => "UTF-16BE",
+            Self::Utf32Le => "UTF-32LE",
+            Self::Utf32Be => "UTF-32BE",
+        }
+    }
+
+    fn web_encoding(self) -> &'static Encoding {
+        match self {
+            Self::Utf16Le => encoding_rs::UTF_16LE,
+            Self::Utf16Be => encoding_rs::UTF_16BE,
+            Self::Utf32Le | Self::Utf32Be => UTF_8,
+        }
+    }
+
+    /// Decode (without BOM), replacing invalid units with U+FFFD.
+    fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf16Le | Self::Utf16Be => self.web_encoding().decode_without_bom_handling(bytes).0.into_owned(),
+            Self::Utf32Le | Self::Utf32Be => {
+                let units = bytes.chunks_exact(4);
+                let truncated = !units.remainder().is_empty();
+                let mut text: String = units
+                    .map(|u| {
+                        let u = [u[0], u[1], u[2], u[3]];
+                        let code = if self == Self::Utf32Le { u32::from_le_bytes(u) } else { u32::from_be_bytes(u) };
+                        char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                    })
+                    .collect();
+                if truncated {
+                    text.push(char::REPLACEMENT_CHARACTER);
+                }
+                text
+            }
+        }
+    }
+
+    /// Encode with the BOM.
+    fn encode(self, text: &str) -> Vec<u8> {
+        let mut bytes = self.bom().to_vec();
+        match self {
+            Self::Utf16Le => bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes)),
+            Self::Utf16Be => bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes)),
+            Self::Utf32Le => bytes.extend(text.chars().flat_map(|c| (c as u32).to_le_bytes())),
+            Self::Utf32Be => bytes.extend(text.chars().flat_map(|c| (c as u32).to_be_bytes())),
+        }
+        bytes
+    }
+}
+
+/// Encoding / BOM / line ending a buffer is saved with (see `TextBuffer::save_format`).
+#[derive(Debug, Clone, Copy)]
+pub struct SaveFormat {
+    encoding: &'static Encoding,
+    wide: Option<WideEncoding>,
+    bom: bool,
+    line_ending: LineEnding,
+}
+
 pub struct TextBuffer {
     rope: Rope,
     undo_stack: Vec<EditOperation>,
     redo_stack: Vec<EditOperation>,
+    /// Total `EditOperation::bytes` of both stacks and the open group.
+    history_bytes: usize,
+    /// Oldest edits were dropped, so undoing everything no longer restores the saved text.
+    history_truncated: bool,
+    /// Open edit group (nesting depth, collected operations).
+    group: Option<(usize, Vec<EditOperation>)>,
     pub is_modified: bool,
+    /// Set for huge files: read-only, served from an mmap (the rope stays empty).
+    lazy: Option<LazyText>,
+    /// Encoding / BOM / line ending of the file on disk, restored on save.
+    encoding: &'static Encoding,
+    /// UTF-16/32 (always saved with a BOM); `encoding` is only a placeholder for UTF-32.
+    wide: Option<WideEncoding>,
+    bom: bool,
+    line_ending: LineEnding,
 }
 
 impl TextBuffer {
     /// Create a new TextBuffer by loading a file from disk.
     /// 인코딩을 자동 감지하여 UTF-8로 변환합니다 (CP949, Shift_JIS, Big5 등 지원).
     pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        Self::from_file_with_encoding(path, None)
+    }
+
+    /// Load a file decoded with `forced` instead of the detected encoding (None = auto-detect).
+    pub fn from_file_with_encoding(path: &Path, forced: Option<&'static Encoding>) -> anyhow::Result<Self> {
+        if forced.is_none() && hex_view::is_binary_file(path)? {
+            anyhow::bail!("Binary file cannot be opened as text: {}", path.display());
+        }
+        // 수 GB 파일은 Rope로 올리지 않고 읽기 전용으로 매핑한다
+        if std::fs::metadata(path)?.len() > lazy_text::LAZY_THRESHOLD_BYTES {
+            let lazy = LazyText::open(path, forced)?;
+            return Ok(Self {
+                encoding: lazy.encoding(),
+                line_ending: lazy.line_ending(),
+                lazy: Some(lazy),
+                ..Self::new()
+            });
+        }
+
         let raw_bytes = std::fs::read(path)?;
 
-        // UTF-8 BOM 체크
-        let bytes = if raw_bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
-            &raw_bytes[3..]
-        } else {
-            &raw_bytes
+        // BOM 체크 (UTF-8 / UTF-16 / UTF-32)
+        let wide = match forced {
+            Some(encoding) => WideEncoding::for_encoding(encoding),
+            None => WideEncoding::detect_bom(&raw_bytes),
+        };
+        let utf8_bom = wide.is_none()
+            && raw_bytes.starts_with(UTF8_BOM)
+            && forced.map_or(true, |e| e == UTF_8);
+        let bom_len = match wide {
+            Some(wide) if raw_bytes.starts_with(wide.bom()) => wide.bom().len(),
+            _ if utf8_bom => UTF8_BOM.len(),
+            _ => 0,
         };
+        let bytes = &raw_bytes[bom_len..];
 
-        // UTF-8로 먼저 시도
-        let text = match std::str::from_utf8(bytes) {
-            Ok(s) => s.to_string(),
-            Err(_) => {
-                // 자동 인코딩 감지
-                let mut detector = EncodingDetector::new();
-                detector.feed(bytes, true);
-                let encoding = detector.guess(None, true);
-                let (decoded, _, had_errors) = encoding.decode(bytes);
-                if had_errors {
-                    // 최후 수단: 손실 허용하여 디코딩
-                    let (decoded, _, _) = Encoding::for_label(b"euc-kr")
-                        .unwrap_or(encoding_rs::WINDOWS_1252)
-                        .decode(bytes);
-                    decoded.into_owned()
-                } else {
-                    decoded.into_owned()
+        let mut encoding = forced.unwrap_or(UTF_8);
+        let text = if let Some(wide) = wide {
+            encoding = wide.web_encoding();
+            wide.decode(bytes)
+        } else if forced.is_some() {
+            // 사용자가 고른 인코딩 (손실 허용)
+            encoding.decode_without_bom_handling(bytes).0.into_owned()
+        } else {
+            // UTF-8로 먼저 시도
+            match std::str::from_utf8(bytes) {
+                Ok(s) => s.to_string(),
+                Err(_) => {
+                    // 자동 인코딩 감지
+                    let mut detector = EncodingDetector::new();
+                    detector.feed(bytes, true);
+                    encoding = detector.guess(None, true);
+                    let (decoded, had_errors) = encoding.decode_without_bom_handling(bytes);
+                    if had_errors {
+                        // 최후 수단: 손실 허용하여 디코딩
+                        encoding = Encoding::for_label(b"euc-kr").unwrap_or(encoding_rs::WINDOWS_1252);
+                        let (decoded, _) = encoding.decode_without_bom_handling(bytes);
+                        decoded.into_owned()
+                    } else {
+                        decoded.into_owned()
+                    }
                 }
             }
         };
+        let line_ending = LineEnding::detect(text.as_bytes());
 
         let rope = Rope::from_str(&text);
         Ok(Self {
             rope,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            history_bytes: 0,
+            history_truncated: false,
+            group: None,
             is_modified: false,
+            lazy: None,
+            encoding,
+            wide,
+            bom: utf8_bom || wide.is_some(),
+            line_ending,
         })
     }
 
@@ -68,7 +303,15 @@ impl TextBuffer {
             rope: Rope::from_str(text),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            history_bytes: 0,
+            history_truncated: false,
+            group: None,
             is_modified: false,
+            lazy: None,
+            encoding: UTF_8,
+            wide: None,
+            bom: false,
+            line_ending: LineEnding::Lf,
         }
     }
 
@@ -78,13 +321,24 @@ impl TextBuffer {
             rope: Rope::new(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            history_bytes: 0,
+            history_truncated: false,
+            group: None,
             is_modified: false,
+            lazy: None,
+            encoding: UTF_8,
+            wide: None,
+            bom: false,
+            line_ending: LineEnding::Lf,
         }
     }
 
     /// Get a chunk of lines for virtual scrolling.
     /// Returns lines from start_line (inclusive) to end_line (exclusive).
     pub fn get_chunk(&self, start_line: usize, end_line: usize) -> Vec<String> {
+        if let Some(lazy) = &self.lazy {
+            return lazy.get_chunk(start_line, end_line);
+        }
         let total = self.rope.len_lines();
         let start = start_line.min(total);
         let end = end_line.min(total);
@@ -98,10 +352,52 @@ impl TextBuffer {
     }
 
     fn push_undo(&mut self, op: EditOperation) {
-        self.undo_stack.push(op);
-        if self.undo_stack.len() > MAX_UNDO {
-            self.undo_stack.drain(0..self.undo_stack.len() - MAX_UNDO);
+        self.clear_redo();
+        self.history_bytes += op.bytes();
+        match self.group.as_mut() {
+            Some((_, ops)) => ops.push(op),
+            None => self.undo_stack.push(op),
         }
+        self.trim_history();
+    }
+
+    /// Enforce `MAX_UNDO` / `MAX_UNDO_BYTES`. Ops collected in an open group count too.
+    fn trim_history(&mut self) {
+        // 개수 또는 용량을 넘으면 오래된 것부터 버린다 (혼자서 한도를 넘는 편집이면 그것까지)
+        let mut excess = self.undo_stack.len().saturating_sub(MAX_UNDO);
+        let mut bytes = self.history_bytes;
+        for op in &self.undo_stack[..excess] {
+            bytes -= op.bytes();
+        }
+        while bytes > MAX_UNDO_BYTES && excess < self.undo_stack.len() {
+            bytes -= self.undo_stack[excess].bytes();
+            excess += 1;
+        }
+        if excess > 0 {
+            self.undo_stack.drain(..excess);
+            self.history_bytes = bytes;
+            self.history_truncated = true;
+        }
+        // 열린 그룹 하나가 한도를 넘으면 그동안 모인 편집도 버린다
+        if self.history_bytes > MAX_UNDO_BYTES {
+            if let Some((_, ops)) = self.group.as_mut() {
+                for op in ops.drain(..) {
+                    self.history_bytes -= op.bytes();
+                }
+                self.history_truncated = true;
+            }
+        }
+    }
+
+    fn clear_redo(&mut self) {
+        for op in self.redo_stack.drain(..) {
+            self.history_bytes -= op.bytes();
+        }
+    }
+
+    /// Memory held by the undo/redo history, in bytes.
+    pub fn undo_memory_bytes(&self) -> usize {
+        self.history_bytes
     }
 
     /// Insert text at a character position.
@@ -112,10 +408,77 @@ impl TextBuffer {
             position: pos,
             text: text.to_string(),
         });
-        self.redo_stack.clear();
+        self.clear_redo();
         self.is_modified = true;
     }
 
+    /// Append text another program wrote to the file (follow mode).
+    /// Not an undoable edit and doesn't mark the buffer modified; earlier undo positions stay valid.
+    pub fn append_from_disk(&mut self, text: &str) {
+        let end = self.rope.len_chars();
+        self.rope.insert(end, text);
+    }
+
+    /// Insert text at a line/column (clamped to the end of the line).
+    pub fn insert_at(&mut self, line: usize, column: usize, unit: ColumnUnit, text: &str) -> bool {
+        match self.line_col_to_char(line, column, unit) {
+            Some(pos) => {
+                self.insert_text(pos, text);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Delete between two line/column positions (end exclusive).
+    pub fn delete_range(
+        &mut self,
+        start_line: usize,
+        start_col: usize,
+        end_line: usize,
+        end_col: usize,
+        unit: ColumnUnit,
+    ) -> bool {
+        match (
+            self.line_col_to_char(start_line, start_col, unit),
+            self.line_col_to_char(end_line, end_col, unit),
+        ) {
+            (Some(start), Some(end)) if start <= end => {
+                self.delete_text(start, end);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Char offset of a line/column, or None if the line doesn't exist.
+    pub fn line_col_to_char(&self, line: usize, column: usize, unit: ColumnUnit) -> Option<usize> {
+        if line >= self.rope.len_lines() {
+            return None;
+        }
+        let content_len = self.line_content_len(line);
+        let column = match unit {
+            ColumnUnit::Char => column.min(content_len),
+            ColumnUnit::Utf16 => {
+                // 서로게이트 쌍 가운데를 가리키면 그 글자의 시작으로 맞춘다
+                let content = self.rope.line(line).slice(..content_len);
+                content.utf16_cu_to_char(column.min(content.len_utf16_cu()))
+            }
+        };
+        Some(self.rope.line_to_char(line) + column)
+    }
+
+    /// Char length of a line without its line break.
+    fn line_content_len(&self, line_idx: usize) -> usize {
+        let line = self.rope.line(line_idx);
+        let len = line.len_chars();
+        match (len.checked_sub(2).map(|i| line.char(i)), len.checked_sub(1).map(|i| line.char(i))) {
+            (Some('\r'), Some('\n')) => len - 2,
+            (_, Some('\n' | '\r')) => len - 1,
+            _ => len, // last line without newline
+        }
+    }
+
     /// Replace the content of a specific line (preserving line ending).
     pub fn replace_line(&mut self, line_idx: usize, new_text: &str) -> bool {
         let total_lines = self.rope.len_lines();
@@ -124,24 +487,11 @@ impl TextBuffer {
         }
 
         let start_char = self.rope.line_to_char(line_idx);
-        let line = self.rope.line(line_idx);
-        let line_str = line.to_string();
-        let line_len = line.len_chars();
-
-        // Determine content length (excluding trailing newline)
-        let content_len = if line_str.ends_with("\r\n") {
-            line_len.saturating_sub(2)
-        } else if line_str.ends_with('\n') || line_str.ends_with('\r') {
-            line_len.saturating_sub(1)
-        } else {
-            line_len // last line without newline
-        };
-
-        let end_char = start_char + content_len;
+        let end_char = start_char + self.line_content_len(line_idx);
         let old_text = self.rope.slice(start_char..end_char).to_string();
 
         // Strip trailing newlines from new_text
-        let new_text_clean = new_text.trim_end_matches(|c: char| c == '\n' || c == '\r');
+        let new_text_clean = new_text.trim_end_matches(['\n', '\r']);
 
         // Remove old content, insert new
         if start_char < end_char {
@@ -156,7 +506,7 @@ impl TextBuffer {
             old_text,
             new_text: new_text_clean.to_string(),
         });
-        self.redo_stack.clear();
+        self.clear_redo();
         self.is_modified = true;
         true
     }
@@ -175,29 +525,47 @@ impl TextBuffer {
             position: start,
             text: deleted,
         });
-        self.redo_stack.clear();
+        self.clear_redo();
         self.is_modified = true;
     }
 
+    /// Start grouping edits so one undo reverses them all (e.g. a typed word or a paste).
+    /// Groups nest; only the outermost `end_group` closes it.
+    pub fn begin_group(&mut self) {
+        match self.group.as_mut() {
+            Some((depth, _)) => *depth += 1,
+            None => self.group = Some((1, Vec::new())),
+        }
+    }
+
+    /// Close the current edit group. Returns false if no group was open.
+    pub fn end_group(&mut self) -> bool {
+        let Some((depth, _)) = self.group.as_mut() else {
+            return false;
+        };
+        *depth -= 1;
+        if *depth == 0 {
+            // 모인 편집의 크기는 push_undo에서 이미 history_bytes에 더했다
+            if let Some((_, mut ops)) = self.group.take() {
+                match ops.len() {
+                    0 => {}
+                    1 => self.undo_stack.push(ops.remove(0)),
+                    _ => self.undo_stack.push(EditOperation::Group(ops)),
+                }
+                self.trim_history();
+            }
+        }
+        true
+    }
+
     /// Undo the last edit operation.
     pub fn undo(&mut self) -> bool {
+        // 열린 그룹이 있으면 먼저 닫아서 한 번에 되돌린다
+        self.close_groups();
         if let Some(op) = self.undo_stack.pop() {
-            match &op {
-                EditOperation::Insert { position, text } => {
-                    let end = *position + text.chars().count();
-                    self.rope.remove(*position..end);
-                }
-                EditOperation::Delete { position, text } => {
-                    self.rope.insert(*position, text);
-                }
-                EditOperation::Replace { position, old_text, new_text } => {
-                    let end = *position + new_text.chars().count();
-                    self.rope.remove(*position..end);
-                    self.rope.insert(*position, old_text);
-                }
-            }
+            Self::apply_undo(&mut self.rope, &op);
             self.redo_stack.push(op);
-            self.is_modified = !self.undo_stack.is_empty();
+            self.is_modified = !self.undo_stack.is_empty() || self.history_truncated;
             true
         } else {
             false
@@ -206,21 +574,9 @@ impl TextBuffer {
 
     /// Redo the last undone edit operation.
     pub fn redo(&mut self) -> bool {
+        self.close_groups();
         if let Some(op) = self.redo_stack.pop() {
-            match &op {
-                EditOperation::Insert { position, text } => {
-                    self.rope.insert(*position, text);
-                }
-                EditOperation::Delete { position, text } => {
-                    let end = *position + text.chars().count();
-                    self.rope.remove(*position..end);
-                }
-                EditOperation::Replace { position, old_text, new_text } => {
-                    let end = *position + old_text.chars().count();
-                    self.rope.remove(*position..end);
-                    self.rope.insert(*position, new_text);
-                }
-            }
+            Self::apply_redo(&mut self.rope, &op);
             self.undo_stack.push(op);
             self.is_modified = true;
             true
@@ -229,27 +585,229 @@ impl TextBuffer {
         }
     }
 
+    fn close_groups(&mut self) {
+        while self.group.is_some() {
+            self.end_group();
+        }
+    }
+
+    fn apply_undo(rope: &mut Rope, op: &EditOperation) {
+        match op {
+            EditOperation::Insert { position, text } => {
+                let end = *position + text.chars().count();
+                rope.remove(*position..end);
+            }
+            EditOperation::Delete { position, text } => {
+                rope.insert(*position, text);
+            }
+            EditOperation::Replace { position, old_text, new_text } => {
+                let end = *position + new_text.chars().count();
+                rope.remove(*position..end);
+                rope.insert(*position, old_text);
+            }
+            EditOperation::BulkReplace { edits } => {
+                // 앞에서부터 되돌리면 각 위치가 원래 좌표와 일치한다
+                for edit in edits {
+                    let end = edit.char_start + edit.new_text.chars().count();
+                    rope.remove(edit.char_start..end);
+                    rope.insert(edit.char_start, &edit.old_text);
+                }
+            }
+            EditOperation::Group(ops) => {
+                for op in ops.iter().rev() {
+                    Self::apply_undo(rope, op);
+                }
+            }
+        }
+    }
+
+    fn apply_redo(rope: &mut Rope, op: &EditOperation) {
+        match op {
+            EditOperation::Insert { position, text } => {
+                rope.insert(*position, text);
+            }
+            EditOperation::Delete { position, text } => {
+                let end = *position + text.chars().count();
+                rope.remove(*position..end);
+            }
+            EditOperation::Replace { position, old_text, new_text } => {
+                let end = *position + old_text.chars().count();
+                rope.remove(*position..end);
+                rope.insert(*position, new_text);
+            }
+            EditOperation::BulkReplace { edits } => Self::apply_bulk(rope, edits),
+            EditOperation::Group(ops) => {
+                for op in ops {
+                    Self::apply_redo(rope, op);
+                }
+            }
+        }
+    }
+
+    /// Apply a replace-all as a single undoable operation. Returns the number of replacements.
+    pub fn bulk_replace(&mut self, edits: Vec<Replacement>) -> usize {
+        if edits.is_empty() {
+            return 0;
+        }
+        Self::apply_bulk(&mut self.rope, &edits);
+        let count = edits.len();
+        self.push_undo(EditOperation::BulkReplace { edits });
+        self.clear_redo();
+        self.is_modified = true;
+        count
+    }
+
+    /// Apply a single search-and-replace as one undoable operation.
+    pub fn apply_replacement(&mut self, edit: Replacement) {
+        let end = edit.char_start + edit.old_text.chars().count();
+        self.rope.remove(edit.char_start..end);
+        self.rope.insert(edit.char_start, &edit.new_text);
+        self.push_undo(EditOperation::Replace {
+            position: edit.char_start,
+            old_text: edit.old_text,
+            new_text: edit.new_text,
+        });
+        self.clear_redo();
+        self.is_modified = true;
+    }
+
+    fn apply_bulk(rope: &mut Rope, edits: &[Replacement]) {
+        // 뒤에서부터 적용해야 앞쪽 위치가 밀리지 않는다
+        for edit in edits.iter().rev() {
+            let end = edit.char_start + edit.old_text.chars().count();
+            rope.remove(edit.char_start..end);
+            rope.insert(edit.char_start, &edit.new_text);
+        }
+    }
+
     /// Save the rope contents to a file.
     pub fn save(&mut self, path: &Path) -> anyhow::Result<()> {
-        let writer = std::io::BufWriter::new(std::fs::File::create(path)?);
-        self.rope.write_to(writer)?;
+        if self.is_read_only() {
+            anyhow::bail!("File is too large to edit and was opened read-only");
+        }
+        if self.encoding == UTF_8 && self.wide.is_none() && !self.bom && self.line_ending == LineEnding::Lf {
+            atomic_write::write_atomic(path, |writer| self.rope.write_to(writer))?;
+        } else {
+            let bytes = self.to_file_bytes()?;
+            atomic_write::write_atomic(path, |writer| writer.write_all(&bytes))?;
+        }
+        self.mark_saved();
+        Ok(())
+    }
+
+    /// The bytes `save()` writes: the text in the file's encoding, BOM and line endings.
+    pub fn to_file_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        if self.is_read_only() {
+            anyhow::bail!("File is too large to edit and was opened read-only");
+        }
+        // 열 때 감지한 인코딩/BOM/줄바꿈으로 되돌려 저장
+        let mut text = self.rope.to_string();
+        if self.line_ending == LineEnding::Crlf {
+            text = to_crlf(&text);
+        }
+        match self.wide {
+            Some(wide) => Ok(wide.encode(&text)),
+            None => encode_text(&text, self.encoding, self.bom),
+        }
+    }
+
+    /// Mark the buffer unmodified after its text was written out elsewhere (e.g. into an EPUB).
+    pub fn mark_saved(&mut self) {
         self.is_modified = false;
         // Clear undo/redo after save
         self.undo_stack.clear();
         self.redo_stack.clear();
-        Ok(())
+        self.history_bytes = 0;
+        self.history_truncated = false;
+        self.group = None;
     }
 
     /// Get total number of lines.
     pub fn get_total_lines(&self) -> usize {
+        if let Some(lazy) = &self.lazy {
+            return lazy.total_lines();
+        }
         self.rope.len_lines()
     }
 
     /// Get total number of characters.
     pub fn get_total_chars(&self) -> usize {
+        if let Some(lazy) = &self.lazy {
+            return lazy.total_chars();
+        }
         self.rope.len_chars()
     }
 
+    /// The current on-disk format, to put back with `restore_save_format` if a save fails.
+    pub fn save_format(&self) -> SaveFormat {
+        SaveFormat {
+            encoding: self.encoding,
+            wide: self.wide,
+            bom: self.bom,
+            line_ending: self.line_ending,
+        }
+    }
+
+    pub fn restore_save_format(&mut self, format: SaveFormat) {
+        self.encoding = format.encoding;
+        self.wide = format.wide;
+        self.bom = format.bom;
+        self.line_ending = format.line_ending;
+    }
+
+    /// Change the on-disk format used by the next `save()` (None keeps the current setting).
+    pub fn set_save_format(
+        &mut self,
+        encoding: Option<&'static Encoding>,
+        bom: Option<bool>,
+        line_ending: Option<LineEnding>,
+    ) {
+        if let Some(encoding) = encoding {
+            self.encoding = encoding;
+            self.wide = WideEncoding::for_encoding(encoding);
+        }
+        let bom = bom.unwrap_or(self.bom);
+        self.bom = self.wide.is_some() || (bom && self.encoding == UTF_8);
+        if let Some(line_ending) = line_ending {
+            self.line_ending = line_ending;
+        }
+    }
+
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// Name of the encoding the file was read with (e.g. "UTF-8", "EUC-KR", "Shift_JIS").
+    pub fn encoding_name(&self) -> &'static str {
+        match self.wide {
+            Some(wide) => wide.name(),
+            None => self.encoding.name(),
+        }
+    }
+
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Whether a line break is a single `\n` byte on disk (not UTF-16/32).
+    pub fn has_byte_line_breaks(&self) -> bool {
+        self.wide.is_none() && self.encoding.is_ascii_compatible()
+    }
+
+    /// Whether this is a lazily mapped huge file (no editing, empty rope).
+    pub fn is_read_only(&self) -> bool {
+        self.lazy.is_some()
+    }
+
+    /// The mmap view of a huge file (None for normal, rope-backed buffers).
+    pub fn lazy(&self) -> Option<&LazyText> {
+        self.lazy.as_ref()
+    }
+
     /// Get a reference to the underlying Rope.
     pub fn rope(&self) -> &Rope {
         &self.rope
@@ -264,6 +822,7 @@ impl TextBuffer {
     pub fn replace_all(&mut self, new_text: &str) {
         let old_text = self.rope.to_string();
         self.rope = Rope::from_str(new_text);
+        self.begin_group();
         self.push_undo(EditOperation::Delete {
             position: 0,
             text: old_text,
@@ -272,12 +831,50 @@ impl TextBuffer {
             position: 0,
             text: new_text.to_string(),
         });
-        self.redo_stack.clear();
+        self.end_group();
+        self.clear_redo();
         self.is_modified = true;
     }
 
     /// Get the full text as a String.
     pub fn to_string_full(&self) -> String {
+        if let Some(lazy) = &self.lazy {
+            return lazy.to_string_full();
+        }
         self.rope.to_string()
     }
 }
+
+/// Turn lone `\n` into `\r\n` (existing `\r\n` pairs are kept).
+fn to_crlf(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + text.len() / 32);
+    let mut prev = '\0';
+    for c in text.chars() {
+        if c == '\n' && prev != '\r' {
+            out.push('\r');
+        }
+        out.push(c);
+        prev = c;
+    }
+    out
+}
+
+/// Encode for saving. Fails instead of writing `&#NNNN;` for chars the encoding can't represent.
+fn encode_text(text: &str, encoding: &'static Encoding, bom: bool) -> anyhow::Result<Vec<u8>> {
+    if encoding == UTF_8 {
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+        if bom {
+            bytes.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+        }
+        bytes.extend_from_slice(text.as_bytes());
+        return Ok(bytes);
+    }
+    let (bytes, _, unmappable) = encoding.encode(text);
+    if unmappable {
+        anyhow::bail!(
+            "The text contains characters that cannot be saved as {}; save it as UTF-8 instead",
+            encoding.name()
+        );
+    }
+    Ok(bytes.into_owned())
+}
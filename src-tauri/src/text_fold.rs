@@ -0,0 +1,145 @@
+//! Search-time text folding: case, diacritics and full/half-width.
+//!
+//! 글자 단위로 접기 때문에 접힌 문자열의 위치를 원문 위치로 되돌릴 수 있다.
+//! 길이가 바뀐 글자만 `Span`으로 기록해 대용량 텍스트에서도 메모리를 적게 쓴다.
+
+use icu_normalizer::{ComposingNormalizerBorrowed, DecomposingNormalizerBorrowed};
+use std::borrow::Cow;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FoldMode {
+    pub lowercase: bool,
+    /// NFKD + strip combining marks (café → cafe, ＡＢＣ → ABC, ｶﾀｶﾅ → カタカナ).
+    pub diacritics_width: bool,
+}
+
+impl FoldMode {
+    fn is_identity(&self) -> bool {
+        !self.lowercase && !self.diacritics_width
+    }
+}
+
+/// A char whose folded form has a different byte length than the original.
+struct Span {
+    folded: Range<usize>,
+    original: Range<usize>,
+}
+
+pub struct Folded<'a> {
+    text: Cow<'a, str>,
+    spans: Vec<Span>,
+}
+
+impl<'a> Folded<'a> {
+    pub fn new(text: &'a str, mode: FoldMode) -> Self {
+        if mode.is_identity() {
+            return Self {
+                text: Cow::Borrowed(text),
+                spans: Vec::new(),
+            };
+        }
+
+        let nfkd = DecomposingNormalizerBorrowed::new_nfkd();
+        let nfc = ComposingNormalizerBorrowed::new_nfc();
+        let mut folded = String::with_capacity(text.len());
+        let mut spans = Vec::new();
+        let mut buf = [0u8; 4];
+        for (orig_pos, c) in text.char_indices() {
+            let start = folded.len();
+            if mode.diacritics_width && !is_fold_stable(c) {
+                let decomposed = nfkd.normalize(c.encode_utf8(&mut buf));
+                let stripped: String = decomposed.chars().filter(|&d| !is_combining_mark(d)).collect();
+                // 한글 자모 등은 다시 조합해 둔다
+                let composed = nfc.normalize(&stripped);
+                push_case(&mut folded, &composed, mode.lowercase);
+            } else if mode.lowercase {
+                folded.extend(c.to_lowercase());
+            } else {
+                folded.push(c);
+            }
+            if folded.len() - start != c.len_utf8() {
+                spans.push(Span {
+                    folded: start..folded.len(),
+                    original: orig_pos..orig_pos + c.len_utf8(),
+                });
+            }
+        }
+        Self {
+            text: Cow::Owned(folded),
+            spans,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Original byte offset for the start of a match at folded offset `pos`.
+    pub fn start_to_original(&self, pos: usize) -> usize {
+        self.to_original(pos, false)
+    }
+
+    /// Original byte offset for the end of a match at folded offset `pos`.
+    pub fn end_to_original(&self, pos: usize) -> usize {
+        self.to_original(pos, true)
+    }
+
+    /// Folded byte offset of original byte offset `pos` (a char boundary).
+    pub fn original_to_folded(&self, pos: usize) -> usize {
+        let idx = self.spans.partition_point(|s| s.original.start < pos);
+        match idx.checked_sub(1).map(|i| &self.spans[i]) {
+            Some(span) if pos < span.original.end => span.folded.start,
+            Some(span) => span.folded.end + (pos - span.original.end),
+            None => pos,
+        }
+    }
+
+    fn to_original(&self, pos: usize, is_end: bool) -> usize {
+        // 끝 위치는 바로 뒤에 지워진 결합 문자(e + ◌́)까지 포함한다
+        let idx = self
+            .spans
+            .partition_point(|s| s.folded.start < pos || (is_end && s.folded.start == pos && s.folded.is_empty()));
+        // pos가 어떤 Span 안쪽(시작 이후)에 있으면 그 글자 전체로 맞춘다
+        if let Some(span) = idx.checked_sub(1).map(|i| &self.spans[i]) {
+            if pos < span.folded.end {
+                return if is_end { span.original.end } else { span.original.start };
+            }
+            return span.original.end + (pos - span.folded.end);
+        }
+        pos
+    }
+}
+
+/// Fold a query the same way as the text it is searched in.
+pub fn fold_str(text: &str, mode: FoldMode) -> String {
+    Folded::new(text, mode).text.into_owned()
+}
+
+fn push_case(out: &mut String, s: &str, lowercase: bool) {
+    if lowercase {
+        out.extend(s.chars().flat_map(char::to_lowercase));
+    } else {
+        out.push_str(s);
+    }
+}
+
+/// Chars that NFKD folding leaves as they are (skip the normalizer on the hot path).
+fn is_fold_stable(c: char) -> bool {
+    matches!(c,
+        '\u{0000}'..='\u{009F}'   // ASCII / C1
+        | '\u{3040}'..='\u{30FF}' // 히라가나/가타카나 (탁점은 유지)
+        | '\u{4E00}'..='\u{9FFF}' // CJK 통합 한자
+        | '\u{AC00}'..='\u{D7A3}' // 한글 음절
+    )
+}
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{FE20}'..='\u{FE2F}'
+    )
+}
@@ -0,0 +1,84 @@
+//! Word / character statistics for a text buffer.
+//!
+//! 로프 청크를 그대로 흘려 넣어 세므로 전체 텍스트를 한 번 더 복사하지 않는다.
+//! 띄어쓰기가 없는 중국어·일본어는 글자 수로 따로 세어 읽기 시간을 추정한다.
+
+use crate::vocabulary::{is_cjk_unspaced, is_word_char};
+use serde::Serialize;
+
+/// Reading speed for space-separated words (English, Korean, ...).
+const WORDS_PER_MINUTE: f64 = 230.0;
+/// Reading speed for Chinese/Japanese characters.
+const CJK_CHARS_PER_MINUTE: f64 = 500.0;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TextStats {
+    /// Space-separated words (CJK characters not included).
+    pub words: usize,
+    /// Chinese characters and kana, counted one by one.
+    pub cjk_chars: usize,
+    pub characters: usize,
+    pub characters_no_spaces: usize,
+    /// Runs of non-blank lines.
+    pub paragraphs: usize,
+    pub reading_minutes: f64,
+}
+
+#[derive(Default)]
+pub struct TextStatsCounter {
+    stats: TextStats,
+    in_word: bool,
+    line_has_text: bool,
+    in_paragraph: bool,
+}
+
+impl TextStatsCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next piece of text (chunks may split words and lines anywhere).
+    pub fn feed(&mut self, text: &str) {
+        let stats = &mut self.stats;
+        for c in text.chars() {
+            stats.characters += 1;
+            if c == '\n' {
+                if !self.line_has_text {
+                    self.in_paragraph = false;
+                }
+                self.line_has_text = false;
+            }
+            if c.is_whitespace() {
+                self.in_word = false;
+                continue;
+            }
+
+            stats.characters_no_spaces += 1;
+            if !self.line_has_text {
+                self.line_has_text = true;
+                if !self.in_paragraph {
+                    self.in_paragraph = true;
+                    stats.paragraphs += 1;
+                }
+            }
+            if is_cjk_unspaced(c) {
+                stats.cjk_chars += 1;
+                self.in_word = false;
+            } else if is_word_char(c) {
+                if !self.in_word {
+                    stats.words += 1;
+                    self.in_word = true;
+                }
+            } else if !matches!(c, '\'' | '’' | '-') {
+                // 단어 안의 아포스트로피/하이픈은 단어를 끊지 않는다
+                self.in_word = false;
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> TextStats {
+        self.stats.reading_minutes = self.stats.words as f64 / WORDS_PER_MINUTE
+            + self.stats.cjk_chars as f64 / CJK_CHARS_PER_MINUTE;
+        self.stats
+    }
+}
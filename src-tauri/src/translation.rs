@@ -0,0 +1,214 @@
+//! Pluggable translation providers for inline translation of selected passages.
+//!
+//! - "deepl":   DeepL API (api-free.deepl.com for ":fx" keys)
+//! - "http":    LibreTranslate 호환 엔드포인트 (POST {q, source, target, format})
+//! - "command": 로컬 명령(모델 등). 원문은 stdin으로, 결과는 stdout으로.
+//!   명령 문자열의 `{target}`은 대상 언어로 치환된다.
+//!
+//! 문단(줄) 단위로 번역을 캐시해서 같은 문단을 다시 요청하지 않는다.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::config::AppConfig;
+
+const MAX_CACHE_ENTRIES: usize = 5000;
+/// DeepL accepts at most 50 texts per request.
+const DEEPL_BATCH: usize = 50;
+
+pub struct TranslationCache {
+    entries: HashMap<(String, String, String), String>,
+}
+
+impl TranslationCache {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    fn key(provider: &str, target_lang: &str, paragraph: &str) -> (String, String, String) {
+        (
+            provider.to_string(),
+            target_lang.to_string(),
+            paragraph.to_string(),
+        )
+    }
+
+    fn insert(&mut self, key: (String, String, String), value: String) {
+        if self.entries.len() >= MAX_CACHE_ENTRIES {
+            self.entries.clear();
+        }
+        self.entries.insert(key, value);
+    }
+}
+
+/// Split into paragraphs (lines); blank lines are kept as-is and never sent.
+fn paragraphs(text: &str) -> Vec<&str> {
+    text.split('\n').map(|l| l.trim_end_matches('\r')).collect()
+}
+
+/// Paragraphs of `text` that still need translating (not cached, not blank), deduplicated.
+pub fn pending_paragraphs(cache: &TranslationCache, config: &AppConfig, text: &str, target_lang: &str) -> Vec<String> {
+    let mut pending: Vec<String> = paragraphs(text)
+        .into_iter()
+        .filter(|p| !p.trim().is_empty())
+        .filter(|p| {
+            !cache.entries.contains_key(&TranslationCache::key(
+                &config.translation_provider,
+                target_lang,
+                p,
+            ))
+        })
+        .map(|p| p.to_string())
+        .collect();
+    pending.sort();
+    pending.dedup();
+    pending
+}
+
+/// Translate paragraphs with the configured provider (no cache involved, may block on network).
+pub fn translate_paragraphs(config: &AppConfig, paragraphs: &[String], target_lang: &str) -> anyhow::Result<Vec<String>> {
+    if paragraphs.is_empty() {
+        return Ok(Vec::new());
+    }
+    match config.translation_provider.as_str() {
+        "deepl" => {
+            let mut out = Vec::with_capacity(paragraphs.len());
+            for batch in paragraphs.chunks(DEEPL_BATCH) {
+                out.extend(deepl(config, batch, target_lang)?);
+            }
+            Ok(out)
+        }
+        "http" => paragraphs
+            .iter()
+            .map(|p| libre_translate(config, p, target_lang))
+            .collect(),
+        "command" => paragraphs
+            .iter()
+            .map(|p| run_command(config, p, target_lang))
+            .collect(),
+        "" => anyhow::bail!("No translation provider configured"),
+        other => anyhow::bail!("Unknown translation provider: {}", other),
+    }
+}
+
+/// Store new translations and assemble the full translated text from the cache.
+pub fn assemble(
+    cache: &mut TranslationCache,
+    config: &AppConfig,
+    text: &str,
+    target_lang: &str,
+    translated: Vec<(String, String)>,
+) -> String {
+    let provider = &config.translation_provider;
+    for (source, translation) in translated {
+        cache.insert(TranslationCache::key(provider, target_lang, &source), translation);
+    }
+    paragraphs(text)
+        .into_iter()
+        .map(|p| {
+            if p.trim().is_empty() {
+                return p.to_string();
+            }
+            cache
+                .entries
+                .get(&TranslationCache::key(provider, target_lang, p))
+                .cloned()
+                .unwrap_or_else(|| p.to_string())
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[derive(Deserialize)]
+struct DeepLResponse {
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeepLTranslation {
+    text: String,
+}
+
+fn deepl(config: &AppConfig, texts: &[String], target_lang: &str) -> anyhow::Result<Vec<String>> {
+    let key = config.translation_api_key.trim();
+    if key.is_empty() {
+        anyhow::bail!("DeepL API key is not set");
+    }
+    let url = if !config.translation_endpoint.trim().is_empty() {
+        config.translation_endpoint.trim().to_string()
+    } else if key.ends_with(":fx") {
+        "https://api-free.deepl.com/v2/translate".to_string()
+    } else {
+        "https://api.deepl.com/v2/translate".to_string()
+    };
+
+    let response: DeepLResponse = ureq::post(&url)
+        .set("Authorization", &format!("DeepL-Auth-Key {}", key))
+        .send_json(serde_json::json!({
+            "text": texts,
+            "target_lang": target_lang.to_uppercase(),
+        }))
+        .map_err(|e| anyhow::anyhow!("DeepL request failed: {}", e))?
+        .into_json()?;
+    if response.translations.len() != texts.len() {
+        anyhow::bail!("DeepL returned {} translations for {} texts", response.translations.len(), texts.len());
+    }
+    Ok(response.translations.into_iter().map(|t| t.text).collect())
+}
+
+#[derive(Deserialize)]
+struct LibreResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+fn libre_translate(config: &AppConfig, text: &str, target_lang: &str) -> anyhow::Result<String> {
+    let url = config.translation_endpoint.trim();
+    if url.is_empty() {
+        anyhow::bail!("Translation endpoint is not set");
+    }
+    let mut body = serde_json::json!({
+        "q": text,
+        "source": "auto",
+        "target": target_lang,
+        "format": "text",
+    });
+    if !config.translation_api_key.trim().is_empty() {
+        body["api_key"] = serde_json::Value::String(config.translation_api_key.trim().to_string());
+    }
+    let response: LibreResponse = ureq::post(url)
+        .send_json(body)
+        .map_err(|e| anyhow::anyhow!("Translation request failed: {}", e))?
+        .into_json()?;
+    Ok(response.translated_text)
+}
+
+fn run_command(config: &AppConfig, text: &str, target_lang: &str) -> anyhow::Result<String> {
+    let command_line = config.translation_command.replace("{target}", target_lang);
+    let mut parts = command_line.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Translation command is not set"))?;
+
+    let mut child = std::process::Command::new(program)
+        .args(parts)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Translation command failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
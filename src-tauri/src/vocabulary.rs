@@ -0,0 +1,196 @@
+//! Word frequency / vocabulary analysis.
+//!
+//! 공백이 없는 중국어·일본어는 어절 단위로 나눌 수 없으므로 `cjk_mode`로 토큰화 방식을
+//! 고른다. 한글은 띄어쓰기가 있으므로 항상 어절 단위로 센다.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+const DEFAULT_TOP_N: usize = 200;
+const MAX_HAPAX_LIST: usize = 500;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VocabularyOptions {
+    /// Han/Kana runs: "char" (each character), "bigram" (overlapping pairs) or
+    /// "run" (whole run as one token). Defaults to "bigram".
+    #[serde(default)]
+    pub cjk_mode: Option<String>,
+    /// Tokens shorter than this (in chars) are ignored.
+    #[serde(default)]
+    pub min_length: Option<usize>,
+    /// Number of most frequent words returned (0 = all).
+    #[serde(default)]
+    pub top_n: Option<usize>,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub include_numbers: bool,
+    #[serde(default)]
+    pub stop_words: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WordCount {
+    pub word: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VocabularyReport {
+    pub total_tokens: usize,
+    pub unique_tokens: usize,
+    /// unique / total (0 for empty text).
+    pub type_token_ratio: f64,
+    pub words: Vec<WordCount>,
+    /// Number of words occurring exactly once.
+    pub hapax_count: usize,
+    /// Words occurring once (first MAX_HAPAX_LIST, alphabetical).
+    pub hapax: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CjkMode {
+    Char,
+    Bigram,
+    Run,
+}
+
+/// Chinese characters and Japanese kana (written without spaces).
+pub(crate) fn is_cjk_unspaced(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF   // Hiragana, Katakana
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xF900..=0xFAFF // CJK Compatibility Ideographs
+        | 0x20000..=0x2FFFF)
+}
+
+pub(crate) fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() && !is_cjk_unspaced(c)
+}
+
+fn push_cjk_run(run: &[char], mode: CjkMode, out: &mut Vec<String>) {
+    match mode {
+        CjkMode::Run => out.push(run.iter().collect()),
+        CjkMode::Char => out.extend(run.iter().map(|c| c.to_string())),
+        CjkMode::Bigram if run.len() == 1 => out.push(run[0].to_string()),
+        CjkMode::Bigram => out.extend(run.windows(2).map(|w| w.iter().collect())),
+    }
+}
+
+fn tokenize(text: &str, mode: CjkMode) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk_run: Vec<char> = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if is_cjk_unspaced(c) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            cjk_run.push(c);
+            continue;
+        }
+        if !cjk_run.is_empty() {
+            push_cjk_run(&cjk_run, mode, &mut tokens);
+            cjk_run.clear();
+        }
+        // 단어 안의 아포스트로피/하이픈은 유지 (don't, well-known)
+        let joins_word = matches!(c, '\'' | '’' | '-')
+            && !word.is_empty()
+            && chars.peek().is_some_and(|&next| is_word_char(next));
+        if is_word_char(c) || joins_word {
+            word.push(c);
+        } else if !word.is_empty() {
+            tokens.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    if !cjk_run.is_empty() {
+        push_cjk_run(&cjk_run, mode, &mut tokens);
+    }
+    tokens
+}
+
+/// `analyze_chunks` over one text.
+pub fn analyze(text: &str, options: &VocabularyOptions) -> anyhow::Result<VocabularyReport> {
+    analyze_chunks(|feed| feed(text), options)
+}
+
+/// Count the words of a text handed over in pieces: `for_each_chunk` calls `feed` with each
+/// one. Pieces must not split a word (e.g. end at a line break).
+pub fn analyze_chunks(
+    for_each_chunk: impl FnOnce(&mut dyn FnMut(&str)),
+    options: &VocabularyOptions,
+) -> anyhow::Result<VocabularyReport> {
+    let mode = match options.cjk_mode.as_deref().unwrap_or("bigram") {
+        "char" => CjkMode::Char,
+        "bigram" => CjkMode::Bigram,
+        "run" => CjkMode::Run,
+        other => anyhow::bail!("Unknown CJK tokenization mode: {}", other),
+    };
+    let min_length = options.min_length.unwrap_or(1);
+    let normalize = |w: &str| {
+        if options.case_sensitive {
+            w.to_string()
+        } else {
+            w.to_lowercase()
+        }
+    };
+    let stop_words: HashSet<String> = options.stop_words.iter().map(|w| normalize(w)).collect();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut total_tokens = 0;
+    for_each_chunk(&mut |text| {
+        for token in tokenize(text, mode) {
+            if token.chars().count() < min_length {
+                continue;
+            }
+            if !options.include_numbers && token.chars().all(|c| c.is_numeric()) {
+                continue;
+            }
+            let token = normalize(&token);
+            if stop_words.contains(&token) {
+                continue;
+            }
+            total_tokens += 1;
+            *counts.entry(token).or_insert(0) += 1;
+        }
+    });
+
+    let mut hapax: Vec<String> = counts
+        .iter()
+        .filter(|(_, &count)| count == 1)
+        .map(|(word, _)| word.clone())
+        .collect();
+    hapax.sort();
+    let hapax_count = hapax.len();
+    hapax.truncate(MAX_HAPAX_LIST);
+
+    let unique_tokens = counts.len();
+    let mut words: Vec<WordCount> = counts
+        .into_iter()
+        .map(|(word, count)| WordCount { word, count })
+        .collect();
+    words.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+    match options.top_n.unwrap_or(DEFAULT_TOP_N) {
+        0 => {}
+        n => words.truncate(n),
+    }
+
+    Ok(VocabularyReport {
+        total_tokens,
+        unique_tokens,
+        type_token_ratio: if total_tokens == 0 {
+            0.0
+        } else {
+            unique_tokens as f64 / total_tokens as f64
+        },
+        words,
+        hapax_count,
+        hapax,
+    })
+}
@@ -0,0 +1,152 @@
+//! Warm-start cache for recently opened books.
+//!
+//! 최근 연 파일의 가벼운 메타데이터(챕터 목록, 이미지 엔트리 목록과 크기)를
+//! 경로 + mtime 기준으로 디스크에 저장한다. 다시 열 때 디렉토리 스캔,
+//! ZIP Central Directory 파싱, EPUB TOC 파싱을 건너뛸 수 있다.
+//! 읽기 전용으로 여는 대용량 텍스트는 전체 줄 수/글자 수를 기억해 줄 색인을 다시 하지 않는다.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::epub_reader::ChapterInfo;
+
+const MAX_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageEntryMeta {
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WarmMeta {
+    Epub {
+        chapters: Vec<ChapterInfo>,
+        #[serde(default)]
+        writing_mode: Option<String>,
+        #[serde(default)]
+        page_direction: Option<String>,
+    },
+    ImageZip { entries: Vec<ImageEntryMeta> },
+    ImageFolder { images: Vec<ImageEntryMeta> },
+    /// Totals of a huge (lazy, read-only) text file in the given encoding.
+    Text {
+        encoding: String,
+        total_lines: usize,
+        total_chars: usize,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WarmEntry {
+    mtime_ms: u64,
+    size: u64,
+    last_used: i64,
+    meta: WarmMeta,
+}
+
+pub struct WarmCache {
+    data: HashMap<String, WarmEntry>,
+    store_path: Option<PathBuf>,
+}
+
+impl WarmCache {
+    /// Load the cache from disk. A missing or unreadable cache is treated as empty.
+    pub fn load() -> Self {
+        let store_path = Self::default_path().ok();
+        let data = store_path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { data, store_path }
+    }
+
+    fn default_path() -> anyhow::Result<PathBuf> {
+        let home = dirs::home_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+        Ok(home.join(".simple-reader").join("warm_cache.json"))
+    }
+
+    /// (mtime in ms, size) used to detect that a file changed since it was cached.
+    fn stamp(path: &Path) -> Option<(u64, u64)> {
+        let meta = std::fs::metadata(path).ok()?;
+        let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some((mtime.as_millis() as u64, meta.len()))
+    }
+
+    /// Get cached metadata for a path, only if the file is unchanged.
+    pub fn get(&mut self, path: &Path) -> Option<WarmMeta> {
+        let (mtime_ms, size) = Self::stamp(path)?;
+        let key = path.to_string_lossy().to_string();
+        let entry = self.data.get_mut(&key)?;
+        if entry.mtime_ms != mtime_ms || entry.size != size {
+            return None;
+        }
+        entry.last_used = chrono::Local::now().timestamp();
+        Some(entry.meta.clone())
+    }
+
+    /// Store metadata for a path and persist the cache.
+    pub fn put(&mut self, path: &Path, meta: WarmMeta) {
+        let Some((mtime_ms, size)) = Self::stamp(path) else {
+            return;
+        };
+        self.data.insert(
+            path.to_string_lossy().to_string(),
+            WarmEntry {
+                mtime_ms,
+                size,
+                last_used: chrono::Local::now().timestamp(),
+                meta,
+            },
+        );
+
+        // 오래 사용하지 않은 항목부터 제거
+        if self.data.len() > MAX_ENTRIES {
+            let mut by_age: Vec<(String, i64)> = self
+                .data
+                .iter()
+                .map(|(k, e)| (k.clone(), e.last_used))
+                .collect();
+            by_age.sort_by_key(|(_, t)| *t);
+            let excess = self.data.len() - MAX_ENTRIES;
+            for (key, _) in by_age.into_iter().take(excess) {
+                self.data.remove(&key);
+            }
+        }
+
+        if let Err(e) = self.save_to_disk() {
+            log::warn!("Failed to save warm cache: {}", e);
+        }
+    }
+
+    fn save_to_disk(&self) -> anyhow::Result<()> {
+        let Some(store_path) = self.store_path.as_ref() else {
+            return Ok(());
+        };
+        if let Some(parent) = store_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string(&self.data)?;
+        std::fs::write(store_path, content)?;
+        Ok(())
+    }
+}
+
+/// Collect (file name, size) for a list of image paths.
+pub fn folder_image_metas(image_paths: &[PathBuf]) -> Vec<ImageEntryMeta> {
+    image_paths
+        .iter()
+        .map(|p| ImageEntryMeta {
+            name: p
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            size: std::fs::metadata(p).map(|m| m.len()).unwrap_or(0),
+        })
+        .collect()
+}
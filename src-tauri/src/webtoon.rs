@@ -0,0 +1,85 @@
+//! Webtoon (long strip) mode: consecutive images laid out as one vertical scroll stream,
+//! with ultra-tall images split into tiles.
+//!
+//! 웹툰은 800×20000px 같은 긴 이미지 여러 장으로 나뉘어 있어서, 한 장을 통째로 WebView에
+//! 올리면 메모리를 많이 먹는다. 레이아웃은 헤더의 크기만으로 계산하고(디코드 없음),
+//! 화면에 보이는 타일만 `tile`로 잘라서 보낸다. 같은 페이지의 타일을 연달아 요청하므로
+//! 탭마다 마지막으로 디코드한 페이지 하나를 이미지 캐시에 두고, 메모리 예산에 함께 센다.
+//! 타일에는 EXIF 방향, 페이지 회전, 야간 모드 필터가 적용된다. 여백 자르기와 최대 크기는
+//! 헤더 크기로 계산한 레이아웃과 맞지 않게 되므로 적용하지 않는다.
+
+use serde::Serialize;
+
+use crate::image_cache::ImageCacheManager;
+
+pub const DEFAULT_TILE_HEIGHT: u32 = 2048;
+pub const DEFAULT_STRIP_WIDTH: u32 = 800;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StripTile {
+    pub index: usize,
+    /// Source rows `y..y + height` of the image (height 0 = the whole image, size unknown).
+    pub y: u32,
+    pub height: u32,
+    /// Position and height in the stream, scaled to the strip width.
+    pub top: f64,
+    pub display_height: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StripLayout {
+    pub width: u32,
+    pub total_height: f64,
+    pub tiles: Vec<StripTile>,
+}
+
+/// Stitch pages (width, height) into one stream of `width`, splitting pages taller than
+/// `tile_height` source pixels. Pages of unknown size get one square placeholder tile.
+pub fn strip_layout(dimensions: &[Option<(u32, u32)>], width: u32, tile_height: u32) -> StripLayout {
+    let mut tiles = Vec::new();
+    let mut top = 0.0;
+    for (index, dimensions) in dimensions.iter().enumerate() {
+        let Some((page_width, page_height)) = dimensions.filter(|(w, h)| *w > 0 && *h > 0) else {
+            tiles.push(StripTile {
+                index,
+                y: 0,
+                height: 0,
+                top,
+                display_height: width as f64,
+            });
+            top += width as f64;
+            continue;
+        };
+        let scale = width as f64 / page_width as f64;
+        let mut y = 0;
+        while y < page_height {
+            let height = tile_height.min(page_height - y);
+            let display_height = height as f64 * scale;
+            tiles.push(StripTile {
+                index,
+                y,
+                height,
+                top,
+                display_height,
+            });
+            top += display_height;
+            y += height;
+        }
+    }
+    StripLayout {
+        width,
+        total_height: top,
+        tiles,
+    }
+}
+
+/// Rows `y..y + height` of a page (height 0 = to the bottom), encoded for display.
+pub fn tile(cache: &ImageCacheManager, file_id: &str, index: usize, y: u32, height: u32) -> anyhow::Result<Vec<u8>> {
+    let image = cache.decoded_page(file_id, index)?;
+    if y >= image.height() {
+        anyhow::bail!("Tile starts below the image: {} >= {}", y, image.height());
+    }
+    let rest = image.height() - y;
+    let height = if height == 0 { rest } else { height.min(rest) };
+    crate::image_process::encode(&image.crop_imm(0, y, image.width(), height))
+}
@@ -6,19 +6,36 @@
 //! of the file. Individual entries are read on demand.
 //!
 //! Uses memory-mapped I/O for zero-copy access.
+//!
+//! Entries up to `set_crc_check_limit` bytes are CRC32-checked after decompression, so a
+//! corrupt archive fails with `AppError::CorruptEntry` instead of showing garbage.
 
 use std::io::Read;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use memmap2::Mmap;
 
+/// Default size limit for CRC checks (checking is cheap next to decoding the image).
+pub const DEFAULT_CRC_CHECK_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+static CRC_CHECK_MAX_BYTES: AtomicU64 = AtomicU64::new(DEFAULT_CRC_CHECK_MAX_BYTES);
+
+/// Verify CRCs of entries up to `max_bytes` (uncompressed); 0 turns checking off.
+pub fn set_crc_check_limit(max_bytes: u64) {
+    CRC_CHECK_MAX_BYTES.store(max_bytes, Ordering::Relaxed);
+}
+
 /// Metadata for a single ZIP entry, parsed from the Central Directory.
 struct EntryMeta {
     name: String,
     compression_method: u16,
+    crc32: u32,
     compressed_size: u64,
     uncompressed_size: u64,
     local_header_offset: u64,
+    /// General purpose flag bit 0 (ZipCrypto / AES).
+    encrypted: bool,
 }
 
 /// Fast ZIP reader that only parses the Central Directory on open.
@@ -83,9 +100,21 @@ impl ZipIndex {
         Ok(Self { mmap, entries })
     }
 
-    /// Iterator over all entry names (files and directories).
-    pub fn entry_names(&self) -> impl Iterator<Item = &str> {
-        self.entries.iter().map(|e| e.name.as_str())
+    /// Iterator over (entry name, uncompressed size) pairs.
+    pub fn entry_sizes(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.uncompressed_size))
+    }
+
+    /// Whether any entry is password protected.
+    pub fn is_encrypted(&self) -> bool {
+        self.entries.iter().any(|e| e.encrypted)
+    }
+
+    /// Whether an entry is password protected (must be read via `archive_password`).
+    pub fn is_entry_encrypted(&self, name: &str) -> bool {
+        self.entries.iter().any(|e| e.name == name && e.encrypted)
     }
 
     /// Read and decompress an entry by name.
@@ -95,12 +124,51 @@ impl ZipIndex {
             .iter()
             .find(|e| e.name == name)
             .ok_or_else(|| anyhow::anyhow!("ZIP entry not found: {}", name))?;
+        if entry.encrypted {
+            return Err(crate::error::AppError::PasswordRequired(name.to_string()).into());
+        }
         self.decompress(entry)
     }
 
+    /// The first `max_bytes` (or fewer) of an entry, decompressing no more than needed
+    /// (for reading image headers). Not CRC-checked.
+    pub fn read_entry_head(&self, name: &str, max_bytes: usize) -> anyhow::Result<Vec<u8>> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|e| e.name == name)
+            .ok_or_else(|| anyhow::anyhow!("ZIP entry not found: {}", name))?;
+        if entry.encrypted {
+            return Err(crate::error::AppError::PasswordRequired(name.to_string()).into());
+        }
+        let compressed = self.compressed_data(entry)?;
+        let limit = max_bytes as u64;
+        let mut buf = Vec::with_capacity(max_bytes.min(entry.uncompressed_size as usize));
+        match entry.compression_method {
+            0 => buf.extend_from_slice(&compressed[..compressed.len().min(max_bytes)]),
+            8 => {
+                flate2::read::DeflateDecoder::new(compressed).take(limit).read_to_end(&mut buf)?;
+            }
+            12 => {
+                bzip2::read::BzDecoder::new(compressed).take(limit).read_to_end(&mut buf)?;
+            }
+            93 => {
+                zstd::stream::read::Decoder::new(compressed)?.take(limit).read_to_end(&mut buf)?;
+            }
+            // LZMA은 스트리밍 디코더가 없어 전체를 푼다
+            _ => {
+                let mut bytes = self.decompress(entry)?;
+                bytes.truncate(max_bytes);
+                buf = bytes;
+            }
+        }
+        Ok(buf)
+    }
+
     // ── internal ────────────────────────────────────────────────────
 
-    fn decompress(&self, entry: &EntryMeta) -> anyhow::Result<Vec<u8>> {
+    /// The compressed bytes of an entry, located through its local header.
+    fn compressed_data(&self, entry: &EntryMeta) -> anyhow::Result<&[u8]> {
         let data = &self.mmap[..];
         let lh = entry.local_header_offset as usize;
 
@@ -119,35 +187,71 @@ impl ZipIndex {
         if data_end > data.len() {
             anyhow::bail!("Compressed data extends beyond file");
         }
+        Ok(&data[data_start..data_end])
+    }
 
-        let compressed = &data[data_start..data_end];
+    fn decompress(&self, entry: &EntryMeta) -> anyhow::Result<Vec<u8>> {
+        let compressed = self.compressed_data(entry)?;
 
-        match entry.compression_method {
+        let bytes = match entry.compression_method {
             0 => {
                 // Stored — no compression
-                Ok(compressed.to_vec())
+                compressed.to_vec()
             }
             8 => {
                 // Deflate
                 let mut decoder = flate2::read::DeflateDecoder::new(compressed);
                 let mut buf = Vec::with_capacity(entry.uncompressed_size as usize);
                 decoder.read_to_end(&mut buf)?;
-                Ok(buf)
+                buf
+            }
+            12 => {
+                // bzip2
+                let mut decoder = bzip2::read::BzDecoder::new(compressed);
+                let mut buf = Vec::with_capacity(entry.uncompressed_size as usize);
+                decoder.read_to_end(&mut buf)?;
+                buf
+            }
+            14 => Self::decompress_lzma(compressed, entry.uncompressed_size)?,
+            93 => {
+                // Zstandard
+                let mut buf = Vec::with_capacity(entry.uncompressed_size as usize);
+                zstd::stream::copy_decode(compressed, &mut buf)?;
+                buf
             }
             m => anyhow::bail!("Unsupported compression method: {}", m),
+        };
+
+        let limit = CRC_CHECK_MAX_BYTES.load(Ordering::Relaxed);
+        if limit > 0 && entry.uncompressed_size <= limit && crc32fast::hash(&bytes) != entry.crc32 {
+            return Err(crate::error::AppError::CorruptEntry(entry.name.clone()).into());
+        }
+        Ok(bytes)
+    }
+
+    /// ZIP LZMA: version (2) + props size (2) + props (5), then a raw LZMA stream
+    /// without the usual 8-byte size field — the size comes from the Central Directory.
+    fn decompress_lzma(compressed: &[u8], uncompressed_size: u64) -> anyhow::Result<Vec<u8>> {
+        if compressed.len() < 9 || r16(compressed, 2) != 5 {
+            anyhow::bail!("Invalid LZMA entry header");
         }
+        let options = lzma_rs::decompress::Options {
+            unpacked_size: lzma_rs::decompress::UnpackedSize::UseProvided(Some(uncompressed_size)),
+            ..Default::default()
+        };
+        let mut buf = Vec::with_capacity(uncompressed_size as usize);
+        lzma_rs::lzma_decompress_with_options(&mut &compressed[4..], &mut buf, &options)
+            .map_err(|e| anyhow::anyhow!("LZMA decode failed: {:?}", e))?;
+        Ok(buf)
     }
 
     /// Scan backwards from end of file for EOCD signature.
     fn find_eocd(data: &[u8]) -> Option<usize> {
         let search_len = std::cmp::min(data.len(), 22 + 65535);
         let start = data.len() - search_len;
-        for i in (start..=data.len().saturating_sub(22)).rev() {
-            if r32(data, i) == EOCD_SIG {
-                return Some(i);
-            }
-        }
-        None
+        (start..=data.len().saturating_sub(22))
+            .rev()
+            .find(|&i| r32(data, i) == EOCD_SIG)
     }
 
     /// Parse EOCD (+ ZIP64 if present). Returns (num_entries, cd_offset).
@@ -181,11 +285,13 @@ impl ZipIndex {
         // First pass: collect raw entries with name bytes
         struct RawEntry {
             compression_method: u16,
+            crc32: u32,
             compressed_size: u64,
             uncompressed_size: u64,
             local_header_offset: u64,
             name_bytes: Vec<u8>,
             is_utf8_flag: bool,
+            encrypted: bool,
         }
 
         let mut raw_entries = Vec::with_capacity(num_entries);
@@ -202,6 +308,7 @@ impl ZipIndex {
             let flags = r16(data, pos + 8);
             let is_utf8_flag = (flags & (1 << 11)) != 0;
             let method = r16(data, pos + 10);
+            let crc32 = r32(data, pos + 16);
             let c32 = r32(data, pos + 20) as u64;
             let u32_ = r32(data, pos + 24) as u64;
             let name_len = r16(data, pos + 28) as usize;
@@ -238,11 +345,13 @@ impl ZipIndex {
 
             raw_entries.push(RawEntry {
                 compression_method: method,
+                crc32,
                 compressed_size: compressed,
                 uncompressed_size: uncompressed,
                 local_header_offset: offset,
                 name_bytes,
                 is_utf8_flag,
+                encrypted: (flags & 1) != 0,
             });
 
             pos = name_end + extra_len + comment_len;
@@ -279,9 +388,11 @@ impl ZipIndex {
                 EntryMeta {
                     name,
                     compression_method: raw.compression_method,
+                    crc32: raw.crc32,
                     compressed_size: raw.compressed_size,
                     uncompressed_size: raw.uncompressed_size,
                     local_header_offset: raw.local_header_offset,
+                    encrypted: raw.encrypted,
                 }
             })
             .collect();